@@ -10,12 +10,12 @@
 
 // Here should go just the Background loop.
 
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 use std::env::temp_dir;
 use std::sync::mpsc::{Sender, Receiver};
 use std::path::PathBuf;
 use std::fs::{DirBuilder, File};
-use std::io::{BufWriter, Write};
+use std::io::{BufReader, BufWriter, Write};
 use std::process::Command;
 use regex::Regex;
 
@@ -23,9 +23,11 @@ use crate::RPFM_PATH;
 use crate::SUPPORTED_GAMES;
 use crate::SHORTCUTS;
 use crate::SETTINGS;
+use crate::PACKED_FILE_TYPES;
 use crate::SCHEMA;
 use crate::DEPENDENCY_DATABASE;
 use crate::FAKE_DEPENDENCY_DATABASE;
+use crate::DATA_PACKFILES_ASSETS;
 use crate::GAME_SELECTED;
 use crate::GlobalMatch;
 use crate::background_thread_extra;
@@ -36,10 +38,18 @@ use crate::error::{Error, ErrorKind};
 use crate::packfile::{PackFile, PFHFlags};
 use crate::packedfile::*;
 use crate::packedfile::loc::*;
+use crate::packedfile::loc::translation_files::{translation_files_mass_export, translation_files_mass_import};
+use crate::packedfile::loc::translations::TranslationData;
 use crate::packedfile::db::*;
 use crate::packedfile::rigidmodel::*;
+use crate::packedfile::query::QueryResult;
+use crate::packedfile::registry::{get_packed_file_type_rules, get_text_encoding_hint};
+use crate::packedfile::lua::{check_lua_references, check_lua_scripts, is_lua_script};
+use crate::packedfile::text::Text;
+use crate::packedfile::xml::check_xml_files;
 use crate::schema::*;
 use crate::schema::assembly_kit::*;
+use crate::schema::docs::generate_schema_docs;
 use crate::updater::*;
 
 /// This is the background loop that's going to be executed in a parallel thread to the UI. No UI or "Unsafe" stuff here.
@@ -208,7 +218,7 @@ pub fn background_loop(
 
                         // Wait until we get the needed data from the UI thread.
                         let new_schema: Schema = if let Data::Schema(data) = check_message_validity_recv(&receiver_data) { data } else { panic!(THREADS_MESSAGE_ERROR); };
-                        match Schema::save(&new_schema, &SUPPORTED_GAMES.get(&**GAME_SELECTED.lock().unwrap()).unwrap().schema) {
+                        match Schema::save_local(&new_schema, &SUPPORTED_GAMES.get(&**GAME_SELECTED.lock().unwrap()).unwrap().schema) {
                             Ok(_) => {
                                 *SCHEMA.lock().unwrap() = Some(new_schema);
                                 sender.send(Data::Success).unwrap();
@@ -220,6 +230,8 @@ pub fn background_loop(
                     // In case we want to change the current settings...
                     Commands::SetSettings => {
                         let new_settings = if let Data::Settings(data) = check_message_validity_recv(&receiver_data) { data } else { panic!(THREADS_MESSAGE_ERROR); };
+                        *PACKED_FILE_TYPES.lock().unwrap() = get_packed_file_type_rules(&new_settings);
+                        *DATA_PACKFILES_ASSETS.lock().unwrap() = None;
                         *SETTINGS.lock().unwrap() = new_settings;
                         match SETTINGS.lock().unwrap().save() {
                             Ok(()) => sender.send(Data::Success).unwrap(),
@@ -261,6 +273,9 @@ pub fn background_loop(
                         // Change the `fake dependency_database` for that game.
                         *FAKE_DEPENDENCY_DATABASE.lock().unwrap() = background_thread_extra::load_fake_dependency_packfiles();
 
+                        // The assets of the data PackFiles are from the old game, so they need to be reloaded when used.
+                        *DATA_PACKFILES_ASSETS.lock().unwrap() = None;
+
                         // If there is a PackFile open, change his id to match the one of the new GameSelected.
                         if !pack_file_decoded.get_file_name().is_empty() { pack_file_decoded.pfh_version = SUPPORTED_GAMES.get(&**GAME_SELECTED.lock().unwrap()).unwrap().id; }
 
@@ -271,7 +286,7 @@ pub fn background_loop(
                             let mut counter = 0;
                             let mut table_list = String::new();
                             for i in pack_file_decoded.packed_files.iter_mut() {
-                                if i.path.starts_with(&["db".to_owned()]) {
+                                if i.path.len() == 3 && i.path.starts_with(&["db".to_owned()]) {
                                     if let Some(ref schema) = *SCHEMA.lock().unwrap() {
 
                                         // For some stupid reason, this fails with decompresion sometimes.
@@ -333,11 +348,33 @@ pub fn background_loop(
 
                         // Reload the currently loaded schema, just in case it was updated.
                         let data = if let Data::VersionsVersions(data) = check_message_validity_recv(&receiver_data) { data } else { panic!(THREADS_MESSAGE_ERROR); };
-                        match update_schemas(&data.0, &data.1) {
-                            Ok(_) => {
+                        match SchemaUpdateSource::from_settings().and_then(|source| update_schemas(&source, &data.0, &data.1)) {
+                            Ok(conflicts) => {
                                 *SCHEMA.lock().unwrap() = Schema::load(&SUPPORTED_GAMES.get(&**GAME_SELECTED.lock().unwrap()).unwrap().schema).ok();
-                                sender.send(Data::Success).unwrap();
+                                sender.send(Data::VecString(conflicts)).unwrap();
+                            }
+                            Err(error) => sender.send(Data::Error(error)).unwrap(),
+                        }
+                    }
+
+                    // In case we want to update our Schemas from a local folder or archive...
+                    Commands::UpdateSchemasFromPath => {
+
+                        // Get the versions from the source, and only update if it has newer schemas than ours.
+                        let path = if let Data::PathBuf(data) = check_message_validity_recv(&receiver_data) { data } else { panic!(THREADS_MESSAGE_ERROR); };
+                        let result = SchemaUpdateSource::from_path(&path).and_then(|source| {
+                            let local_versions: Versions = serde_json::from_reader(BufReader::new(File::open(RPFM_PATH.to_path_buf().join(PathBuf::from("schemas/versions.json")))?))?;
+                            let remote_versions = source.get_versions()?;
+                            if has_schema_updates(&local_versions, &remote_versions) { update_schemas(&source, &local_versions, &remote_versions).map(Some) }
+                            else { Ok(None) }
+                        });
+
+                        match result {
+                            Ok(Some(conflicts)) => {
+                                *SCHEMA.lock().unwrap() = Schema::load(&SUPPORTED_GAMES.get(&**GAME_SELECTED.lock().unwrap()).unwrap().schema).ok();
+                                sender.send(Data::VecString(conflicts)).unwrap();
                             }
+                            Ok(None) => sender.send(Data::Success).unwrap(),
                             Err(error) => sender.send(Data::Error(error)).unwrap(),
                         }
                     }
@@ -451,6 +488,32 @@ pub fn background_loop(
                         ))).unwrap();
                     }
 
+                    // In case we want to know the type of a PackedFile, to open it with the right view...
+                    Commands::GetPackedFileType => {
+
+                        // Wait until we get the needed data from the UI thread.
+                        let path = if let Data::VecString(data) = check_message_validity_recv(&receiver_data) { data } else { panic!(THREADS_MESSAGE_ERROR) };
+                        match pack_file_decoded.packed_files.iter_mut().find(|x| x.path == path) {
+                            Some(packed_file) => sender.send(Data::DecodeablePackedFileType(detect_packed_file_type(packed_file))).unwrap(),
+                            None => sender.send(Data::Error(Error::from(ErrorKind::PackedFileNotFound))).unwrap(),
+                        }
+                    }
+
+                    // In case we want to know the types of the PackedFiles we can only know from their data, for the TreeView...
+                    Commands::GetDetectedPackedFileTypes => {
+
+                        // Wait until we get the needed data from the UI thread. Only the provided paths are checked.
+                        let (is_extra_packfile, paths) = if let Data::BoolVecVecString(data) = check_message_validity_recv(&receiver_data) { data } else { panic!(THREADS_MESSAGE_ERROR) };
+                        let paths = paths.into_iter().collect::<BTreeSet<Vec<String>>>();
+                        let pack_file = if is_extra_packfile { &mut pack_file_decoded_extra } else { &mut pack_file_decoded };
+                        let types = pack_file.packed_files.iter_mut()
+                            .filter(|x| paths.contains(&x.path))
+                            .map(|x| (x.path.to_vec(), detect_packed_file_type(x)))
+                            .filter(|(_, packed_file_type)| *packed_file_type != DecodeablePackedFileType::None)
+                            .collect::<Vec<(Vec<String>, DecodeablePackedFileType)>>();
+                        sender.send(Data::VecVecStringDecodeablePackedFileType(types)).unwrap();
+                    }
+
                     // In case we want to move stuff from one PackFile to another...
                     Commands::AddPackedFileFromPackFile => {
 
@@ -481,6 +544,16 @@ pub fn background_loop(
                         }
                     }
 
+                    // In case we want to import raw tables from the Assembly Kit as DB Tables...
+                    Commands::ImportAssemblyKitTables => {
+                        let paths = if let Data::VecPathBuf(data) = check_message_validity_recv(&receiver_data) { data } else { panic!(THREADS_MESSAGE_ERROR); };
+                        let version = SUPPORTED_GAMES.get(&**GAME_SELECTED.lock().unwrap()).unwrap().raw_db_version;
+                        match import_raw_tables(&mut pack_file_decoded, &paths, version) {
+                            Ok(result) => sender.send(Data::VecVecStringVecVecString(result)).unwrap(),
+                            Err(error) => sender.send(Data::Error(error)).unwrap(),
+                        }
+                    }
+
                     // In case we want to Mass-Export TSV Files...
                     Commands::MassExportTSV => {
 
@@ -492,6 +565,33 @@ pub fn background_loop(
                         }
                     }
 
+                    // In case we want to Mass-Import translation files (PO/XLIFF) into Loc PackedFiles...
+                    Commands::MassImportTranslationFiles => {
+                        let paths = if let Data::VecPathBuf(data) = check_message_validity_recv(&receiver_data) { data } else { panic!(THREADS_MESSAGE_ERROR); };
+                        match translation_files_mass_import(&paths, &mut pack_file_decoded) {
+                            Ok(result) => sender.send(Data::VecVecStringVecVecString(result)).unwrap(),
+                            Err(error) => sender.send(Data::Error(error)).unwrap(),
+                        }
+                    }
+
+                    // In case we want to Mass-Export Loc PackedFiles as translation files (PO/XLIFF)...
+                    Commands::MassExportTranslationFiles => {
+                        let data = if let Data::VecPathTypePathBufTranslationFileFormat(data) = check_message_validity_recv(&receiver_data) { data } else { panic!(THREADS_MESSAGE_ERROR); };
+                        match translation_files_mass_export(&data.0, &data.1, data.2, &mut pack_file_decoded) {
+                            Ok(result) => sender.send(Data::String(result)).unwrap(),
+                            Err(error) => sender.send(Data::Error(error)).unwrap(),
+                        }
+                    }
+
+                    // In case we want to export the DB Tables as raw tables for the Assembly Kit...
+                    Commands::ExportAssemblyKitTables => {
+                        let data = if let Data::VecPathTypePathBuf(data) = check_message_validity_recv(&receiver_data) { data } else { panic!(THREADS_MESSAGE_ERROR); };
+                        match export_raw_tables(&data.0, &mut pack_file_decoded, &data.1) {
+                            Ok(result) => sender.send(Data::String(result)).unwrap(),
+                            Err(error) => sender.send(Data::Error(error)).unwrap(),
+                        }
+                    }
+
                     // In case we want to decode a Loc PackedFile...
                     Commands::DecodePackedFileLoc => {
 
@@ -546,13 +646,13 @@ pub fn background_loop(
                                         // We try to decode it as a DB PackedFile.
                                         match packed_file.get_data_and_keep_it() {
                                             Ok(data) => {
-                                                match DB::read(
-                                                    &data,
-                                                    &packed_file.path[1],
-                                                    schema,
-                                                ) {
-                                                    Ok(packed_file_decoded) => sender.send(Data::DB(packed_file_decoded)).unwrap(),
-                                                    Err(error) => sender.send(Data::Error(error)).unwrap(),
+                                                // Tables outside the `db` folder need to find out what table they are first.
+                                                match DB::get_table_name(&packed_file.path, &data, schema) {
+                                                    Some(table_name) => match DB::read(&data, &table_name, schema) {
+                                                        Ok(packed_file_decoded) => sender.send(Data::DB(packed_file_decoded)).unwrap(),
+                                                        Err(error) => sender.send(Data::Error(error)).unwrap(),
+                                                    }
+                                                    None => sender.send(Data::Error(Error::from(ErrorKind::DBTableUnknownTableName))).unwrap(),
                                                 }
                                             }
                                             Err(_) => sender.send(Data::Error(Error::from(ErrorKind::PackedFileDataCouldNotBeLoaded))).unwrap(),
@@ -611,21 +711,10 @@ pub fn background_loop(
                             Some(packed_file) => {
                                 match packed_file.get_data_and_keep_it() {
                                     Ok(data) => {
-                                        
-                                        // NOTE: This only works for UTF-8 and ISO_8859_1 encoded files. Check their encoding before adding them here to be decoded.
-                                        // Try to decode the PackedFile as a normal UTF-8 string.
-                                        let mut decoded_string = decode_string_u8(&data);
-
-                                        // If there is an error, try again as ISO_8859_1, as there are some text files using that encoding.
-                                        if decoded_string.is_err() {
-                                            if let Ok(string) = decode_string_u8_iso_8859_1(&data) {
-                                                decoded_string = Ok(string);
-                                            }
-                                        }
 
-                                        // Depending if the decoding worked or not, send back the text file or an error.
-                                        match decoded_string {
-                                            Ok(text) => sender.send(Data::String(text)).unwrap(),
+                                        // Decode the PackedFile, keeping his encoding so we can use it again when saving it.
+                                        match Text::read_with_hint(&data, get_text_encoding_hint(&path)) {
+                                            Ok(text) => sender.send(Data::Text(text)).unwrap(),
                                             Err(error) => sender.send(Data::Error(error)).unwrap(),
                                         }
                                     }
@@ -640,17 +729,20 @@ pub fn background_loop(
                     Commands::EncodePackedFileText => {
 
                         // Wait until we get the needed data from the UI thread.
-                        let data = if let Data::StringVecString(data) = check_message_validity_recv(&receiver_data) { data } else { panic!(THREADS_MESSAGE_ERROR); };
-
-                        // Encode the text.
-                        let encoded_text = encode_string_u8(&data.0);
-
-                        // Update the PackFile to reflect the changes.
-                        background_thread_extra::update_packed_file_data_text(
-                            &encoded_text,
-                            &mut pack_file_decoded,
-                            &data.1
-                        );
+                        let data = if let Data::TextVecString(data) = check_message_validity_recv(&receiver_data) { data } else { panic!(THREADS_MESSAGE_ERROR); };
+
+                        // Encode the text with his encoding and, if it worked, update the PackFile to reflect the changes.
+                        match data.0.save() {
+                            Ok(encoded_text) => {
+                                background_thread_extra::update_packed_file_data_text(
+                                    &encoded_text,
+                                    &mut pack_file_decoded,
+                                    &data.1
+                                );
+                                sender.send(Data::Success).unwrap();
+                            }
+                            Err(error) => sender.send(Data::Error(error)).unwrap(),
+                        }
                     }
 
                     // In case we want to decode a RigidModel...
@@ -837,6 +929,15 @@ pub fn background_loop(
                         }
                     }
 
+                    // In case we want to check a list of PackFiles for conflicts between them...
+                    Commands::CheckPackFilesConflicts => {
+                        let paths = if let Data::VecPathBuf(data) = check_message_validity_recv(&receiver_data) { data } else { panic!(THREADS_MESSAGE_ERROR); };
+                        match background_thread_extra::get_packfiles_conflicts(&paths) {
+                            Ok(report) => sender.send(Data::String(report)).unwrap(),
+                            Err(error) => sender.send(Data::Error(error)).unwrap(),
+                        }
+                    }
+
                     // In case we want to generate a new Pak File for our Game Selected...
                     Commands::GeneratePakFile => {
 
@@ -850,6 +951,62 @@ pub fn background_loop(
                         *FAKE_DEPENDENCY_DATABASE.lock().unwrap() = background_thread_extra::load_fake_dependency_packfiles();
                     }
 
+                    // In case we want to import the definitions of the Assembly Kit into the schema of our Game Selected...
+                    Commands::ImportSchemaFromAssemblyKit => {
+                        let path = if let Data::PathBuf(data) = check_message_validity_recv(&receiver_data) { data } else { panic!(THREADS_MESSAGE_ERROR) };
+                        let game_info = SUPPORTED_GAMES.get(&**GAME_SELECTED.lock().unwrap()).unwrap().clone();
+                        let mut schema = SCHEMA.lock().unwrap();
+                        if schema.is_none() { *schema = Some(Schema::new()); }
+                        if let Some(ref mut schema) = *schema {
+                            match import_schema(schema, &path, game_info.raw_db_version) {
+                                Ok(report) => match schema.save_local(&game_info.schema) {
+                                    Ok(_) => sender.send(Data::ImportSchemaReport(report)).unwrap(),
+                                    Err(error) => sender.send(Data::Error(error)).unwrap(),
+                                }
+                                Err(error) => sender.send(Data::Error(error)).unwrap(),
+                            }
+                        }
+                    }
+
+                    // In case we want to check if the schema can decode all the tables of our Game Selected...
+                    Commands::ValidateSchema => {
+                        match background_thread_extra::validate_schema() {
+                            Ok(report) => sender.send(Data::String(report)).unwrap(),
+                            Err(error) => sender.send(Data::Error(error)).unwrap(),
+                        }
+                    }
+
+                    // In case we want to generate the documentation of the schema of our Game Selected...
+                    Commands::GenerateSchemaDocs => {
+                        let path = if let Data::PathBuf(data) = check_message_validity_recv(&receiver_data) { data } else { panic!(THREADS_MESSAGE_ERROR) };
+                        match *SCHEMA.lock().unwrap() {
+                            Some(ref schema) => {
+                                let game_info = SUPPORTED_GAMES.get(&**GAME_SELECTED.lock().unwrap()).unwrap().clone();
+
+                                // The descriptions from the Assembly Kit are optional, so ignore any error getting them.
+                                let field_descriptions = match get_game_selected_assembly_kit_path() {
+                                    Some(assembly_kit_path) => get_field_descriptions(&assembly_kit_path.join("raw_data").join("db"), game_info.raw_db_version).unwrap_or_else(|_| BTreeMap::new()),
+                                    None => BTreeMap::new(),
+                                };
+
+                                match generate_schema_docs(schema, &format!("{} Schema", game_info.display_name), &field_descriptions, &path) {
+                                    Ok(_) => sender.send(Data::Success).unwrap(),
+                                    Err(error) => sender.send(Data::Error(error)).unwrap(),
+                                }
+                            }
+                            None => sender.send(Data::Error(Error::from(ErrorKind::SchemaNotFound))).unwrap(),
+                        }
+                    }
+
+                    // In case we want to get the differences between two schema files...
+                    Commands::DiffSchemaFiles => {
+                        let (old_path, new_path, export_path) = if let Data::PathBufPathBufPathBuf(data) = check_message_validity_recv(&receiver_data) { data } else { panic!(THREADS_MESSAGE_ERROR) };
+                        match Schema::get_diff_from_files(&old_path, &new_path).and_then(|diff| diff.save(&export_path).map(|_| diff.to_markdown())) {
+                            Ok(diff) => sender.send(Data::String(diff)).unwrap(),
+                            Err(error) => sender.send(Data::Error(error)).unwrap(),
+                        }
+                    }
+
                     // In case we want to get the PackFiles List of our PackFile...
                     Commands::GetPackFilesList => {
                         sender.send(Data::VecString(pack_file_decoded.pack_files.to_vec())).unwrap();
@@ -888,7 +1045,7 @@ pub fn background_loop(
                             }
                             None => BTreeMap::new(),
                         };
-                        sender.send(Data::BTreeMapI32VecString(dependency_data)).unwrap();
+                        sender.send(Data::BTreeMapI32BTreeMapStringString(dependency_data)).unwrap();
                     }
 
                     // In case we want to use Kailua to check if your script has errors...
@@ -909,7 +1066,7 @@ pub fn background_loop(
 
                                 // Extract every lua file in the PackFile, respecting his path.
                                 for packed_file in &mut pack_file_decoded.packed_files {
-                                    if packed_file.path.last().unwrap().ends_with(".lua") {
+                                    if is_lua_script(&packed_file.path) {
                                         let path: PathBuf = temp_folder_path.to_path_buf().join(packed_file.path.iter().collect::<PathBuf>());
 
                                         // If the path doesn't exist, create it.
@@ -953,6 +1110,30 @@ pub fn background_loop(
                         else { sender.send(Data::Error(Error::from(ErrorKind::NoTypesFileFound))).unwrap(); }
                     }
 
+                    // In case we want to check the syntax of every Lua script in the PackFile...
+                    Commands::CheckLuaSyntax => {
+                        match check_lua_scripts(&mut pack_file_decoded) {
+                            Ok(errors) => sender.send(Data::VecLuaSyntaxError(errors)).unwrap(),
+                            Err(error) => sender.send(Data::Error(error)).unwrap(),
+                        }
+                    }
+
+                    // In case we want to check the keys the Lua scripts in the PackFile use...
+                    Commands::CheckLuaReferences => {
+                        match check_lua_references(&mut pack_file_decoded) {
+                            Ok(unknown_keys) => sender.send(Data::VecString(unknown_keys)).unwrap(),
+                            Err(error) => sender.send(Data::Error(error)).unwrap(),
+                        }
+                    }
+
+                    // In case we want to check the XML PackedFiles in the PackFile...
+                    Commands::CheckXMLFiles => {
+                        match check_xml_files(&mut pack_file_decoded) {
+                            Ok(errors) => sender.send(Data::VecString(errors)).unwrap(),
+                            Err(error) => sender.send(Data::Error(error)).unwrap(),
+                        }
+                    }
+
                     // In case we want to perform a "Global Search"...
                     Commands::GlobalSearch => {
 
@@ -964,53 +1145,14 @@ pub fn background_loop(
                         let loc_definition = TableDefinition::new_loc_definition();
                         for packed_file in &mut pack_file_decoded.packed_files {
                             let path = packed_file.path.to_vec();
-                            let packedfile_name = path.last().unwrap().to_owned();
-                            let packed_file_type: &str =
-
-                                // If it's in the "db" folder, it's a DB PackedFile (or you put something were it shouldn't be).
-                                if path[0] == "db" { "DB" }
-
-                                // If it ends in ".loc", it's a localisation PackedFile.
-                                else if packedfile_name.ends_with(".loc") { "LOC" }
-
-                                // If it ends in ".rigid_model_v2", it's a RigidModel PackedFile.
-                                else if packedfile_name.ends_with(".rigid_model_v2") { "RIGIDMODEL" }
-
-                                // If it ends in any of these, it's a plain text PackedFile.
-                                else if packedfile_name.ends_with(".lua") ||
-                                        packedfile_name.ends_with(".xml") ||
-                                        packedfile_name.ends_with(".xml.shader") ||
-                                        packedfile_name.ends_with(".xml.material") ||
-                                        packedfile_name.ends_with(".variantmeshdefinition") ||
-                                        packedfile_name.ends_with(".environment") ||
-                                        packedfile_name.ends_with(".lighting") ||
-                                        packedfile_name.ends_with(".wsmodel") ||
-                                        packedfile_name.ends_with(".csv") ||
-                                        packedfile_name.ends_with(".tsv") ||
-                                        packedfile_name.ends_with(".inl") ||
-                                        packedfile_name.ends_with(".battle_speech_camera") ||
-                                        packedfile_name.ends_with(".bob") ||
-                                        packedfile_name.ends_with(".cindyscene") ||
-                                        packedfile_name.ends_with(".cindyscenemanager") ||
-                                        //packedfile_name.ends_with(".benchmark") || // This one needs special decoding/encoding.
-                                        packedfile_name.ends_with(".txt") { "TEXT" }
-
-                                // If it ends in any of these, it's an image.
-                                else if packedfile_name.ends_with(".jpg") ||
-                                        packedfile_name.ends_with(".jpeg") ||
-                                        packedfile_name.ends_with(".tga") ||
-                                        packedfile_name.ends_with(".dds") ||
-                                        packedfile_name.ends_with(".png") { "IMAGE" }
-
-                                // Otherwise, we don't have a decoder for that PackedFile... yet.
-                                else { "None" };
+                            let packed_file_type = detect_packed_file_type(packed_file);
 
                             // Then, depending of his type we decode it properly (if we have it implemented support
                             // for his type).
                             match packed_file_type {
 
                                 // If the file is a Loc PackedFile, decode it and search in his key and text columns.
-                                "LOC" => {
+                                DecodeablePackedFileType::Loc => {
 
                                     let data = match packed_file.get_data_and_keep_it() {
                                         Ok(data) => data,
@@ -1054,7 +1196,7 @@ pub fn background_loop(
                                 }
 
                                 // If the file is a DB PackedFile...
-                                "DB" => {
+                                DecodeablePackedFileType::DB => {
 
                                     let data = match packed_file.get_data_and_keep_it() {
                                         Ok(data) => data,
@@ -1065,8 +1207,8 @@ pub fn background_loop(
                                         }
                                     };
 
-                                    if let Some(ref schema) = *SCHEMA.lock().unwrap() {   
-                                        if let Ok(packed_file) = DB::read(&data, &path[1], &schema) {
+                                    if let Some(ref schema) = *SCHEMA.lock().unwrap() {
+                                        if let Some(Ok(packed_file)) = DB::get_table_name(&path, &data, &schema).map(|x| DB::read(&data, &x, &schema)) {
 
                                             let mut matches_in_file = vec![];
                                             for (index, row) in packed_file.entries.iter().enumerate() {
@@ -1129,53 +1271,14 @@ pub fn background_loop(
 
                             if paths.contains(&packed_file.path) || is_in_folder {
                                 let path = packed_file.path.to_vec();
-                                let packedfile_name = path.last().unwrap().to_owned();
-                                let packed_file_type: &str =
-
-                                    // If it's in the "db" folder, it's a DB PackedFile (or you put something were it shouldn't be).
-                                    if path[0] == "db" { "DB" }
-
-                                    // If it ends in ".loc", it's a localisation PackedFile.
-                                    else if packedfile_name.ends_with(".loc") { "LOC" }
-
-                                    // If it ends in ".rigid_model_v2", it's a RigidModel PackedFile.
-                                    else if packedfile_name.ends_with(".rigid_model_v2") { "RIGIDMODEL" }
-
-                                    // If it ends in any of these, it's a plain text PackedFile.
-                                    else if packedfile_name.ends_with(".lua") ||
-                                            packedfile_name.ends_with(".xml") ||
-                                            packedfile_name.ends_with(".xml.shader") ||
-                                            packedfile_name.ends_with(".xml.material") ||
-                                            packedfile_name.ends_with(".variantmeshdefinition") ||
-                                            packedfile_name.ends_with(".environment") ||
-                                            packedfile_name.ends_with(".lighting") ||
-                                            packedfile_name.ends_with(".wsmodel") ||
-                                            packedfile_name.ends_with(".csv") ||
-                                            packedfile_name.ends_with(".tsv") ||
-                                            packedfile_name.ends_with(".inl") ||
-                                            packedfile_name.ends_with(".battle_speech_camera") ||
-                                            packedfile_name.ends_with(".bob") ||
-                                            packedfile_name.ends_with(".cindyscene") ||
-                                            packedfile_name.ends_with(".cindyscenemanager") ||
-                                            //packedfile_name.ends_with(".benchmark") || // This one needs special decoding/encoding.
-                                            packedfile_name.ends_with(".txt") { "TEXT" }
-
-                                    // If it ends in any of these, it's an image.
-                                    else if packedfile_name.ends_with(".jpg") ||
-                                            packedfile_name.ends_with(".jpeg") ||
-                                            packedfile_name.ends_with(".tga") ||
-                                            packedfile_name.ends_with(".dds") ||
-                                            packedfile_name.ends_with(".png") { "IMAGE" }
-
-                                    // Otherwise, we don't have a decoder for that PackedFile... yet.
-                                    else { "None" };
+                                let packed_file_type = detect_packed_file_type(packed_file);
 
                                 // Then, depending of his type we decode it properly (if we have it implemented support
                                 // for his type).
                                 match packed_file_type {
 
                                     // If the file is a Loc PackedFile, decode it and search in his key and text columns.
-                                    "LOC" => {
+                                    DecodeablePackedFileType::Loc => {
 
                                         let data = match packed_file.get_data_and_keep_it() {
                                             Ok(data) => data,
@@ -1219,7 +1322,7 @@ pub fn background_loop(
                                     }
 
                                     // If the file is a DB PackedFile...
-                                    "DB" => {
+                                    DecodeablePackedFileType::DB => {
 
                                         let data = match packed_file.get_data_and_keep_it() {
                                             Ok(data) => data,
@@ -1230,8 +1333,8 @@ pub fn background_loop(
                                             }
                                         };
 
-                                        if let Some(ref schema) = *SCHEMA.lock().unwrap() {   
-                                            if let Ok(packed_file) = DB::read(&data, &path[1], &schema) {
+                                        if let Some(ref schema) = *SCHEMA.lock().unwrap() {
+                                            if let Some(Ok(packed_file)) = DB::get_table_name(&path, &data, &schema).map(|x| DB::read(&data, &x, &schema)) {
 
                                                 let mut matches_in_file = vec![];
                                                 for (index, row) in packed_file.entries.iter().enumerate() {
@@ -1335,12 +1438,64 @@ pub fn background_loop(
                         }
                     }
 
+                    // In case we want to check the DB tables for missing locs...
+                    Commands::CheckMissingLocs => {
+                        match get_missing_locs(&mut pack_file_decoded) {
+                            Ok(missing_locs) => sender.send(Data::VecString(missing_locs)).unwrap(),
+                            Err(error) => sender.send(Data::Error(error)).unwrap(),
+                        }
+                    }
+
+                    // In case we want to create a Loc PackedFile with placeholders for the missing locs...
+                    Commands::CreateMissingLocsPackedFile => {
+                        let loc_keys = if let Data::VecString(data) = check_message_validity_recv(&receiver_data) { data } else { panic!(THREADS_MESSAGE_ERROR); };
+                        match create_missing_locs_packed_file(&mut pack_file_decoded, &loc_keys) {
+                            Ok(path) => sender.send(Data::VecString(path)).unwrap(),
+                            Err(error) => sender.send(Data::Error(error)).unwrap(),
+                        }
+                    }
+
+                    // In case we want to get the entries of all the Loc PackedFiles, lined up by key, for the Translation View...
+                    Commands::GetTranslationData => {
+                        match TranslationData::new(&mut pack_file_decoded) {
+                            Ok(data) => sender.send(Data::TranslationData(data)).unwrap(),
+                            Err(error) => sender.send(Data::Error(error)).unwrap(),
+                        }
+                    }
+
+                    // In case we want to save the edits from the Translation View to the Loc PackedFiles...
+                    Commands::SaveTranslationData => {
+                        let data = if let Data::TranslationData(data) = check_message_validity_recv(&receiver_data) { data } else { panic!(THREADS_MESSAGE_ERROR); };
+                        match data.save(&mut pack_file_decoded) {
+                            Ok(_) => sender.send(Data::Success).unwrap(),
+                            Err(error) => sender.send(Data::Error(error)).unwrap(),
+                        }
+                    }
+
+                    // In case we want to run a query over the DB Tables...
+                    Commands::RunQuery => {
+                        let query = if let Data::String(data) = check_message_validity_recv(&receiver_data) { data } else { panic!(THREADS_MESSAGE_ERROR); };
+                        match QueryResult::new(&query, &pack_file_decoded) {
+                            Ok(result) => sender.send(Data::QueryResult(result)).unwrap(),
+                            Err(error) => sender.send(Data::Error(error)).unwrap(),
+                        }
+                    }
+
+                    // In case we want to know what rows are going to be lost when merging DB or Loc Tables...
+                    Commands::GetMergeTablesPreview => {
+                        let (paths, table_types, strategy) = if let Data::VecVecStringBoolMergeStrategy(data) = check_message_validity_recv(&receiver_data) { data } else { panic!(THREADS_MESSAGE_ERROR); };
+                        match get_merge_tables_preview(&pack_file_decoded, &paths, table_types, strategy) {
+                            Ok(data) => sender.send(Data::VecString(data)).unwrap(),
+                            Err(error) => sender.send(Data::Error(error)).unwrap(),
+                        }
+                    }
+
                     // In case we want to merge DB or Loc Tables from a PackFile...
                     Commands::MergeTables => {
 
                         // Delete the PackedFiles from the PackFile, changing his return in case of success.
-                        let (paths, name, delete_source_files, table_types) = if let Data::VecVecStringStringBoolBool(data) = check_message_validity_recv(&receiver_data) { data } else { panic!(THREADS_MESSAGE_ERROR); };
-                        match merge_tables(&mut pack_file_decoded, &paths, &name, delete_source_files, table_types) {
+                        let (paths, name, delete_source_files, table_types, strategy) = if let Data::VecVecStringStringBoolBoolMergeStrategy(data) = check_message_validity_recv(&receiver_data) { data } else { panic!(THREADS_MESSAGE_ERROR); };
+                        match merge_tables(&mut pack_file_decoded, &paths, &name, delete_source_files, table_types, strategy) {
                             Ok(data) => sender.send(Data::VecStringVecPathType(data)).unwrap(),
                             Err(error) => sender.send(Data::Error(error)).unwrap(),
                         }
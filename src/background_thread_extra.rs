@@ -13,6 +13,7 @@
 
 use bincode::deserialize;
 
+use std::collections::BTreeMap;
 use std::fs::{File, DirBuilder};
 use std::io::{Read, Write};
 use std::path::PathBuf;
@@ -28,7 +29,7 @@ use crate::common::*;
 use crate::error::{Error, ErrorKind, Result};
 use crate::packfile::{PackFile, PFHVersion, PFHFileType, PathType};
 use crate::packfile::packedfile::PackedFile;
-use crate::packedfile::DecodedData;
+use crate::packedfile::{DecodeablePackedFileType, DecodedData, get_decoded_data_as_string, get_packed_file_type};
 use crate::packedfile::loc::Loc;
 use crate::packedfile::db::DB;
 use crate::packedfile::rigidmodel::RigidModel;
@@ -88,7 +89,7 @@ pub fn load_dependency_packfiles(dependencies: &[String]) -> Vec<PackedFile> {
                 for packed_file in pack_file.packed_files.iter() {
 
                     // If it's a Loc file...
-                    if !packed_file.path.is_empty() && packed_file.path.last().unwrap().ends_with(".loc") {
+                    if !packed_file.path.is_empty() && get_packed_file_type(&packed_file.path) == DecodeablePackedFileType::Loc {
 
                         // Clone the PackedFile, and add it to the list.
                         let mut packed_file = packed_file.clone();
@@ -130,7 +131,7 @@ pub fn load_dependency_packfiles(dependencies: &[String]) -> Vec<PackedFile> {
                         for packed_file in pack_file.packed_files.iter() {
 
                             // If it's a Loc file...
-                            if !packed_file.path.is_empty() && packed_file.path.last().unwrap().ends_with(".loc") {
+                            if !packed_file.path.is_empty() && get_packed_file_type(&packed_file.path) == DecodeablePackedFileType::Loc {
 
                                 // Clone the PackedFile, and add it to the list.
                                 let mut packed_file = packed_file.clone();
@@ -172,7 +173,7 @@ pub fn load_dependency_packfiles(dependencies: &[String]) -> Vec<PackedFile> {
                         for packed_file in pack_file.packed_files.iter() {
 
                             // If it's a Loc file...
-                            if !packed_file.path.is_empty() && packed_file.path.last().unwrap().ends_with(".loc") {
+                            if !packed_file.path.is_empty() && get_packed_file_type(&packed_file.path) == DecodeablePackedFileType::Loc {
 
                                 // Clone the PackedFile and add it to the PackedFiles List.
                                 let mut packed_file = packed_file.clone();
@@ -253,80 +254,229 @@ pub fn open_packfiles(
             } else { Err(ErrorKind::OpenPackFileInvalidExtension)?}
         }
 
-        // Get all the PackedFiles from each PackFile. First Boot type, then Release type, then Patch type, then Mod type, then Movie type.
-        let mut boot_files = vec![];
-        for pack_file in &pack_files {
-            if let PFHFileType::Boot = pack_file.pfh_file_type {
-                pack_file.packed_files.iter().for_each(|x| boot_files.push(x.clone()));
-            }
+        // For each path, get the PackedFile that the game will load.
+        for indexes in get_packed_files_load_order(&pack_files, ignore_mods).values() {
+            let (pack_file_index, packed_file_index) = indexes[0];
+            pack_file.packed_files.push(pack_files[pack_file_index].packed_files[packed_file_index].clone());
+        }
+
+        // Set it as type "Other(200)", so we can easely identify it as fake in other places.
+        // Used to lock the CA Files.
+        if lock_packfile_type {
+            pack_file.pfh_file_type = PFHFileType::Other(200);
+        }
+    
+        // Return the new PackedFiles list.
+        Ok(pack_file)
+    }
+}
+
+/// This function returns the priority the game gives to the PackedFiles of each PackFile Type in case of collision.
+/// Lower is better. PackFiles of types the game doesn't load (or mods, if we ignore them) return None.
+fn get_pfh_file_type_priority(pfh_file_type: PFHFileType, ignore_mods: bool) -> Option<u8> {
+    match pfh_file_type {
+        PFHFileType::Movie => Some(0),
+        PFHFileType::Mod => if ignore_mods { None } else { Some(1) },
+        PFHFileType::Patch => Some(2),
+        PFHFileType::Release => Some(3),
+        PFHFileType::Boot => Some(4),
+        PFHFileType::Other(_) => None,
+    }
+}
+
+/// This function returns, for every path in the provided PackFiles (in load order), the indexes of the PackFiles that
+/// contain it (and of the PackedFile inside them), sorted the way the game will use them. That means the first index
+/// is the PackFile the game will load that PackedFile from. The priority in case of collision is:
+/// - Same Type: First to come is the valid one.
+/// - Different Type: Movie, then Mod, then Patch, then Release, then Boot.
+pub fn get_packed_files_load_order(pack_files: &[PackFile], ignore_mods: bool) -> BTreeMap<Vec<String>, Vec<(usize, usize)>> {
+    let mut pack_files_sorted = pack_files.iter().enumerate()
+        .filter_map(|(index, pack_file)| get_pfh_file_type_priority(pack_file.pfh_file_type, ignore_mods).map(|priority| (priority, index)))
+        .collect::<Vec<(u8, usize)>>();
+    pack_files_sorted.sort();
+
+    let mut load_order = BTreeMap::new();
+    for (_, index) in pack_files_sorted {
+        for (packed_file_index, packed_file) in pack_files[index].packed_files.iter().enumerate() {
+            load_order.entry(packed_file.path.to_vec()).or_insert_with(Vec::new).push((index, packed_file_index));
         }
-        
-        let mut release_files = vec![];
-        for pack_file in &pack_files {
-            if let PFHFileType::Release = pack_file.pfh_file_type {
-                pack_file.packed_files.iter().for_each(|x| release_files.push(x.clone()));
+    }
+    load_order
+}
+
+/// This function checks the provided mod PackFiles for conflicts between them. It returns an HTML report with the PackedFiles
+/// overwritten by other PackFiles, and the rows of the DB/Loc Tables loaded from different PackFiles with the same key but different data.
+///
+/// The PackFiles are checked in the order the game loads the mods of the data folder: by their names, in alphabetical order.
+pub fn get_packfiles_conflicts(packs_paths: &[PathBuf]) -> Result<String> {
+
+    // Get the schema, or return an error.
+    let schema = match *SCHEMA.lock().unwrap() {
+        Some(ref schema) => schema.clone(),
+        None => return Err(ErrorKind::SchemaNotFound)?
+    };
+
+    let mut packs_paths = packs_paths.to_vec();
+    packs_paths.sort_by_key(|x| x.file_name().map(|x| x.to_string_lossy().to_string()));
+
+    let mut pack_files = vec![];
+    for path in &packs_paths {
+        if path.file_name().map_or(false, |x| x.to_string_lossy().ends_with(".pack")) {
+            pack_files.push(PackFile::read(path.to_path_buf(), true)?);
+        } else { Err(ErrorKind::OpenPackFileInvalidExtension)?}
+    }
+    let load_order_report = format!("<p>Load order: {}.</p>", pack_files.iter().map(|x| format!("<b>{}</b>", x.get_file_name())).collect::<Vec<String>>().join(", "));
+
+    // First, the PackedFiles that are in more than one PackFile.
+    let load_order = get_packed_files_load_order(&pack_files, false);
+    let mut overwritten_packed_files = vec![];
+    for (path, indexes) in load_order.iter().filter(|x| x.1.len() > 1) {
+        overwritten_packed_files.push(format!("<li><i>{}</i>: loaded from <b>{}</b>, overwriting {}.</li>",
+            path.join("/"),
+            pack_files[indexes[0].0].get_file_name(),
+            indexes[1..].iter().map(|x| pack_files[x.0].get_file_name()).collect::<Vec<String>>().join(", ")
+        ));
+    }
+
+    // Then, get all the rows of all the tables the game will load, grouped by table type and key.
+    // Overwritten tables don't get loaded, so they can't conflict.
+    // PackedFiles we cannot read are skipped, and reported so the user knows they were not checked.
+    let mut rows: BTreeMap<(String, String), Vec<(usize, Vec<String>, Vec<(String, String)>)>> = BTreeMap::new();
+    let mut unreadable_packed_files = vec![];
+    for (path, indexes) in &load_order {
+        let (pack_file_index, packed_file_index) = indexes[0];
+        let packed_file = &pack_files[pack_file_index].packed_files[packed_file_index];
+        let is_db = path.len() == 3 && path[0] == "db";
+        let is_loc = !is_db && get_packed_file_type(path) == DecodeablePackedFileType::Loc;
+        if !is_db && !is_loc { continue; }
+
+        let data = match packed_file.get_data() {
+            Ok(data) => data,
+            Err(error) => {
+                unreadable_packed_files.push(format!("<li><i>{}</i> from <b>{}</b>: {}</li>", path.join("/"), pack_files[pack_file_index].get_file_name(), error));
+                continue;
+            }
+        };
+
+        if is_db {
+            if let Ok(table) = DB::read(&data, &path[1], &schema) {
+                let key_columns = table.table_definition.fields.iter().enumerate().filter(|(_, x)| x.field_is_key).map(|(x, _)| x).collect::<Vec<usize>>();
+                if key_columns.is_empty() { continue; }
+                for row in &table.entries {
+                    let key = key_columns.iter().map(|x| get_decoded_data_as_string(&row[*x])).collect::<Vec<String>>().join("|");
+                    let data = table.table_definition.fields.iter().zip(row.iter()).map(|(x, y)| (x.field_name.to_owned(), get_decoded_data_as_string(y))).collect();
+                    rows.entry((path[1].to_owned(), key)).or_insert_with(Vec::new).push((pack_file_index, path.to_vec(), data));
+                }
             }
         }
 
-        let mut patch_files = vec![];
-        for pack_file in &pack_files {
-            if let PFHFileType::Patch = pack_file.pfh_file_type {
-                pack_file.packed_files.iter().for_each(|x| patch_files.push(x.clone()));
+        else if let Ok(table) = Loc::read(&data) {
+            for row in &table.entries {
+                let key = get_decoded_data_as_string(&row[0]);
+                let data = vec![("text".to_owned(), get_decoded_data_as_string(&row[1])), ("tooltip".to_owned(), get_decoded_data_as_string(&row[2]))];
+                rows.entry(("loc".to_owned(), key)).or_insert_with(Vec::new).push((pack_file_index, path.to_vec(), data));
             }
         }
+    }
 
-        let mut mod_files = vec![];
-        if !ignore_mods {
-            for pack_file in &pack_files {
-                if let PFHFileType::Mod = pack_file.pfh_file_type {
-                    pack_file.packed_files.iter().for_each(|x| mod_files.push(x.clone()));
+    // A row conflicts if it's in more than one PackFile, and the columns they have in common have different data.
+    let mut conflicting_rows = vec![];
+    for ((table_type, key), sources) in &rows {
+        if sources.iter().all(|x| x.0 == sources[0].0) { continue; }
+
+        let mut columns = vec![];
+        for (index, source) in sources.iter().enumerate() {
+            for other_source in &sources[index + 1..] {
+                if source.0 == other_source.0 { continue; }
+                for (column, data) in &source.2 {
+                    if let Some((_, other_data)) = other_source.2.iter().find(|x| &x.0 == column) {
+                        if data != other_data && !columns.contains(column) { columns.push(column.to_owned()); }
+                    }
                 }
             }
         }
 
-        let mut movie_files = vec![];
-        for pack_file in &pack_files {
-            if let PFHFileType::Movie = pack_file.pfh_file_type {
-                pack_file.packed_files.iter().for_each(|x| movie_files.push(x.clone()));
-            }
+        if !columns.is_empty() {
+            conflicting_rows.push(format!("<li><i>{}</i>, key <i>{}</i>: different <i>{}</i> in {}.</li>",
+                table_type,
+                key,
+                columns.join(", "),
+                sources.iter().map(|x| format!("<b>{}</b> ({})", pack_files[x.0].get_file_name(), x.1.join("/"))).collect::<Vec<String>>().join(", ")
+            ));
         }
+    }
 
-        // The priority in case of collision is:
-        // - Same Type: First to come is the valid one.
-        // - Different Type: Last to come is the valid one.
-        boot_files.sort_by_key(|x| x.path.to_vec());
-        boot_files.dedup_by_key(|x| x.path.to_vec());
+    let unreadable_report = if unreadable_packed_files.is_empty() { String::new() }
+    else { format!("<h3>Unreadable PackedFiles</h3><p>These PackedFiles couldn't be read, so their rows were not checked:</p><ul>{}</ul>", unreadable_packed_files.concat()) };
 
-        release_files.sort_by_key(|x| x.path.to_vec());
-        release_files.dedup_by_key(|x| x.path.to_vec());
+    if overwritten_packed_files.is_empty() && conflicting_rows.is_empty() { Ok(format!("{}<p>No conflicts detected.</p>{}", load_order_report, unreadable_report)) }
+    else {
+        Ok(format!("{}<h3>Overwritten PackedFiles</h3><ul>{}</ul><h3>Conflicting Rows</h3><ul>{}</ul>{}",
+            load_order_report,
+            overwritten_packed_files.concat(),
+            conflicting_rows.concat(),
+            unreadable_report
+        ))
+    }
+}
 
-        patch_files.sort_by_key(|x| x.path.to_vec());
-        patch_files.dedup_by_key(|x| x.path.to_vec());
+/// This function tries to decode every DB Table in the CA PackFiles of the Game Selected with the current schema. It returns an HTML
+/// report with the tables that failed to decode (with the position where the decoding stopped and the bytes left), the tables
+/// without a definition, and the tables that were decoded correctly. Useful to know what tables a new patch broke.
+pub fn validate_schema() -> Result<String> {
 
-        mod_files.sort_by_key(|x| x.path.to_vec());
-        mod_files.dedup_by_key(|x| x.path.to_vec());
+    let mut decoded = vec![];
+    let mut no_definition = vec![];
+    let mut failed = vec![];
 
-        movie_files.sort_by_key(|x| x.path.to_vec());
-        movie_files.dedup_by_key(|x| x.path.to_vec());
+    let packs_paths = get_game_selected_data_packfiles_paths().ok_or_else(|| Error::from(ErrorKind::GamePathNotConfigured))?;
+    if let Some(ref schema) = *SCHEMA.lock().unwrap() {
+        for pack_path in &packs_paths {
+            let pack_file = match open_packfiles(&[pack_path.to_path_buf()], true, true, false) {
+                Ok(pack_file) => pack_file,
+                Err(_) => continue,
+            };
 
-        pack_file.packed_files.append(&mut movie_files);
-        pack_file.packed_files.append(&mut mod_files);
-        pack_file.packed_files.append(&mut patch_files);
-        pack_file.packed_files.append(&mut release_files);
-        pack_file.packed_files.append(&mut boot_files);
+            // Mods are not part of the game, so we ignore them.
+            match pack_file.pfh_file_type {
+                PFHFileType::Boot | PFHFileType::Release | PFHFileType::Patch => {},
+                _ => continue,
+            }
 
-        pack_file.packed_files.sort_by_key(|x| x.path.to_vec());
-        pack_file.packed_files.dedup_by_key(|x| x.path.to_vec());
+            for packed_file in pack_file.packed_files.iter().filter(|x| x.path.len() == 3 && x.path[0] == "db") {
+                let path = format!("{}: {}", pack_file.get_file_name(), packed_file.path.join("/"));
+                let data = match packed_file.get_data() {
+                    Ok(data) => data,
+                    Err(error) => {
+                        failed.push(format!("<li>{}: {}</li>", path, error));
+                        continue;
+                    }
+                };
 
-        // Set it as type "Other(200)", so we can easely identify it as fake in other places.
-        // Used to lock the CA Files.
-        if lock_packfile_type {
-            pack_file.pfh_file_type = PFHFileType::Other(200);
+                let mut index = 0;
+                match DB::read_with_index(&data, &packed_file.path[1], &schema, &mut index) {
+                    Ok(table) => decoded.push(format!("<li>{} (v{})</li>", path, table.version)),
+                    Err(error) => match error.kind() {
+                        ErrorKind::SchemaTableDefinitionNotFound | ErrorKind::DBTableEmptyWithNoTableDefinition => {
+                            let version = DB::get_header_data(&data).map(|x| x.0.to_string()).unwrap_or_else(|_| "?".to_owned());
+                            no_definition.push(format!("<li>{} (v{})</li>", path, version));
+                        }
+                        _ => failed.push(format!("<li>{}: stopped at byte {}, with {} bytes left. {}</li>", path, index, data.len().saturating_sub(index), error)),
+                    }
+                }
+            }
         }
-    
-        // Return the new PackedFiles list.
-        Ok(pack_file)
     }
+    else { return Err(ErrorKind::SchemaNotFound)? }
+
+    Ok(format!("<p>{} tables decoded, {} without definition, {} failed.</p><h3>Failed</h3><ul>{}</ul><h3>No Definition</h3><ul>{}</ul><h3>Decoded</h3><ul>{}</ul>", 
+        decoded.len(),
+        no_definition.len(),
+        failed.len(),
+        failed.join(""),
+        no_definition.join(""),
+        decoded.join(""),
+    ))
 }
 
 /// This function is used to take an open PackFile, encode it and save it into the disk. We return
@@ -1118,7 +1268,7 @@ pub fn optimize_packfile(pack_file: &mut PackFile) -> Result<Vec<PathType>> {
     // Get a list of every Loc and DB PackedFiles in our dependency's files. For performance reasons, we decode every one of them here.
     // Otherwise, they may have to be decoded multiple times, making this function take ages to finish. 
     let game_locs = DEPENDENCY_DATABASE.lock().unwrap().iter()
-        .filter(|x| x.path.last().unwrap().ends_with(".loc"))
+        .filter(|x| get_packed_file_type(&x.path) == DecodeablePackedFileType::Loc)
         .map(|x| x.get_data())
         .filter(|x| x.is_ok())
         .map(|x| Loc::read(&x.unwrap()))
@@ -1126,7 +1276,7 @@ pub fn optimize_packfile(pack_file: &mut PackFile) -> Result<Vec<PathType>> {
         .map(|x| x.unwrap())
         .collect::<Vec<Loc>>();
 
-    let mut game_dbs = if let Some(ref schema) = *SCHEMA.lock().unwrap() {
+    let game_dbs = if let Some(ref schema) = *SCHEMA.lock().unwrap() {
         DEPENDENCY_DATABASE.lock().unwrap().iter()
             .filter(|x| x.path.len() == 3 && x.path[0] == "db")
             .map(|x| (x.get_data(), x.path[1].to_owned()))
@@ -1137,11 +1287,8 @@ pub fn optimize_packfile(pack_file: &mut PackFile) -> Result<Vec<PathType>> {
             .collect::<Vec<DB>>()
     } else { vec![] };
 
-    // Due to precision issues with float fields, we have to round every float field from the tables to 3 decimals max.
-    game_dbs.iter_mut().for_each(|x| x.entries.iter_mut()
-        .for_each(|x| x.iter_mut()
-        .for_each(|x| if let DecodedData::Float(data) = x { *data = (*data * 1000f32).round() / 1000f32 })
-    ));
+    // Due to precision issues with float fields, we compare them using the tolerance in the settings instead of rounding them.
+    let float_tolerance = SETTINGS.lock().unwrap().settings_string["optimize_float_tolerance"].parse::<f32>().unwrap_or(0.0);
 
     let database_path_list = DEPENDENCY_DATABASE.lock().unwrap().iter().map(|x| x.path.to_vec()).collect::<Vec<Vec<String>>>();
     for mut packed_file in &mut pack_file.packed_files {
@@ -1160,16 +1307,10 @@ pub fn optimize_packfile(pack_file: &mut PackFile) -> Result<Vec<PathType>> {
                     Err(_) => continue,
                 };
 
-                // We have to round our floats too.
-                optimized_table.entries.iter_mut()
-                    .for_each(|x| x.iter_mut()
-                    .for_each(|x| if let DecodedData::Float(data) = x { *data = (*data * 1000f32).round() / 1000f32 })
-                );
-
                 // For each vanilla DB Table that coincide with our own, compare it row by row, cell by cell, with our own DB Table. Then delete in reverse every coincidence.
                 for game_db in &game_dbs {
                     if game_db.db_type == optimized_table.db_type && game_db.version == optimized_table.version {
-                        let rows_to_delete = optimized_table.entries.iter().enumerate().filter(|(_, entry)| game_db.entries.contains(entry)).map(|(row, _)| row).collect::<Vec<usize>>();
+                        let rows_to_delete = optimized_table.entries.iter().enumerate().filter(|(_, entry)| game_db.entries.iter().any(|x| are_rows_equal(x, entry, float_tolerance))).map(|(row, _)| row).collect::<Vec<usize>>();
                         for row in rows_to_delete.iter().rev() {
                             optimized_table.entries.remove(*row);
                         } 
@@ -1188,7 +1329,7 @@ pub fn optimize_packfile(pack_file: &mut PackFile) -> Result<Vec<PathType>> {
         }
 
         // If it's a Loc PackedFile and there are some Locs in our dependencies...
-        else if packed_file.path.last().unwrap().ends_with(".loc") && !game_locs.is_empty() {
+        else if get_packed_file_type(&packed_file.path) == DecodeablePackedFileType::Loc && !game_locs.is_empty() {
 
             // Try to decode our Loc. If it's empty, skip it and continue with the next one.
             let mut optimized_loc = match Loc::read(&(packed_file.get_data_and_keep_it()?)) {
@@ -1224,3 +1365,11 @@ pub fn optimize_packfile(pack_file: &mut PackFile) -> Result<Vec<PathType>> {
     // Return the deleted file's types.
     Ok(deleted_files_type)
 }
+
+/// This function checks if two rows are equal, considering two floats equal if their difference is not bigger than the provided tolerance.
+pub fn are_rows_equal(row_a: &[DecodedData], row_b: &[DecodedData], float_tolerance: f32) -> bool {
+    row_a.len() == row_b.len() && row_a.iter().zip(row_b.iter()).all(|x| match x {
+        (DecodedData::Float(a), DecodedData::Float(b)) => (a - b).abs() <= float_tolerance,
+        (a, b) => a == b,
+    })
+}
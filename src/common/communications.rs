@@ -24,7 +24,13 @@ use crate::packedfile::*;
 use crate::packedfile::loc::*;
 use crate::packedfile::db::*;
 use crate::packedfile::rigidmodel::*;
+use crate::packedfile::loc::translation_files::TranslationFileFormat;
+use crate::packedfile::loc::translations::TranslationData;
+use crate::packedfile::query::QueryResult;
+use crate::packedfile::lua::LuaSyntaxError;
+use crate::packedfile::text::Text;
 use crate::schema::*;
+use crate::schema::assembly_kit::ImportSchemaReport;
 use crate::settings::*;
 use crate::settings::shortcuts::Shortcuts;
 use crate::updater::*;
@@ -44,6 +50,7 @@ pub enum Commands {
     SavePackFile,
     SavePackFileAs,
     LoadAllCAPackFiles,
+    CheckPackFilesConflicts,
     SetPackFileType,
     ChangeIndexIncludesTimestamp,
     ChangeDataIsCompressed,
@@ -55,6 +62,7 @@ pub enum Commands {
     IsThereASchema,
     PatchSiegeAI,
     UpdateSchemas,
+    UpdateSchemasFromPath,
     AddPackedFile,
     DeletePackedFile,
     ExtractPackedFile,
@@ -63,9 +71,15 @@ pub enum Commands {
     CreatePackedFile,
     GetPackFileDataForTreeView,
     GetPackFileExtraDataForTreeView,
+    GetPackedFileType,
+    GetDetectedPackedFileTypes,
     AddPackedFileFromPackFile,
     MassImportTSV,
+    ImportAssemblyKitTables,
     MassExportTSV,
+    MassImportTranslationFiles,
+    MassExportTranslationFiles,
+    ExportAssemblyKitTables,
     DecodePackedFileLoc,
     EncodePackedFileLoc,
     DecodePackedFileDB,
@@ -82,10 +96,17 @@ pub enum Commands {
     GetTableVersionFromDependencyPackFile,
     OptimizePackFile,
     GeneratePakFile,
+    ImportSchemaFromAssemblyKit,
+    ValidateSchema,
+    DiffSchemaFiles,
+    GenerateSchemaDocs,
     GetPackFilesList,
     SetPackFilesList,
     DecodeDependencyDB,
     CheckScriptWithKailua,
+    CheckLuaSyntax,
+    CheckLuaReferences,
+    CheckXMLFiles,
     GlobalSearch,
     UpdateGlobalSearchData,
     OpenWithExternalProgram,
@@ -93,6 +114,12 @@ pub enum Commands {
     ImportTSVPackedFile,
     ExportTSVPackedFile,
     CheckTables,
+    CheckMissingLocs,
+    GetTranslationData,
+    SaveTranslationData,
+    CreateMissingLocsPackedFile,
+    RunQuery,
+    GetMergeTablesPreview,
     MergeTables,
     GenerateSchemaDiff,
     GetNotes,
@@ -109,6 +136,7 @@ pub enum Data {
     Error(Error),
 
     Bool(bool),
+    BoolVecVecString((bool, Vec<Vec<String>>)),
     U32(u32),
     I32(i32),
     I64(i64),
@@ -118,6 +146,7 @@ pub enum Data {
     StringVecVecString((String, Vec<Vec<String>>)),
     PathBuf(PathBuf),
     PathBufI16((PathBuf, i16)),
+    PathBufPathBufPathBuf((PathBuf, PathBuf, PathBuf)),
     
     Settings(Settings),
     Shortcuts(Shortcuts),
@@ -141,6 +170,7 @@ pub enum Data {
     RigidModelVecString((RigidModel, Vec<String>)),
 
     PathType(PathType),
+    DecodeablePackedFileType(DecodeablePackedFileType),
 
     OptionStringVecPathBuf((Option<String>, Vec<PathBuf>)),
     StringI64VecVecString((String, i64, Vec<Vec<String>>)),
@@ -148,17 +178,26 @@ pub enum Data {
     VecPathBufVecVecString((Vec<PathBuf>, Vec<Vec<String>>)),
     VecString(Vec<String>),
     VecStringPackedFileType((Vec<String>, PackedFileType)),
-    VecVecStringStringBoolBool((Vec<Vec<String>>, String, bool, bool)),
+    VecVecStringBoolMergeStrategy((Vec<Vec<String>>, bool, MergeStrategy)),
+    VecVecStringStringBoolBoolMergeStrategy((Vec<Vec<String>>, String, bool, bool, MergeStrategy)),
     VecVecStringVecVecString((Vec<Vec<String>>, Vec<Vec<String>>)),
     VecGlobalMatch(Vec<GlobalMatch>),
     VersionsVersions((Versions, Versions)),
     VecPathTypeString(Vec<(PathType, String)>),
+    VecVecStringDecodeablePackedFileType(Vec<(Vec<String>, DecodeablePackedFileType)>),
     VecPathType(Vec<PathType>),
     VecStringVecPathType((Vec<String>, Vec<PathType>)),
     VecPathTypePathBuf((Vec<PathType>, PathBuf)),
     VecPathBuf(Vec<PathBuf>),
     TableDefinition(TableDefinition),
-    BTreeMapI32VecString(BTreeMap<i32, Vec<String>>),
+    BTreeMapI32BTreeMapStringString(BTreeMap<i32, BTreeMap<String, String>>),
+    QueryResult(QueryResult),
+    ImportSchemaReport(ImportSchemaReport),
+    TranslationData(TranslationData),
+    Text(Text),
+    TextVecString((Text, Vec<String>)),
+    VecLuaSyntaxError(Vec<LuaSyntaxError>),
+    VecPathTypePathBufTranslationFileFormat((Vec<PathType>, PathBuf, TranslationFileFormat)),
 }
 
 /// This functions serves as "message checker" for the communication between threads, for situations where we can hang the thread.
@@ -12,7 +12,28 @@
 // a update, as these are used all over the program and if one of them breaks, half of the program
 // will break with it.
 
-use crate::coding_helpers::*;
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::background_thread_extra::are_rows_equal;
+use crate::common::coding_helpers::*;
+use crate::error::ErrorKind;
+use crate::packedfile::{DecodeablePackedFileType, DecodedData, MergeStrategy, export_tsv, get_decoded_data_as_string, get_merged_tables_data, get_missing_locs_from_keys, import_tsv};
+use crate::packedfile::db::DB;
+use crate::packedfile::loc::Loc;
+use crate::packedfile::lua::{check_lua_syntax, get_script_string_arguments, get_unknown_script_references, parse_script_functions, LuaSyntaxError, ScriptReference};
+use crate::packedfile::loc::markup::{get_markup_errors, get_placeholders, MarkupTags};
+use crate::packedfile::loc::translations::{get_loc_texts_from_packed_files, TranslationData, TranslationStatus};
+use crate::packedfile::loc::translation_files::{escape_po_string, escape_xml_attribute, escape_xml_string, import_po, import_xliff, unescape_po_string};
+use crate::packedfile::query::QueryResult;
+use crate::packedfile::registry::{PackedFileMatcher, PackedFileTypeRule};
+use crate::packedfile::text::{Text, TextEncoding, TEXT_ENCODINGS};
+use crate::packedfile::xml::check_xml_files_with_assets;
+use crate::packfile::{PackFile, PathType};
+use crate::packfile::packedfile::PackedFile;
+use crate::schema::assembly_kit::{export_raw_tables_with_schema, import_raw_table};
+use crate::schema::docs::update_book_summary;
+use crate::schema::{Field, FieldDiff, FieldType, Schema, SchemaDiff, TableDefinition, TableDefinitionDiff, TableDefinitions};
+use crate::updater::{get_archive_schemas_files, is_valid_schema_file_name, read_tar, update_schemas, SchemaUpdateSource};
 
 //-----------------------------------------------------//
 //          Decoding helpers (Common decoders)
@@ -678,3 +699,1448 @@ fn test_encode_packedfile_optional_string_u16() {
     assert_eq!(encode_packedfile_optional_string_u16("Waha"), vec![1, 4, 0, 87, 0, 97, 0, 104, 0, 97, 0]);
     assert_eq!(encode_packedfile_optional_string_u16(""), vec![0]);
 }
+
+//-----------------------------------------------------//
+//                  Loc PackedFiles
+//-----------------------------------------------------//
+
+/// Strings that used to get corrupted when escaping them, or that can be problematic for the escape scheme of the Locs.
+const LOC_TEST_STRINGS: [&str; 10] = [
+    "",
+    "Normal text",
+    "Line one\nLine two",
+    "Tab\tseparated\r\n",
+    "Literal \\n and \\t, not real ones",
+    "Backslash at the end \\",
+    "\\\\double\\\\\n",
+    "[[col:red]]Unknown \\x escape[[/col]]",
+    "Non-ASCII: ñ, 漢字 and 🐉",
+    "\t\n\r\\",
+];
+
+/// This function builds the raw data of a Loc PackedFile with the provided entries, without using `Loc::save`.
+fn build_loc_data(entries: &[(&str, &str, bool)]) -> Vec<u8> {
+    let mut data = vec![0xFF, 0xFE];
+    data.extend_from_slice(b"LOC");
+    data.push(0);
+    data.extend_from_slice(&encode_integer_u32(1));
+    data.extend_from_slice(&encode_integer_u32(entries.len() as u32));
+    for (key, text, tooltip) in entries {
+        data.extend_from_slice(&encode_packedfile_string_u16(key));
+        data.extend_from_slice(&encode_packedfile_string_u16(text));
+        data.push(encode_bool(*tooltip));
+    }
+    data
+}
+
+/// Test to make sure a Loc PackedFile is saved exactly as it was read (`Loc::save(Loc::read(x)) == x`).
+#[test]
+fn test_loc_read_save_roundtrip() {
+
+    // Check a Loc with every problematic string as key and as text is saved back without changes.
+    let entries = LOC_TEST_STRINGS.iter().enumerate().map(|(index, x)| (*x, LOC_TEST_STRINGS[LOC_TEST_STRINGS.len() - 1 - index], index % 2 == 0)).collect::<Vec<(&str, &str, bool)>>();
+    let data = build_loc_data(&entries);
+    assert_eq!(Loc::read(&data).unwrap().save(), data);
+
+    // Check an empty Loc too.
+    let data = build_loc_data(&[]);
+    assert_eq!(Loc::read(&data).unwrap().save(), data);
+}
+
+/// Test to make sure the entries of a Loc PackedFile are stored unescaped in memory.
+#[test]
+fn test_loc_read_unescaped() {
+    let data = build_loc_data(&[("key\\n", "Line one\nLine two\tTabbed", true)]);
+    let loc = Loc::read(&data).unwrap();
+    assert_eq!(loc.entries[0], vec![
+        DecodedData::StringU16("key\\n".to_owned()),
+        DecodedData::StringU16("Line one\nLine two\tTabbed".to_owned()),
+        DecodedData::Boolean(true),
+    ]);
+}
+
+/// Test to make sure the escaping of the Loc text (`Loc::escape_text()` and `Loc::unescape_text()`) works properly.
+#[test]
+fn test_loc_escape_text() {
+
+    // Check every string gets back to the original one after escaping and unescaping it, and that the escaped one is single-line.
+    for string in LOC_TEST_STRINGS.iter() {
+        let escaped = Loc::escape_text(string);
+        assert_eq!(escaped.contains('\n') || escaped.contains('\t') || escaped.contains('\r'), false);
+        assert_eq!(Loc::unescape_text(&escaped), *string);
+    }
+
+    // Check the escaped text is what we expect.
+    assert_eq!(Loc::escape_text("a\\nb\nc"), "a\\\\nb\\nc");
+
+    // Check unknown escape sequences and lone backslashes are kept when unescaping.
+    assert_eq!(Loc::unescape_text("C:\\folder\\x"), "C:\\folder\\x");
+    assert_eq!(Loc::unescape_text("end\\"), "end\\");
+}
+
+//-----------------------------------------------------//
+//                  Loc Markup
+//-----------------------------------------------------//
+
+/// Test to make sure the known tags (`MarkupTags::new()`) include the ones used in the vanilla texts.
+#[test]
+fn test_markup_tags() {
+    let mut vanilla_texts = BTreeMap::new();
+    vanilla_texts.insert("key_1".to_owned(), "[[new_paired:a]]text[[/new_paired]]".to_owned());
+    vanilla_texts.insert("key_2".to_owned(), "[[new_standalone:a]] and [[img:a]]text[[/img]]".to_owned());
+
+    // Check the tags always closed in vanilla are paired, the rest standalone, and our own lists are kept.
+    let tags = MarkupTags::new(&vanilla_texts);
+    assert_eq!(tags.paired.contains("new_paired"), true);
+    assert_eq!(tags.paired.contains("col"), true);
+    assert_eq!(tags.standalone.contains("new_standalone"), true);
+    assert_eq!(tags.standalone.contains("img"), true);
+    assert_eq!(tags.are_complete, true);
+
+    // Check that without vanilla texts we don't know if our lists are complete.
+    assert_eq!(MarkupTags::new(&BTreeMap::new()).are_complete, false);
+}
+
+/// Test to make sure the markup errors (`get_markup_errors()`) of tags and `{{...}}` blocks are detected properly.
+#[test]
+fn test_get_markup_errors() {
+    let mut vanilla_texts = BTreeMap::new();
+    vanilla_texts.insert("key".to_owned(), "[[custom:a]]".to_owned());
+    let tags = MarkupTags::new(&vanilla_texts);
+
+    // Check valid texts have no errors.
+    assert_eq!(get_markup_errors("Plain text", &tags), Vec::<String>::new());
+    assert_eq!(get_markup_errors("[[col:red]]Red [[b]]bold[[/b]][[/col]] [[img:icon_money]]", &tags), Vec::<String>::new());
+    assert_eq!(get_markup_errors("[[sl:unit]]Unit[[/sl]] [[sl_tooltip:x]]Tip[[/sl_tooltip]] [[custom:a]]", &tags), Vec::<String>::new());
+    assert_eq!(get_markup_errors("{{tr:key}} and {{ a }}", &tags), Vec::<String>::new());
+
+    // Check broken tags are reported.
+    assert_eq!(get_markup_errors("[[unknown]]", &tags), vec!["Unknown tag: [[unknown]]".to_owned()]);
+    assert_eq!(get_markup_errors("[[col:red]]text", &tags), vec!["Tag not closed: [[col]]".to_owned()]);
+    assert_eq!(get_markup_errors("text[[/col]]", &tags), vec!["Closing tag without opening tag: [[/col]]".to_owned()]);
+    assert_eq!(get_markup_errors("[[col:red]][[b]]text[[/col]][[/b]]", &tags), vec![
+        "Tag [[b]] closed after [[/col]].".to_owned(),
+        "Closing tag without opening tag: [[/b]]".to_owned(),
+    ]);
+    assert_eq!(get_markup_errors("[[col:red]text", &tags), vec!["Tag with missing brackets.".to_owned()]);
+
+    // Check broken blocks are reported.
+    assert_eq!(get_markup_errors("{{}}", &tags), vec!["Empty {{}} block.".to_owned()]);
+    assert_eq!(get_markup_errors("{{tr: }}", &tags), vec!["{{tr:}} block without loc key.".to_owned()]);
+    assert_eq!(get_markup_errors("{{tr:key}", &tags), vec!["{{...}} block with missing braces.".to_owned()]);
+
+    // Check unknown tags are not reported if we don't have the vanilla tags.
+    assert_eq!(get_markup_errors("[[unknown]]", &MarkupTags::new(&BTreeMap::new())), Vec::<String>::new());
+}
+
+/// Test to make sure the placeholders (`get_placeholders()`) of a text are counted properly.
+#[test]
+fn test_get_placeholders() {
+    let placeholders = get_placeholders("%s has %d units, %s has %.2f%% of %5d. Not: %%d");
+    assert_eq!(placeholders.into_iter().collect::<Vec<(String, usize)>>(), vec![
+        ("%.2f".to_owned(), 1),
+        ("%5d".to_owned(), 1),
+        ("%d".to_owned(), 1),
+        ("%s".to_owned(), 2),
+    ]);
+    assert_eq!(get_placeholders("No placeholders, 100%").is_empty(), true);
+}
+
+//-----------------------------------------------------//
+//                  Translation Files
+//-----------------------------------------------------//
+
+/// Test to make sure PO files (`import_po()`) are parsed properly.
+#[test]
+fn test_import_po() {
+    let file = r#"# Loc PackedFile exported with RPFM.
+msgid ""
+msgstr ""
+"Content-Type: text/plain; charset=UTF-8\n"
+"X-RPFM-Loc-Path: text/db/my_mod.loc\n"
+
+#. tooltip: false
+msgctxt "key_1"
+msgid "Source"
+msgstr "Translated"
+
+#. tooltip: true
+msgctxt "key_2"
+msgid "Split"
+msgstr ""
+"in two "
+"lines"
+
+msgctxt "key_3"
+msgid "Untranslated"
+msgstr ""
+
+#, fuzzy, c-format
+msgctxt "key_4"
+msgid "Fuzzy"
+msgstr "Not reviewed"
+
+msgid "key_5"
+msgstr "No context"
+
+#~ msgctxt "key_6"
+#~ msgid "Obsolete"
+#~ msgstr "Obsolete"
+"#;
+
+    // Check the path, the keys, the texts and the tooltips are read, and the untranslated, fuzzy and obsolete entries are ignored.
+    let (loc_path, entries) = import_po(file).unwrap();
+    assert_eq!(loc_path, Some(vec!["text".to_owned(), "db".to_owned(), "my_mod.loc".to_owned()]));
+    assert_eq!(entries.iter().map(|x| (x.key.as_str(), x.text.as_str(), x.tooltip)).collect::<Vec<(&str, &str, Option<bool>)>>(), vec![
+        ("key_1", "Translated", Some(false)),
+        ("key_2", "in two lines", Some(true)),
+        ("key_5", "No context", None),
+    ]);
+
+    // Check broken lines are reported with their line number, starting from 1.
+    assert_eq!(import_po("msgid \"a\"\nmsgstr \"b\"\nbroken").unwrap_err().kind(), ErrorKind::ImportTranslationFileIncorrectLine(3));
+    assert_eq!(import_po("msgid \"a\"\nmsgstr \"b").unwrap_err().kind(), ErrorKind::ImportTranslationFileIncorrectLine(2));
+}
+
+/// Test to make sure XLIFF files (`import_xliff()`) are parsed properly.
+#[test]
+fn test_import_xliff() {
+    let file = r#"<?xml version="1.0" encoding="UTF-8"?>
+<xliff version="1.2" xmlns="urn:oasis:names:tc:xliff:document:1.2">
+  <file source-language="en" datatype="plaintext" original="text/db/my_mod.loc">
+    <body>
+      <trans-unit resname="key_1" id="key_1">
+        <source>Source</source>
+        <target state="translated">Translated &amp; &lt;escaped&gt; &#169;</target>
+        <note from="RPFM">tooltip: false</note>
+      </trans-unit>
+      <trans-unit id='key_2'>
+        <source>Source</source>
+        <target><![CDATA[<b>CDATA</b> & text]]></target>
+        <alt-trans><target>Alternative</target></alt-trans>
+      </trans-unit>
+      <trans-unit id="key_3">
+        <source>Source</source>
+        <target>With <g id="1">inline</g> tags</target>
+      </trans-unit>
+      <trans-unit id="key_4">
+        <source>Untranslated</source>
+        <target/>
+      </trans-unit>
+    </body>
+  </file>
+  <file original="other.xml">
+    <body>
+      <trans-unit id="key_5">
+        <source>Source</source>
+        <target>Without Loc path</target>
+      </trans-unit>
+    </body>
+  </file>
+</xliff>
+"#;
+
+    // Check every file is read with his path, and the entities, CDATA sections and inline tags are resolved.
+    let files = import_xliff(file).unwrap();
+    assert_eq!(files.len(), 2);
+    assert_eq!(files[0].0, Some(vec!["text".to_owned(), "db".to_owned(), "my_mod.loc".to_owned()]));
+    assert_eq!(files[0].1.iter().map(|x| (x.key.as_str(), x.text.as_str(), x.tooltip)).collect::<Vec<(&str, &str, Option<bool>)>>(), vec![
+        ("key_1", "Translated & <escaped> ©", Some(false)),
+        ("key_2", "<b>CDATA</b> & text", None),
+        ("key_3", "With inline tags", None),
+    ]);
+    assert_eq!(files[1].0, None);
+    assert_eq!(files[1].1[0].text, "Without Loc path");
+
+    // Check broken files are reported with their line number, starting from 1, and files without XLIFF content fail.
+    assert_eq!(import_xliff("<xliff>\n<file>\n</xliff>").unwrap_err().kind(), ErrorKind::ImportTranslationFileIncorrectLine(3));
+    assert_eq!(import_xliff("<html></html>").unwrap_err().kind(), ErrorKind::ImportTranslationFileUnknownFormat);
+}
+
+/// Test to make sure the texts of the Locs get back unchanged after escaping them in translation files and importing them back.
+#[test]
+fn test_translation_files_escape_roundtrip() {
+    for string in LOC_TEST_STRINGS.iter() {
+        let po_string = escape_po_string(string);
+        assert_eq!(po_string.contains('\n') || po_string.contains('\r'), false);
+        assert_eq!(unescape_po_string(&format!("\"{}\"", po_string)).unwrap(), *string);
+
+        // Empty texts are not imported, so we only check the rest through the parsers.
+        if !string.is_empty() {
+            let po = format!("msgctxt \"{0}\"\nmsgid \"{0}\"\nmsgstr \"{0}\"\n", po_string);
+            let (_, entries) = import_po(&po).unwrap();
+            assert_eq!(entries[0].key, *string);
+            assert_eq!(entries[0].text, *string);
+
+            let xliff = format!("<xliff><file original=\"a.loc\"><body><trans-unit id=\"{0}\" xml:space=\"preserve\"><target>{1}</target></trans-unit></body></file></xliff>", escape_xml_attribute(string), escape_xml_string(string));
+            let files = import_xliff(&xliff).unwrap();
+            assert_eq!(files[0].1[0].key, *string);
+            assert_eq!(files[0].1[0].text, *string);
+        }
+    }
+}
+
+//-----------------------------------------------------//
+//                  Translations
+//-----------------------------------------------------//
+
+/// This function builds the PackFile used in the translation tests, with an english and a french Loc PackedFile, and a PackedFile that is not a Loc.
+fn build_translation_pack_file() -> PackFile {
+    let mut pack_file = PackFile::new();
+    pack_file.packed_files.push(PackedFile::read_from_vec(vec!["text".to_owned(), "db".to_owned(), "mod_en.loc".to_owned()], 0, false, build_loc_data(&[("k1", "Hello", true), ("k2", "Sword", true)])));
+    pack_file.packed_files.push(PackedFile::read_from_vec(vec!["text".to_owned(), "db".to_owned(), "mod_fr.loc".to_owned()], 0, false, build_loc_data(&[("k1", "Bonjour", true), ("k3", "Extra", false)])));
+    pack_file.packed_files.push(PackedFile::read_from_vec(vec!["script".to_owned(), "mod.lua".to_owned()], 0, false, b"k1".to_vec()));
+    pack_file
+}
+
+/// Test to make sure the vanilla text is taken only from the Loc PackedFiles provided.
+#[test]
+fn test_get_loc_texts_from_packed_files() {
+    let pack_file = build_translation_pack_file();
+    let texts = get_loc_texts_from_packed_files(&pack_file.packed_files);
+    assert_eq!(texts.into_iter().collect::<Vec<(String, String)>>(), vec![
+        ("k1".to_owned(), "Bonjour".to_owned()),
+        ("k2".to_owned(), "Sword".to_owned()),
+        ("k3".to_owned(), "Extra".to_owned()),
+    ]);
+}
+
+/// Test to make sure the entries of the Loc PackedFiles are lined up by key, with their vanilla text and the vanilla text they were translated from.
+#[test]
+fn test_translation_data_new() {
+    let mut pack_file = build_translation_pack_file();
+    let mut vanilla_texts = BTreeMap::new();
+    vanilla_texts.insert("k1".to_owned(), "Hello".to_owned());
+    vanilla_texts.insert("k2".to_owned(), "Sword (old)".to_owned());
+    let mut memory = BTreeMap::new();
+    memory.insert("text\\db\\mod_fr.loc".to_owned(), vec![("k1".to_owned(), "Hi".to_owned())].into_iter().collect::<BTreeMap<String, String>>());
+
+    let data = TranslationData::new_from_data(&mut pack_file, &vanilla_texts, &memory).unwrap();
+    assert_eq!(data.locs.iter().map(|x| x.0.join("/")).collect::<Vec<String>>(), vec!["text/db/mod_en.loc", "text/db/mod_fr.loc"]);
+    assert_eq!(data.entries.iter().map(|x| &*x.key).collect::<Vec<&str>>(), vec!["k1", "k2", "k3"]);
+
+    // Texts we don't know the origin of are considered translated from the current vanilla text.
+    assert_eq!(data.entries[0].vanilla_text, Some("Hello".to_owned()));
+    assert_eq!(data.entries[0].texts, vec![Some("Hello".to_owned()), Some("Bonjour".to_owned())]);
+    assert_eq!(data.entries[0].translated_from, vec![Some("Hello".to_owned()), Some("Hi".to_owned())]);
+    assert_eq!(data.entries[1].texts, vec![Some("Sword".to_owned()), None]);
+    assert_eq!(data.entries[1].translated_from, vec![Some("Sword (old)".to_owned()), None]);
+    assert_eq!(data.entries[2].vanilla_text, None);
+    assert_eq!(data.entries[2].translated_from, vec![None, None]);
+
+    let french = Some("fr".to_owned());
+    assert_eq!(data.entries[0].get_status(0, &Some("en".to_owned())), TranslationStatus::Translated);
+    assert_eq!(data.entries[0].get_status(1, &french), TranslationStatus::Stale);
+    assert_eq!(data.entries[1].get_status(1, &french), TranslationStatus::Missing);
+    assert_eq!(data.entries[2].get_status(1, &french), TranslationStatus::Translated);
+}
+
+/// Test to make sure saving the translations updates and adds the entries of every Loc PackedFile, and that the vanilla text they were
+/// translated from is remembered, so they're reported as stale when it changes.
+#[test]
+fn test_translation_data_save_memory_roundtrip() {
+    let mut pack_file = build_translation_pack_file();
+    let mut vanilla_texts = BTreeMap::new();
+    vanilla_texts.insert("k1".to_owned(), "Hello".to_owned());
+    vanilla_texts.insert("k2".to_owned(), "Sword".to_owned());
+    let mut data = TranslationData::new_from_data(&mut pack_file, &vanilla_texts, &BTreeMap::new()).unwrap();
+
+    // Translate `k1` again and `k2` for the first time in the french Loc, like the Translation View does.
+    data.entries[0].texts[1] = Some("Salut".to_owned());
+    data.entries[0].translated_from[1] = data.entries[0].vanilla_text.clone();
+    data.entries[1].texts[1] = Some("Épée".to_owned());
+    data.entries[1].translated_from[1] = data.entries[1].vanilla_text.clone();
+
+    let mut memory = BTreeMap::new();
+    data.save_with_memory(&mut pack_file, &mut memory).unwrap();
+
+    // Every Loc gets saved: the english one without changes, and the french one with the existing entry updated in place and the new one at the end.
+    assert_eq!(pack_file.packed_files[0].get_data().unwrap(), build_loc_data(&[("k1", "Hello", true), ("k2", "Sword", true)]));
+    assert_eq!(pack_file.packed_files[1].get_data().unwrap(), build_loc_data(&[("k1", "Salut", true), ("k3", "Extra", false), ("k2", "Épée", true)]));
+    assert_eq!(memory["text\\db\\mod_en.loc"].len(), 2);
+    assert_eq!(memory["text\\db\\mod_fr.loc"].iter().map(|(x, y)| (&**x, &**y)).collect::<Vec<(&str, &str)>>(), vec![("k1", "Hello"), ("k2", "Sword")]);
+
+    // After the vanilla text changes, the translations made from the old one are stale, and the rest are still translated.
+    vanilla_texts.insert("k1".to_owned(), "Hello there".to_owned());
+    let data = TranslationData::new_from_data(&mut pack_file, &vanilla_texts, &memory).unwrap();
+    let french = Some("fr".to_owned());
+    assert_eq!(data.entries[0].texts[1], Some("Salut".to_owned()));
+    assert_eq!(data.entries[0].translated_from[1], Some("Hello".to_owned()));
+    assert_eq!(data.entries[0].get_status(1, &french), TranslationStatus::Stale);
+    assert_eq!(data.entries[1].get_status(1, &french), TranslationStatus::Translated);
+    assert_eq!(data.entries[2].get_status(1, &french), TranslationStatus::Translated);
+}
+
+//-----------------------------------------------------//
+//                  PackedFile Types
+//-----------------------------------------------------//
+
+/// Test to make sure DB rules of the registry only apply to paths with a table folder, and the other rules to any path they match.
+#[test]
+fn test_packed_file_type_rule_is_match() {
+    let path = |x: &str| x.split('/').map(|x| x.to_owned()).collect::<Vec<String>>();
+    let db_rule = PackedFileTypeRule { matcher: PackedFileMatcher::new(".bin").unwrap(), packed_file_type: DecodeablePackedFileType::DB, encoding: None };
+    assert!(db_rule.is_match(&path("db/units_tables/data.bin")));
+    assert!(db_rule.is_match(&path("custom/units_tables/data.bin")));
+    assert!(!db_rule.is_match(&path("data.bin")));
+    assert!(!db_rule.is_match(&path("db/data.bin")));
+    assert!(!db_rule.is_match(&path("db/units_tables/data.txt")));
+
+    let db_folder_rule = PackedFileTypeRule { matcher: PackedFileMatcher::new("db").unwrap(), packed_file_type: DecodeablePackedFileType::DB, encoding: None };
+    assert!(db_folder_rule.is_match(&path("db/units_tables/data")));
+    assert!(!db_folder_rule.is_match(&path("db")));
+
+    let text_rule = PackedFileTypeRule { matcher: PackedFileMatcher::new(".LUA").unwrap(), packed_file_type: DecodeablePackedFileType::Text, encoding: None };
+    assert!(text_rule.is_match(&path("script.lua")));
+    assert!(text_rule.is_match(&path("script/campaign/mod/Script.LUA")));
+    assert!(!text_rule.is_match(&path("script/campaign/mod/script.lua.bak")));
+}
+
+//-----------------------------------------------------//
+//                  Text PackedFiles
+//-----------------------------------------------------//
+
+/// Test to make sure a Text PackedFile is read back with the same text and encoding it was saved with (`Text::read(Text::save(x)) == x`).
+#[test]
+fn test_text_read_save_roundtrip() {
+    let texts = ["Plain ASCII text\r\nwith two lines", "Texto en español: ñ, á, ü", "Русский текст\nи 漢字 🐉"];
+    for encoding in TEXT_ENCODINGS.iter() {
+        for contents in texts.iter() {
+            let text = Text { contents: (*contents).to_owned(), encoding: *encoding };
+
+            // ISO-8859-1 cannot encode every character, so texts with unsupported characters must fail to save.
+            match text.save() {
+                Ok(data) => {
+                    let text = Text::read(&data).unwrap();
+                    assert_eq!(text.contents, *contents);
+
+                    // ASCII text without BOM is valid UTF-8 too, so it cannot be detected as ISO-8859-1.
+                    if *encoding == TextEncoding::Iso8859_1 && contents.is_ascii() { assert_eq!(text.encoding, TextEncoding::Utf8); }
+                    else { assert_eq!(text.encoding, *encoding); }
+                }
+                Err(_) => assert_eq!((*encoding, contents.chars().all(|x| (x as u32) < 256)), (TextEncoding::Iso8859_1, false)),
+            }
+        }
+    }
+}
+
+/// Test to make sure the encoding of Text PackedFiles without BOM (`Text::read()`) is detected properly.
+#[test]
+fn test_text_read_encoding_detection() {
+
+    // Check UTF-16 LE text without BOM is detected even if it has no ASCII characters.
+    let data = "Привет, мир".encode_utf16().flat_map(|x| x.to_le_bytes().to_vec()).collect::<Vec<u8>>();
+    assert_eq!(Text::read(&data).unwrap().encoding, TextEncoding::Utf16Le);
+    let data = "漢字のテキストです。".encode_utf16().flat_map(|x| x.to_le_bytes().to_vec()).collect::<Vec<u8>>();
+    assert_eq!(Text::read(&data).unwrap().encoding, TextEncoding::Utf16Le);
+
+    // Check even-length UTF-8 text is not detected as UTF-16.
+    assert_eq!(Text::read(b"abcd").unwrap().encoding, TextEncoding::Utf8);
+    assert_eq!(Text::read("ñandú".as_bytes()).unwrap().encoding, TextEncoding::Utf8);
+
+    // Check text that is neither UTF-8 nor UTF-16 is detected as ISO-8859-1.
+    let text = Text::read(&[0x45, 0x73, 0x70, 0x61, 0xF1, 0x61]).unwrap();
+    assert_eq!((text.contents.as_str(), text.encoding), ("España", TextEncoding::Iso8859_1));
+}
+
+//-----------------------------------------------------//
+//                  XML PackedFiles
+//-----------------------------------------------------//
+
+/// This function builds a PackedFile with the provided path and text.
+fn build_text_packed_file(path: &str, text: &str) -> PackedFile {
+    PackedFile::read_from_vec(path.split('/').map(|x| x.to_owned()).collect(), 0, false, text.as_bytes().to_vec())
+}
+
+/// Test to make sure malformed XML PackedFiles are reported with the line of the error, and that files that are not XML are ignored.
+#[test]
+fn test_check_xml_files_parse_errors() {
+    let mut pack_file = PackFile::new();
+    pack_file.packed_files.push(build_text_packed_file("ui/broken.xml", "<root>\n<a>\n</b>\n</root>"));
+    pack_file.packed_files.push(build_text_packed_file("ui/valid.xml", "<root>\n<a>text</a>\n</root>"));
+    pack_file.packed_files.push(build_text_packed_file("ui/not_xml.xml", "Just some text </b>"));
+
+    // Without references, we don't need the assets of the game.
+    let errors = check_xml_files_with_assets(&mut pack_file, &mut None, || unreachable!()).unwrap();
+    assert_eq!(errors.len(), 1);
+    assert!(errors[0].starts_with("ui/broken.xml:3:"), "{}", errors[0]);
+}
+
+/// Test to make sure the assets referenced by XML PackedFiles are searched in the PackFile and in the assets of the game, ignoring case and separators.
+#[test]
+fn test_check_xml_files_missing_assets() {
+    let mut pack_file = PackFile::new();
+    pack_file.packed_files.push(build_text_packed_file("variantmeshes/unit.variantmeshdefinition", "<VARIANT_MESH>
+<SLOT name=\"body\">
+<VARIANT_MESH model=\"VariantMeshes/Body/In_Pack.rigid_model_v2\"/>
+<VARIANT_MESH model=\"variantmeshes\\body\\in_data.rigid_model_v2\"/>
+<VARIANT_MESH model=\"variantmeshes/body/missing.rigid_model_v2\"/>
+<texture>variantmeshes/body/missing.dds</texture>
+<name>not/an_asset.txt</name>
+</SLOT>
+</VARIANT_MESH>
+"));
+    pack_file.packed_files.push(PackedFile::read_from_vec(vec!["variantmeshes".to_owned(), "body".to_owned(), "in_pack.rigid_model_v2".to_owned()], 0, false, vec![]));
+
+    let mut data_assets = BTreeSet::new();
+    data_assets.insert("variantmeshes/body/in_data.rigid_model_v2".to_owned());
+    let mut data_assets = Some(data_assets);
+    assert_eq!(check_xml_files_with_assets(&mut pack_file, &mut data_assets, || unreachable!()).unwrap(), vec![
+        "variantmeshes/unit.variantmeshdefinition:5: 'variantmeshes/body/missing.rigid_model_v2' is neither in this PackFile nor in the game's data PackFiles.".to_owned(),
+        "variantmeshes/unit.variantmeshdefinition:6: 'variantmeshes/body/missing.dds' is neither in this PackFile nor in the game's data PackFiles.".to_owned(),
+    ]);
+
+    // The assets of the game are only got when needed, and if we cannot get them, missing assets are not reported.
+    let mut data_assets = None;
+    assert!(check_xml_files_with_assets(&mut pack_file, &mut data_assets, || None).unwrap().is_empty());
+}
+
+//-----------------------------------------------------//
+//                  Lua Scripts
+//-----------------------------------------------------//
+
+/// Test to make sure valid Lua 5.1 scripts pass the syntax check (`check_lua_syntax()`).
+#[test]
+fn test_check_lua_syntax_valid() {
+    let scripts = [
+        "",
+        "#!/usr/bin/lua\nprint(1)",
+        "local x, y = 1, 2\nprint(x + y * 2 ^ -3 .. 'text')",
+        "function a.b.c:d(e, ...) local t = {...} return #t end",
+        "local f = function(...) return select('#', ...) end; f(1, 2)",
+        "local t = { 1, 2; x = 3, ['y'] = 4, { nested = true }, }",
+        "for i = 1, 10, 2 do if i > 5 then break elseif i == 3 then else end end",
+        "for k, v in pairs(t) do while k do repeat k = nil until not k end end",
+        "do local s = [==[long\nstring]] with ]==] --[[ long\ncomment ]] end",
+        "x = \"escapes \\\" \\n \\065 \\\n continue\"",
+        "x = 1e+5 + .5 + 3.25E-2 + 0xFF + 0x1e+5",
+        "f{1, 2} f'string' f\"string\" obj:method{} obj:method'x'",
+        "return",
+    ];
+
+    for script in scripts.iter() {
+        assert_eq!(check_lua_syntax(script), Ok(()), "{}", script);
+    }
+
+    // Check deeply nested scripts are fine, as long as they're under the limit.
+    assert_eq!(check_lua_syntax(&format!("x = {}1{}", "(".repeat(150), ")".repeat(150))), Ok(()));
+}
+
+/// Test to make sure the syntax errors of Lua scripts (`check_lua_syntax()`) are the ones Lua 5.1 gives, in the right line and column.
+#[test]
+fn test_check_lua_syntax_errors() {
+    let scripts = [
+        ("x = ", 1, 5, "unexpected symbol near '<eof>'"),
+        ("if x then\n  print(1)\n", 3, 1, "'end' expected (to close 'if' at line 1) near '<eof>'"),
+        ("if x then print(1)", 1, 19, "'end' expected near '<eof>'"),
+        ("local s = 'abc\nprint(s)", 1, 11, "unfinished string near ''abc'"),
+        ("x = 3..2", 1, 5, "malformed number near '3..2'"),
+        ("x = 0x1g", 1, 5, "malformed number near '0x1g'"),
+        ("f()\n(g)()", 2, 1, "ambiguous syntax (function call x new statement) near '('"),
+        ("function f() return ... end", 1, 21, "cannot use '...' outside a vararg function near '...'"),
+        ("x = 1 1", 1, 7, "unexpected symbol near '1'"),
+        ("x.y", 1, 4, "syntax error near '<eof>'"),
+        ("return 1\nx = 2", 2, 1, "'<eof>' expected near 'x'"),
+        ("--[[ unfinished\ncomment", 1, 1, "unfinished long comment near '<eof>'"),
+        ("x = @", 1, 5, "unexpected symbol near '@'"),
+    ];
+
+    for (script, line, column, message) in scripts.iter() {
+        assert_eq!(check_lua_syntax(script), Err(LuaSyntaxError { path: vec![], line: *line, column: *column, message: (*message).to_owned() }), "{}", script);
+    }
+
+    // Check scripts nested too deep fail instead of overflowing the stack.
+    let error = check_lua_syntax(&format!("x = {}1{}", "(".repeat(250), ")".repeat(250))).unwrap_err();
+    assert_eq!((error.line, error.message.as_str()), (1, "chunk has too many syntax levels"));
+    let error = check_lua_syntax(&"do ".repeat(250)).unwrap_err();
+    assert_eq!((error.line, error.message.as_str()), (1, "chunk has too many syntax levels"));
+}
+
+/// Test to make sure the string literals passed to functions in Lua scripts (`get_script_string_arguments()`) are found properly.
+#[test]
+fn test_get_script_string_arguments() {
+    let script = "cm:apply_effect_bundle(\"bundle_a\", faction_var, 5)\nx = f(g('a'), \"b\", 'c' .. d, [[long]], \"esc\\\\n\", \"\")";
+    assert_eq!(get_script_string_arguments(script), Some(vec![
+        ("apply_effect_bundle".to_owned(), 0, "bundle_a".to_owned(), 1),
+        ("f".to_owned(), 1, "b".to_owned(), 2),
+        ("g".to_owned(), 0, "a".to_owned(), 2),
+    ]));
+
+    // Check scripts that cannot be split into tokens return nothing.
+    assert_eq!(get_script_string_arguments("f('unfinished"), None);
+}
+
+/// Test to make sure only the parameters we know that reference keys are checked in Lua scripts (`parse_script_functions()`
+/// and `get_unknown_script_references()`).
+#[test]
+fn test_get_unknown_script_references() {
+    let types_file = "
+        --# assume CM.apply_effect_bundle: method(bundle: string, faction: string, turns: number)
+        --# assume CA_REGION_MANAGER.region_by_key: method(key: string) --> CA_REGION
+        --# assume RAM.new_force: method(key: string)
+        --# assume CM.grant_unit: method(
+        --#     unitstring: string, factionName: string, callback: function(unit: string, faction: string) --> boolean, unit: string
+        --# )
+        --# assume MISSION_MANAGER.add_heading: method(heading_loc_key: string)
+        --# assume CM.faction_is_playable: method(faction: CA_FACTION) --> boolean
+    ";
+
+    // Check the references of each parameter are found by exact name, and the ones of functions within parameters are ignored.
+    let functions = parse_script_functions(types_file);
+    assert_eq!(functions["apply_effect_bundle"], vec![Some(ScriptReference::Table("effect_bundles_tables")), Some(ScriptReference::Table("factions_tables")), None]);
+    assert_eq!(functions["region_by_key"], vec![Some(ScriptReference::Table("regions_tables"))]);
+    assert_eq!(functions["new_force"], vec![None]);
+    assert_eq!(functions["grant_unit"], vec![None, Some(ScriptReference::Table("factions_tables")), None, Some(ScriptReference::Table("main_units_tables"))]);
+    assert_eq!(functions["add_heading"], vec![Some(ScriptReference::Loc)]);
+    assert_eq!(functions["faction_is_playable"], vec![None]);
+
+    // Check only the unknown keys of the referencing parameters are reported.
+    let script = "
+        cm:apply_effect_bundle(\"known_bundle\", \"unknown_faction\", 5)
+        cm:grant_unit(\"not_a_key\", \"known_faction\", nil, \"unknown_unit\")
+        ram:new_force(\"not_a_key\")
+        mm:add_heading(\"unknown_loc\")
+        local region = region_manager:region_by_key(\"known_region\")
+    ";
+    let mut is_known = |reference: ScriptReference, value: &str| match reference {
+        ScriptReference::Loc => false,
+        ScriptReference::Table(_) => value.starts_with("known_"),
+    };
+
+    let path = vec!["script".to_owned(), "test.lua".to_owned()];
+    assert_eq!(get_unknown_script_references(&path, script, &functions, &mut is_known), vec![
+        "script/test.lua:2: 'unknown_faction' is not a known key of factions_tables, used in apply_effect_bundle.".to_owned(),
+        "script/test.lua:3: 'unknown_unit' is not a known key of main_units_tables, used in grant_unit.".to_owned(),
+        "script/test.lua:5: 'unknown_loc' is not a known loc key, used in add_heading.".to_owned(),
+    ]);
+}
+
+//-----------------------------------------------------//
+//                  Queries
+//-----------------------------------------------------//
+
+/// This function builds the schema used in the query tests, with an `units` table that references an `unit_groups` table.
+fn build_query_schema() -> Schema {
+    let mut schema = Schema::new();
+
+    let mut units = TableDefinition::new(1);
+    units.fields.push(Field::new("key".to_owned(), FieldType::StringU8, true, None, String::new()));
+    units.fields.push(Field::new("group".to_owned(), FieldType::StringU8, false, Some(("unit_groups".to_owned(), "key".to_owned())), String::new()));
+    units.fields.push(Field::new("cost".to_owned(), FieldType::Integer, false, None, String::new()));
+    let mut table_definitions = TableDefinitions::new("units_tables");
+    table_definitions.add_table_definition(units);
+    schema.add_table_definitions(table_definitions);
+
+    let mut unit_groups = TableDefinition::new(1);
+    unit_groups.fields.push(Field::new("key".to_owned(), FieldType::StringU8, true, None, String::new()));
+    unit_groups.fields.push(Field::new("name".to_owned(), FieldType::StringU8, false, None, String::new()));
+    let mut table_definitions = TableDefinitions::new("unit_groups_tables");
+    table_definitions.add_table_definition(unit_groups);
+    schema.add_table_definitions(table_definitions);
+
+    schema
+}
+
+/// This function builds a PackedFile with a version 1 DB Table with the provided rows, without using `DB::save`.
+fn build_db_packed_file(path: &str, rows: &[Vec<DecodedData>]) -> PackedFile {
+    build_db_packed_file_with_version(path, 1, rows)
+}
+
+/// This function builds a PackedFile with a DB Table of the provided version with the provided rows, without using `DB::save`.
+fn build_db_packed_file_with_version(path: &str, version: i32, rows: &[Vec<DecodedData>]) -> PackedFile {
+    let mut data = vec![252, 253, 254, 255];
+    data.extend_from_slice(&encode_integer_i32(version));
+    data.push(1);
+    data.extend_from_slice(&encode_integer_u32(rows.len() as u32));
+    for cell in rows.iter().flatten() {
+        match cell {
+            DecodedData::Boolean(data_bool) => data.push(encode_bool(*data_bool)),
+            DecodedData::Float(data_float) => data.extend_from_slice(&encode_float_f32(*data_float)),
+            DecodedData::Integer(data_integer) => data.extend_from_slice(&encode_integer_i32(*data_integer)),
+            DecodedData::LongInteger(data_integer) => data.extend_from_slice(&encode_integer_i64(*data_integer)),
+            DecodedData::StringU8(data_string) => data.extend_from_slice(&encode_packedfile_string_u8(data_string)),
+            DecodedData::StringU16(data_string) => data.extend_from_slice(&encode_packedfile_string_u16(data_string)),
+            DecodedData::OptionalStringU8(data_string) => data.extend_from_slice(&encode_packedfile_optional_string_u8(data_string)),
+            DecodedData::OptionalStringU16(data_string) => data.extend_from_slice(&encode_packedfile_optional_string_u16(data_string)),
+        }
+    }
+    PackedFile::read_from_vec(path.split('/').map(|x| x.to_owned()).collect(), 0, false, data)
+}
+
+/// This function builds a row of the `units` table of the query tests.
+fn build_unit_row(key: &str, group: &str, cost: i32) -> Vec<DecodedData> {
+    vec![DecodedData::StringU8(key.to_owned()), DecodedData::StringU8(group.to_owned()), DecodedData::Integer(cost)]
+}
+
+/// This function builds the PackFile used in the query tests.
+fn build_query_pack_file() -> PackFile {
+    let mut pack_file = PackFile::new();
+    pack_file.packed_files.push(build_db_packed_file("db/units_tables/mod_units", &[
+        build_unit_row("knights", "cavalry", 1200),
+        build_unit_row("spearmen", "infantry", 400),
+        build_unit_row("archers", "missile", 500),
+        build_unit_row("swordsmen", "infantry", 600),
+    ]));
+    pack_file.packed_files.push(build_db_packed_file("db/unit_groups_tables/mod_groups", &[
+        vec![DecodedData::StringU8("cavalry".to_owned()), DecodedData::StringU8("Cavalry".to_owned())],
+        vec![DecodedData::StringU8("infantry".to_owned()), DecodedData::StringU8("Infantry".to_owned())],
+    ]));
+    pack_file
+}
+
+/// This function runs a query over the provided PackFile and dependencies, with the schema of the query tests.
+fn run_query(query: &str, pack_file: &PackFile, dep_db: &mut Vec<PackedFile>) -> QueryResult {
+    QueryResult::new_from_data(query, pack_file, &build_query_schema(), dep_db, &[]).unwrap()
+}
+
+/// This function returns the provided column of the rows of a query result, as text.
+fn get_query_column(result: &QueryResult, column: usize) -> Vec<String> {
+    result.rows.iter().map(|x| get_decoded_data_as_string(&x[column])).collect()
+}
+
+/// Test to make sure invalid queries are rejected when parsing them.
+#[test]
+fn test_query_parse_errors() {
+    let pack_file = build_query_pack_file();
+    let schema = build_query_schema();
+    for query in &[
+        "",
+        "key FROM units",
+        "SELECT FROM units",
+        "SELECT key units",
+        "SELECT key, FROM units",
+        "SELECT key FROM units WHERE cost",
+        "SELECT key FROM units WHERE cost ! 5",
+        "SELECT key FROM units WHERE key = 'unclosed",
+        "SELECT key FROM units JOIN unit_groups ON group > key",
+        "SELECT key FROM units ORDER key",
+        "SELECT key FROM units LIMIT many",
+        "SELECT key FROM units LIMIT 5 extra",
+    ] {
+        assert!(QueryResult::new_from_data(query, &pack_file, &schema, &mut vec![], &[]).is_err(), "{}", query);
+    }
+
+    // Check tables and columns that don't exist are errors too.
+    assert!(QueryResult::new_from_data("SELECT key FROM missing", &pack_file, &schema, &mut vec![], &[]).is_err());
+    assert!(QueryResult::new_from_data("SELECT missing FROM units", &pack_file, &schema, &mut vec![], &[]).is_err());
+}
+
+/// Test to make sure the columns, conditions, ordering and limits of the queries work properly.
+#[test]
+fn test_query_run() {
+    let pack_file = build_query_pack_file();
+
+    // Check all the columns are returned with their table's name, and keywords are case-insensitive.
+    let result = run_query("select * from units_tables", &pack_file, &mut vec![]);
+    assert_eq!(result.columns, vec!["units.key", "units.group", "units.cost"]);
+    assert_eq!(result.rows.len(), 4);
+
+    // Check the conditions, with AND having priority over OR.
+    let result = run_query("SELECT key FROM units WHERE group = infantry AND cost > 500 OR key = 'archers' ORDER BY key", &pack_file, &mut vec![]);
+    assert_eq!(get_query_column(&result, 0), vec!["archers", "swordsmen"]);
+
+    let result = run_query("SELECT key FROM units WHERE key LIKE '%men' AND cost <= 400", &pack_file, &mut vec![]);
+    assert_eq!(get_query_column(&result, 0), vec!["spearmen"]);
+
+    let result = run_query("SELECT key FROM units WHERE key NOT LIKE '%men' AND key CONTAINS 'NIG'", &pack_file, &mut vec![]);
+    assert_eq!(get_query_column(&result, 0), vec!["knights"]);
+
+    // Check a value that's not valid for the type of the column only passes `!=`.
+    let result = run_query("SELECT key FROM units WHERE cost != lots", &pack_file, &mut vec![]);
+    assert_eq!(result.rows.len(), 4);
+
+    // Check numbers are sorted as numbers, and the limit is applied after sorting.
+    let result = run_query("SELECT key, cost FROM units ORDER BY cost DESC LIMIT 2", &pack_file, &mut vec![]);
+    assert_eq!(get_query_column(&result, 1), vec!["1200", "600"]);
+}
+
+/// Test to make sure the joins of the queries work properly, with and without `ON`.
+#[test]
+fn test_query_join() {
+    let pack_file = build_query_pack_file();
+
+    // Check the join uses the references of the tables. Rows without a match in the other table are left out.
+    let result = run_query("SELECT units.key, unit_groups.name FROM units JOIN unit_groups ORDER BY units.key", &pack_file, &mut vec![]);
+    assert_eq!(result.columns, vec!["units.key", "unit_groups.name"]);
+    assert_eq!(get_query_column(&result, 0), vec!["knights", "spearmen", "swordsmen"]);
+    assert_eq!(get_query_column(&result, 1), vec!["Cavalry", "Infantry", "Infantry"]);
+
+    // Check the join works the same from the other table, and with the columns in any order.
+    let result = run_query("SELECT name FROM unit_groups JOIN units ON units.group = unit_groups.key WHERE units.key = knights", &pack_file, &mut vec![]);
+    assert_eq!(get_query_column(&result, 0), vec!["Cavalry"]);
+}
+
+/// Test to make sure the rows of the PackFile override the ones of the dependencies with the same key.
+#[test]
+fn test_query_pack_file_overrides_dependencies() {
+    let pack_file = build_query_pack_file();
+    let mut dep_db = vec![build_db_packed_file("db/units_tables/data__", &[
+        build_unit_row("knights", "cavalry", 1000),
+        build_unit_row("peasants", "infantry", 100),
+    ])];
+
+    let result = run_query("SELECT key, cost FROM units ORDER BY key", &pack_file, &mut dep_db);
+    assert_eq!(get_query_column(&result, 0), vec!["archers", "knights", "peasants", "spearmen", "swordsmen"]);
+    assert_eq!(get_query_column(&result, 1), vec!["500", "1200", "100", "400", "600"]);
+}
+
+/// Test to make sure tables we cannot decode are reported and left out of the query, instead of failing it.
+#[test]
+fn test_query_skip_undecodable_tables() {
+    let mut pack_file = build_query_pack_file();
+    pack_file.packed_files.push(PackedFile::read_from_vec(vec!["db".to_owned(), "units_tables".to_owned(), "broken".to_owned()], 0, false, vec![1, 2, 3]));
+
+    let result = run_query("SELECT key FROM units", &pack_file, &mut vec![]);
+    assert_eq!(result.rows.len(), 4);
+    assert_eq!(result.skipped_tables, vec!["db/units_tables/broken"]);
+
+    // If none of the tables can be decoded, it's an error.
+    let mut pack_file = PackFile::new();
+    pack_file.packed_files.push(PackedFile::read_from_vec(vec!["db".to_owned(), "units_tables".to_owned(), "broken".to_owned()], 0, false, vec![1, 2, 3]));
+    assert!(QueryResult::new_from_data("SELECT key FROM units", &pack_file, &build_query_schema(), &mut vec![], &[]).is_err());
+}
+
+//-----------------------------------------------------//
+//                  DB PackedFiles
+//-----------------------------------------------------//
+
+/// Test to make sure truncated or corrupted DB Tables return an error instead of crashing when decoding them.
+#[test]
+fn test_db_read_truncated() {
+    let schema = build_query_schema();
+    let data = build_db_packed_file("db/units_tables/mod_units", &[build_unit_row("knights", "cavalry", 1200)]).get_data().unwrap();
+    assert!(DB::read(&data, "units_tables", &schema).is_ok());
+
+    // Check every truncated version of the table, and the table with garbage at the end, fail to decode.
+    for size in 0..data.len() {
+        assert!(DB::read(&data[..size], "units_tables", &schema).is_err());
+    }
+
+    let mut data_garbage = data.to_vec();
+    data_garbage.push(0);
+    assert!(DB::read(&data_garbage, "units_tables", &schema).is_err());
+
+    // Check it doesn't crash when starting to decode past the end of the data either.
+    let mut index = data.len() - 2;
+    assert!(DB::read_with_index(&data, "units_tables", &schema, &mut index).is_err());
+}
+
+/// Test to make sure removing a version of a table from the schema works, and returns an error if the version is not there.
+#[test]
+fn test_db_remove_table_version() {
+    let mut schema = build_query_schema();
+    assert!(DB::remove_table_version("units_tables", 2, &mut schema).is_err());
+    assert!(DB::remove_table_version("missing_tables", 1, &mut schema).is_err());
+
+    assert!(DB::remove_table_version("units_tables", 1, &mut schema).is_ok());
+    assert!(DB::get_schema("units_tables", 1, &schema).is_none());
+    assert!(DB::remove_table_version("units_tables", 1, &mut schema).is_err());
+}
+
+/// Test to make sure we get the right table name for DB Tables inside and outside the `db` folder, and no name when we cannot know it.
+#[test]
+fn test_db_get_table_name() {
+    let schema = build_query_schema();
+    let path = |x: &str| x.split('/').map(|x| x.to_owned()).collect::<Vec<String>>();
+    let units = build_db_packed_file("units", &[build_unit_row("knights", "cavalry", 1200)]).get_data().unwrap();
+    let groups = build_db_packed_file("groups", &[vec![DecodedData::StringU8("cavalry".to_owned()), DecodedData::StringU8("Cavalry".to_owned())]]).get_data().unwrap();
+    let empty = build_db_packed_file("empty", &[]).get_data().unwrap();
+
+    // Tables in the `db` folder or in a table folder take the name of their folder, without checking their data.
+    assert_eq!(DB::get_table_name(&path("db/units_tables/mod_units"), &groups, &schema), Some("units_tables".to_owned()));
+    assert_eq!(DB::get_table_name(&path("custom/unit_groups_tables/mod_groups.bin"), &units, &schema), Some("unit_groups_tables".to_owned()));
+
+    // Any other table takes the name of the table that can decode it.
+    assert_eq!(DB::get_table_name(&path("mod_units.bin"), &units, &schema), Some("units_tables".to_owned()));
+    assert_eq!(DB::get_table_name(&path("custom/mod_groups.bin"), &groups, &schema), Some("unit_groups_tables".to_owned()));
+
+    // Empty tables, tables no table can decode, and data that is not a table have no name.
+    assert_eq!(DB::get_table_name(&path("mod_empty.bin"), &empty, &schema), None);
+    assert_eq!(DB::get_table_name(&path("mod_units.bin"), &units[..units.len() - 1], &schema), None);
+    assert_eq!(DB::get_table_name(&path("mod_units.bin"), &[1, 2], &schema), None);
+
+    // Tables that more than one table can decode have no name either.
+    let mut schema = schema;
+    let mut factions = TableDefinition::new(1);
+    factions.fields.push(Field::new("key".to_owned(), FieldType::StringU8, true, None, String::new()));
+    factions.fields.push(Field::new("name".to_owned(), FieldType::StringU8, false, None, String::new()));
+    let mut table_definitions = TableDefinitions::new("factions_tables");
+    table_definitions.add_table_definition(factions);
+    schema.add_table_definitions(table_definitions);
+    assert_eq!(DB::get_table_name(&path("custom/mod_groups.bin"), &groups, &schema), None);
+    assert_eq!(DB::get_table_name(&path("mod_units.bin"), &units, &schema), Some("units_tables".to_owned()));
+}
+
+/// Test to make sure floats survive reading a DB Table, exporting it to TSV, importing it back and saving it, without changes.
+#[test]
+fn test_db_float_tsv_roundtrip() {
+    let mut schema = Schema::new();
+    let mut floats = TableDefinition::new(1);
+    floats.fields.push(Field::new("key".to_owned(), FieldType::StringU8, true, None, String::new()));
+    floats.fields.push(Field::new("value".to_owned(), FieldType::Float, false, None, String::new()));
+    let mut table_definitions = TableDefinitions::new("floats_tables");
+    table_definitions.add_table_definition(floats);
+    schema.add_table_definitions(table_definitions);
+
+    let values = [0.1, 1.0 / 3.0, 123_456.79, -0.000_012_345, 16_777_216.0, std::f32::MAX, std::f32::MIN_POSITIVE];
+    let rows = values.iter().enumerate().map(|(x, y)| vec![DecodedData::StringU8(x.to_string()), DecodedData::Float(*y)]).collect::<Vec<Vec<DecodedData>>>();
+    let data = build_db_packed_file("db/floats_tables/mod_floats", &rows).get_data().unwrap();
+    let table = DB::read(&data, "floats_tables", &schema).unwrap();
+
+    let path = std::env::temp_dir().join("rpfm_test_db_float_tsv_roundtrip.tsv");
+    let headers = table.table_definition.fields.iter().map(|x| x.field_name.to_owned()).collect::<Vec<String>>();
+    export_tsv(&table.entries, &path, &headers, ("floats_tables", 1)).unwrap();
+    let entries = import_tsv(&table.table_definition, &path, "floats_tables", 1);
+    std::fs::remove_file(&path).unwrap();
+
+    let mut new_table = DB::new("floats_tables", 1, table.table_definition.clone());
+    new_table.entries = entries.unwrap();
+    let new_table = DB::read(&new_table.save(), "floats_tables", &schema).unwrap();
+    for (row, value) in new_table.entries.iter().zip(values.iter()) {
+        match row[1] {
+            DecodedData::Float(data) => assert_eq!(data.to_bits(), value.to_bits()),
+            _ => panic!("Row {:?} has no float.", row),
+        }
+    }
+    assert_eq!(new_table.entries, rows);
+}
+
+/// Test to make sure rows are compared with the float tolerance in both directions, and without it for the rest of the cells.
+#[test]
+fn test_are_rows_equal() {
+    let row = |key: &str, value: f32| vec![DecodedData::StringU8(key.to_owned()), DecodedData::Float(value)];
+    assert!(are_rows_equal(&row("a", 1.0), &row("a", 1.0005), 0.001));
+    assert!(are_rows_equal(&row("a", 1.0005), &row("a", 1.0), 0.001));
+    assert!(!are_rows_equal(&row("a", 1.0), &row("a", 1.002), 0.001));
+    assert!(!are_rows_equal(&row("a", 1.002), &row("a", 1.0), 0.001));
+
+    // Without tolerance, only the same floats are equal.
+    assert!(are_rows_equal(&row("a", 0.1), &row("a", 0.1), 0.0));
+    assert!(!are_rows_equal(&row("a", 0.1), &row("a", 0.100_001), 0.0));
+
+    // The tolerance doesn't apply to other cells, nor to rows of different length.
+    assert!(!are_rows_equal(&row("a", 1.0), &row("b", 1.0), 0.001));
+    assert!(!are_rows_equal(&[DecodedData::Integer(1)], &[DecodedData::Integer(2)], 10.0));
+    assert!(!are_rows_equal(&row("a", 1.0), &row("a", 1.0)[..1], 0.001));
+}
+
+/// Test to make sure the missing locs of the DB Tables are the ones of their localised fields, from the schema and from the loc keys we already have.
+#[test]
+fn test_get_missing_locs() {
+    let mut schema = build_query_schema();
+    let units_index = schema.get_table_definitions("units_tables").unwrap();
+    schema.tables_definitions[units_index].versions[0].localised_fields.push("onscreen_name".to_owned());
+    let pack_file = build_query_pack_file();
+
+    // `units` uses `onscreen_name` from the schema. `unit_groups` uses `localised_name`, as one of his rows already has a loc with it.
+    let loc_keys = ["units_onscreen_name_knights", "unit_groups_localised_name_cavalry", "unrelated_key"].iter().map(|x| (*x).to_owned()).collect::<BTreeSet<String>>();
+    assert_eq!(get_missing_locs_from_keys(&pack_file, &schema, &loc_keys), vec![
+        "unit_groups_localised_name_infantry".to_owned(),
+        "units_onscreen_name_archers".to_owned(),
+        "units_onscreen_name_spearmen".to_owned(),
+        "units_onscreen_name_swordsmen".to_owned(),
+    ]);
+
+    // Without loc keys, only the localised fields of the schema are checked.
+    let missing_locs = get_missing_locs_from_keys(&pack_file, &schema, &BTreeSet::new());
+    assert_eq!(missing_locs, vec![
+        "units_onscreen_name_archers".to_owned(),
+        "units_onscreen_name_knights".to_owned(),
+        "units_onscreen_name_spearmen".to_owned(),
+        "units_onscreen_name_swordsmen".to_owned(),
+    ]);
+    assert!(get_missing_locs_from_keys(&pack_file, &build_query_schema(), &BTreeSet::new()).is_empty());
+
+    // With every loc key there, nothing is missing.
+    let mut loc_keys = missing_locs.into_iter().collect::<BTreeSet<String>>();
+    loc_keys.insert("unit_groups_localised_name_cavalry".to_owned());
+    loc_keys.insert("unit_groups_localised_name_infantry".to_owned());
+    assert!(get_missing_locs_from_keys(&pack_file, &schema, &loc_keys).is_empty());
+}
+
+//-----------------------------------------------------//
+//                  Merge Tables
+//-----------------------------------------------------//
+
+/// This function builds the PackFile used in the merge tests, with two `units` tables with one duplicated row and one conflicting row.
+fn build_merge_pack_file() -> PackFile {
+    let mut pack_file = PackFile::new();
+    pack_file.packed_files.push(build_db_packed_file("db/units_tables/mod_units_a", &[
+        build_unit_row("knights", "cavalry", 1200),
+        build_unit_row("spearmen", "infantry", 400),
+    ]));
+    pack_file.packed_files.push(build_db_packed_file("db/units_tables/mod_units_b", &[
+        build_unit_row("knights", "cavalry", 1200),
+        build_unit_row("spearmen", "infantry", 500),
+        build_unit_row("archers", "missile", 500),
+    ]));
+    pack_file
+}
+
+/// This function returns the paths of the provided PackedFiles of the merge tests.
+fn get_merge_paths(names: &[&str]) -> Vec<Vec<String>> {
+    names.iter().map(|x| vec!["db".to_owned(), "units_tables".to_owned(), (*x).to_owned()]).collect()
+}
+
+/// Test to make sure merging tables with the `FirstWins` strategy keeps the first row of every key, and reports the rest.
+#[test]
+fn test_merge_tables_first_wins() {
+    let schema = build_query_schema();
+    let paths = get_merge_paths(&["mod_units_a", "mod_units_b"]);
+    let (data, report) = get_merged_tables_data(&build_merge_pack_file(), &paths, true, MergeStrategy::FirstWins, Some(&schema)).unwrap();
+    assert_eq!(DB::read(&data, "units_tables", &schema).unwrap().entries, vec![
+        build_unit_row("knights", "cavalry", 1200),
+        build_unit_row("spearmen", "infantry", 400),
+        build_unit_row("archers", "missile", 500),
+    ]);
+    assert_eq!(report, vec![
+        "Key <i>knights</i>: duplicated row from <i>db/units_tables/mod_units_b</i> dropped.".to_owned(),
+        "Key <i>spearmen</i>: row from <i>db/units_tables/mod_units_b</i> dropped.".to_owned(),
+    ]);
+}
+
+/// Test to make sure merging tables with the `LastWins` strategy keeps the last row of every key in the place of the first one, and reports the rest.
+#[test]
+fn test_merge_tables_last_wins() {
+    let schema = build_query_schema();
+    let paths = get_merge_paths(&["mod_units_a", "mod_units_b"]);
+    let (data, report) = get_merged_tables_data(&build_merge_pack_file(), &paths, true, MergeStrategy::LastWins, Some(&schema)).unwrap();
+    assert_eq!(DB::read(&data, "units_tables", &schema).unwrap().entries, vec![
+        build_unit_row("knights", "cavalry", 1200),
+        build_unit_row("spearmen", "infantry", 500),
+        build_unit_row("archers", "missile", 500),
+    ]);
+    assert_eq!(report, vec![
+        "Key <i>knights</i>: duplicated row from <i>db/units_tables/mod_units_b</i> dropped.".to_owned(),
+        "Key <i>spearmen</i>: row overwritten by the one from <i>db/units_tables/mod_units_b</i>.".to_owned(),
+    ]);
+}
+
+/// Test to make sure merging tables with the `ReportConflicts` strategy fails only when rows with the same key have different data.
+#[test]
+fn test_merge_tables_report_conflicts() {
+    let schema = build_query_schema();
+    let paths = get_merge_paths(&["mod_units_a", "mod_units_b"]);
+    assert_eq!(get_merged_tables_data(&build_merge_pack_file(), &paths, true, MergeStrategy::ReportConflicts, Some(&schema)).unwrap_err().kind(), ErrorKind::MergeTablesConflicts(vec![
+        "Key <i>spearmen</i>: different data in <i>db/units_tables/mod_units_b</i>.".to_owned(),
+    ]));
+
+    // Identical rows are not conflicts.
+    let mut pack_file = build_merge_pack_file();
+    pack_file.packed_files.push(build_db_packed_file("db/units_tables/mod_units_c", &[build_unit_row("knights", "cavalry", 1200)]));
+    let paths = get_merge_paths(&["mod_units_a", "mod_units_c"]);
+    let (data, report) = get_merged_tables_data(&pack_file, &paths, true, MergeStrategy::ReportConflicts, Some(&schema)).unwrap();
+    assert_eq!(DB::read(&data, "units_tables", &schema).unwrap().entries.len(), 2);
+    assert_eq!(report.len(), 1);
+
+    // Without schema, DB Tables cannot be merged.
+    assert_eq!(get_merged_tables_data(&pack_file, &paths, true, MergeStrategy::ReportConflicts, None).unwrap_err().kind(), ErrorKind::SchemaNotFound);
+}
+
+/// Test to make sure merging tables of different versions updates all of them to the newest version before merging.
+#[test]
+fn test_merge_tables_different_versions() {
+    let mut schema = build_query_schema();
+    let mut units = schema.tables_definitions[0].versions[0].clone();
+    units.version = 2;
+    let mut upkeep = Field::new("upkeep".to_owned(), FieldType::Integer, false, None, String::new());
+    upkeep.field_default_value = Some("50".to_owned());
+    units.fields.push(upkeep);
+    schema.tables_definitions[0].add_table_definition(units);
+
+    let mut pack_file = build_merge_pack_file();
+    let mut archers = build_unit_row("archers", "missile", 500);
+    archers.push(DecodedData::Integer(80));
+    pack_file.packed_files[1] = build_db_packed_file_with_version("db/units_tables/mod_units_b", 2, &[archers.to_vec()]);
+
+    let paths = get_merge_paths(&["mod_units_a", "mod_units_b"]);
+    let (data, report) = get_merged_tables_data(&pack_file, &paths, true, MergeStrategy::ReportConflicts, Some(&schema)).unwrap();
+    let table = DB::read(&data, "units_tables", &schema).unwrap();
+    let mut knights = build_unit_row("knights", "cavalry", 1200);
+    knights.push(DecodedData::Integer(50));
+    let mut spearmen = build_unit_row("spearmen", "infantry", 400);
+    spearmen.push(DecodedData::Integer(50));
+    assert_eq!(table.version, 2);
+    assert_eq!(table.entries, vec![knights, spearmen, archers]);
+    assert!(report.is_empty());
+}
+
+/// Test to make sure the keys of tables with more than one key column are compared column by column when merging.
+#[test]
+fn test_merge_tables_composite_keys() {
+    let mut schema = Schema::new();
+    let mut pairs = TableDefinition::new(1);
+    pairs.fields.push(Field::new("first".to_owned(), FieldType::StringU8, true, None, String::new()));
+    pairs.fields.push(Field::new("second".to_owned(), FieldType::StringU8, true, None, String::new()));
+    pairs.fields.push(Field::new("value".to_owned(), FieldType::Integer, false, None, String::new()));
+    let mut table_definitions = TableDefinitions::new("pairs_tables");
+    table_definitions.add_table_definition(pairs);
+    schema.add_table_definitions(table_definitions);
+
+    // These keys would be the same if we joined the key columns with a `|`.
+    let row = |first: &str, second: &str, value: i32| vec![DecodedData::StringU8(first.to_owned()), DecodedData::StringU8(second.to_owned()), DecodedData::Integer(value)];
+    let mut pack_file = PackFile::new();
+    pack_file.packed_files.push(build_db_packed_file("db/pairs_tables/a", &[row("a|b", "c", 1)]));
+    pack_file.packed_files.push(build_db_packed_file("db/pairs_tables/b", &[row("a", "b|c", 2), row("a|b", "c", 3)]));
+
+    let paths = vec![vec!["db".to_owned(), "pairs_tables".to_owned(), "a".to_owned()], vec!["db".to_owned(), "pairs_tables".to_owned(), "b".to_owned()]];
+    let (data, report) = get_merged_tables_data(&pack_file, &paths, true, MergeStrategy::FirstWins, Some(&schema)).unwrap();
+    assert_eq!(DB::read(&data, "pairs_tables", &schema).unwrap().entries, vec![row("a|b", "c", 1), row("a", "b|c", 2)]);
+    assert_eq!(report, vec!["Key <i>a|b, c</i>: row from <i>db/pairs_tables/b</i> dropped.".to_owned()]);
+}
+
+//-----------------------------------------------------//
+//                  Schemas
+//-----------------------------------------------------//
+
+/// Test to make sure the differences between schemas are detected properly, and that adding a field doesn't report the rest as moved.
+#[test]
+fn test_schema_get_diff() {
+    let old_schema = build_query_schema();
+    let mut new_schema = old_schema.clone();
+
+    // Add a field at the start of `units` and change the description of another one.
+    let units = &mut new_schema.tables_definitions[0].versions[0];
+    units.fields.insert(0, Field::new("id".to_owned(), FieldType::Integer, false, None, String::new()));
+    units.fields[3].field_description = "Cost of the unit.".to_owned();
+
+    // Swap the fields of `unit_groups`, and add a new version of `units` and a new table.
+    new_schema.tables_definitions[1].versions[0].fields.swap(0, 1);
+    new_schema.tables_definitions[0].add_table_definition(TableDefinition::new(2));
+    new_schema.add_table_definitions(TableDefinitions::new("factions_tables"));
+
+    let diff = old_schema.get_diff(&new_schema);
+    assert_eq!(diff.tables_added, vec!["factions_tables".to_owned()]);
+    assert!(diff.tables_removed.is_empty());
+    assert_eq!(diff.versions_added, vec![("units_tables".to_owned(), 2)]);
+    assert!(diff.versions_removed.is_empty());
+    assert_eq!(diff.versions_changed.len(), 2);
+
+    let units_diff = &diff.versions_changed[0];
+    assert_eq!(units_diff.table_name, "units_tables");
+    assert_eq!(units_diff.fields_added.iter().map(|x| &*x.field_name).collect::<Vec<&str>>(), vec!["id"]);
+    assert!(units_diff.fields_removed.is_empty());
+    assert_eq!(units_diff.fields_changed.len(), 1);
+    assert_eq!(units_diff.fields_changed[0].field_name, "cost");
+    assert_eq!(units_diff.fields_changed[0].changes, vec![("Description".to_owned(), String::new(), "Cost of the unit.".to_owned())]);
+
+    let unit_groups_diff = &diff.versions_changed[1];
+    assert_eq!(unit_groups_diff.table_name, "unit_groups_tables");
+    assert_eq!(unit_groups_diff.fields_changed.len(), 2);
+    assert_eq!(unit_groups_diff.fields_changed[0].changes, vec![("Position".to_owned(), "0".to_owned(), "1".to_owned())]);
+    assert_eq!(unit_groups_diff.fields_changed[1].changes, vec![("Position".to_owned(), "1".to_owned(), "0".to_owned())]);
+
+    // The other way around, the new stuff is reported as removed.
+    let diff = new_schema.get_diff(&old_schema);
+    assert_eq!(diff.tables_removed, vec!["factions_tables".to_owned()]);
+    assert_eq!(diff.versions_removed, vec![("units_tables".to_owned(), 2)]);
+    assert_eq!(diff.versions_changed[0].fields_removed.iter().map(|x| &*x.field_name).collect::<Vec<&str>>(), vec!["id"]);
+
+    // And there are no differences between an schema and itself.
+    assert!(old_schema.get_diff(&old_schema).is_empty());
+}
+
+/// Test to make sure the differences between schemas are turned into MarkDown properly.
+#[test]
+fn test_schema_diff_to_markdown() {
+    assert_eq!(SchemaDiff::default().to_markdown(), "No differences found.\n");
+
+    let diff = SchemaDiff {
+        tables_added: vec!["factions_tables".to_owned()],
+        versions_removed: vec![("units_tables".to_owned(), 2)],
+        versions_changed: vec![TableDefinitionDiff {
+            table_name: "unit_groups_tables".to_owned(),
+            version: 1,
+            fields_added: vec![Field::new("name".to_owned(), FieldType::StringU8, false, None, String::new())],
+            fields_removed: vec![],
+            fields_changed: vec![FieldDiff {
+                field_name: "cost".to_owned(),
+                changes: vec![("Type".to_owned(), "Integer".to_owned(), "Long Integer".to_owned())],
+            }],
+        }],
+        ..Default::default()
+    };
+
+    assert_eq!(diff.to_markdown(), "\
+- **New tables**:
+  - *factions_tables*.
+- **Removed versions**:
+  - *units_tables*: *2*.
+- **Changed versions**:
+  - ***unit_groups_tables*** (version *1*):
+    - **New fields**:
+      - ***name***: *StringU8*.
+    - **Changed fields**:
+      - **cost**:
+        - ***Type***: *Integer* => *Long Integer*.
+");
+}
+
+/// Test to make sure the entries of the schema docs are added to the `SUMMARY.md` of the book, replacing the old ones if there are any.
+#[test]
+fn test_schema_docs_update_book_summary() {
+    let book_summary = "# Summary\n\n- [Intro](./intro.md)\n\t- [Usage](./usage.md)\n\n---\n- [Changelogs](./changelogs.md)\n";
+    let entries = "- [Schema](./schema/index.md)\n\t- [units_tables](./schema/units_tables.md)\n";
+    let book_summary = update_book_summary(book_summary, entries, "schema");
+    assert_eq!(book_summary, "# Summary\n\n- [Intro](./intro.md)\n\t- [Usage](./usage.md)\n- [Schema](./schema/index.md)\n\t- [units_tables](./schema/units_tables.md)\n\n---\n- [Changelogs](./changelogs.md)\n");
+
+    let entries = "- [Schema](./schema/index.md)\n\t- [unit_groups_tables](./schema/unit_groups_tables.md)\n";
+    let book_summary = update_book_summary(&book_summary, entries, "schema");
+    assert_eq!(book_summary, "# Summary\n\n- [Intro](./intro.md)\n\t- [Usage](./usage.md)\n- [Schema](./schema/index.md)\n\t- [unit_groups_tables](./schema/unit_groups_tables.md)\n\n---\n- [Changelogs](./changelogs.md)\n");
+}
+
+/// Test to make sure merging a local overlay replaces the changed versions, adds the new ones, removes the removed ones, and keeps everything sorted.
+#[test]
+fn test_schema_merge() {
+    let mut schema = build_query_schema();
+    let mut overlay = Schema::new();
+
+    // A changed version of `units`, a new version of it, and a new table.
+    let mut units_v1 = schema.tables_definitions[0].versions[0].clone();
+    units_v1.fields[2].field_description = "Cost of the unit.".to_owned();
+    let mut units_v2 = units_v1.clone();
+    units_v2.version = 2;
+    let mut units = TableDefinitions::new("units_tables");
+    units.add_table_definition(units_v1.clone());
+    units.add_table_definition(units_v2.clone());
+    overlay.add_table_definitions(units);
+
+    let mut abilities = TableDefinitions::new("abilities_tables");
+    abilities.add_table_definition(TableDefinition::new(1));
+    overlay.add_table_definitions(abilities);
+
+    // And the only version of `unit_groups` removed, together with a version that doesn't exist.
+    overlay.removed_versions.push(("unit_groups_tables".to_owned(), 1));
+    overlay.removed_versions.push(("missing_tables".to_owned(), 1));
+
+    schema.merge(&overlay);
+    assert_eq!(schema.tables_definitions.iter().map(|x| x.name.to_owned()).collect::<Vec<String>>(), vec!["abilities_tables", "units_tables"]);
+    assert_eq!(schema.tables_definitions[0].versions, vec![TableDefinition::new(1)]);
+    assert_eq!(schema.tables_definitions[1].versions, vec![units_v2, units_v1]);
+}
+
+/// Test to make sure only the new and changed versions are saved to the local overlay, that loading the overlay on top of the shipped schema gives back the saved one, and that the overlay is removed when there are no changes.
+#[test]
+fn test_schema_save_local() {
+    let shipped_schema = build_query_schema();
+    let mut schema = shipped_schema.clone();
+    let mut units_v2 = schema.tables_definitions[0].versions[0].clone();
+    units_v2.version = 2;
+    schema.tables_definitions[0].add_table_definition(units_v2.clone());
+    schema.tables_definitions[1].versions[0].fields[1].field_description = "Name of the group.".to_owned();
+
+    let path = std::env::temp_dir().join("rpfm_test_schema_save_local.local.json");
+    schema.save_local_to_path(&shipped_schema, &path).unwrap();
+    let overlay = Schema::load_from_path(&path);
+    shipped_schema.save_local_to_path(&shipped_schema, &path).unwrap();
+    assert!(!path.is_file());
+
+    let overlay = overlay.unwrap();
+    assert_eq!(overlay.tables_definitions.iter().map(|x| x.name.to_owned()).collect::<Vec<String>>(), vec!["units_tables", "unit_groups_tables"]);
+    assert_eq!(overlay.tables_definitions[0].versions, vec![units_v2.clone()]);
+    assert_eq!(overlay.tables_definitions[1].versions, schema.tables_definitions[1].versions);
+    assert!(overlay.removed_versions.is_empty());
+
+    let mut loaded_schema = shipped_schema.clone();
+    loaded_schema.merge(&overlay);
+    assert_eq!(loaded_schema.tables_definitions[0], schema.tables_definitions[1]);
+    assert_eq!(loaded_schema.tables_definitions[1].versions, vec![units_v2, shipped_schema.tables_definitions[0].versions[0].clone()]);
+
+    // Without changes, there is nothing to put in the overlay.
+    let overlay = shipped_schema.get_local_overlay(&shipped_schema);
+    assert!(overlay.tables_definitions.is_empty());
+    assert!(overlay.removed_versions.is_empty());
+}
+
+/// Test to make sure versions of the shipped schema deleted by the user stay deleted after saving the local overlay and loading the schema again.
+#[test]
+fn test_schema_remove_version_save_reload() {
+    let shipped_schema = build_query_schema();
+    let mut schema = shipped_schema.clone();
+    DB::remove_table_version("unit_groups_tables", 1, &mut schema).unwrap();
+    schema.tables_definitions.retain(|x| !x.versions.is_empty());
+
+    let path = std::env::temp_dir().join("rpfm_test_schema_remove_version_save_reload.local.json");
+    schema.save_local_to_path(&shipped_schema, &path).unwrap();
+    let overlay = Schema::load_from_path(&path);
+    std::fs::remove_file(&path).unwrap();
+
+    let overlay = overlay.unwrap();
+    assert!(overlay.tables_definitions.is_empty());
+    assert_eq!(overlay.removed_versions, vec![("unit_groups_tables".to_owned(), 1)]);
+
+    let mut loaded_schema = shipped_schema.clone();
+    loaded_schema.merge(&overlay);
+    assert!(DB::get_schema("unit_groups_tables", 1, &loaded_schema).is_none());
+    assert!(DB::get_schema("units_tables", 1, &loaded_schema).is_some());
+}
+
+/// Test to make sure only the versions defined in both, the schema and the local overlay, but in a different way, are reported as conflicts.
+#[test]
+fn test_schema_get_local_conflicts() {
+    let schema = build_query_schema();
+    let mut overlay = Schema::new();
+
+    // A changed version of `units`, a new version of it, an unchanged version of `unit_groups`, and a new table.
+    let mut units = TableDefinitions::new("units_tables");
+    let mut units_v1 = schema.tables_definitions[0].versions[0].clone();
+    units_v1.fields.pop();
+    let mut units_v2 = units_v1.clone();
+    units_v2.version = 2;
+    units.add_table_definition(units_v1);
+    units.add_table_definition(units_v2);
+    overlay.add_table_definitions(units);
+
+    let mut unit_groups = TableDefinitions::new("unit_groups_tables");
+    unit_groups.add_table_definition(schema.tables_definitions[1].versions[0].clone());
+    overlay.add_table_definitions(unit_groups);
+
+    let mut abilities = TableDefinitions::new("abilities_tables");
+    abilities.add_table_definition(TableDefinition::new(1));
+    overlay.add_table_definitions(abilities);
+
+    assert_eq!(schema.get_local_conflicts(&overlay), vec!["units_tables, version 1".to_owned()]);
+    assert!(schema.get_local_conflicts(&Schema::new()).is_empty());
+}
+
+//-----------------------------------------------------//
+//                  Assembly Kit
+//-----------------------------------------------------//
+
+/// This function builds a row of the `units` table of the raw tables tests.
+fn build_raw_unit_row(key: &str, enabled: bool, cost: f32, description: &str, name: &str) -> Vec<DecodedData> {
+    vec![
+        DecodedData::StringU8(key.to_owned()),
+        DecodedData::Boolean(enabled),
+        DecodedData::Float(cost),
+        DecodedData::OptionalStringU8(description.to_owned()),
+        DecodedData::StringU16(name.to_owned()),
+    ]
+}
+
+/// Test to make sure DB Tables exported as raw tables for the Assembly Kit are imported back with the same data, and that tables of the same type are merged when exporting.
+#[test]
+fn test_raw_tables_export_import_roundtrip() {
+    let mut schema = Schema::new();
+    let mut units = TableDefinition::new(1);
+    units.fields.push(Field::new("key".to_owned(), FieldType::StringU8, true, None, String::new()));
+    units.fields.push(Field::new("enabled".to_owned(), FieldType::Boolean, false, None, String::new()));
+    units.fields.push(Field::new("cost".to_owned(), FieldType::Float, false, None, String::new()));
+    units.fields.push(Field::new("description".to_owned(), FieldType::OptionalStringU8, false, None, String::new()));
+    units.fields.push(Field::new("name".to_owned(), FieldType::StringU16, false, None, String::new()));
+    let mut table_definitions = TableDefinitions::new("units_tables");
+    table_definitions.add_table_definition(units);
+    schema.add_table_definitions(table_definitions);
+
+    let mut pack_file = PackFile::new();
+    pack_file.packed_files.push(build_db_packed_file("db/units_tables/mod_units_a", &[
+        build_raw_unit_row("knights", true, 1.5, "", "Fish & Chips <3>"),
+        build_raw_unit_row("spearmen", false, 0.1, "Pointy 'sticks'", ""),
+    ]));
+    pack_file.packed_files.push(build_db_packed_file("db/units_tables/mod_units_b", &[
+        build_raw_unit_row("knights", false, 2.0, "Duplicated", "Knights"),
+        build_raw_unit_row("archers", true, -3.25, "", "Archers \"Elite\""),
+    ]));
+
+    // Both tables are exported as one, without the duplicated key.
+    let export_path = std::env::temp_dir().join("rpfm_test_raw_tables_export_import_roundtrip");
+    let report = export_raw_tables_with_schema(&[PathType::PackFile], &mut pack_file, &export_path, Some(&schema));
+    let table = import_raw_table(&export_path.join("units.xml"), 2, &schema);
+    std::fs::remove_dir_all(&export_path).unwrap();
+
+    assert_eq!(report.unwrap(), "<p>All exportable tables have been exported.</p><p>Rows with duplicated keys have been removed from the following tables:</p><ul><li>units_tables (1 rows)</li></ul>");
+    let table = table.unwrap();
+    assert_eq!(table.db_type, "units_tables");
+    assert_eq!(table.version, 1);
+    assert_eq!(table.entries, vec![
+        build_raw_unit_row("knights", true, 1.5, "", "Fish & Chips <3>"),
+        build_raw_unit_row("spearmen", false, 0.1, "Pointy 'sticks'", ""),
+        build_raw_unit_row("archers", true, -3.25, "", "Archers \"Elite\""),
+    ]);
+
+    // Without schema, nothing can be exported.
+    assert_eq!(export_raw_tables_with_schema(&[PathType::PackFile], &mut pack_file, &export_path, None).unwrap_err().kind(), ErrorKind::SchemaNotFound);
+}
+
+//-----------------------------------------------------//
+//                  Schema Updates
+//-----------------------------------------------------//
+
+/// This function builds an entry of a `.tar` archive, with his header and his data padded to 512 bytes.
+/// If a prefix is provided, the header is an ustar one.
+fn build_tar_entry(name: &str, prefix: Option<&str>, file_type: u8, data: &[u8]) -> Vec<u8> {
+    let mut header = vec![0; 512];
+    header[..name.len()].copy_from_slice(name.as_bytes());
+    header[124..135].copy_from_slice(format!("{:011o}", data.len()).as_bytes());
+    header[156] = file_type;
+    if let Some(prefix) = prefix {
+        header[257..263].copy_from_slice(b"ustar\0");
+        header[345..345 + prefix.len()].copy_from_slice(prefix.as_bytes());
+    }
+
+    let mut entry = header;
+    entry.extend_from_slice(data);
+    entry.resize(512 + (data.len() + 511) / 512 * 512, 0);
+    entry
+}
+
+/// Test to make sure the files inside `.tar` archives are read with their full paths, including the long ones.
+#[test]
+fn test_read_tar() {
+    let long_name = format!("schemas/{}.json", "a".repeat(150));
+    let mut data = vec![];
+    data.extend_from_slice(&build_tar_entry("./schemas/", None, b'5', &[]));
+    data.extend_from_slice(&build_tar_entry("./schemas/versions.json", None, b'0', b"{}"));
+    data.extend_from_slice(&build_tar_entry("././@LongLink", None, b'L', long_name.as_bytes()));
+    data.extend_from_slice(&build_tar_entry(&long_name[..99], None, b'0', b"long"));
+    data.extend_from_slice(&build_tar_entry("schema_wh2.json", Some("rpfm/schemas"), b'0', &[1; 600]));
+    data.extend_from_slice(&build_tar_entry("other/versions.json", None, 0, b"other"));
+    data.extend_from_slice(&[0; 1024]);
+
+    let files = read_tar(&data).unwrap();
+    assert_eq!(files.keys().map(|x| &**x).collect::<Vec<&str>>(), vec!["other/versions.json", "rpfm/schemas/schema_wh2.json", &*long_name, "schemas/versions.json"]);
+    assert_eq!(files["schemas/versions.json"], b"{}");
+    assert_eq!(files[&long_name], b"long");
+    assert_eq!(files["rpfm/schemas/schema_wh2.json"], vec![1; 600]);
+
+    // Truncated archives must fail.
+    assert!(read_tar(&data[..512 * 8 + 100]).is_err());
+}
+
+/// Test to make sure the files of an schema update archive are taken from the folder with the shallowest `versions.json`.
+#[test]
+fn test_get_archive_schemas_files() {
+    let mut files = BTreeMap::new();
+    files.insert("update/versions.json".to_owned(), vec![0]);
+    files.insert("update/schema_wh2.json".to_owned(), vec![1]);
+    files.insert("update/old/versions.json".to_owned(), vec![2]);
+    files.insert("readme.txt".to_owned(), vec![3]);
+
+    let files = get_archive_schemas_files(files);
+    assert_eq!(files.keys().map(|x| &**x).collect::<Vec<&str>>(), vec!["old/versions.json", "schema_wh2.json", "versions.json"]);
+    assert_eq!(files["versions.json"], vec![0]);
+
+    // Without a `versions.json` the files are left as they are.
+    let mut files = BTreeMap::new();
+    files.insert("schema_wh2.json".to_owned(), vec![1]);
+    assert_eq!(get_archive_schemas_files(files.clone()), files);
+}
+
+/// Test to make sure only names of schema files are accepted as names of schemas to update, so an update cannot write outside the schemas folder.
+#[test]
+fn test_is_valid_schema_file_name() {
+    for name in &["schema_wh2.json", "schema_3k.json", "schema_new_game.json"] {
+        assert!(is_valid_schema_file_name(name), "{}", name);
+    }
+
+    for name in &["", "schema_.json", "schema_wh2", "wh2.json", "versions.json", "checksums.json", "schema_wh2.local.json", "schema_wh2.json.tmp", "../schema_wh2.json", "schema_../../wh2.json", "schema_a/b.json", "schema_a\\b.json", "/schema_wh2.json"] {
+        assert!(!is_valid_schema_file_name(name), "{}", name);
+    }
+
+    // An update with an invalid name fails before getting any schema.
+    let mut files = BTreeMap::new();
+    files.insert("checksums.json".to_owned(), b"{}".to_vec());
+    let mut remote_versions = BTreeMap::new();
+    remote_versions.insert("../schema_wh2.json".to_owned(), 1);
+    assert_eq!(update_schemas(&SchemaUpdateSource::Archive(files), &BTreeMap::new(), &remote_versions).unwrap_err().kind(), ErrorKind::SchemaUpdateInvalidFileName("../schema_wh2.json".to_owned()));
+}
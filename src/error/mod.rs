@@ -23,6 +23,8 @@ use std::result;
 use std::io;
 use std::string;
 
+use crate::ui::escape_html;
+
 pub mod logger;
 
 /// Alias for handling errors more easely.
@@ -57,6 +59,9 @@ pub enum ErrorKind {
     // Error for when we're trying to merge two invalid files.
     InvalidFilesForMerging,
 
+    // Error for when we're trying to merge tables with rows with the same key and different data.
+    MergeTablesConflicts(Vec<String>),
+
     // Error for when we're trying add/rename/whatever a file with a reserved path.
     ReservedFiles,
 
@@ -108,6 +113,14 @@ pub enum ErrorKind {
     ImportTSVInvalidVersion,
     TSVErrorGeneric,
 
+    //-----------------------------------------------------//
+    //           Translation File-related Errors
+    //-----------------------------------------------------//
+
+    // Error for when we try to import a PO/XLIFF file with a broken line (with the number of the line), or an unknown extension.
+    ImportTranslationFileIncorrectLine(usize),
+    ImportTranslationFileUnknownFormat,
+
     //-----------------------------------------------------//
     //                 PackFile Errors
     //-----------------------------------------------------//
@@ -189,8 +202,20 @@ pub enum ErrorKind {
     // Error for when a DB Table is empty and it doesn't have an schema, so it's undecodeable.
     DBTableEmptyWithNoTableDefinition,
 
-    // Error for when we find missing references when checking a DB Table.
-    DBMissingReferences(Vec<String>),
+    // Error for when we cannot know what table is a DB Table outside the `db` folder.
+    DBTableUnknownTableName,
+
+    // Error for when we find missing references in DB Tables, or markup errors in Loc PackedFiles, when checking the PackFile.
+    CheckTablesErrors(Vec<String>, Vec<String>),
+
+    // Error for when a query over the DB Tables is not valid.
+    QueryInvalid(String),
+
+    // Error for when a query uses a table we don't have.
+    QueryTableNotFound(String),
+
+    // Error for when a query uses a column we don't have in the queried tables.
+    QueryColumnNotFound(String),
 
     // Error for when we don't have an schema to use.
     SchemaNotFound,
@@ -198,6 +223,21 @@ pub enum ErrorKind {
     // Error for when we don't have a table definition for an specific version of a table.
     SchemaTableDefinitionNotFound,
 
+    // Error for when the source of a schema update is not a valid one.
+    SchemaUpdateSourceNotValid(String),
+
+    // Error for when a file we need is missing from the source of a schema update.
+    SchemaUpdateFileNotFound(String),
+
+    // Error for when a schema of an update doesn't match his checksum.
+    SchemaUpdateChecksumMismatch(String),
+
+    // Error for when the name of a schema of an update is not a valid schema file name.
+    SchemaUpdateInvalidFileName(String),
+
+    // Error for when the destination of the schema docs is not inside the source folder of an mdBook.
+    SchemaDocsSummaryNotFound,
+
     //--------------------------------//
     // RigidModel Errors
     //--------------------------------//
@@ -228,6 +268,9 @@ pub enum ErrorKind {
     // Error for when a Text PackedFile fails to decode.
     TextDecode(String),
 
+    // Error for when a Text PackedFile has characters his encoding cannot represent.
+    TextEncodingUnsupportedCharacters(String),
+
     // Error for when we try to use Kailua without a types file.
     NoTypesFileFound,
 
@@ -302,6 +345,12 @@ pub enum ErrorKind {
     // Error for when you can't do something with a PackedFile open in the right side.
     OperationNotAllowedWithPackedFileOpen,
 
+    // Error for when trying to use the raw tables of the Assembly Kit of a game we don't support.
+    AssemblyKitUnsupportedVersion,
+
+    // Error for when trying to import the Assembly Kit's schema without the vanilla tables loaded in the Dependency Database.
+    AssemblyKitNoVanillaTables,
+
     //-----------------------------------------------------//
     //                Contextual Errors
     //-----------------------------------------------------//
@@ -385,7 +434,8 @@ impl Display for ErrorKind {
             ErrorKind::XMLDeserializerError => write!(f, "<p>This should never happen.</p>"),
             ErrorKind::BincodeSerializerError => write!(f, "<p>This should never happen.</p>"),
             ErrorKind::NonExistantFile => write!(f, "<p>The file you tried to... use doesn't exist. This is a bug, because if everything worked propetly, you'll never see this message.</p>"),
-            ErrorKind::InvalidFilesForMerging => write!(f, "<p>The files you selected are not all LOCs, neither DB Tables of the same type and version.</p>"),
+            ErrorKind::InvalidFilesForMerging => write!(f, "<p>The files you selected are not all LOCs, neither DB Tables of the same type.</p>"),
+            ErrorKind::MergeTablesConflicts(conflicts) => write!(f, "<p>The tables you selected have rows with the same key but different data:</p><ul>{}</ul>", conflicts.iter().map(|x| format!("<li>{}</li>", x)).collect::<String>()),
             ErrorKind::ReservedFiles => write!(f, "<p>One or more of the files you're trying to add/create/rename to have a reserved name. Those names are reserved for internal use in RPFM. Please, try again with another name.</p>"),
 
             //-----------------------------------------------------//
@@ -415,6 +465,12 @@ impl Display for ErrorKind {
             ErrorKind::ImportTSVInvalidVersion => write!(f, "<p>This TSV file has an invalid version value at line 1.</p>"),
             ErrorKind::TSVErrorGeneric => write!(f, "<p>Error while trying to import/export a TSV file.</p>"),
 
+            //-----------------------------------------------------//
+            //           Translation File-related Errors
+            //-----------------------------------------------------//
+            ErrorKind::ImportTranslationFileIncorrectLine(line) => write!(f, "<p>This translation file has an error in the <b>line <i>{}</i></b>. Please, check it and make sure it's a valid PO or XLIFF file.</p>", line),
+            ErrorKind::ImportTranslationFileUnknownFormat => write!(f, "<p>This file is not a translation file RPFM can import. Only Gettext PO (.po, .pot) and XLIFF 1.2 (.xlf, .xliff) files are supported.</p>"),
+
             //-----------------------------------------------------//
             //                 PackFile Errors
             //-----------------------------------------------------//
@@ -467,9 +523,22 @@ impl Display for ErrorKind {
             ErrorKind::DBTableReplaceInvalidData => write!(f, "<p>Error while trying to replace the data of a Cell.</p><p>This means you tried to replace a number cell with text, or used a too big, too low or invalid number. Don't do it. It wont end well.</p>"),
             ErrorKind::DBTableDecode(cause) => write!(f, "<p>Error while trying to decode the DB Table:</p><p>{}</p>", cause),
             ErrorKind::DBTableEmptyWithNoTableDefinition => write!(f, "<p>This DB Table is empty and there is not a Table Definition for it. That means is undecodeable.</p>"),
-            ErrorKind::DBMissingReferences(references) => write!(f, "<p>The currently open PackFile has reference errors in the following tables:<ul>{}</ul></p>", references.iter().map(|x| format!("<li>{}<li>", x)).collect::<String>()),
+            ErrorKind::DBTableUnknownTableName => write!(f, "<p>We don't know what table this DB Table is. Tables outside the <i>db</i> folder need to be in a folder with the name of their table (like <i>units_tables</i>), or to be a non-empty table decodeable by only one table of the Schema.</p>"),
+            ErrorKind::CheckTablesErrors(references, markup) => {
+                if !references.is_empty() { write!(f, "<p>The currently open PackFile has reference errors in the following tables:<ul>{}</ul></p>", references.iter().map(|x| format!("<li>{}<li>", x)).collect::<String>())?; }
+                if !markup.is_empty() { write!(f, "<p>The currently open PackFile has markup or placeholder errors in the following loc entries:<ul>{}</ul></p>", markup.iter().map(|x| format!("<li>{}</li>", escape_html(x))).collect::<String>())?; }
+                Ok(())
+            }
+            ErrorKind::QueryInvalid(cause) => write!(f, "<p>Error while trying to parse the query:</p><p>{}</p>", cause),
+            ErrorKind::QueryTableNotFound(table) => write!(f, "<p>The table <i>'{}'</i> has not been found in the open PackFile, nor in the Dependency Database.</p>", table),
+            ErrorKind::QueryColumnNotFound(column) => write!(f, "<p>The column <i>'{}'</i> has not been found in the queried tables.</p>", column),
             ErrorKind::SchemaNotFound => write!(f, "<p>There is no Schema for the Game Selected.</p>"),
             ErrorKind::SchemaTableDefinitionNotFound => write!(f, "<p>There is no Table Definition for this specific version of the table in the Schema.</p>"),
+            ErrorKind::SchemaUpdateSourceNotValid(source) => write!(f, "<p>The source <i>'{}'</i> is not a valid source for schema updates. It has to be an URL, a folder, or a <i>.tar</i>/<i>.tar.xz</i> archive.</p>", source),
+            ErrorKind::SchemaUpdateFileNotFound(file_name) => write!(f, "<p>The file <i>'{}'</i> has not been found in the source of the schema update. No schema has been updated.</p>", file_name),
+            ErrorKind::SchemaUpdateChecksumMismatch(file_name) => write!(f, "<p>The schema <i>'{}'</i> doesn't match his checksum. It's probably corrupted or incomplete, so no schema has been updated.</p>", file_name),
+            ErrorKind::SchemaUpdateInvalidFileName(file_name) => write!(f, "<p>The schema <i>'{}'</i> doesn't have a valid schema file name. Only names like <i>schema_xxx.json</i> are allowed, so no schema has been updated.</p>", escape_html(file_name)),
+            ErrorKind::SchemaDocsSummaryNotFound => write!(f, "<p>There is no <i>SUMMARY.md</i> file in the parent folder of the destination folder. The docs have to be generated in a folder inside the source folder of an mdBook, like <i>docs_src</i>.</p>"),
 
             //--------------------------------//
             // RigidModel Errors
@@ -488,6 +557,7 @@ impl Display for ErrorKind {
 
             // Error for when a Text PackedFile fails to decode.
             ErrorKind::TextDecode(cause) => write!(f, "<p>Error while trying to decode the Text PackedFile:</p><p>{}</p>", cause),
+            ErrorKind::TextEncodingUnsupportedCharacters(encoding) => write!(f, "<p>This Text PackedFile contains characters that cannot be saved as <b>{}</b>, so the last changes have not been saved. Use <i>Convert Encoding</i> to change it to UTF-8 and try again.</p>", encoding),
             ErrorKind::NoTypesFileFound => write!(f, "<p>There is no Types file for the current Game Selected, so you can't use Kailua.</p>"),
             ErrorKind::KailuaNotFound => write!(f, "<p>Kailua executable not found. Install it and try again.</p>"),
 
@@ -530,6 +600,8 @@ impl Display for ErrorKind {
             ErrorKind::PatchSiegeAIEmptyPackFile => write!(f, "<p>This packfile is empty, so we can't patch it.</p>"),
             ErrorKind::PatchSiegeAINoPatchableFiles => write!(f, "<p>There are not files in this Packfile that could be patched/deleted.</p>"),
             ErrorKind::OperationNotAllowedWithPackedFileOpen => write!(f, "<p>This operation cannot be done while there is a PackedFile open. Select a folder or the PackFile to close it and try again.</p>"),
+            ErrorKind::AssemblyKitUnsupportedVersion => write!(f, "<p>The raw tables of the Assembly Kit of the Game Selected are not supported.</p>"),
+            ErrorKind::AssemblyKitNoVanillaTables => write!(f, "<p>There are no vanilla DB Tables in the Dependency Database, so we can't know what versions of the tables to import. Make sure the path of the Game Selected is configured in the <i>'Preferences'</i>, open or create a PackFile so the dependencies get loaded, and try again.</p>"),
 
             //-----------------------------------------------------//
             //                Contextual Errors
@@ -78,7 +78,7 @@ use qt_core::variant::Variant;
 use cpp_utils::StaticCast;
 
 use std::env::args;
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 use std::ops::DerefMut;
 use std::cell::RefCell;
 use std::rc::Rc;
@@ -102,8 +102,9 @@ use crate::packfile::{CompressionState, PathType};
 use crate::packfile::packedfile::PackedFile;
 use crate::packedfile::*;
 use crate::packedfile::db::DB;
+use crate::packedfile::loc::translation_files::TranslationFileFormat;
+use crate::packedfile::registry::{get_packed_file_type_rules, PackedFileTypeRule};
 use crate::packfile::{PFHVersion, PFHFileType, PFHFlags};
-use crate::schema::assembly_kit::*;
 use crate::schema::Schema;
 use crate::settings::*;
 use crate::settings::shortcuts::Shortcuts;
@@ -117,6 +118,8 @@ use crate::ui::packedfile_text::packfile_notes::*;
 use crate::ui::packedfile_rigidmodel::*;
 use crate::ui::packfile_treeview::*;
 use crate::ui::qt_custom_stuff::*;
+use crate::ui::schema_editor::*;
+use crate::ui::translation_view::*;
 use crate::ui::settings::*;
 use crate::ui::table_state::*;
 use crate::ui::updater::*;
@@ -563,6 +566,9 @@ lazy_static! {
     static ref SETTINGS: Arc<Mutex<Settings>> = Arc::new(Mutex::new(Settings::load().unwrap_or_else(|_|Settings::new())));
     static ref SHORTCUTS: Arc<Mutex<Shortcuts>> = Arc::new(Mutex::new(Shortcuts::load().unwrap_or_else(|_|Shortcuts::new())));
 
+    /// The registry of PackedFile types, built from the current Settings. Same as the one above, only edited from the background thread.
+    static ref PACKED_FILE_TYPES: Arc<Mutex<Vec<PackedFileTypeRule>>> = Arc::new(Mutex::new(get_packed_file_type_rules(&SETTINGS.lock().unwrap())));
+
     /// The current GameSelected. Same as the one above, only edited from the background thread.
     static ref GAME_SELECTED: Arc<Mutex<String>> = Arc::new(Mutex::new(SETTINGS.lock().unwrap().settings_string["default_game"].to_owned()));
 
@@ -572,6 +578,10 @@ lazy_static! {
     /// DB Files from the Pak File of the current game. Only for dependency checking.
     static ref FAKE_DEPENDENCY_DATABASE: Mutex<Vec<DB>> = Mutex::new(vec![]);
 
+    /// Paths (lowercase) of the PackedFiles in the PackFiles of the `/data` folder of the current game. Only for asset checking.
+    /// It's loaded the first time it's needed, and cleared when the GameSelected or the Settings change.
+    static ref DATA_PACKFILES_ASSETS: Mutex<Option<BTreeSet<String>>> = Mutex::new(None);
+
     /// Currently loaded schema.
     static ref SCHEMA: Arc<Mutex<Option<Schema>>> = Arc::new(Mutex::new(None));
 
@@ -594,9 +604,8 @@ lazy_static! {
 /// in two different places in every update.
 const VERSION: &str = env!("CARGO_PKG_VERSION");
 
-/// This constant is used to enable or disable the generation of a new Schema file in compile time.
-/// If you don't want to explicity create a new Schema for a game, leave this disabled.
-const GENERATE_NEW_SCHEMA: bool = false;
+/// This constant is the maximum amount of dropped or overwritten rows we show before merging tables.
+const MERGE_PREVIEW_MAX_ROWS: usize = 50;
 
 /// Custom type to deal with QStrings more easely.
 type QString = qt_core::string::String;
@@ -649,6 +658,7 @@ pub struct AppUI {
     pub save_packfile: *mut Action,
     pub save_packfile_as: *mut Action,
     pub load_all_ca_packfiles: *mut Action,
+    pub check_conflicts: *mut Action,
     pub preferences: *mut Action,
     pub quit: *mut Action,
 
@@ -677,6 +687,11 @@ pub struct AppUI {
     
     pub open_game_data_folder: *mut Action,
     pub open_game_assembly_kit_folder: *mut Action,
+    pub import_schema_from_assembly_kit: *mut Action,
+    pub validate_schema: *mut Action,
+    pub diff_schema_files: *mut Action,
+    pub generate_schema_docs: *mut Action,
+    pub open_schema_editor: *mut Action,
 
     pub three_kingdoms: *mut Action,
     pub warhammer_2: *mut Action,
@@ -742,6 +757,7 @@ pub struct AppUI {
     pub patreon_link: *mut Action,
     pub check_updates: *mut Action,
     pub check_schema_updates: *mut Action,
+    pub update_schemas_from_file: *mut Action,
 
     //-------------------------------------------------------------------------------//
     // "Contextual" menu for the TreeView.
@@ -754,7 +770,12 @@ pub struct AppUI {
     pub context_menu_create_loc: *mut Action,
     pub context_menu_create_text: *mut Action,
     pub context_menu_mass_import_tsv: *mut Action,
+    pub context_menu_import_assembly_kit_tables: *mut Action,
     pub context_menu_mass_export_tsv: *mut Action,
+    pub context_menu_export_assembly_kit_tables: *mut Action,
+    pub context_menu_import_translation_files: *mut Action,
+    pub context_menu_export_po: *mut Action,
+    pub context_menu_export_xliff: *mut Action,
     pub context_menu_rename: *mut Action,
     pub context_menu_delete: *mut Action,
     pub context_menu_extract: *mut Action,
@@ -765,6 +786,11 @@ pub struct AppUI {
     pub context_menu_open_in_multi_view: *mut Action,
     pub context_menu_open_notes: *mut Action,
     pub context_menu_check_tables: *mut Action,
+    pub context_menu_check_missing_locs: *mut Action,
+    pub context_menu_check_script_references: *mut Action,
+    pub context_menu_check_xml_files: *mut Action,
+    pub context_menu_open_translation_view: *mut Action,
+    pub context_menu_query_tables: *mut Action,
     pub context_menu_merge_tables: *mut Action,
     pub context_menu_global_search: *mut Action,
 
@@ -1020,6 +1046,7 @@ fn main() {
             save_packfile: menu_bar_packfile.as_mut().unwrap().add_action(&QString::from_std_str("&Save PackFile")),
             save_packfile_as: menu_bar_packfile.as_mut().unwrap().add_action(&QString::from_std_str("Save PackFile &As...")),
             load_all_ca_packfiles: menu_bar_packfile.as_mut().unwrap().add_action(&QString::from_std_str("&Load All CA PackFiles...")),
+            check_conflicts: menu_bar_packfile.as_mut().unwrap().add_action(&QString::from_std_str("Check &Conflicts...")),
             preferences: menu_bar_packfile.as_mut().unwrap().add_action(&QString::from_std_str("&Preferences")),
             quit: menu_bar_packfile.as_mut().unwrap().add_action(&QString::from_std_str("&Quit")),
 
@@ -1048,6 +1075,11 @@ fn main() {
 
             open_game_data_folder: menu_bar_game_seleted.as_mut().unwrap().add_action(&QString::from_std_str("&Open Game's Data Folder")),
             open_game_assembly_kit_folder: menu_bar_game_seleted.as_mut().unwrap().add_action(&QString::from_std_str("Open &Game's Assembly Kit Folder")),
+            import_schema_from_assembly_kit: menu_bar_game_seleted.as_mut().unwrap().add_action(&QString::from_std_str("&Import Schema from Assembly Kit")),
+            validate_schema: menu_bar_game_seleted.as_mut().unwrap().add_action(&QString::from_std_str("&Validate Schema against Game Files")),
+            diff_schema_files: menu_bar_game_seleted.as_mut().unwrap().add_action(&QString::from_std_str("&Compare Schema Files")),
+            generate_schema_docs: menu_bar_game_seleted.as_mut().unwrap().add_action(&QString::from_std_str("&Generate Schema Docs")),
+            open_schema_editor: menu_bar_game_seleted.as_mut().unwrap().add_action(&QString::from_std_str("&Schema Editor")),
         
             three_kingdoms: menu_bar_game_seleted.as_mut().unwrap().add_action(&QString::from_std_str("Three &Kingdoms")),
             warhammer_2: menu_bar_game_seleted.as_mut().unwrap().add_action(&QString::from_std_str("&Warhammer 2")),
@@ -1115,6 +1147,7 @@ fn main() {
             patreon_link: menu_bar_about.as_mut().unwrap().add_action(&QString::from_std_str("&Support me on Patreon")),
             check_updates: menu_bar_about.as_mut().unwrap().add_action(&QString::from_std_str("&Check Updates")),
             check_schema_updates: menu_bar_about.as_mut().unwrap().add_action(&QString::from_std_str("Check Schema &Updates")),
+            update_schemas_from_file: menu_bar_about.as_mut().unwrap().add_action(&QString::from_std_str("Update Schemas from &File")),
 
             //-------------------------------------------------------------------------------//
             // "Contextual" Menu for the TreeView.
@@ -1130,7 +1163,12 @@ fn main() {
             context_menu_create_text: menu_create.as_mut().unwrap().add_action(&QString::from_std_str("Create &Text")),
 
             context_menu_mass_import_tsv: menu_create.as_mut().unwrap().add_action(&QString::from_std_str("Mass-Import TSV")),
+            context_menu_import_assembly_kit_tables: menu_create.as_mut().unwrap().add_action(&QString::from_std_str("Import from Assembly Kit")),
             context_menu_mass_export_tsv: menu_create.as_mut().unwrap().add_action(&QString::from_std_str("Mass-Export TSV")),
+            context_menu_export_assembly_kit_tables: menu_create.as_mut().unwrap().add_action(&QString::from_std_str("Export to Assembly Kit")),
+            context_menu_import_translation_files: menu_create.as_mut().unwrap().add_action(&QString::from_std_str("Import PO/XLIFF")),
+            context_menu_export_po: menu_create.as_mut().unwrap().add_action(&QString::from_std_str("Export Locs to PO")),
+            context_menu_export_xliff: menu_create.as_mut().unwrap().add_action(&QString::from_std_str("Export Locs to XLIFF")),
 
             context_menu_rename: folder_tree_view_context_menu.add_action(&QString::from_std_str("&Rename")),
             context_menu_delete: folder_tree_view_context_menu.add_action(&QString::from_std_str("&Delete")),
@@ -1144,6 +1182,11 @@ fn main() {
             context_menu_open_notes: menu_open.as_mut().unwrap().add_action(&QString::from_std_str("Open &Notes")),
             
             context_menu_check_tables: folder_tree_view_context_menu.add_action(&QString::from_std_str("&Check Tables")),
+            context_menu_check_missing_locs: folder_tree_view_context_menu.add_action(&QString::from_std_str("Check Missing &Locs")),
+            context_menu_check_script_references: folder_tree_view_context_menu.add_action(&QString::from_std_str("Check Script &References")),
+            context_menu_check_xml_files: folder_tree_view_context_menu.add_action(&QString::from_std_str("Check &XML Files")),
+            context_menu_open_translation_view: folder_tree_view_context_menu.add_action(&QString::from_std_str("&Translation View")),
+            context_menu_query_tables: folder_tree_view_context_menu.add_action(&QString::from_std_str("&Query Tables")),
             context_menu_merge_tables: folder_tree_view_context_menu.add_action(&QString::from_std_str("&Merge Tables")),
             context_menu_global_search: folder_tree_view_context_menu.add_action(&QString::from_std_str("&Global Search")),
 
@@ -1244,17 +1287,24 @@ fn main() {
         unsafe { app_ui.save_packfile.as_mut().unwrap().set_shortcut(&KeySequence::from_string(&QString::from_std_str(&SHORTCUTS.lock().unwrap().menu_bar_packfile["save_packfile"]))); }
         unsafe { app_ui.save_packfile_as.as_mut().unwrap().set_shortcut(&KeySequence::from_string(&QString::from_std_str(&SHORTCUTS.lock().unwrap().menu_bar_packfile["save_packfile_as"]))); }
         unsafe { app_ui.load_all_ca_packfiles.as_mut().unwrap().set_shortcut(&KeySequence::from_string(&QString::from_std_str(&SHORTCUTS.lock().unwrap().menu_bar_packfile["load_all_ca_packfiles"]))); }
+        unsafe { app_ui.check_conflicts.as_mut().unwrap().set_shortcut(&KeySequence::from_string(&QString::from_std_str(&SHORTCUTS.lock().unwrap().menu_bar_packfile["check_conflicts"]))); }
         unsafe { app_ui.preferences.as_mut().unwrap().set_shortcut(&KeySequence::from_string(&QString::from_std_str(&SHORTCUTS.lock().unwrap().menu_bar_packfile["preferences"]))); }
         unsafe { app_ui.quit.as_mut().unwrap().set_shortcut(&KeySequence::from_string(&QString::from_std_str(&SHORTCUTS.lock().unwrap().menu_bar_packfile["quit"]))); }
 
         unsafe { app_ui.open_game_data_folder.as_mut().unwrap().set_shortcut(&KeySequence::from_string(&QString::from_std_str(&SHORTCUTS.lock().unwrap().menu_bar_game_selected["open_game_data_folder"]))); }
         unsafe { app_ui.open_game_assembly_kit_folder.as_mut().unwrap().set_shortcut(&KeySequence::from_string(&QString::from_std_str(&SHORTCUTS.lock().unwrap().menu_bar_game_selected["open_game_assembly_kit_folder"]))); }
+        unsafe { app_ui.import_schema_from_assembly_kit.as_mut().unwrap().set_shortcut(&KeySequence::from_string(&QString::from_std_str(&SHORTCUTS.lock().unwrap().menu_bar_game_selected["import_schema_from_assembly_kit"]))); }
+        unsafe { app_ui.validate_schema.as_mut().unwrap().set_shortcut(&KeySequence::from_string(&QString::from_std_str(&SHORTCUTS.lock().unwrap().menu_bar_game_selected["validate_schema"]))); }
+        unsafe { app_ui.diff_schema_files.as_mut().unwrap().set_shortcut(&KeySequence::from_string(&QString::from_std_str(&SHORTCUTS.lock().unwrap().menu_bar_game_selected["diff_schema_files"]))); }
+        unsafe { app_ui.generate_schema_docs.as_mut().unwrap().set_shortcut(&KeySequence::from_string(&QString::from_std_str(&SHORTCUTS.lock().unwrap().menu_bar_game_selected["generate_schema_docs"]))); }
+        unsafe { app_ui.open_schema_editor.as_mut().unwrap().set_shortcut(&KeySequence::from_string(&QString::from_std_str(&SHORTCUTS.lock().unwrap().menu_bar_game_selected["open_schema_editor"]))); }
         
         unsafe { app_ui.about_qt.as_mut().unwrap().set_shortcut(&KeySequence::from_string(&QString::from_std_str(&SHORTCUTS.lock().unwrap().menu_bar_about["about_qt"]))); }
         unsafe { app_ui.about_rpfm.as_mut().unwrap().set_shortcut(&KeySequence::from_string(&QString::from_std_str(&SHORTCUTS.lock().unwrap().menu_bar_about["about_rpfm"]))); }
         unsafe { app_ui.open_manual.as_mut().unwrap().set_shortcut(&KeySequence::from_string(&QString::from_std_str(&SHORTCUTS.lock().unwrap().menu_bar_about["open_manual"]))); }
         unsafe { app_ui.check_updates.as_mut().unwrap().set_shortcut(&KeySequence::from_string(&QString::from_std_str(&SHORTCUTS.lock().unwrap().menu_bar_about["check_updates"]))); }
         unsafe { app_ui.check_schema_updates.as_mut().unwrap().set_shortcut(&KeySequence::from_string(&QString::from_std_str(&SHORTCUTS.lock().unwrap().menu_bar_about["check_schema_updates"]))); }
+        unsafe { app_ui.update_schemas_from_file.as_mut().unwrap().set_shortcut(&KeySequence::from_string(&QString::from_std_str(&SHORTCUTS.lock().unwrap().menu_bar_about["update_schemas_from_file"]))); }
 
         // Set the shortcuts to only trigger in the TreeView.
         unsafe { app_ui.new_packfile.as_mut().unwrap().set_shortcut_context(ShortcutContext::Application); }
@@ -1262,17 +1312,24 @@ fn main() {
         unsafe { app_ui.save_packfile.as_mut().unwrap().set_shortcut_context(ShortcutContext::Application); }
         unsafe { app_ui.save_packfile_as.as_mut().unwrap().set_shortcut_context(ShortcutContext::Application); }
         unsafe { app_ui.load_all_ca_packfiles.as_mut().unwrap().set_shortcut_context(ShortcutContext::Application); }
+        unsafe { app_ui.check_conflicts.as_mut().unwrap().set_shortcut_context(ShortcutContext::Application); }
         unsafe { app_ui.preferences.as_mut().unwrap().set_shortcut_context(ShortcutContext::Application); }
         unsafe { app_ui.quit.as_mut().unwrap().set_shortcut_context(ShortcutContext::Application); }
 
         unsafe { app_ui.open_game_data_folder.as_mut().unwrap().set_shortcut_context(ShortcutContext::Application); }
         unsafe { app_ui.open_game_assembly_kit_folder.as_mut().unwrap().set_shortcut_context(ShortcutContext::Application); }
+        unsafe { app_ui.import_schema_from_assembly_kit.as_mut().unwrap().set_shortcut_context(ShortcutContext::Application); }
+        unsafe { app_ui.validate_schema.as_mut().unwrap().set_shortcut_context(ShortcutContext::Application); }
+        unsafe { app_ui.diff_schema_files.as_mut().unwrap().set_shortcut_context(ShortcutContext::Application); }
+        unsafe { app_ui.generate_schema_docs.as_mut().unwrap().set_shortcut_context(ShortcutContext::Application); }
+        unsafe { app_ui.open_schema_editor.as_mut().unwrap().set_shortcut_context(ShortcutContext::Application); }
         
         unsafe { app_ui.about_qt.as_mut().unwrap().set_shortcut_context(ShortcutContext::Application); }
         unsafe { app_ui.about_rpfm.as_mut().unwrap().set_shortcut_context(ShortcutContext::Application); }
         unsafe { app_ui.open_manual.as_mut().unwrap().set_shortcut_context(ShortcutContext::Application); }
         unsafe { app_ui.check_updates.as_mut().unwrap().set_shortcut_context(ShortcutContext::Application); }
         unsafe { app_ui.check_schema_updates.as_mut().unwrap().set_shortcut_context(ShortcutContext::Application); }
+        unsafe { app_ui.update_schemas_from_file.as_mut().unwrap().set_shortcut_context(ShortcutContext::Application); }
 
         //---------------------------------------------------------------------------------------//
         // Preparing initial state of the Main Window...
@@ -1336,7 +1393,12 @@ fn main() {
             app_ui.context_menu_create_loc.as_mut().unwrap().set_enabled(false);
             app_ui.context_menu_create_text.as_mut().unwrap().set_enabled(false);
             app_ui.context_menu_mass_import_tsv.as_mut().unwrap().set_enabled(false);
+            app_ui.context_menu_import_assembly_kit_tables.as_mut().unwrap().set_enabled(false);
             app_ui.context_menu_mass_export_tsv.as_mut().unwrap().set_enabled(false);
+            app_ui.context_menu_export_assembly_kit_tables.as_mut().unwrap().set_enabled(false);
+            app_ui.context_menu_import_translation_files.as_mut().unwrap().set_enabled(false);
+            app_ui.context_menu_export_po.as_mut().unwrap().set_enabled(false);
+            app_ui.context_menu_export_xliff.as_mut().unwrap().set_enabled(false);
             app_ui.context_menu_delete.as_mut().unwrap().set_enabled(false);
             app_ui.context_menu_extract.as_mut().unwrap().set_enabled(false);
             app_ui.context_menu_rename.as_mut().unwrap().set_enabled(false);
@@ -1353,12 +1415,22 @@ fn main() {
         unsafe { app_ui.context_menu_add_folder.as_mut().unwrap().set_shortcut(&KeySequence::from_string(&QString::from_std_str(&SHORTCUTS.lock().unwrap().tree_view["add_folder"]))); }
         unsafe { app_ui.context_menu_add_from_packfile.as_mut().unwrap().set_shortcut(&KeySequence::from_string(&QString::from_std_str(&SHORTCUTS.lock().unwrap().tree_view["add_from_packfile"]))); }
         unsafe { app_ui.context_menu_check_tables.as_mut().unwrap().set_shortcut(&KeySequence::from_string(&QString::from_std_str(&SHORTCUTS.lock().unwrap().tree_view["check_tables"]))); }
+        unsafe { app_ui.context_menu_check_missing_locs.as_mut().unwrap().set_shortcut(&KeySequence::from_string(&QString::from_std_str(&SHORTCUTS.lock().unwrap().tree_view["check_missing_locs"]))); }
+        unsafe { app_ui.context_menu_check_script_references.as_mut().unwrap().set_shortcut(&KeySequence::from_string(&QString::from_std_str(&SHORTCUTS.lock().unwrap().tree_view["check_script_references"]))); }
+        unsafe { app_ui.context_menu_check_xml_files.as_mut().unwrap().set_shortcut(&KeySequence::from_string(&QString::from_std_str(&SHORTCUTS.lock().unwrap().tree_view["check_xml_files"]))); }
+        unsafe { app_ui.context_menu_open_translation_view.as_mut().unwrap().set_shortcut(&KeySequence::from_string(&QString::from_std_str(&SHORTCUTS.lock().unwrap().tree_view["open_translation_view"]))); }
+        unsafe { app_ui.context_menu_query_tables.as_mut().unwrap().set_shortcut(&KeySequence::from_string(&QString::from_std_str(&SHORTCUTS.lock().unwrap().tree_view["query_tables"]))); }
         unsafe { app_ui.context_menu_create_folder.as_mut().unwrap().set_shortcut(&KeySequence::from_string(&QString::from_std_str(&SHORTCUTS.lock().unwrap().tree_view["create_folder"]))); }
         unsafe { app_ui.context_menu_create_db.as_mut().unwrap().set_shortcut(&KeySequence::from_string(&QString::from_std_str(&SHORTCUTS.lock().unwrap().tree_view["create_db"]))); }
         unsafe { app_ui.context_menu_create_loc.as_mut().unwrap().set_shortcut(&KeySequence::from_string(&QString::from_std_str(&SHORTCUTS.lock().unwrap().tree_view["create_loc"]))); }
         unsafe { app_ui.context_menu_create_text.as_mut().unwrap().set_shortcut(&KeySequence::from_string(&QString::from_std_str(&SHORTCUTS.lock().unwrap().tree_view["create_text"]))); }
         unsafe { app_ui.context_menu_mass_import_tsv.as_mut().unwrap().set_shortcut(&KeySequence::from_string(&QString::from_std_str(&SHORTCUTS.lock().unwrap().tree_view["mass_import_tsv"]))); }
+        unsafe { app_ui.context_menu_import_assembly_kit_tables.as_mut().unwrap().set_shortcut(&KeySequence::from_string(&QString::from_std_str(&SHORTCUTS.lock().unwrap().tree_view["import_assembly_kit_tables"]))); }
         unsafe { app_ui.context_menu_mass_export_tsv.as_mut().unwrap().set_shortcut(&KeySequence::from_string(&QString::from_std_str(&SHORTCUTS.lock().unwrap().tree_view["mass_export_tsv"]))); }
+        unsafe { app_ui.context_menu_export_assembly_kit_tables.as_mut().unwrap().set_shortcut(&KeySequence::from_string(&QString::from_std_str(&SHORTCUTS.lock().unwrap().tree_view["export_assembly_kit_tables"]))); }
+        unsafe { app_ui.context_menu_import_translation_files.as_mut().unwrap().set_shortcut(&KeySequence::from_string(&QString::from_std_str(&SHORTCUTS.lock().unwrap().tree_view["import_translation_files"]))); }
+        unsafe { app_ui.context_menu_export_po.as_mut().unwrap().set_shortcut(&KeySequence::from_string(&QString::from_std_str(&SHORTCUTS.lock().unwrap().tree_view["export_po"]))); }
+        unsafe { app_ui.context_menu_export_xliff.as_mut().unwrap().set_shortcut(&KeySequence::from_string(&QString::from_std_str(&SHORTCUTS.lock().unwrap().tree_view["export_xliff"]))); }
         unsafe { app_ui.context_menu_merge_tables.as_mut().unwrap().set_shortcut(&KeySequence::from_string(&QString::from_std_str(&SHORTCUTS.lock().unwrap().tree_view["merge_tables"]))); }
         unsafe { app_ui.context_menu_delete.as_mut().unwrap().set_shortcut(&KeySequence::from_string(&QString::from_std_str(&SHORTCUTS.lock().unwrap().tree_view["delete"]))); }
         unsafe { app_ui.context_menu_extract.as_mut().unwrap().set_shortcut(&KeySequence::from_string(&QString::from_std_str(&SHORTCUTS.lock().unwrap().tree_view["extract"]))); }
@@ -1378,12 +1450,22 @@ fn main() {
         unsafe { app_ui.context_menu_add_folder.as_mut().unwrap().set_shortcut_context(ShortcutContext::Widget); }
         unsafe { app_ui.context_menu_add_from_packfile.as_mut().unwrap().set_shortcut_context(ShortcutContext::Widget); }
         unsafe { app_ui.context_menu_check_tables.as_mut().unwrap().set_shortcut_context(ShortcutContext::Widget); }
+        unsafe { app_ui.context_menu_check_missing_locs.as_mut().unwrap().set_shortcut_context(ShortcutContext::Widget); }
+        unsafe { app_ui.context_menu_check_script_references.as_mut().unwrap().set_shortcut_context(ShortcutContext::Widget); }
+        unsafe { app_ui.context_menu_check_xml_files.as_mut().unwrap().set_shortcut_context(ShortcutContext::Widget); }
+        unsafe { app_ui.context_menu_open_translation_view.as_mut().unwrap().set_shortcut_context(ShortcutContext::Widget); }
+        unsafe { app_ui.context_menu_query_tables.as_mut().unwrap().set_shortcut_context(ShortcutContext::Widget); }
         unsafe { app_ui.context_menu_create_folder.as_mut().unwrap().set_shortcut_context(ShortcutContext::Widget); }
         unsafe { app_ui.context_menu_create_db.as_mut().unwrap().set_shortcut_context(ShortcutContext::Widget); }
         unsafe { app_ui.context_menu_create_loc.as_mut().unwrap().set_shortcut_context(ShortcutContext::Widget); }
         unsafe { app_ui.context_menu_create_text.as_mut().unwrap().set_shortcut_context(ShortcutContext::Widget); }
         unsafe { app_ui.context_menu_mass_import_tsv.as_mut().unwrap().set_shortcut_context(ShortcutContext::Widget); }
+        unsafe { app_ui.context_menu_import_assembly_kit_tables.as_mut().unwrap().set_shortcut_context(ShortcutContext::Widget); }
         unsafe { app_ui.context_menu_mass_export_tsv.as_mut().unwrap().set_shortcut_context(ShortcutContext::Widget); }
+        unsafe { app_ui.context_menu_export_assembly_kit_tables.as_mut().unwrap().set_shortcut_context(ShortcutContext::Widget); }
+        unsafe { app_ui.context_menu_import_translation_files.as_mut().unwrap().set_shortcut_context(ShortcutContext::Widget); }
+        unsafe { app_ui.context_menu_export_po.as_mut().unwrap().set_shortcut_context(ShortcutContext::Widget); }
+        unsafe { app_ui.context_menu_export_xliff.as_mut().unwrap().set_shortcut_context(ShortcutContext::Widget); }
         unsafe { app_ui.context_menu_merge_tables.as_mut().unwrap().set_shortcut_context(ShortcutContext::Widget); }
         unsafe { app_ui.context_menu_delete.as_mut().unwrap().set_shortcut_context(ShortcutContext::Widget); }
         unsafe { app_ui.context_menu_extract.as_mut().unwrap().set_shortcut_context(ShortcutContext::Widget); }
@@ -1403,12 +1485,22 @@ fn main() {
         unsafe { app_ui.folder_tree_view.as_mut().unwrap().add_action(app_ui.context_menu_add_folder); }
         unsafe { app_ui.folder_tree_view.as_mut().unwrap().add_action(app_ui.context_menu_add_from_packfile); }
         unsafe { app_ui.folder_tree_view.as_mut().unwrap().add_action(app_ui.context_menu_check_tables); }
+        unsafe { app_ui.folder_tree_view.as_mut().unwrap().add_action(app_ui.context_menu_check_missing_locs); }
+        unsafe { app_ui.folder_tree_view.as_mut().unwrap().add_action(app_ui.context_menu_check_script_references); }
+        unsafe { app_ui.folder_tree_view.as_mut().unwrap().add_action(app_ui.context_menu_check_xml_files); }
+        unsafe { app_ui.folder_tree_view.as_mut().unwrap().add_action(app_ui.context_menu_open_translation_view); }
+        unsafe { app_ui.folder_tree_view.as_mut().unwrap().add_action(app_ui.context_menu_query_tables); }
         unsafe { app_ui.folder_tree_view.as_mut().unwrap().add_action(app_ui.context_menu_create_folder); }
         unsafe { app_ui.folder_tree_view.as_mut().unwrap().add_action(app_ui.context_menu_create_db); }
         unsafe { app_ui.folder_tree_view.as_mut().unwrap().add_action(app_ui.context_menu_create_loc); }
         unsafe { app_ui.folder_tree_view.as_mut().unwrap().add_action(app_ui.context_menu_create_text); }
         unsafe { app_ui.folder_tree_view.as_mut().unwrap().add_action(app_ui.context_menu_mass_import_tsv); }
+        unsafe { app_ui.folder_tree_view.as_mut().unwrap().add_action(app_ui.context_menu_import_assembly_kit_tables); }
         unsafe { app_ui.folder_tree_view.as_mut().unwrap().add_action(app_ui.context_menu_mass_export_tsv); }
+        unsafe { app_ui.folder_tree_view.as_mut().unwrap().add_action(app_ui.context_menu_export_assembly_kit_tables); }
+        unsafe { app_ui.folder_tree_view.as_mut().unwrap().add_action(app_ui.context_menu_import_translation_files); }
+        unsafe { app_ui.folder_tree_view.as_mut().unwrap().add_action(app_ui.context_menu_export_po); }
+        unsafe { app_ui.folder_tree_view.as_mut().unwrap().add_action(app_ui.context_menu_export_xliff); }
         unsafe { app_ui.folder_tree_view.as_mut().unwrap().add_action(app_ui.context_menu_merge_tables); }
         unsafe { app_ui.folder_tree_view.as_mut().unwrap().add_action(app_ui.context_menu_delete); }
         unsafe { app_ui.folder_tree_view.as_mut().unwrap().add_action(app_ui.context_menu_extract); }
@@ -1436,6 +1528,7 @@ fn main() {
         unsafe { app_ui.save_packfile.as_mut().unwrap().set_status_tip(&QString::from_std_str("Save the changes made in the currently open PackFile to disk.")); }
         unsafe { app_ui.save_packfile_as.as_mut().unwrap().set_status_tip(&QString::from_std_str("Save the currently open PackFile as a new PackFile, instead of overwriting the original one.")); }
         unsafe { app_ui.load_all_ca_packfiles.as_mut().unwrap().set_status_tip(&QString::from_std_str("Try to load every PackedFile from every vanilla PackFile of the selected game into RPFM at the same time, using lazy-loading to load the PackedFiles. Keep in mind that if you try to save it, your PC may die.")); }
+        unsafe { app_ui.check_conflicts.as_mut().unwrap().set_status_tip(&QString::from_std_str("Check a list of mod PackFiles, in the order you select them, for overwritten PackedFiles and conflicting DB/Loc rows between them.")); }
         unsafe { app_ui.change_packfile_type_boot.as_mut().unwrap().set_status_tip(&QString::from_std_str("Changes the PackFile's Type to Boot. You should never use it.")); }
         unsafe { app_ui.change_packfile_type_release.as_mut().unwrap().set_status_tip(&QString::from_std_str("Changes the PackFile's Type to Release. You should never use it.")); }
         unsafe { app_ui.change_packfile_type_patch.as_mut().unwrap().set_status_tip(&QString::from_std_str("Changes the PackFile's Type to Patch. You should never use it.")); }
@@ -1455,6 +1548,11 @@ fn main() {
         // Menu bar, Game Selected.
         unsafe { app_ui.open_game_data_folder.as_mut().unwrap().set_status_tip(&QString::from_std_str("Tries to open the currently selected game's Data folder (if exists) in the default file manager.")); }
         unsafe { app_ui.open_game_assembly_kit_folder.as_mut().unwrap().set_status_tip(&QString::from_std_str("Tries to open the currently selected game's Assembly Kit folder (if exists) in the default file manager.")); }
+        unsafe { app_ui.import_schema_from_assembly_kit.as_mut().unwrap().set_status_tip(&QString::from_std_str("Imports the table definitions from the currently selected game's Assembly Kit into his schema, and reports the new tables, the new versions and the fields that differ from the Assembly Kit.")); }
+        unsafe { app_ui.validate_schema.as_mut().unwrap().set_status_tip(&QString::from_std_str("Tries to decode every DB Table in the currently selected game's PackFiles with the current schema, and reports what tables failed to decode, what tables have no definition and what tables decoded correctly.")); }
+        unsafe { app_ui.diff_schema_files.as_mut().unwrap().set_status_tip(&QString::from_std_str("Compares two schema files and exports the tables, versions and fields added, removed or changed between them as JSON and MarkDown.")); }
+        unsafe { app_ui.generate_schema_docs.as_mut().unwrap().set_status_tip(&QString::from_std_str("Generates MarkDown documentation of the currently selected game's schema, with one page per table, in a folder inside the source folder of an mdBook. The pages are added to the SUMMARY.md of the book.")); }
+        unsafe { app_ui.open_schema_editor.as_mut().unwrap().set_status_tip(&QString::from_std_str("Opens the Schema Editor, to browse and edit the table definitions of the currently selected game's schema.")); }
         
         unsafe { app_ui.three_kingdoms.as_mut().unwrap().set_status_tip(&QString::from_std_str("Sets 'TW:Three Kingdoms' as 'Game Selected'.")); }
         unsafe { app_ui.warhammer_2.as_mut().unwrap().set_status_tip(&QString::from_std_str("Sets 'TW:Warhammer 2' as 'Game Selected'.")); }
@@ -1499,18 +1597,29 @@ fn main() {
         unsafe { app_ui.patreon_link.as_mut().unwrap().set_status_tip(&QString::from_std_str("Open RPFM's Patreon page. Even if you are not interested in becoming a Patron, check it out. I post info about the next updates and in-dev features from time to time.")); }
         unsafe { app_ui.check_updates.as_mut().unwrap().set_status_tip(&QString::from_std_str("Checks if there is any update available for RPFM.")); }
         unsafe { app_ui.check_schema_updates.as_mut().unwrap().set_status_tip(&QString::from_std_str("Checks if there is any update available for the schemas. This is what you have to use after a game's patch.")); }
+        unsafe { app_ui.update_schemas_from_file.as_mut().unwrap().set_status_tip(&QString::from_std_str("Updates the schemas from a local folder (select his 'versions.json') or a '.tar'/'.tar.xz' archive, instead of downloading them. Useful when you have no internet connection.")); }
 
         // Context Menu.
         unsafe { app_ui.context_menu_add_file.as_mut().unwrap().set_status_tip(&QString::from_std_str("Add one or more files to the currently open PackFile. Existing files are not overwriten!")); }
         unsafe { app_ui.context_menu_add_folder.as_mut().unwrap().set_status_tip(&QString::from_std_str("Add a folder to the currently open PackFile. Existing files are not overwriten!")); }
         unsafe { app_ui.context_menu_add_from_packfile.as_mut().unwrap().set_status_tip(&QString::from_std_str("Add files from another PackFile to the currently open PackFile. Existing files are not overwriten!")); }
-        unsafe { app_ui.context_menu_check_tables.as_mut().unwrap().set_status_tip(&QString::from_std_str("Check all the DB Tables of the currently open PackFile for dependency errors.")); }
+        unsafe { app_ui.context_menu_check_tables.as_mut().unwrap().set_status_tip(&QString::from_std_str("Check all the DB Tables of the currently open PackFile for dependency errors, and all the Loc PackedFiles for unbalanced or unknown markup tags and placeholders that differ from the vanilla ones.")); }
+        unsafe { app_ui.context_menu_check_missing_locs.as_mut().unwrap().set_status_tip(&QString::from_std_str("Check all the DB Tables of the currently open PackFile for missing localisation entries, and optionally create a Loc PackedFile with placeholders for them.")); }
+        unsafe { app_ui.context_menu_check_script_references.as_mut().unwrap().set_status_tip(&QString::from_std_str("Check the DB keys and loc keys the Lua scripts of the currently open PackFile pass to the CA interface functions, reporting the ones not found in the PackFile or its dependencies.")); }
+        unsafe { app_ui.context_menu_check_xml_files.as_mut().unwrap().set_status_tip(&QString::from_std_str("Check all the XML PackedFiles of the currently open PackFile for parse errors, and for references to meshes, materials and textures not found in the PackFile or in the game's data PackFiles.")); }
+        unsafe { app_ui.context_menu_open_translation_view.as_mut().unwrap().set_status_tip(&QString::from_std_str("Open the Translation View, to edit all the Loc PackedFiles of the currently open PackFile side by side, highlighting the missing, untranslated and stale entries.")); }
+        unsafe { app_ui.context_menu_query_tables.as_mut().unwrap().set_status_tip(&QString::from_std_str("Run SQL-like queries over the DB Tables of the currently open PackFile and the Dependency Database.")); }
         unsafe { app_ui.context_menu_create_folder.as_mut().unwrap().set_status_tip(&QString::from_std_str("Open the dialog to create an empty folder. Due to how the PackFiles are done, these are NOT KEPT ON SAVING if they stay empty.")); }
         unsafe { app_ui.context_menu_create_loc.as_mut().unwrap().set_status_tip(&QString::from_std_str("Open the dialog to create a Loc File (used by the game to store the texts you see ingame) in the selected folder.")); }
         unsafe { app_ui.context_menu_create_db.as_mut().unwrap().set_status_tip(&QString::from_std_str("Open the dialog to create a DB Table (used by the game for... most of the things).")); }
         unsafe { app_ui.context_menu_create_text.as_mut().unwrap().set_status_tip(&QString::from_std_str("Open the dialog to create a Plain Text File. It accepts different extensions, like '.xml', '.lua', '.txt',....")); }
         unsafe { app_ui.context_menu_mass_import_tsv.as_mut().unwrap().set_status_tip(&QString::from_std_str("Import a bunch of TSV files at the same time. It automatically checks if they are DB Tables, Locs or invalid TSVs, and imports them all at once. Existing files will be overwritten!")); }
+        unsafe { app_ui.context_menu_import_assembly_kit_tables.as_mut().unwrap().set_status_tip(&QString::from_std_str("Import one or more raw tables from the Assembly Kit as DB Tables, using the definition of each table for the current version of the game. Each table needs his TWaD_ file in the same folder. Existing files will be overwritten!")); }
         unsafe { app_ui.context_menu_mass_export_tsv.as_mut().unwrap().set_status_tip(&QString::from_std_str("Export every DB Table and Loc PackedFile from this PackFile as TSV files at the same time. Existing files will be overwritten!")); }
+        unsafe { app_ui.context_menu_export_assembly_kit_tables.as_mut().unwrap().set_status_tip(&QString::from_std_str("Export the selected DB Tables as raw tables for the Assembly Kit, with their TWaD_ definitions. Tables of the same type are merged into one. Existing files will be overwritten!")); }
+        unsafe { app_ui.context_menu_import_translation_files.as_mut().unwrap().set_status_tip(&QString::from_std_str("Import Gettext PO or XLIFF 1.2 files into the Loc PackedFiles they were exported from, updating the existing entries and adding the new ones by key. Missing Loc PackedFiles are created.")); }
+        unsafe { app_ui.context_menu_export_po.as_mut().unwrap().set_status_tip(&QString::from_std_str("Export the selected Loc PackedFiles (or all of them, if the PackFile is selected) as Gettext PO files, with the vanilla english text as source. Existing files will be overwritten!")); }
+        unsafe { app_ui.context_menu_export_xliff.as_mut().unwrap().set_status_tip(&QString::from_std_str("Export the selected Loc PackedFiles (or all of them, if the PackFile is selected) as XLIFF 1.2 files, with the vanilla english text as source. Existing files will be overwritten!")); }
         unsafe { app_ui.context_menu_merge_tables.as_mut().unwrap().set_status_tip(&QString::from_std_str("Merge multple DB Tables/Loc PackedFiles into one.")); }
         unsafe { app_ui.context_menu_delete.as_mut().unwrap().set_status_tip(&QString::from_std_str("Delete the selected File/Folder.")); }
         unsafe { app_ui.context_menu_extract.as_mut().unwrap().set_status_tip(&QString::from_std_str("Extract the selected File/Folder from the PackFile.")); }
@@ -1968,6 +2077,40 @@ fn main() {
             }
         ));
 
+        // What happens when we trigger the "Check Conflicts" action.
+        let slot_check_conflicts = SlotBool::new(clone!(
+            sender_qt,
+            sender_qt_data,
+            receiver_qt => move |_| {
+
+                // Create the FileDialog to get the PackFiles to check and configure it.
+                let mut file_dialog = unsafe { FileDialog::new_unsafe((
+                    app_ui.window as *mut Widget,
+                    &QString::from_std_str("Select PackFiles to Check"),
+                )) };
+                file_dialog.set_name_filter(&QString::from_std_str("PackFiles (*.pack)"));
+                file_dialog.set_file_mode(FileMode::ExistingFiles);
+
+                // Run it and expect a response (1 => Accept, 0 => Cancel).
+                if file_dialog.exec() == 1 {
+                    let mut paths = vec![];
+                    for index in 0..file_dialog.selected_files().count(()) {
+                        paths.push(PathBuf::from(file_dialog.selected_files().at(index).to_std_string()));
+                    }
+
+                    unsafe { (app_ui.window.as_mut().unwrap() as &mut Widget).set_enabled(false); }
+                    sender_qt.send(Commands::CheckPackFilesConflicts).unwrap();
+                    sender_qt_data.send(Data::VecPathBuf(paths)).unwrap();
+                    match check_message_validity_tryrecv(&receiver_qt) {
+                        Data::String(report) => show_report_dialog(app_ui.window, "Conflicts", &report),
+                        Data::Error(error) => show_dialog(app_ui.window, false, error),
+                        _ => panic!(THREADS_MESSAGE_ERROR),
+                    }
+                    unsafe { (app_ui.window.as_mut().unwrap() as &mut Widget).set_enabled(true); }
+                }
+            }
+        ));
+
         // What happens when we trigger the "Preferences" action.
         let slot_preferences = SlotBool::new(clone!(
             mode,
@@ -2056,6 +2199,7 @@ fn main() {
         unsafe { app_ui.save_packfile.as_ref().unwrap().signals().triggered().connect(&slot_save_packfile); }
         unsafe { app_ui.save_packfile_as.as_ref().unwrap().signals().triggered().connect(&slot_save_packfile_as); }
         unsafe { app_ui.load_all_ca_packfiles.as_ref().unwrap().signals().triggered().connect(&slot_load_all_ca_packfiles); }
+        unsafe { app_ui.check_conflicts.as_ref().unwrap().signals().triggered().connect(&slot_check_conflicts); }
 
         unsafe { app_ui.change_packfile_type_boot.as_ref().unwrap().signals().triggered().connect(&slot_change_packfile_type); }
         unsafe { app_ui.change_packfile_type_release.as_ref().unwrap().signals().triggered().connect(&slot_change_packfile_type); }
@@ -2089,9 +2233,165 @@ fn main() {
             else { show_dialog(app_ui.window, false, ErrorKind::GamePathNotConfigured); }
         });
 
+        // What happens when we trigger the "Import Schema from Assembly Kit" action.
+        let slot_import_schema_from_assembly_kit = SlotBool::new(clone!(
+            sender_qt,
+            sender_qt_data,
+            receiver_qt => move |_| {
+
+                // For Rome 2+, we need the game path set. For Shogun 2, we have to ask for a path.
+                let version = SUPPORTED_GAMES.get(&**GAME_SELECTED.lock().unwrap()).unwrap().raw_db_version;
+                let path = match version {
+
+                    // Post-Shogun 2 games.
+                    2 => {
+                        match get_game_selected_assembly_kit_path() {
+                            Some(mut path) => {
+                                path.push("raw_data");
+                                path.push("db");
+                                path
+                            }
+                            None => return show_dialog(app_ui.window, false, ErrorKind::GamePathNotConfigured),
+                        }
+                    }
+
+                    // Shogun 2.
+                    1 => {
+
+                        // Create the FileDialog to get the path of the Assembly Kit.
+                        let mut file_dialog = unsafe { FileDialog::new_unsafe((
+                            app_ui.window as *mut Widget,
+                            &QString::from_std_str("Select Assembly Kit's Folder"),
+                        )) };
+
+                        // Set it to only search Folders.
+                        file_dialog.set_file_mode(FileMode::Directory);
+                        file_dialog.set_option(ShowDirsOnly);
+
+                        // Run it and expect a response (1 => Accept, 0 => Cancel).
+                        if file_dialog.exec() == 1 { 
+                            let mut path = PathBuf::from(file_dialog.selected_files().at(0).to_std_string());
+                            path.push("raw_data");
+                            path.push("db");
+                            path
+                        } else { return }
+                    }
+
+                    // Any other game doesn't have a supported Assembly Kit.
+                    _ => return show_dialog(app_ui.window, false, ErrorKind::AssemblyKitUnsupportedVersion),
+                };
+
+                unsafe { (app_ui.window.as_mut().unwrap() as &mut Widget).set_enabled(false); }
+                sender_qt.send(Commands::ImportSchemaFromAssemblyKit).unwrap();
+                sender_qt_data.send(Data::PathBuf(path)).unwrap();
+                match check_message_validity_tryrecv(&receiver_qt) {
+                    Data::ImportSchemaReport(report) => show_report_dialog(app_ui.window, "Schema Import Report", &report.to_string()),
+                    Data::Error(error) => show_dialog(app_ui.window, false, error),
+                    _ => panic!(THREADS_MESSAGE_ERROR),
+                }
+                unsafe { (app_ui.window.as_mut().unwrap() as &mut Widget).set_enabled(true); }
+            }
+        ));
+
+        // What happens when we trigger the "Validate Schema against Game Files" action.
+        let slot_validate_schema = SlotBool::new(clone!(
+            sender_qt,
+            receiver_qt => move |_| {
+                unsafe { (app_ui.window.as_mut().unwrap() as &mut Widget).set_enabled(false); }
+                sender_qt.send(Commands::ValidateSchema).unwrap();
+                match check_message_validity_tryrecv(&receiver_qt) {
+                    Data::String(report) => show_report_dialog(app_ui.window, "Schema Validation Report", &report),
+                    Data::Error(error) => show_dialog(app_ui.window, false, error),
+                    _ => panic!(THREADS_MESSAGE_ERROR),
+                }
+                unsafe { (app_ui.window.as_mut().unwrap() as &mut Widget).set_enabled(true); }
+            }
+        ));
+
+        // What happens when we trigger the "Compare Schema Files" action.
+        let slot_diff_schema_files = SlotBool::new(clone!(
+            sender_qt,
+            sender_qt_data,
+            receiver_qt => move |_| {
+
+                // We need the old schema, the new schema, and where to export the diff.
+                let mut paths = vec![];
+                for (title, is_export) in &[("Select the Old Schema File", false), ("Select the New Schema File", false), ("Export Schema Diff", true)] {
+                    let mut file_dialog = unsafe { FileDialog::new_unsafe((
+                        app_ui.window as *mut Widget,
+                        &QString::from_std_str(title),
+                    )) };
+
+                    file_dialog.set_name_filter(&QString::from_std_str("JSON Files (*.json)"));
+                    if *is_export {
+                        file_dialog.set_accept_mode(qt_widgets::file_dialog::AcceptMode::Save);
+                        file_dialog.set_confirm_overwrite(true);
+                        file_dialog.set_default_suffix(&QString::from_std_str("json"));
+                    }
+
+                    if file_dialog.exec() == 1 { paths.push(PathBuf::from(file_dialog.selected_files().at(0).to_std_string())); }
+                    else { return }
+                }
+
+                sender_qt.send(Commands::DiffSchemaFiles).unwrap();
+                sender_qt_data.send(Data::PathBufPathBufPathBuf((paths[0].to_path_buf(), paths[1].to_path_buf(), paths[2].to_path_buf()))).unwrap();
+                match check_message_validity_recv2(&receiver_qt) {
+                    Data::String(diff) => show_report_dialog(app_ui.window, "Schema Diff", &format!("<pre>{}</pre>", escape_html(&diff))),
+                    Data::Error(error) => show_dialog(app_ui.window, false, error),
+                    _ => panic!(THREADS_MESSAGE_ERROR),
+                }
+            }
+        ));
+
+        // What happens when we trigger the "Generate Schema Docs" action.
+        let slot_generate_schema_docs = SlotBool::new(clone!(
+            sender_qt,
+            sender_qt_data,
+            receiver_qt => move |_| {
+
+                // Create the FileDialog to get the folder where to generate the docs.
+                let mut file_dialog = unsafe { FileDialog::new_unsafe((
+                    app_ui.window as *mut Widget,
+                    &QString::from_std_str("Select Destination Folder"),
+                )) };
+
+                // Set it to only search Folders.
+                file_dialog.set_file_mode(FileMode::Directory);
+                file_dialog.set_option(ShowDirsOnly);
+
+                // Run it and expect a response (1 => Accept, 0 => Cancel).
+                if file_dialog.exec() == 1 {
+                    let path = PathBuf::from(file_dialog.selected_files().at(0).to_std_string());
+                    unsafe { (app_ui.window.as_mut().unwrap() as &mut Widget).set_enabled(false); }
+                    sender_qt.send(Commands::GenerateSchemaDocs).unwrap();
+                    sender_qt_data.send(Data::PathBuf(path)).unwrap();
+                    match check_message_validity_tryrecv(&receiver_qt) {
+                        Data::Success => show_dialog(app_ui.window, true, "Schema docs successfully generated and added to the SUMMARY.md of the book."),
+                        Data::Error(error) => show_dialog(app_ui.window, false, error),
+                        _ => panic!(THREADS_MESSAGE_ERROR),
+                    }
+                    unsafe { (app_ui.window.as_mut().unwrap() as &mut Widget).set_enabled(true); }
+                }
+            }
+        ));
+
+        // What happens when we trigger the "Schema Editor" action.
+        let slot_open_schema_editor = SlotBool::new(clone!(
+            sender_qt,
+            sender_qt_data,
+            receiver_qt => move |_| {
+                create_schema_editor_dialog(&app_ui, &sender_qt, &sender_qt_data, &receiver_qt);
+            }
+        ));
+
         // "Game Selected" menu actions.
         unsafe { app_ui.open_game_data_folder.as_ref().unwrap().signals().triggered().connect(&slot_open_game_data_folder); }
         unsafe { app_ui.open_game_assembly_kit_folder.as_ref().unwrap().signals().triggered().connect(&slot_open_game_assembly_kit_folder); }
+        unsafe { app_ui.import_schema_from_assembly_kit.as_ref().unwrap().signals().triggered().connect(&slot_import_schema_from_assembly_kit); }
+        unsafe { app_ui.validate_schema.as_ref().unwrap().signals().triggered().connect(&slot_validate_schema); }
+        unsafe { app_ui.diff_schema_files.as_ref().unwrap().signals().triggered().connect(&slot_diff_schema_files); }
+        unsafe { app_ui.generate_schema_docs.as_ref().unwrap().signals().triggered().connect(&slot_generate_schema_docs); }
+        unsafe { app_ui.open_schema_editor.as_ref().unwrap().signals().triggered().connect(&slot_open_schema_editor); }
 
         //-----------------------------------------------------//
         // "Special Stuff" Menu...
@@ -2383,6 +2683,12 @@ fn main() {
             sender_qt_data,
             receiver_qt => move |_| { check_schema_updates(&app_ui, true, &sender_qt, &sender_qt_data, &receiver_qt) }));
 
+        // What happens when we trigger the "Update Schemas from File" action.
+        let slot_update_schemas_from_file = SlotBool::new(clone!(
+            sender_qt,
+            sender_qt_data,
+            receiver_qt => move |_| { update_schemas_from_file(&app_ui, &sender_qt, &sender_qt_data, &receiver_qt) }));
+
         // "About" Menu Actions.
         unsafe { app_ui.about_qt.as_ref().unwrap().signals().triggered().connect(&slot_about_qt); }
         unsafe { app_ui.about_rpfm.as_ref().unwrap().signals().triggered().connect(&slot_about_rpfm); }
@@ -2390,6 +2696,7 @@ fn main() {
         unsafe { app_ui.patreon_link.as_ref().unwrap().signals().triggered().connect(&slot_patreon_link); }
         unsafe { app_ui.check_updates.as_ref().unwrap().signals().triggered().connect(&slot_check_updates); }
         unsafe { app_ui.check_schema_updates.as_ref().unwrap().signals().triggered().connect(&slot_check_schema_updates); }
+        unsafe { app_ui.update_schemas_from_file.as_ref().unwrap().signals().triggered().connect(&slot_update_schemas_from_file); }
 
         //-----------------------------------------------------//
         // TreeView "Contextual" Menu...
@@ -2431,12 +2738,22 @@ fn main() {
                             app_ui.context_menu_add_folder.as_mut().unwrap().set_enabled(false);
                             app_ui.context_menu_add_from_packfile.as_mut().unwrap().set_enabled(true);
                             app_ui.context_menu_check_tables.as_mut().unwrap().set_enabled(false);
+                            app_ui.context_menu_check_missing_locs.as_mut().unwrap().set_enabled(false);
+                            app_ui.context_menu_check_script_references.as_mut().unwrap().set_enabled(false);
+                            app_ui.context_menu_check_xml_files.as_mut().unwrap().set_enabled(false);
+                            app_ui.context_menu_open_translation_view.as_mut().unwrap().set_enabled(false);
+                            app_ui.context_menu_query_tables.as_mut().unwrap().set_enabled(false);
                             app_ui.context_menu_create_folder.as_mut().unwrap().set_enabled(false);
                             app_ui.context_menu_create_db.as_mut().unwrap().set_enabled(true);
                             app_ui.context_menu_create_loc.as_mut().unwrap().set_enabled(false);
                             app_ui.context_menu_create_text.as_mut().unwrap().set_enabled(false);
                             app_ui.context_menu_mass_import_tsv.as_mut().unwrap().set_enabled(true);
+                            app_ui.context_menu_import_assembly_kit_tables.as_mut().unwrap().set_enabled(true);
                             app_ui.context_menu_mass_export_tsv.as_mut().unwrap().set_enabled(true);
+                            app_ui.context_menu_export_assembly_kit_tables.as_mut().unwrap().set_enabled(true);
+                            app_ui.context_menu_import_translation_files.as_mut().unwrap().set_enabled(true);
+                            app_ui.context_menu_export_po.as_mut().unwrap().set_enabled(true);
+                            app_ui.context_menu_export_xliff.as_mut().unwrap().set_enabled(true);
                             app_ui.context_menu_delete.as_mut().unwrap().set_enabled(true);
                             app_ui.context_menu_extract.as_mut().unwrap().set_enabled(true);
                             app_ui.context_menu_rename.as_mut().unwrap().set_enabled(true);
@@ -2476,8 +2793,18 @@ fn main() {
                         unsafe {
                             app_ui.context_menu_add_from_packfile.as_mut().unwrap().set_enabled(true);
                             app_ui.context_menu_mass_import_tsv.as_mut().unwrap().set_enabled(true);
+                            app_ui.context_menu_import_assembly_kit_tables.as_mut().unwrap().set_enabled(true);
                             app_ui.context_menu_mass_export_tsv.as_mut().unwrap().set_enabled(true);
+                            app_ui.context_menu_export_assembly_kit_tables.as_mut().unwrap().set_enabled(true);
+                            app_ui.context_menu_import_translation_files.as_mut().unwrap().set_enabled(true);
+                            app_ui.context_menu_export_po.as_mut().unwrap().set_enabled(true);
+                            app_ui.context_menu_export_xliff.as_mut().unwrap().set_enabled(true);
                             app_ui.context_menu_check_tables.as_mut().unwrap().set_enabled(false);
+                            app_ui.context_menu_check_missing_locs.as_mut().unwrap().set_enabled(false);
+                            app_ui.context_menu_check_script_references.as_mut().unwrap().set_enabled(false);
+                            app_ui.context_menu_check_xml_files.as_mut().unwrap().set_enabled(false);
+                            app_ui.context_menu_open_translation_view.as_mut().unwrap().set_enabled(false);
+                            app_ui.context_menu_query_tables.as_mut().unwrap().set_enabled(false);
                             app_ui.context_menu_create_db.as_mut().unwrap().set_enabled(true);
                             app_ui.context_menu_merge_tables.as_mut().unwrap().set_enabled(false);
                             app_ui.context_menu_delete.as_mut().unwrap().set_enabled(true);
@@ -2509,12 +2836,22 @@ fn main() {
                             app_ui.context_menu_add_folder.as_mut().unwrap().set_enabled(false);
                             app_ui.context_menu_add_from_packfile.as_mut().unwrap().set_enabled(true);
                             app_ui.context_menu_check_tables.as_mut().unwrap().set_enabled(false);
+                            app_ui.context_menu_check_missing_locs.as_mut().unwrap().set_enabled(false);
+                            app_ui.context_menu_check_script_references.as_mut().unwrap().set_enabled(false);
+                            app_ui.context_menu_check_xml_files.as_mut().unwrap().set_enabled(false);
+                            app_ui.context_menu_open_translation_view.as_mut().unwrap().set_enabled(false);
+                            app_ui.context_menu_query_tables.as_mut().unwrap().set_enabled(false);
                             app_ui.context_menu_create_folder.as_mut().unwrap().set_enabled(false);
                             app_ui.context_menu_create_db.as_mut().unwrap().set_enabled(true);
                             app_ui.context_menu_create_loc.as_mut().unwrap().set_enabled(false);
                             app_ui.context_menu_create_text.as_mut().unwrap().set_enabled(false);
                             app_ui.context_menu_mass_import_tsv.as_mut().unwrap().set_enabled(true);
+                            app_ui.context_menu_import_assembly_kit_tables.as_mut().unwrap().set_enabled(true);
                             app_ui.context_menu_mass_export_tsv.as_mut().unwrap().set_enabled(true);
+                            app_ui.context_menu_export_assembly_kit_tables.as_mut().unwrap().set_enabled(true);
+                            app_ui.context_menu_import_translation_files.as_mut().unwrap().set_enabled(true);
+                            app_ui.context_menu_export_po.as_mut().unwrap().set_enabled(true);
+                            app_ui.context_menu_export_xliff.as_mut().unwrap().set_enabled(true);
                             app_ui.context_menu_merge_tables.as_mut().unwrap().set_enabled(false);
                             app_ui.context_menu_delete.as_mut().unwrap().set_enabled(true);
                             app_ui.context_menu_extract.as_mut().unwrap().set_enabled(true);
@@ -2535,12 +2872,22 @@ fn main() {
                             app_ui.context_menu_add_folder.as_mut().unwrap().set_enabled(true);
                             app_ui.context_menu_add_from_packfile.as_mut().unwrap().set_enabled(true);
                             app_ui.context_menu_check_tables.as_mut().unwrap().set_enabled(true);
+                            app_ui.context_menu_check_missing_locs.as_mut().unwrap().set_enabled(true);
+                            app_ui.context_menu_check_script_references.as_mut().unwrap().set_enabled(true);
+                            app_ui.context_menu_check_xml_files.as_mut().unwrap().set_enabled(true);
+                            app_ui.context_menu_open_translation_view.as_mut().unwrap().set_enabled(true);
+                            app_ui.context_menu_query_tables.as_mut().unwrap().set_enabled(true);
                             app_ui.context_menu_create_folder.as_mut().unwrap().set_enabled(true);
                             app_ui.context_menu_create_db.as_mut().unwrap().set_enabled(true);
                             app_ui.context_menu_create_loc.as_mut().unwrap().set_enabled(true);
                             app_ui.context_menu_create_text.as_mut().unwrap().set_enabled(true);
                             app_ui.context_menu_mass_import_tsv.as_mut().unwrap().set_enabled(true);
+                            app_ui.context_menu_import_assembly_kit_tables.as_mut().unwrap().set_enabled(true);
                             app_ui.context_menu_mass_export_tsv.as_mut().unwrap().set_enabled(true);
+                            app_ui.context_menu_export_assembly_kit_tables.as_mut().unwrap().set_enabled(true);
+                            app_ui.context_menu_import_translation_files.as_mut().unwrap().set_enabled(true);
+                            app_ui.context_menu_export_po.as_mut().unwrap().set_enabled(true);
+                            app_ui.context_menu_export_xliff.as_mut().unwrap().set_enabled(true);
                             app_ui.context_menu_merge_tables.as_mut().unwrap().set_enabled(false);
                             app_ui.context_menu_delete.as_mut().unwrap().set_enabled(true);
                             app_ui.context_menu_extract.as_mut().unwrap().set_enabled(true);
@@ -2561,12 +2908,22 @@ fn main() {
                             app_ui.context_menu_add_folder.as_mut().unwrap().set_enabled(false);
                             app_ui.context_menu_add_from_packfile.as_mut().unwrap().set_enabled(true);
                             app_ui.context_menu_check_tables.as_mut().unwrap().set_enabled(true);
+                            app_ui.context_menu_check_missing_locs.as_mut().unwrap().set_enabled(true);
+                            app_ui.context_menu_check_script_references.as_mut().unwrap().set_enabled(true);
+                            app_ui.context_menu_check_xml_files.as_mut().unwrap().set_enabled(true);
+                            app_ui.context_menu_open_translation_view.as_mut().unwrap().set_enabled(true);
+                            app_ui.context_menu_query_tables.as_mut().unwrap().set_enabled(true);
                             app_ui.context_menu_create_folder.as_mut().unwrap().set_enabled(false);
                             app_ui.context_menu_create_db.as_mut().unwrap().set_enabled(true);
                             app_ui.context_menu_create_loc.as_mut().unwrap().set_enabled(false);
                             app_ui.context_menu_create_text.as_mut().unwrap().set_enabled(false);
                             app_ui.context_menu_mass_import_tsv.as_mut().unwrap().set_enabled(true);
+                            app_ui.context_menu_import_assembly_kit_tables.as_mut().unwrap().set_enabled(true);
                             app_ui.context_menu_mass_export_tsv.as_mut().unwrap().set_enabled(true);
+                            app_ui.context_menu_export_assembly_kit_tables.as_mut().unwrap().set_enabled(true);
+                            app_ui.context_menu_import_translation_files.as_mut().unwrap().set_enabled(true);
+                            app_ui.context_menu_export_po.as_mut().unwrap().set_enabled(true);
+                            app_ui.context_menu_export_xliff.as_mut().unwrap().set_enabled(true);
                             app_ui.context_menu_merge_tables.as_mut().unwrap().set_enabled(false);
                             app_ui.context_menu_delete.as_mut().unwrap().set_enabled(true);
                             app_ui.context_menu_extract.as_mut().unwrap().set_enabled(true);
@@ -2587,12 +2944,22 @@ fn main() {
                             app_ui.context_menu_add_folder.as_mut().unwrap().set_enabled(false);
                             app_ui.context_menu_add_from_packfile.as_mut().unwrap().set_enabled(true);
                             app_ui.context_menu_check_tables.as_mut().unwrap().set_enabled(true);
+                            app_ui.context_menu_check_missing_locs.as_mut().unwrap().set_enabled(true);
+                            app_ui.context_menu_check_script_references.as_mut().unwrap().set_enabled(true);
+                            app_ui.context_menu_check_xml_files.as_mut().unwrap().set_enabled(true);
+                            app_ui.context_menu_open_translation_view.as_mut().unwrap().set_enabled(true);
+                            app_ui.context_menu_query_tables.as_mut().unwrap().set_enabled(true);
                             app_ui.context_menu_create_folder.as_mut().unwrap().set_enabled(false);
                             app_ui.context_menu_create_db.as_mut().unwrap().set_enabled(true);
                             app_ui.context_menu_create_loc.as_mut().unwrap().set_enabled(false);
                             app_ui.context_menu_create_text.as_mut().unwrap().set_enabled(false);
                             app_ui.context_menu_mass_import_tsv.as_mut().unwrap().set_enabled(true);
+                            app_ui.context_menu_import_assembly_kit_tables.as_mut().unwrap().set_enabled(true);
                             app_ui.context_menu_mass_export_tsv.as_mut().unwrap().set_enabled(true);
+                            app_ui.context_menu_export_assembly_kit_tables.as_mut().unwrap().set_enabled(true);
+                            app_ui.context_menu_import_translation_files.as_mut().unwrap().set_enabled(true);
+                            app_ui.context_menu_export_po.as_mut().unwrap().set_enabled(true);
+                            app_ui.context_menu_export_xliff.as_mut().unwrap().set_enabled(true);
                             app_ui.context_menu_delete.as_mut().unwrap().set_enabled(true);
                             app_ui.context_menu_extract.as_mut().unwrap().set_enabled(true);
                             app_ui.context_menu_rename.as_mut().unwrap().set_enabled(false);
@@ -2612,12 +2979,22 @@ fn main() {
                             app_ui.context_menu_add_folder.as_mut().unwrap().set_enabled(false);
                             app_ui.context_menu_add_from_packfile.as_mut().unwrap().set_enabled(true);
                             app_ui.context_menu_check_tables.as_mut().unwrap().set_enabled(true);
+                            app_ui.context_menu_check_missing_locs.as_mut().unwrap().set_enabled(true);
+                            app_ui.context_menu_check_script_references.as_mut().unwrap().set_enabled(true);
+                            app_ui.context_menu_check_xml_files.as_mut().unwrap().set_enabled(true);
+                            app_ui.context_menu_open_translation_view.as_mut().unwrap().set_enabled(true);
+                            app_ui.context_menu_query_tables.as_mut().unwrap().set_enabled(true);
                             app_ui.context_menu_create_folder.as_mut().unwrap().set_enabled(false);
                             app_ui.context_menu_create_db.as_mut().unwrap().set_enabled(true);
                             app_ui.context_menu_create_loc.as_mut().unwrap().set_enabled(false);
                             app_ui.context_menu_create_text.as_mut().unwrap().set_enabled(false);
                             app_ui.context_menu_mass_import_tsv.as_mut().unwrap().set_enabled(true);
+                            app_ui.context_menu_import_assembly_kit_tables.as_mut().unwrap().set_enabled(true);
                             app_ui.context_menu_mass_export_tsv.as_mut().unwrap().set_enabled(true);
+                            app_ui.context_menu_export_assembly_kit_tables.as_mut().unwrap().set_enabled(true);
+                            app_ui.context_menu_import_translation_files.as_mut().unwrap().set_enabled(true);
+                            app_ui.context_menu_export_po.as_mut().unwrap().set_enabled(true);
+                            app_ui.context_menu_export_xliff.as_mut().unwrap().set_enabled(true);
                             app_ui.context_menu_merge_tables.as_mut().unwrap().set_enabled(false);
                             app_ui.context_menu_delete.as_mut().unwrap().set_enabled(true);
                             app_ui.context_menu_extract.as_mut().unwrap().set_enabled(true);
@@ -2638,12 +3015,22 @@ fn main() {
                             app_ui.context_menu_add_folder.as_mut().unwrap().set_enabled(false);
                             app_ui.context_menu_add_from_packfile.as_mut().unwrap().set_enabled(false);
                             app_ui.context_menu_check_tables.as_mut().unwrap().set_enabled(false);
+                            app_ui.context_menu_check_missing_locs.as_mut().unwrap().set_enabled(false);
+                            app_ui.context_menu_check_script_references.as_mut().unwrap().set_enabled(false);
+                            app_ui.context_menu_check_xml_files.as_mut().unwrap().set_enabled(false);
+                            app_ui.context_menu_open_translation_view.as_mut().unwrap().set_enabled(false);
+                            app_ui.context_menu_query_tables.as_mut().unwrap().set_enabled(false);
                             app_ui.context_menu_create_folder.as_mut().unwrap().set_enabled(false);
                             app_ui.context_menu_create_db.as_mut().unwrap().set_enabled(false);
                             app_ui.context_menu_create_loc.as_mut().unwrap().set_enabled(false);
                             app_ui.context_menu_create_text.as_mut().unwrap().set_enabled(false);
                             app_ui.context_menu_mass_import_tsv.as_mut().unwrap().set_enabled(false);
+                            app_ui.context_menu_import_assembly_kit_tables.as_mut().unwrap().set_enabled(false);
                             app_ui.context_menu_mass_export_tsv.as_mut().unwrap().set_enabled(false);
+                            app_ui.context_menu_export_assembly_kit_tables.as_mut().unwrap().set_enabled(false);
+                            app_ui.context_menu_import_translation_files.as_mut().unwrap().set_enabled(false);
+                            app_ui.context_menu_export_po.as_mut().unwrap().set_enabled(false);
+                            app_ui.context_menu_export_xliff.as_mut().unwrap().set_enabled(false);
                             app_ui.context_menu_merge_tables.as_mut().unwrap().set_enabled(false);
                             app_ui.context_menu_delete.as_mut().unwrap().set_enabled(false);
                             app_ui.context_menu_extract.as_mut().unwrap().set_enabled(false);
@@ -2669,9 +3056,19 @@ fn main() {
                 // If there is no dependency_database or schema for our GameSelected, ALWAYS disable creating new DB Tables and exporting them.
                 if !is_there_a_dependency_database || !is_there_a_schema {
                     unsafe { app_ui.context_menu_check_tables.as_mut().unwrap().set_enabled(false); }
+                    unsafe { app_ui.context_menu_check_missing_locs.as_mut().unwrap().set_enabled(false); }
+                    unsafe { app_ui.context_menu_check_script_references.as_mut().unwrap().set_enabled(false); }
+                    unsafe { app_ui.context_menu_check_xml_files.as_mut().unwrap().set_enabled(false); }
+                    unsafe { app_ui.context_menu_open_translation_view.as_mut().unwrap().set_enabled(false); }
+                    unsafe { app_ui.context_menu_query_tables.as_mut().unwrap().set_enabled(false); }
                     unsafe { app_ui.context_menu_create_db.as_mut().unwrap().set_enabled(false); }
                     unsafe { app_ui.context_menu_mass_import_tsv.as_mut().unwrap().set_enabled(false); }
+                    unsafe { app_ui.context_menu_import_assembly_kit_tables.as_mut().unwrap().set_enabled(false); }
                     unsafe { app_ui.context_menu_mass_export_tsv.as_mut().unwrap().set_enabled(false); }
+                    unsafe { app_ui.context_menu_export_assembly_kit_tables.as_mut().unwrap().set_enabled(false); }
+                    unsafe { app_ui.context_menu_import_translation_files.as_mut().unwrap().set_enabled(false); }
+                    unsafe { app_ui.context_menu_export_po.as_mut().unwrap().set_enabled(false); }
+                    unsafe { app_ui.context_menu_export_xliff.as_mut().unwrap().set_enabled(false); }
                 }
             }
         ));
@@ -3454,6 +3851,265 @@ fn main() {
             }
         ));
 
+        // What happens when we trigger the "Import from Assembly Kit" Action.
+        let slot_contextual_menu_import_assembly_kit_tables = SlotBool::new(clone!(
+            packedfiles_open_in_packedfile_view,
+            global_search_explicit_paths,
+            table_state_data,
+            sender_qt,
+            sender_qt_data,
+            receiver_qt => move |_| {
+
+                // Don't do anything if there is a PackedFile open. This fixes the situation where you could overwrite data already in the UI.
+                if !packedfiles_open_in_packedfile_view.borrow().is_empty() { return show_dialog(app_ui.window, false, ErrorKind::PackedFileIsOpen) }
+
+                // Create the FileDialog to get the raw tables to import and configure it.
+                let mut file_dialog = unsafe { FileDialog::new_unsafe((
+                    app_ui.window as *mut Widget,
+                    &QString::from_std_str("Select Assembly Kit's Raw Tables"),
+                )) };
+                file_dialog.set_name_filter(&QString::from_std_str("Raw Tables (*.xml)"));
+                file_dialog.set_file_mode(FileMode::ExistingFiles);
+
+                // Run it and expect a response (1 => Accept, 0 => Cancel).
+                if file_dialog.exec() == 1 {
+                    let mut paths = vec![];
+                    for index in 0..file_dialog.selected_files().count(()) {
+                        paths.push(PathBuf::from(file_dialog.selected_files().at(index).to_std_string()));
+                    }
+
+                    unsafe { (app_ui.window.as_mut().unwrap() as &mut Widget).set_enabled(false); }
+                    sender_qt.send(Commands::ImportAssemblyKitTables).unwrap();
+                    sender_qt_data.send(Data::VecPathBuf(paths)).unwrap();
+                    match check_message_validity_tryrecv(&receiver_qt) {
+                        
+                        // If it's success....
+                        Data::VecVecStringVecVecString(paths) => {
+
+                            // Get the list of paths to add, removing those we "replaced".
+                            let mut paths_to_add = paths.1.to_vec();
+                            paths_to_add.retain(|x| !paths.0.contains(&x));
+                            let paths_to_add2 = paths_to_add.iter().map(|x| TreePathType::File(x.to_vec())).collect::<Vec<TreePathType>>();
+
+                            // Update the TreeView.
+                            update_treeview(
+                                &sender_qt,
+                                &sender_qt_data,
+                                &receiver_qt,
+                                &app_ui,
+                                app_ui.folder_tree_view,
+                                Some(app_ui.folder_tree_filter),
+                                app_ui.folder_tree_model,
+                                TreeViewOperation::Add(paths_to_add2),
+                            );
+
+                            // Update the global search stuff, if needed.
+                            global_search_explicit_paths.borrow_mut().append(&mut paths_to_add);
+                            unsafe { update_global_search_stuff.as_mut().unwrap().trigger(); }
+
+                            // For each file added, remove it from the data history if exists.
+                            for path in &paths.1 {
+                                if table_state_data.borrow().get(path).is_some() {
+                                    table_state_data.borrow_mut().remove(path);
+                                }
+
+                                let data = TableStateData::new_empty();
+                                table_state_data.borrow_mut().insert(path.to_vec(), data);
+                            }
+                        }
+
+                        Data::Error(error) => show_dialog(app_ui.window, false, error),
+                        _ => panic!(THREADS_MESSAGE_ERROR),
+                    }
+
+                    // Re-enable the Main Window.
+                    unsafe { (app_ui.window.as_mut().unwrap() as &mut Widget).set_enabled(true); }
+                }
+            }
+        ));
+
+        // What happens when we trigger the "Export to Assembly Kit" Action.
+        let slot_contextual_menu_export_assembly_kit_tables = SlotBool::new(clone!(
+            sender_qt,
+            sender_qt_data,
+            receiver_qt => move |_| {
+
+                // Get the currently selected paths, and a "Folder-only" FileDialog.
+                let selected_items = get_items_from_main_treeview_selection(&app_ui);
+                let selected_types = selected_items.iter().map(|x| From::from(&get_type_of_item(*x, app_ui.folder_tree_model))).collect::<Vec<PathType>>();
+                let export_path = unsafe { FileDialog::get_existing_directory_unsafe((
+                    app_ui.window as *mut Widget,
+                    &QString::from_std_str("Select destination folder")
+                )) };
+
+                // If we got an export path and it's not empty, try to export all the selected tables there.
+                if !export_path.is_empty() {
+                    let export_path = PathBuf::from(export_path.to_std_string());
+                    if export_path.is_dir() {
+                        unsafe { (app_ui.window.as_mut().unwrap() as &mut Widget).set_enabled(false); }
+                        sender_qt.send(Commands::ExportAssemblyKitTables).unwrap();
+                        sender_qt_data.send(Data::VecPathTypePathBuf((selected_types, export_path))).unwrap();
+                        match check_message_validity_tryrecv(&receiver_qt) {
+                            Data::String(response) => show_dialog(app_ui.window, true, response),
+                            Data::Error(error) => show_dialog(app_ui.window, false, error),
+                            _ => panic!(THREADS_MESSAGE_ERROR),
+                        }
+                        unsafe { (app_ui.window.as_mut().unwrap() as &mut Widget).set_enabled(true); }
+                    }
+                }
+            }
+        ));
+
+        // What happens when we trigger the "Import PO/XLIFF" Action.
+        let slot_contextual_menu_import_translation_files = SlotBool::new(clone!(
+            packedfiles_open_in_packedfile_view,
+            global_search_explicit_paths,
+            table_state_data,
+            sender_qt,
+            sender_qt_data,
+            receiver_qt => move |_| {
+
+                // Don't do anything if there is a PackedFile open. This fixes the situation where you could overwrite data already in the UI.
+                if !packedfiles_open_in_packedfile_view.borrow().is_empty() { return show_dialog(app_ui.window, false, ErrorKind::PackedFileIsOpen) }
+
+                // Create the FileDialog to get the translation files to import and configure it.
+                let mut file_dialog = unsafe { FileDialog::new_unsafe((
+                    app_ui.window as *mut Widget,
+                    &QString::from_std_str("Select Translation Files"),
+                )) };
+                file_dialog.set_name_filter(&QString::from_std_str("Translation Files (*.po *.pot *.xlf *.xliff)"));
+                file_dialog.set_file_mode(FileMode::ExistingFiles);
+
+                // Run it and expect a response (1 => Accept, 0 => Cancel).
+                if file_dialog.exec() == 1 {
+                    let mut paths = vec![];
+                    for index in 0..file_dialog.selected_files().count(()) {
+                        paths.push(PathBuf::from(file_dialog.selected_files().at(index).to_std_string()));
+                    }
+
+                    unsafe { (app_ui.window.as_mut().unwrap() as &mut Widget).set_enabled(false); }
+                    sender_qt.send(Commands::MassImportTranslationFiles).unwrap();
+                    sender_qt_data.send(Data::VecPathBuf(paths)).unwrap();
+                    match check_message_validity_tryrecv(&receiver_qt) {
+
+                        // If it's success, the Loc PackedFiles that already existed are marked as modified, and the rest are added.
+                        Data::VecVecStringVecVecString(paths) => {
+                            let mut paths_to_add = paths.1.to_vec();
+                            paths_to_add.retain(|x| !paths.0.contains(&x));
+                            let paths_to_add2 = paths_to_add.iter().map(|x| TreePathType::File(x.to_vec())).collect::<Vec<TreePathType>>();
+                            let paths_to_modify = paths.0.iter().map(|x| TreePathType::File(x.to_vec())).collect::<Vec<TreePathType>>();
+
+                            update_treeview(
+                                &sender_qt,
+                                &sender_qt_data,
+                                &receiver_qt,
+                                &app_ui,
+                                app_ui.folder_tree_view,
+                                Some(app_ui.folder_tree_filter),
+                                app_ui.folder_tree_model,
+                                TreeViewOperation::Add(paths_to_add2),
+                            );
+
+                            update_treeview(
+                                &sender_qt,
+                                &sender_qt_data,
+                                &receiver_qt,
+                                &app_ui,
+                                app_ui.folder_tree_view,
+                                Some(app_ui.folder_tree_filter),
+                                app_ui.folder_tree_model,
+                                TreeViewOperation::Modify(paths_to_modify),
+                            );
+
+                            // Update the global search stuff, if needed.
+                            global_search_explicit_paths.borrow_mut().append(&mut paths.1.to_vec());
+                            unsafe { update_global_search_stuff.as_mut().unwrap().trigger(); }
+
+                            // For each file imported, remove it from the data history if exists.
+                            for path in &paths.1 {
+                                if table_state_data.borrow().get(path).is_some() {
+                                    table_state_data.borrow_mut().remove(path);
+                                }
+
+                                let data = TableStateData::new_empty();
+                                table_state_data.borrow_mut().insert(path.to_vec(), data);
+                            }
+                        }
+
+                        Data::Error(error) => show_dialog(app_ui.window, false, error),
+                        _ => panic!(THREADS_MESSAGE_ERROR),
+                    }
+
+                    // Re-enable the Main Window.
+                    unsafe { (app_ui.window.as_mut().unwrap() as &mut Widget).set_enabled(true); }
+                }
+            }
+        ));
+
+        // What happens when we trigger the "Export Locs to PO" Action.
+        let slot_contextual_menu_export_po = SlotBool::new(clone!(
+            sender_qt,
+            sender_qt_data,
+            receiver_qt => move |_| {
+
+                // Get the currently selected paths, and a "Folder-only" FileDialog.
+                let selected_items = get_items_from_main_treeview_selection(&app_ui);
+                let selected_types = selected_items.iter().map(|x| From::from(&get_type_of_item(*x, app_ui.folder_tree_model))).collect::<Vec<PathType>>();
+                let export_path = unsafe { FileDialog::get_existing_directory_unsafe((
+                    app_ui.window as *mut Widget,
+                    &QString::from_std_str("Select destination folder")
+                )) };
+
+                // If we got an export path and it's not empty, try to export all the selected Loc PackedFiles there.
+                if !export_path.is_empty() {
+                    let export_path = PathBuf::from(export_path.to_std_string());
+                    if export_path.is_dir() {
+                        unsafe { (app_ui.window.as_mut().unwrap() as &mut Widget).set_enabled(false); }
+                        sender_qt.send(Commands::MassExportTranslationFiles).unwrap();
+                        sender_qt_data.send(Data::VecPathTypePathBufTranslationFileFormat((selected_types, export_path, TranslationFileFormat::Po))).unwrap();
+                        match check_message_validity_tryrecv(&receiver_qt) {
+                            Data::String(response) => show_dialog(app_ui.window, true, response),
+                            Data::Error(error) => show_dialog(app_ui.window, false, error),
+                            _ => panic!(THREADS_MESSAGE_ERROR),
+                        }
+                        unsafe { (app_ui.window.as_mut().unwrap() as &mut Widget).set_enabled(true); }
+                    }
+                }
+            }
+        ));
+
+        // What happens when we trigger the "Export Locs to XLIFF" Action.
+        let slot_contextual_menu_export_xliff = SlotBool::new(clone!(
+            sender_qt,
+            sender_qt_data,
+            receiver_qt => move |_| {
+
+                // Get the currently selected paths, and a "Folder-only" FileDialog.
+                let selected_items = get_items_from_main_treeview_selection(&app_ui);
+                let selected_types = selected_items.iter().map(|x| From::from(&get_type_of_item(*x, app_ui.folder_tree_model))).collect::<Vec<PathType>>();
+                let export_path = unsafe { FileDialog::get_existing_directory_unsafe((
+                    app_ui.window as *mut Widget,
+                    &QString::from_std_str("Select destination folder")
+                )) };
+
+                // If we got an export path and it's not empty, try to export all the selected Loc PackedFiles there.
+                if !export_path.is_empty() {
+                    let export_path = PathBuf::from(export_path.to_std_string());
+                    if export_path.is_dir() {
+                        unsafe { (app_ui.window.as_mut().unwrap() as &mut Widget).set_enabled(false); }
+                        sender_qt.send(Commands::MassExportTranslationFiles).unwrap();
+                        sender_qt_data.send(Data::VecPathTypePathBufTranslationFileFormat((selected_types, export_path, TranslationFileFormat::Xliff))).unwrap();
+                        match check_message_validity_tryrecv(&receiver_qt) {
+                            Data::String(response) => show_dialog(app_ui.window, true, response),
+                            Data::Error(error) => show_dialog(app_ui.window, false, error),
+                            _ => panic!(THREADS_MESSAGE_ERROR),
+                        }
+                        unsafe { (app_ui.window.as_mut().unwrap() as &mut Widget).set_enabled(true); }
+                    }
+                }
+            }
+        ));
+
         // What happens when we trigger the "Check Tables" action in the Contextual Menu.
         let slot_contextual_menu_check_tables = SlotBool::new(clone!(
             sender_qt,
@@ -3471,6 +4127,160 @@ fn main() {
             }
         ));
 
+        // What happens when we trigger the "Translation View" action in the Contextual Menu.
+        let slot_contextual_menu_open_translation_view = SlotBool::new(clone!(
+            packedfiles_open_in_packedfile_view,
+            global_search_explicit_paths,
+            table_state_data,
+            sender_qt,
+            sender_qt_data,
+            receiver_qt => move |_| {
+
+                // Don't do anything if there is a PackedFile open. This fixes the situation where you could overwrite data already in the UI.
+                if !packedfiles_open_in_packedfile_view.borrow().is_empty() { return show_dialog(app_ui.window, false, ErrorKind::PackedFileIsOpen) }
+                create_translation_view_dialog(
+                    &app_ui,
+                    &sender_qt,
+                    &sender_qt_data,
+                    &receiver_qt,
+                    &packedfiles_open_in_packedfile_view,
+                    &global_search_explicit_paths,
+                    update_global_search_stuff,
+                    &table_state_data,
+                );
+            }
+        ));
+
+        // What happens when we trigger the "Check XML Files" action in the Contextual Menu.
+        let slot_contextual_menu_check_xml_files = SlotBool::new(clone!(
+            sender_qt,
+            receiver_qt => move |_| {
+
+                // Disable the window and trigger the check for all XML files in the PackFile.
+                unsafe { (app_ui.window.as_mut().unwrap() as &mut Widget).set_enabled(false); }
+                sender_qt.send(Commands::CheckXMLFiles).unwrap();
+                let errors = match check_message_validity_tryrecv(&receiver_qt) {
+                    Data::VecString(data) => data,
+                    Data::Error(error) => {
+                        unsafe { (app_ui.window.as_mut().unwrap() as &mut Widget).set_enabled(true); }
+                        show_dialog(app_ui.window, false, error);
+                        return
+                    }
+                    _ => panic!(THREADS_MESSAGE_ERROR),
+                };
+                unsafe { (app_ui.window.as_mut().unwrap() as &mut Widget).set_enabled(true); }
+
+                if errors.is_empty() { show_dialog(app_ui.window, true, "No errors found in the XML PackedFiles."); }
+                else {
+                    let report = format!("<p>The following errors have been found in the XML PackedFiles:</p><ul>{}</ul>", errors.iter().map(|x| format!("<li>{}</li>", escape_html(x))).collect::<String>());
+                    show_report_dialog(app_ui.window, "XML Check", &report);
+                }
+            }
+        ));
+
+        // What happens when we trigger the "Check Script References" action in the Contextual Menu.
+        let slot_contextual_menu_check_script_references = SlotBool::new(clone!(
+            sender_qt,
+            receiver_qt => move |_| {
+
+                // Disable the window and trigger the check for all scripts in the PackFile.
+                unsafe { (app_ui.window.as_mut().unwrap() as &mut Widget).set_enabled(false); }
+                sender_qt.send(Commands::CheckLuaReferences).unwrap();
+                let unknown_keys = match check_message_validity_tryrecv(&receiver_qt) {
+                    Data::VecString(data) => data,
+                    Data::Error(error) => {
+                        unsafe { (app_ui.window.as_mut().unwrap() as &mut Widget).set_enabled(true); }
+                        show_dialog(app_ui.window, false, error);
+                        return
+                    }
+                    _ => panic!(THREADS_MESSAGE_ERROR),
+                };
+                unsafe { (app_ui.window.as_mut().unwrap() as &mut Widget).set_enabled(true); }
+
+                if unknown_keys.is_empty() { show_dialog(app_ui.window, true, "No unknown keys found in the Lua scripts."); }
+                else {
+                    let report = format!("<p>The following keys used in the Lua scripts are not in any table or Loc PackedFile:</p><ul>{}</ul>", unknown_keys.iter().map(|x| format!("<li>{}</li>", escape_html(x))).collect::<String>());
+                    show_report_dialog(app_ui.window, "Script References", &report);
+                }
+            }
+        ));
+
+        // What happens when we trigger the "Check Missing Locs" action in the Contextual Menu.
+        let slot_contextual_menu_check_missing_locs = SlotBool::new(clone!(
+            sender_qt,
+            sender_qt_data,
+            receiver_qt,
+            global_search_explicit_paths => move |_| {
+                
+                // Disable the window and trigger the check for all tables in the PackFile.
+                unsafe { (app_ui.window.as_mut().unwrap() as &mut Widget).set_enabled(false); }
+                sender_qt.send(Commands::CheckMissingLocs).unwrap();
+                let missing_locs = match check_message_validity_tryrecv(&receiver_qt) {
+                    Data::VecString(data) => data,
+                    Data::Error(error) => {
+                        unsafe { (app_ui.window.as_mut().unwrap() as &mut Widget).set_enabled(true); }
+                        show_dialog(app_ui.window, false, error);
+                        return
+                    }
+                    _ => panic!(THREADS_MESSAGE_ERROR),
+                };
+                unsafe { (app_ui.window.as_mut().unwrap() as &mut Widget).set_enabled(true); }
+
+                if missing_locs.is_empty() { show_dialog(app_ui.window, true, "No missing locs detected."); }
+                else {
+
+                    // Show the list of missing locs, and ask if we want to create placeholders for them.
+                    let report = format!("<p>The following loc keys are missing:</p><ul>{}</ul>", missing_locs.iter().map(|x| format!("<li>{}</li>", escape_html(x))).collect::<String>());
+                    show_report_dialog(app_ui.window, "Missing Locs", &report);
+
+                    let mut dialog = unsafe { MessageBox::new_unsafe((
+                        &QString::from_std_str("Missing Locs"),
+                        &QString::from_std_str(&format!("<p>There are {} missing loc keys. Do you want to create a Loc PackedFile with placeholders for them?</p>", missing_locs.len())),
+                        message_box::Icon::Warning,
+                        65536, // No
+                        16384, // Yes
+                        1, // By default, select yes.
+                        app_ui.window as *mut Widget,
+                    )) };
+
+                    // Yes => 3, No => 4.
+                    if dialog.exec() == 3 {
+                        sender_qt.send(Commands::CreateMissingLocsPackedFile).unwrap();
+                        sender_qt_data.send(Data::VecString(missing_locs)).unwrap();
+                        match check_message_validity_recv2(&receiver_qt) {
+                            Data::VecString(path) => {
+                                update_treeview(
+                                    &sender_qt,
+                                    &sender_qt_data,
+                                    &receiver_qt,
+                                    &app_ui,
+                                    app_ui.folder_tree_view,
+                                    Some(app_ui.folder_tree_filter),
+                                    app_ui.folder_tree_model,
+                                    TreeViewOperation::Add(vec![TreePathType::File(path.to_vec()); 1]),
+                                );
+
+                                // Update the global search stuff, if needed.
+                                global_search_explicit_paths.borrow_mut().append(&mut vec![path]);
+                                unsafe { update_global_search_stuff.as_mut().unwrap().trigger(); }
+                            }
+                            Data::Error(error) => show_dialog(app_ui.window, false, error),
+                            _ => panic!(THREADS_MESSAGE_ERROR),
+                        }
+                    }
+                }
+            }
+        ));
+
+        // What happens when we trigger the "Query Tables" action in the Contextual Menu.
+        let slot_contextual_menu_query_tables = SlotBool::new(clone!(
+            sender_qt,
+            sender_qt_data,
+            receiver_qt => move |_| {
+                create_query_tables_dialog(&app_ui, &sender_qt, &sender_qt_data, &receiver_qt);
+            }
+        ));
+
         // What happens when we trigger the "Merge" action in the Contextual Menu.
         let slot_contextual_menu_merge_tables = SlotBool::new(clone!(
             sender_qt,
@@ -3546,15 +4356,42 @@ fn main() {
                     }
 
                     // Get the info for the merged file.
-                    if let Some((mut name, delete_source_files)) = create_merge_tables_dialog(&app_ui) {
+                    if let Some((mut name, delete_source_files, strategy)) = create_merge_tables_dialog(&app_ui) {
 
                         // If it's a loc file and the name doesn't end in a ".loc" termination, call it ".loc".
                         if loc_pass && !name.ends_with(".loc") {
                             name.push_str(".loc");
                         }
 
+                        // Before merging, check what rows are going to be lost in the process, and ask if we want to continue.
+                        sender_qt.send(Commands::GetMergeTablesPreview).unwrap();
+                        sender_qt_data.send(Data::VecVecStringBoolMergeStrategy((selected_paths.to_vec(), db_pass, strategy))).unwrap();
+                        match check_message_validity_recv2(&receiver_qt) {
+                            Data::VecString(preview) => {
+                                if !preview.is_empty() {
+
+                                    // The list can be huge, so we only show the first rows of it.
+                                    let mut list = preview.iter().take(MERGE_PREVIEW_MAX_ROWS).map(|x| format!("<li>{}</li>", x)).collect::<String>();
+                                    if preview.len() > MERGE_PREVIEW_MAX_ROWS { list.push_str(&format!("<li>…and {} more.</li>", preview.len() - MERGE_PREVIEW_MAX_ROWS)); }
+                                    let mut dialog = unsafe { MessageBox::new_unsafe((
+                                        message_box::Icon::Warning,
+                                        &QString::from_std_str("Rows dropped or overwritten"),
+                                        &QString::from_std_str(&format!("<p>The following rows are going to be dropped or overwritten while merging:</p><ul>{}</ul><p>Are you sure you want to continue?</p>", list)),
+                                        Flags::from_int(16384) | Flags::from_int(65536),
+                                        app_ui.window as *mut Widget,
+                                    )) };
+
+                                    // 16384 means yes.
+                                    if dialog.exec() != 16384 { return }
+                                }
+                            }
+
+                            Data::Error(error) => { show_dialog(app_ui.window, false, error); return }
+                            _ => panic!(THREADS_MESSAGE_ERROR),
+                        }
+
                         sender_qt.send(Commands::MergeTables).unwrap();
-                        sender_qt_data.send(Data::VecVecStringStringBoolBool((selected_paths, name, delete_source_files, if db_pass { true } else { false }))).unwrap();
+                        sender_qt_data.send(Data::VecVecStringStringBoolBoolMergeStrategy((selected_paths, name, delete_source_files, db_pass, strategy))).unwrap();
                         match check_message_validity_recv2(&receiver_qt) {
                             Data::VecStringVecPathType((path_to_add, items_to_remove)) => {
                                 let items_to_remove = items_to_remove.iter().map(|x| From::from(x)).collect::<Vec<TreePathType>>();
@@ -4105,12 +4942,22 @@ fn main() {
         unsafe { app_ui.context_menu_add_folder.as_ref().unwrap().signals().triggered().connect(&slot_contextual_menu_add_folder); }
         unsafe { app_ui.context_menu_add_from_packfile.as_ref().unwrap().signals().triggered().connect(&slot_contextual_menu_add_from_packfile); }
         unsafe { app_ui.context_menu_check_tables.as_ref().unwrap().signals().triggered().connect(&slot_contextual_menu_check_tables); }
+        unsafe { app_ui.context_menu_check_missing_locs.as_ref().unwrap().signals().triggered().connect(&slot_contextual_menu_check_missing_locs); }
+        unsafe { app_ui.context_menu_check_script_references.as_ref().unwrap().signals().triggered().connect(&slot_contextual_menu_check_script_references); }
+        unsafe { app_ui.context_menu_check_xml_files.as_ref().unwrap().signals().triggered().connect(&slot_contextual_menu_check_xml_files); }
+        unsafe { app_ui.context_menu_open_translation_view.as_ref().unwrap().signals().triggered().connect(&slot_contextual_menu_open_translation_view); }
+        unsafe { app_ui.context_menu_query_tables.as_ref().unwrap().signals().triggered().connect(&slot_contextual_menu_query_tables); }
         unsafe { app_ui.context_menu_create_folder.as_ref().unwrap().signals().triggered().connect(&slot_contextual_menu_create_folder); }
         unsafe { app_ui.context_menu_create_db.as_ref().unwrap().signals().triggered().connect(&slot_contextual_menu_create_packed_file_db); }
         unsafe { app_ui.context_menu_create_loc.as_ref().unwrap().signals().triggered().connect(&slot_contextual_menu_create_packed_file_loc); }
         unsafe { app_ui.context_menu_create_text.as_ref().unwrap().signals().triggered().connect(&slot_contextual_menu_create_packed_file_text); }
         unsafe { app_ui.context_menu_mass_import_tsv.as_ref().unwrap().signals().triggered().connect(&slot_contextual_menu_mass_import_tsv); }
+        unsafe { app_ui.context_menu_import_assembly_kit_tables.as_ref().unwrap().signals().triggered().connect(&slot_contextual_menu_import_assembly_kit_tables); }
         unsafe { app_ui.context_menu_mass_export_tsv.as_ref().unwrap().signals().triggered().connect(&slot_contextual_menu_mass_export_tsv); }
+        unsafe { app_ui.context_menu_export_assembly_kit_tables.as_ref().unwrap().signals().triggered().connect(&slot_contextual_menu_export_assembly_kit_tables); }
+        unsafe { app_ui.context_menu_import_translation_files.as_ref().unwrap().signals().triggered().connect(&slot_contextual_menu_import_translation_files); }
+        unsafe { app_ui.context_menu_export_po.as_ref().unwrap().signals().triggered().connect(&slot_contextual_menu_export_po); }
+        unsafe { app_ui.context_menu_export_xliff.as_ref().unwrap().signals().triggered().connect(&slot_contextual_menu_export_xliff); }
         unsafe { app_ui.context_menu_merge_tables.as_ref().unwrap().signals().triggered().connect(&slot_contextual_menu_merge_tables); }
         unsafe { app_ui.context_menu_delete.as_ref().unwrap().signals().triggered().connect(&slot_contextual_menu_delete); }
         unsafe { app_ui.context_menu_extract.as_ref().unwrap().signals().triggered().connect(&slot_contextual_menu_extract); }
@@ -4937,22 +5784,6 @@ fn main() {
         // If we have it enabled in the prefs, check if there are schema updates.
         if SETTINGS.lock().unwrap().settings_bool["check_schema_updates_on_start"] { check_schema_updates(&app_ui, false, &sender_qt, &sender_qt_data, &receiver_qt) };
 
-        // This is to get the new schemas. It's controlled by a global const. Don't enable this unless you know what you're doing.
-        if GENERATE_NEW_SCHEMA {
-
-            // These are the paths needed for the new schemas. First one should be `assembly_kit/raw_data/db`.
-            // The second one should contain all the tables of the game, extracted directly from `data.pack`.
-            let assembly_kit_schemas_path: PathBuf = PathBuf::from("/home/frodo45127/test stuff/db_raw");
-            let testing_tables_path: PathBuf = PathBuf::from("/home/frodo45127/test stuff/db_bin/");
-            match import_schema(Schema::load("schema_wh.json").ok(), &assembly_kit_schemas_path, &testing_tables_path) {
-                Ok(_) => show_dialog(app_ui.window, true, "Schema successfully created."),
-                Err(error) => show_dialog(app_ui.window, false, error),
-            }
-
-            // Close the program with code 69
-            return 69
-        }
-
         // And launch it.
         Application::exec()
     })
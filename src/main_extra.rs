@@ -224,8 +224,14 @@ pub fn open_packedfile(
                     }
                 }
 
-                // We get his type to decode it properly
-                let packed_file_type = get_packed_file_type(&path);
+                // We get his type to decode it properly. If his path is not enough, the background thread checks his data.
+                sender_qt.send(Commands::GetPackedFileType).unwrap();
+                sender_qt_data.send(Data::VecString(path.to_vec())).unwrap();
+                let packed_file_type = match check_message_validity_recv2(&receiver_qt) {
+                    Data::DecodeablePackedFileType(data) => data,
+                    Data::Error(error) => return Err(error),
+                    _ => panic!(THREADS_MESSAGE_ERROR),
+                };
 
                 // Create the widget that'll act as a container for the view.
                 let widget = Widget::new().into_raw();
@@ -1226,22 +1232,7 @@ pub fn create_packed_files(
                             if !name.ends_with(".loc") { name.push_str(".loc"); }
                         }
                         if let PackedFileType::Text(_) = packed_file_type {
-                            if !name.ends_with(".lua") &&
-                                !name.ends_with(".xml") &&
-                                !name.ends_with(".xml.shader") &&
-                                !name.ends_with(".xml.material") &&
-                                !name.ends_with(".variantmeshdefinition") &&
-                                !name.ends_with(".environment") &&
-                                !name.ends_with(".lighting") &&
-                                !name.ends_with(".wsmodel") &&
-                                !name.ends_with(".csv") &&
-                                !name.ends_with(".tsv") &&
-                                !name.ends_with(".inl") &&
-                                !name.ends_with(".battle_speech_camera") &&
-                                !name.ends_with(".bob") &&
-                                !name.ends_with(".cindyscene") &&
-                                !name.ends_with(".cindyscenemanager") &&
-                                !name.ends_with(".txt") {
+                            if get_packed_file_type(&[name.to_owned()]) != DecodeablePackedFileType::Text {
                                 name.push_str(".txt");
                             }
                         }
@@ -72,28 +72,39 @@ impl DB {
 
         // Create the index that we'll use to decode the entire table.
         let mut index = 0;
+        Self::read_with_index(packed_file_data, db_type, master_schema, &mut index)
+    }
+
+    /// This function does the same as `read`, but it uses the provided index to decode the table, so in case
+    /// of error we can know the position where the decoding stopped.
+    pub fn read_with_index(
+        packed_file_data: &[u8],
+        db_type: &str,
+        master_schema: &Schema,
+        index: &mut usize,
+    ) -> Result<Self> {
 
         // Checks to ensure this is a decodeable DB Table.
-        if packed_file_data.len() < 5 { return Err(ErrorKind::DBTableIsNotADBTable)? }
+        if packed_file_data.len() < *index + 5 { return Err(ErrorKind::DBTableIsNotADBTable)? }
 
         // If there is a GUID_MARKER, skip it together with the GUID itself (4 bytes for the marker, 74 for the GUID).
-        if &packed_file_data[index..(index + 4)] == GUID_MARKER { index += 78; }
+        if packed_file_data.get(*index..(*index + 4)) == Some(GUID_MARKER) { *index += 78; }
 
         // If there is a VERSION_MARKER, we get the version. Otherwise, we default to 0.
         let version = 
-            if (index + 4) < packed_file_data.len() {
-                if &packed_file_data[index..(index + 4)] == VERSION_MARKER { 
-                    if (index + 8) < packed_file_data.len() { 
-                        index += 8;
-                        decode_integer_i32(&packed_file_data[(index - 4)..(index)])?
+            if (*index + 4) < packed_file_data.len() {
+                if packed_file_data.get(*index..(*index + 4)) == Some(VERSION_MARKER) { 
+                    if (*index + 8) < packed_file_data.len() { 
+                        *index += 8;
+                        decode_integer_i32(&packed_file_data[(*index - 4)..(*index)])?
                     } else { return Err(ErrorKind::DBTableIsNotADBTable)? }
                 } else { 0 }
             } else { return Err(ErrorKind::DBTableIsNotADBTable)? };
 
         // We get the rest of the data from the header.
-        let mysterious_byte = if (index) < packed_file_data.len() { packed_file_data[index] } else { return Err(ErrorKind::DBTableIsNotADBTable)? };
-        index += 1;
-        let entry_count = if (index + 4) <= packed_file_data.len() { decode_packedfile_integer_u32(&packed_file_data[(index)..(index + 4)], &mut index)? } else { return Err(ErrorKind::DBTableIsNotADBTable)? };
+        let mysterious_byte = if (*index) < packed_file_data.len() { packed_file_data[*index] } else { return Err(ErrorKind::DBTableIsNotADBTable)? };
+        *index += 1;
+        let entry_count = if (*index + 4) <= packed_file_data.len() { decode_packedfile_integer_u32(&packed_file_data[(*index)..(*index + 4)], index)? } else { return Err(ErrorKind::DBTableIsNotADBTable)? };
 
         // These tables use the not-yet-implemented type "List" in the following versions:
         // - models_artillery: 0,
@@ -125,50 +136,50 @@ impl DB {
 
                     let decoded_cell = match table_definition.fields[column].field_type {
                         FieldType::Boolean => {
-                            if packed_file_data.get(index).is_some() { 
-                                if let Ok(data) = decode_packedfile_bool(packed_file_data[index], &mut index) { DecodedData::Boolean(data) }
+                            if packed_file_data.get(*index).is_some() { 
+                                if let Ok(data) = decode_packedfile_bool(packed_file_data[*index], index) { DecodedData::Boolean(data) }
                                 else { return Err(ErrorKind::HelperDecodingEncodingError(format!("<p>Error trying to decode the <i><b>Row {}, Cell {}</b></i> as a <b><i>Boolean</b></i> value: the value is not a boolean.</p>", row + 1, column + 1)))? }}
                             else { return Err(ErrorKind::HelperDecodingEncodingError(format!("<p>Error trying to decode the <i><b>Row {}, Cell {}</b></i> as a <b><i>Boolean</b></i> value: insufficient bytes to decode.</p>", row + 1, column + 1)))? }
                         }
                         FieldType::Float => {
-                            if packed_file_data.get(index + 3).is_some() {
-                                if let Ok(data) = decode_packedfile_float_f32(&packed_file_data[index..(index + 4)], &mut index) { DecodedData::Float(data) }
+                            if packed_file_data.get(*index + 3).is_some() {
+                                if let Ok(data) = decode_packedfile_float_f32(&packed_file_data[*index..(*index + 4)], index) { DecodedData::Float(data) }
                                 else { return Err(ErrorKind::HelperDecodingEncodingError(format!("<p>Error trying to decode the <i><b>Row {}, Cell {}</b></i> as a <b><i>F32</b></i> value: the value is not a valid F32.</p>", row + 1, column + 1)))? }}
                             else { return Err(ErrorKind::HelperDecodingEncodingError(format!("<p>Error trying to decode the <i><b>Row {}, Cell {}</b></i> as a <b><i>F32</b></i> value: insufficient bytes to decode.</p>", row + 1, column + 1)))? }
                         }
                         FieldType::Integer => {
-                            if packed_file_data.get(index + 3).is_some() {
-                                if let Ok(data) = decode_packedfile_integer_i32(&packed_file_data[index..(index + 4)], &mut index) { DecodedData::Integer(data) }
+                            if packed_file_data.get(*index + 3).is_some() {
+                                if let Ok(data) = decode_packedfile_integer_i32(&packed_file_data[*index..(*index + 4)], index) { DecodedData::Integer(data) }
                                 else { return Err(ErrorKind::HelperDecodingEncodingError(format!("<p>Error trying to decode the <i><b>Row {}, Cell {}</b></i> as a <b><i>I32</b></i> value: the value is not a valid I32.</p>", row + 1, column + 1)))? }}
                             else { return Err(ErrorKind::HelperDecodingEncodingError(format!("<p>Error trying to decode the <i><b>Row {}, Cell {}</b></i> as a <b><i>I32</b></i> value: insufficient bytes to decode.</p>", row + 1, column + 1)))? }
                         }
                         FieldType::LongInteger => {
-                            if packed_file_data.get(index + 7).is_some() {
-                                if let Ok(data) = decode_packedfile_integer_i64(&packed_file_data[index..(index + 8)], &mut index) { DecodedData::LongInteger(data) }
+                            if packed_file_data.get(*index + 7).is_some() {
+                                if let Ok(data) = decode_packedfile_integer_i64(&packed_file_data[*index..(*index + 8)], index) { DecodedData::LongInteger(data) }
                                 else { return Err(ErrorKind::HelperDecodingEncodingError(format!("<p>Error trying to decode the <i><b>Row {}, Cell {}</b></i> as a <b><i>I64</b></i> value: the value is not a valid I64.</p>", row + 1, column + 1)))? }}
                             else { return Err(ErrorKind::HelperDecodingEncodingError(format!("<p>Error trying to decode the <i><b>Row {}, Cell {}</b></i> as a <b><i>I64</b></i> value: insufficient bytes to decode.</p>", row + 1, column + 1)))? }
                         }
                         FieldType::StringU8 => {
-                            if packed_file_data.get(index + 1).is_some() { 
-                                if let Ok(data) = decode_packedfile_string_u8(&packed_file_data[index..], &mut index) { DecodedData::StringU8(data) }
+                            if packed_file_data.get(*index + 1).is_some() { 
+                                if let Ok(data) = decode_packedfile_string_u8(&packed_file_data[*index..], index) { DecodedData::StringU8(data) }
                                 else { return Err(ErrorKind::HelperDecodingEncodingError(format!("<p>Error trying to decode the <i><b>Row {}, Cell {}</b></i> as an <b><i>UTF-8 String</b></i> value: the value is not a valid UTF-8 String.</p>", row + 1, column + 1)))? }}
                             else { return Err(ErrorKind::HelperDecodingEncodingError(format!("<p>Error trying to decode the <i><b>Row {}, Cell {}</b></i> as an <b><i>UTF-8 String</b></i> value: insufficient bytes to decode.</p>", row + 1, column + 1)))? }
                         }
                         FieldType::StringU16 => {
-                            if packed_file_data.get(index + 1).is_some() { 
-                                if let Ok(data) = decode_packedfile_string_u16(&packed_file_data[index..], &mut index) { DecodedData::StringU16(data) }
+                            if packed_file_data.get(*index + 1).is_some() { 
+                                if let Ok(data) = decode_packedfile_string_u16(&packed_file_data[*index..], index) { DecodedData::StringU16(data) }
                                 else { return Err(ErrorKind::HelperDecodingEncodingError(format!("<p>Error trying to decode the <i><b>Row {}, Cell {}</b></i> as an <b><i>UTF-16 String</b></i> value: the value is not a valid UTF-16 String.</p>", row + 1, column + 1)))? }}
                             else { return Err(ErrorKind::HelperDecodingEncodingError(format!("<p>Error trying to decode the <i><b>Row {}, Cell {}</b></i> as an <b><i>UTF-16 String</b></i> value: insufficient bytes to decode.</p>", row + 1, column + 1)))? }
                         }
                         FieldType::OptionalStringU8 => {
-                            if packed_file_data.get(index).is_some() { 
-                                if let Ok(data) = decode_packedfile_optional_string_u8(&packed_file_data[index..], &mut index) { DecodedData::OptionalStringU8(data) }
+                            if packed_file_data.get(*index).is_some() { 
+                                if let Ok(data) = decode_packedfile_optional_string_u8(&packed_file_data[*index..], index) { DecodedData::OptionalStringU8(data) }
                                 else { return Err(ErrorKind::HelperDecodingEncodingError(format!("<p>Error trying to decode the <i><b>Row {}, Cell {}</b></i> as an <b><i>Optional UTF-8 String</b></i> value: the value is not a valid Optional UTF-8 String.</p>", row + 1, column + 1)))? }}
                             else { return Err(ErrorKind::HelperDecodingEncodingError(format!("<p>Error trying to decode the <i><b>Row {}, Cell {}</b></i> as an <b><i>Optional UTF-8 String</b></i> value: insufficient bytes to decode.</p>", row + 1, column + 1)))? }
                         }
                         FieldType::OptionalStringU16 => {
-                            if packed_file_data.get(index).is_some() { 
-                                if let Ok(data) = decode_packedfile_optional_string_u16(&packed_file_data[index..], &mut index) { DecodedData::OptionalStringU16(data) }
+                            if packed_file_data.get(*index).is_some() { 
+                                if let Ok(data) = decode_packedfile_optional_string_u16(&packed_file_data[*index..], index) { DecodedData::OptionalStringU16(data) }
                                 else { return Err(ErrorKind::HelperDecodingEncodingError(format!("<p>Error trying to decode the <i><b>Row {}, Cell {}</b></i> as an <b><i>Optional UTF-16 String</b></i> value: the value is not a valid Optional UTF-16 String.</p>", row + 1, column + 1)))? }}
                             else { return Err(ErrorKind::HelperDecodingEncodingError(format!("<p>Error trying to decode the <i><b>Row {}, Cell {}</b></i> as an <b><i>Optional UTF-16 String</b></i> value: insufficient bytes to decode.</p>", row + 1, column + 1)))? }
                         }
@@ -179,7 +190,7 @@ impl DB {
             }
 
             // If we are not in the last byte, it means we didn't parse the entire file, which means this file is corrupt.
-            if index != packed_file_data.len() { return Err(ErrorKind::PackedFileSizeIsNotWhatWeExpect(packed_file_data.len(), index))? }
+            if *index != packed_file_data.len() { return Err(ErrorKind::PackedFileSizeIsNotWhatWeExpect(packed_file_data.len(), *index))? }
 
             // If we've reached this, we've succesfully decoded the table.
             Ok(Self {
@@ -232,6 +243,21 @@ impl DB {
         packed_file
     }
 
+    /// This function updates the table to the provided table definition. Columns are matched by their name and, if
+    /// one of them is not in our current definition or his data cannot be converted to his new type, it gets a default value.
+    pub fn set_table_definition(&mut self, table_definition: &TableDefinition) {
+        let columns = table_definition.fields.iter().map(|x| self.table_definition.fields.iter().position(|y| y.field_name == x.field_name)).collect::<Vec<Option<usize>>>();
+        for row in &mut self.entries {
+            *row = columns.iter().zip(table_definition.fields.iter()).map(|(column, field)| match column {
+                Some(column) => row[*column].convert_to_type(field.field_type).unwrap_or_else(|| DecodedData::default_from_field(field)),
+                None => DecodedData::default_from_field(field),
+            }).collect();
+        }
+
+        self.version = table_definition.version;
+        self.table_definition = table_definition.clone();
+    }
+
     /// This functions returns the version and entry count of a DB Table, without decoding the entire table. It just emulates what the `read` function does.
     pub fn get_header_data(packed_file_data: &[u8]) -> Result<(i32, u32, usize)> {
 
@@ -261,6 +287,35 @@ impl DB {
         Ok((version, entry_count, index))
     }
 
+    /// This function returns if the provided data starts like a DB Table. Only tables with a GUID or version marker can be detected,
+    /// as the header of tables without them has nothing special.
+    pub fn is_db_table(packed_file_data: &[u8]) -> bool {
+        (packed_file_data.starts_with(GUID_MARKER) || packed_file_data.starts_with(VERSION_MARKER)) &&
+            Self::get_header_data(packed_file_data).is_ok()
+    }
+
+    /// This function returns the name of the table of the provided DB Table, like `units_tables`. Tables inside the `db` folder
+    /// use the name of their folder. For any other table, we use his folder if it looks like a table name, or the only table of the
+    /// schema with the version of the table that can decode it. If more than one can decode it we cannot know which one it is, and
+    /// empty tables can be decoded by any of them, so in those cases it has no name.
+    pub fn get_table_name(path: &[String], packed_file_data: &[u8], schema: &Schema) -> Option<String> {
+        if path.len() == 3 && path[0] == "db" { return Some(path[1].to_owned()) }
+        if path.len() > 1 && path[path.len() - 2].ends_with("_tables") { return Some(path[path.len() - 2].to_owned()) }
+
+        let (version, entry_count, _) = Self::get_header_data(packed_file_data).ok()?;
+        if entry_count == 0 { return None }
+
+        // We only need to know if there is more than one table that can decode it, so we stop at the second one.
+        let table_names = schema.tables_definitions.iter()
+            .filter(|x| x.versions.iter().any(|x| x.version == version && !x.fields.is_empty()))
+            .filter(|x| Self::read(packed_file_data, &x.name, schema).is_ok())
+            .take(2)
+            .map(|x| x.name.to_owned())
+            .collect::<Vec<String>>();
+
+        if table_names.len() == 1 { table_names.into_iter().next() } else { None }
+    }
+
     /// This function gets the schema corresponding to the table we passed it, if it exists.
     pub fn get_schema(db_name: &str, version: i32, schema: &Schema) -> Option<TableDefinition> {
         if let Some(index_table_definitions) = schema.get_table_definitions(db_name) {
@@ -284,14 +339,14 @@ impl DB {
     }
 
     /// This function removes from the schema the version of a table with the provided version.
+    /// If the table or the version are not in the schema, it returns an error.
     pub fn remove_table_version(table_name: &str, version: i32, schema: &mut Schema) -> Result<()> {
         if let Some(index_table_definitions) = schema.get_table_definitions(table_name) {
             if let Some(index_table_versions) = schema.tables_definitions[index_table_definitions].get_table_version(version) {
                 schema.tables_definitions[index_table_definitions].versions.remove(index_table_versions);
                 return Ok(())
             }
-            unreachable!();
         }
-        unreachable!();
+        Err(ErrorKind::SchemaTableDefinitionNotFound)?
     }
 }
@@ -0,0 +1,167 @@
+//---------------------------------------------------------------------------//
+// Copyright (c) 2017-2019 Ismael Gutiérrez González. All rights reserved.
+//
+// This file is part of the Rusted PackFile Manager (RPFM) project,
+// which can be found here: https://github.com/Frodo45127/rpfm.
+//
+// This file is licensed under the MIT license, which can be found here:
+// https://github.com/Frodo45127/rpfm/blob/master/LICENSE.
+//---------------------------------------------------------------------------//
+
+// In this file are all the functions needed to validate the inline markup (`[[col:red]]...[[/col]]`, `[[img:...]]`,
+// `{{tr:...}}`) and the placeholders (`%s`, `%d`,...) of the text of Loc PackedFiles.
+
+use lazy_static::lazy_static;
+use regex::Regex;
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::error::Result;
+use crate::packfile::PackFile;
+use crate::packedfile::{DecodeablePackedFileType, DecodedData, get_packed_file_type};
+use super::Loc;
+use super::translations::get_vanilla_english_texts;
+
+/// Tags that need to be closed, like `[[col:red]]text[[/col]]`. More are taken from the vanilla Locs, if we have them.
+const PAIRED_TAGS: [&str; 9] = ["col", "rgba", "b", "i", "u", "url", "tooltip", "sl", "sl_tooltip"];
+
+/// Tags that don't need to be closed, like `[[img:icon_money]]`. Their closing tag is optional.
+const STANDALONE_TAGS: [&str; 1] = ["img"];
+
+lazy_static! {
+
+    /// Regex to find the tags of a text. The first group is the `/` of closing tags, and the second one the name of the tag.
+    static ref TAG_REGEX: Regex = Regex::new(r"\[\[(/?)([^\]:/]*)(?::[^\]]*)?\]\]").unwrap();
+
+    /// Regex to find the `{{...}}` blocks of a text.
+    static ref BLOCK_REGEX: Regex = Regex::new(r"\{\{([^{}]*)\}\}").unwrap();
+
+    /// Regex to find the `%s`-style placeholders of a text, and the escaped percents (`%%`).
+    static ref PLACEHOLDER_REGEX: Regex = Regex::new(r"%(%|[0-9]*(?:\.[0-9]+)?[a-zA-Z])").unwrap();
+}
+
+/// This struct contains the tags we know, to check the markup of the Locs against them.
+/// - paired: tags that need to be closed.
+/// - standalone: tags that don't need to be closed.
+/// - are_complete: if the tags of the vanilla Locs are in the lists. If not, unknown tags are not reported, as they may be valid.
+#[derive(Clone, Debug)]
+pub struct MarkupTags {
+    pub paired: BTreeSet<String>,
+    pub standalone: BTreeSet<String>,
+    pub are_complete: bool,
+}
+
+/// Implementation of `MarkupTags`.
+impl MarkupTags {
+
+    /// This function creates the list of known tags from our own lists and the tags used in the provided vanilla texts.
+    /// Tags always closed in vanilla are considered paired. The rest are considered standalone.
+    pub fn new(vanilla_texts: &BTreeMap<String, String>) -> Self {
+        let mut paired = PAIRED_TAGS.iter().map(|x| (*x).to_owned()).collect::<BTreeSet<String>>();
+        let mut standalone = STANDALONE_TAGS.iter().map(|x| (*x).to_owned()).collect::<BTreeSet<String>>();
+
+        // Amount of times each vanilla tag is opened and closed.
+        let mut vanilla_tags: BTreeMap<String, (usize, usize)> = BTreeMap::new();
+        for text in vanilla_texts.values() {
+            for tag in TAG_REGEX.captures_iter(text) {
+                let count = vanilla_tags.entry(tag[2].to_lowercase()).or_insert((0, 0));
+                if tag[1].is_empty() { count.0 += 1; } else { count.1 += 1; }
+            }
+        }
+
+        for (name, (opened, closed)) in vanilla_tags {
+            if paired.contains(&name) || standalone.contains(&name) { continue }
+            if closed >= opened { paired.insert(name); } else { standalone.insert(name); }
+        }
+
+        Self {
+            paired,
+            standalone,
+            are_complete: !vanilla_texts.is_empty(),
+        }
+    }
+}
+
+/// This function returns the markup errors in the provided text: unknown tags, unbalanced tags and broken `{{...}}` blocks.
+pub fn get_markup_errors(text: &str, tags: &MarkupTags) -> Vec<String> {
+    let mut errors = vec![];
+
+    // Tags are checked with a stack, so we can report tags closed in the wrong order. Unknown tags are treated as standalone ones.
+    let mut open_tags: Vec<String> = vec![];
+    for tag in TAG_REGEX.captures_iter(text) {
+        let is_closing = !tag[1].is_empty();
+        let name = tag[2].to_lowercase();
+
+        if tags.standalone.contains(&name) { continue }
+        else if !tags.paired.contains(&name) { if tags.are_complete { errors.push(format!("Unknown tag: {}", &tag[0])); } }
+        else if !is_closing { open_tags.push(name); }
+        else if open_tags.last() == Some(&name) { open_tags.pop(); }
+        else if let Some(position) = open_tags.iter().rposition(|x| x == &name) {
+            for inner_tag in open_tags.drain(position..).skip(1) {
+                errors.push(format!("Tag [[{}]] closed after [[/{}]].", inner_tag, name));
+            }
+        }
+        else { errors.push(format!("Closing tag without opening tag: {}", &tag[0])); }
+    }
+
+    for tag in &open_tags { errors.push(format!("Tag not closed: [[{}]]", tag)); }
+
+    // Anything left after removing the valid tags means there is a broken one.
+    let remaining = TAG_REGEX.replace_all(text, "");
+    if remaining.contains("[[") || remaining.contains("]]") { errors.push("Tag with missing brackets.".to_owned()); }
+
+    for block in BLOCK_REGEX.captures_iter(text) {
+        let content = block[1].trim();
+        if content.is_empty() { errors.push("Empty {{}} block.".to_owned()); }
+        else if content.starts_with("tr:") && content[3..].trim().is_empty() { errors.push("{{tr:}} block without loc key.".to_owned()); }
+    }
+
+    let remaining = BLOCK_REGEX.replace_all(text, "");
+    if remaining.contains("{{") || remaining.contains("}}") { errors.push("{{...}} block with missing braces.".to_owned()); }
+
+    errors
+}
+
+/// This function returns the `%s`-style placeholders of the provided text, with the amount of times each one is used.
+/// Escaped percents (`%%`) are not placeholders.
+pub fn get_placeholders(text: &str) -> BTreeMap<String, usize> {
+    let mut placeholders = BTreeMap::new();
+    for placeholder in PLACEHOLDER_REGEX.find_iter(text).map(|x| x.as_str()).filter(|x| *x != "%%") {
+        *placeholders.entry(placeholder.to_owned()).or_insert(0) += 1;
+    }
+    placeholders
+}
+
+/// This function checks the markup of every entry of every Loc PackedFile in the provided PackFile, and compares his placeholders
+/// with the ones of the vanilla english entry with the same key. It returns the list of problems found, one per entry.
+pub fn check_loc_markup(pack_file: &mut PackFile) -> Result<Vec<String>> {
+    let vanilla_texts = get_vanilla_english_texts();
+    let tags = MarkupTags::new(&vanilla_texts);
+    let mut broken_entries = vec![];
+
+    for packed_file in pack_file.packed_files.iter_mut().filter(|x| get_packed_file_type(&x.path) == DecodeablePackedFileType::Loc) {
+        if let Ok(loc) = Loc::read(&packed_file.get_data_and_keep_it()?) {
+            for row in &loc.entries {
+                if let (DecodedData::StringU16(key), DecodedData::StringU16(text)) = (&row[0], &row[1]) {
+                    let mut errors = get_markup_errors(text, &tags);
+
+                    if let Some(vanilla_text) = vanilla_texts.get(key) {
+                        let placeholders = get_placeholders(text);
+                        let vanilla_placeholders = get_placeholders(vanilla_text);
+                        if placeholders != vanilla_placeholders {
+                            let format_placeholders = |placeholders: &BTreeMap<String, usize>| if placeholders.is_empty() { "none".to_owned() }
+                                else { placeholders.iter().map(|(x, y)| format!("{} x{}", x, y)).collect::<Vec<String>>().join(", ") };
+                            errors.push(format!("Placeholders ({}) differ from the vanilla ones ({}).", format_placeholders(&placeholders), format_placeholders(&vanilla_placeholders)));
+                        }
+                    }
+
+                    if !errors.is_empty() {
+                        broken_entries.push(format!("Loc: {}, Key: {}: {}", packed_file.path.join("/"), key, errors.join(" ")));
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(broken_entries)
+}
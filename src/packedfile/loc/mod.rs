@@ -15,6 +15,10 @@ use crate::common::coding_helpers::*;
 use crate::error::{ErrorKind, Result};
 use super::DecodedData;
 
+pub mod markup;
+pub mod translation_files;
+pub mod translations;
+
 /// This const represents the value that every LOC PackedFile has in their first 2 bytes.
 const BYTEORDER_MARK: u16 = 65279; // FF FE
 
@@ -39,6 +43,14 @@ impl Loc {
         Self { entries: vec![] }
     }
 
+    /// This function returns if the provided data starts like a Loc PackedFile: BOM, "LOC" and his version.
+    pub fn is_loc(packed_file_data: &[u8]) -> bool {
+        packed_file_data.len() >= 14 &&
+            decode_integer_u16(&packed_file_data[0..2]).ok() == Some(BYTEORDER_MARK) &&
+            &packed_file_data[2..5] == PACKED_FILE_TYPE.as_bytes() &&
+            decode_integer_u32(&packed_file_data[6..10]).ok() == Some(PACKED_FILE_VERSION)
+    }
+
     /// This function creates a new decoded Loc from the data of a PackedFile.
     pub fn read(packed_file_data: &[u8]) -> Result<Self> {
 
@@ -56,17 +68,15 @@ impl Loc {
         let mut index = 14 as usize;
         for _ in 0..entry_count {
 
-            // Decode the three fields escaping \t and \n to avoid weird behavior.
+            // Decode the three fields. Strings are kept as they are, escaping is only done when showing them in the UI or in TSV files.
             let mut entry = vec![];
             if index < packed_file_data.len() { 
-                let mut key = decode_packedfile_string_u16(&packed_file_data[index..], &mut index)?;
-                key = key.replace("\t", "\\t").replace("\n", "\\n");
+                let key = decode_packedfile_string_u16(&packed_file_data[index..], &mut index)?;
                 entry.push(DecodedData::StringU16(key));
             } else { return Err(ErrorKind::LocPackedFileCorrupted)? };
 
             if index < packed_file_data.len() { 
-                let mut text = decode_packedfile_string_u16(&packed_file_data[index..], &mut index)?;
-                text = text.replace("\t", "\\t").replace("\n", "\\n");
+                let text = decode_packedfile_string_u16(&packed_file_data[index..], &mut index)?;
                 entry.push(DecodedData::StringU16(text));
             } else { return Err(ErrorKind::LocPackedFileCorrupted)? };
             
@@ -104,7 +114,7 @@ impl Loc {
             for cell in row {
                 match *cell {
                     DecodedData::Boolean(data) => packed_file.push(encode_bool(data)),
-                    DecodedData::StringU16(ref data) => packed_file.extend_from_slice(&encode_packedfile_string_u16(&data.replace("\\t", "\t").replace("\\n", "\n"))),
+                    DecodedData::StringU16(ref data) => packed_file.extend_from_slice(&encode_packedfile_string_u16(data)),
                     _ => unreachable!()
                 }
             }
@@ -113,4 +123,46 @@ impl Loc {
         // And return the encoded PackedFile.
         packed_file
     }
+
+    /// This function escapes the text of a Loc entry, so it can be shown in a single line in the UI or in a TSV file.
+    ///
+    /// Backslashes are escaped as `\\`, tabs as `\t`, newlines as `\n` and carriage returns as `\r`, so `unescape_text`
+    /// can always get the original text back.
+    pub fn escape_text(text: &str) -> String {
+        let mut escaped = String::with_capacity(text.len());
+        for character in text.chars() {
+            match character {
+                '\\' => escaped.push_str("\\\\"),
+                '\t' => escaped.push_str("\\t"),
+                '\n' => escaped.push_str("\\n"),
+                '\r' => escaped.push_str("\\r"),
+                _ => escaped.push(character),
+            }
+        }
+        escaped
+    }
+
+    /// This function reverses `escape_text`. Backslashes not followed by `\\`, `t`, `n` or `r` are kept as they are,
+    /// so text written by hand without escaping its backslashes doesn't lose them.
+    pub fn unescape_text(text: &str) -> String {
+        let mut unescaped = String::with_capacity(text.len());
+        let mut characters = text.chars().peekable();
+        while let Some(character) = characters.next() {
+            if character == '\\' {
+                match characters.peek() {
+                    Some('\\') => unescaped.push('\\'),
+                    Some('t') => unescaped.push('\t'),
+                    Some('n') => unescaped.push('\n'),
+                    Some('r') => unescaped.push('\r'),
+                    _ => {
+                        unescaped.push(character);
+                        continue;
+                    }
+                }
+                characters.next();
+            }
+            else { unescaped.push(character); }
+        }
+        unescaped
+    }
 }
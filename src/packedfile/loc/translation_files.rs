@@ -0,0 +1,537 @@
+//---------------------------------------------------------------------------//
+// Copyright (c) 2017-2019 Ismael Gutiérrez González. All rights reserved.
+//
+// This file is part of the Rusted PackFile Manager (RPFM) project,
+// which can be found here: https://github.com/Frodo45127/rpfm.
+//
+// This file is licensed under the MIT license, which can be found here:
+// https://github.com/Frodo45127/rpfm/blob/master/LICENSE.
+//---------------------------------------------------------------------------//
+
+// In this file are all the functions needed to import and export Loc PackedFiles as translation files
+// for CAT tools: Gettext PO files and XLIFF 1.2 files.
+//
+// In both formats the loc key is the context (PO) or the id (XLIFF) of each entry, the vanilla english text
+// is the source, the text of the Loc PackedFile is the translation and the tooltip flag is kept in a note.
+// The path of the Loc PackedFile is kept in the header (PO) or in the `original` attribute (XLIFF).
+
+use serde_derive::{Serialize, Deserialize};
+use xml::attribute::OwnedAttribute;
+use xml::common::Position;
+use xml::reader::{EventReader, XmlEvent};
+
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{BufReader, Read, Write};
+use std::path::PathBuf;
+
+use crate::common::*;
+use crate::error::{Error, ErrorKind, Result};
+use crate::packfile::{PackFile, PathType};
+use crate::packfile::packedfile::PackedFile;
+use crate::packedfile::{DecodeablePackedFileType, DecodedData, get_packed_file_type};
+use super::Loc;
+use super::translations::{get_loc_language, get_vanilla_english_texts};
+
+/// Header field of PO files where we keep the path of the Loc PackedFile.
+const PO_HEADER_LOC_PATH: &str = "X-RPFM-Loc-Path";
+
+/// Prefix of the notes where we keep the tooltip flag of each entry.
+const TOOLTIP_NOTE: &str = "tooltip: ";
+
+/// This enum represents the formats of translation files we can import/export Loc PackedFiles to.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum TranslationFileFormat {
+    Po,
+    Xliff,
+}
+
+/// This struct represents an entry of a translation file, as it should be imported into a Loc PackedFile.
+/// - key: the loc key.
+/// - text: the translated text.
+/// - tooltip: the tooltip flag, if the file has it.
+#[derive(Clone, Debug)]
+pub struct TranslationFileEntry {
+    pub key: String,
+    pub text: String,
+    pub tooltip: Option<bool>,
+}
+
+/// Implementation of `TranslationFileFormat`.
+impl TranslationFileFormat {
+
+    /// This function returns the format of the provided file, depending on his extension.
+    pub fn from_path(path: &PathBuf) -> Option<Self> {
+        match path.extension().and_then(|x| x.to_str()).map(|x| x.to_lowercase()) {
+            Some(ref extension) if extension == "po" || extension == "pot" => Some(TranslationFileFormat::Po),
+            Some(ref extension) if extension == "xlf" || extension == "xliff" => Some(TranslationFileFormat::Xliff),
+            _ => None,
+        }
+    }
+
+    /// This function returns the extension we use for files of this format.
+    pub fn get_extension(self) -> &'static str {
+        match self {
+            TranslationFileFormat::Po => "po",
+            TranslationFileFormat::Xliff => "xlf",
+        }
+    }
+}
+
+//----------------------------------------------------------------//
+// Export Functions.
+//----------------------------------------------------------------//
+
+/// This function creates a translation file with the entries of a Loc PackedFile, in the provided format.
+///
+/// The source of each entry is his vanilla english text. If the key is not a vanilla one, his current text is used instead.
+pub fn export_translation_file(
+    entries: &[Vec<DecodedData>],
+    path: &PathBuf,
+    loc_path: &[String],
+    vanilla_texts: &BTreeMap<String, String>,
+    format: TranslationFileFormat,
+) -> Result<()> {
+    let language = get_loc_language(loc_path);
+    let entries = entries.iter().filter_map(|x| match (&x[0], &x[1], &x[2]) {
+        (DecodedData::StringU16(key), DecodedData::StringU16(text), DecodedData::Boolean(tooltip)) => {
+            let source = match vanilla_texts.get(key) {
+                Some(source) => source.to_owned(),
+                None => text.to_owned(),
+            };
+            Some((key, source, text, *tooltip))
+        }
+        _ => None,
+    }).collect::<Vec<(&String, String, &String, bool)>>();
+
+    let mut file = String::new();
+    match format {
+        TranslationFileFormat::Po => {
+            file.push_str("# Loc PackedFile exported with RPFM.\n");
+            file.push_str("msgid \"\"\nmsgstr \"\"\n");
+            file.push_str("\"MIME-Version: 1.0\\n\"\n");
+            file.push_str("\"Content-Type: text/plain; charset=UTF-8\\n\"\n");
+            file.push_str("\"Content-Transfer-Encoding: 8bit\\n\"\n");
+            if let Some(ref language) = language { file.push_str(&format!("\"Language: {}\\n\"\n", language)); }
+            file.push_str(&format!("\"{}: {}\\n\"\n", PO_HEADER_LOC_PATH, escape_po_string(&loc_path.join("/"))));
+
+            // PO files use an empty source for the header, so empty sources are replaced with the key.
+            for (key, source, text, tooltip) in &entries {
+                file.push_str(&format!("\n#. {}{}\n", TOOLTIP_NOTE, tooltip));
+                file.push_str(&format!("msgctxt \"{}\"\n", escape_po_string(key)));
+                file.push_str(&format!("msgid \"{}\"\n", escape_po_string(if source.is_empty() { key.as_str() } else { source.as_str() })));
+                file.push_str(&format!("msgstr \"{}\"\n", escape_po_string(text)));
+            }
+        }
+
+        TranslationFileFormat::Xliff => {
+            file.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+            file.push_str("<xliff version=\"1.2\" xmlns=\"urn:oasis:names:tc:xliff:document:1.2\">\n");
+            match language {
+                Some(ref language) => file.push_str(&format!("  <file original=\"{}\" source-language=\"en\" target-language=\"{}\" datatype=\"plaintext\">\n", escape_xml_attribute(&loc_path.join("/")), escape_xml_attribute(language))),
+                None => file.push_str(&format!("  <file original=\"{}\" source-language=\"en\" datatype=\"plaintext\">\n", escape_xml_attribute(&loc_path.join("/")))),
+            }
+            file.push_str("    <body>\n");
+            for (key, source, text, tooltip) in &entries {
+                file.push_str(&format!("      <trans-unit id=\"{0}\" resname=\"{0}\" xml:space=\"preserve\">\n", escape_xml_attribute(key)));
+                file.push_str(&format!("        <source>{}</source>\n", escape_xml_string(source)));
+                file.push_str(&format!("        <target>{}</target>\n", escape_xml_string(text)));
+                file.push_str(&format!("        <note from=\"RPFM\">{}{}</note>\n", TOOLTIP_NOTE, tooltip));
+                file.push_str("      </trans-unit>\n");
+            }
+            file.push_str("    </body>\n  </file>\n</xliff>\n");
+        }
+    }
+
+    File::create(&path)?.write_all(file.as_bytes())?;
+    Ok(())
+}
+
+/// This function is used to Mass-Export the Loc PackedFiles within the provided paths as translation files.
+/// Note that this will OVERWRITE any existing file that has a name conflict with the exported files.
+pub fn translation_files_mass_export(
+    item_types: &[PathType],
+    export_path: &PathBuf,
+    format: TranslationFileFormat,
+    pack_file: &mut PackFile
+) -> Result<String> {
+    let vanilla_texts = get_vanilla_english_texts();
+    let mut error_list = vec![];
+    let mut exported_files = vec![];
+
+    for packed_file in &mut pack_file.packed_files {
+        let is_selected = item_types.iter().any(|x| match x {
+            PathType::File(path) => path == &packed_file.path,
+            PathType::Folder(path) => packed_file.path.starts_with(path),
+            PathType::PackFile => true,
+            PathType::None => false,
+        });
+
+        if is_selected && get_packed_file_type(&packed_file.path) == DecodeablePackedFileType::Loc {
+            match Loc::read(&(packed_file.get_data_and_keep_it()?)) {
+                Ok(loc) => {
+
+                    // His name will be "file_name.loc.po". If that's taken, we'll add an index until we find one available.
+                    let file_name = packed_file.path.last().unwrap().to_owned();
+                    let mut name = format!("{}.{}", file_name, format.get_extension());
+                    let mut index = 1;
+                    while exported_files.contains(&name) {
+                        name = format!("{}_{}.{}", file_name, index, format.get_extension());
+                        index += 1;
+                    }
+
+                    let mut export_path = export_path.to_path_buf();
+                    export_path.push(name.to_owned());
+                    match export_translation_file(&loc.entries, &export_path, &packed_file.path, &vanilla_texts, format) {
+                        Ok(_) => exported_files.push(name),
+                        Err(error) => error_list.push((packed_file.path.join("\\"), error)),
+                    }
+                }
+                Err(error) => error_list.push((packed_file.path.join("\\"), error)),
+            }
+        }
+    }
+
+    if exported_files.is_empty() && error_list.is_empty() { Ok("<p>There are no Loc PackedFiles to export in the selection.</p>".to_owned()) }
+    else if !error_list.is_empty() {
+        let error_files_string = error_list.iter().map(|x| format!("<li>{}</li>", x.0)).collect::<String>();
+        Ok(format!("<p>All Loc PackedFiles have been exported, except the following ones:</p><ul>{}</ul>", error_files_string))
+    }
+    else { Ok(format!("<p>{} Loc PackedFiles have been exported.</p>", exported_files.len())) }
+}
+
+//----------------------------------------------------------------//
+// Import Functions.
+//----------------------------------------------------------------//
+
+/// This function reads a translation file, returning the entries of each Loc PackedFile in it, with the path of the Loc
+/// PackedFile, if the file has it. Entries without translation are ignored.
+pub fn import_translation_file(path: &PathBuf) -> Result<Vec<(Option<Vec<String>>, Vec<TranslationFileEntry>)>> {
+    let mut file = String::new();
+    BufReader::new(File::open(&path)?).read_to_string(&mut file)?;
+    let file = file.trim_start_matches('\u{feff}');
+
+    match TranslationFileFormat::from_path(path) {
+        Some(TranslationFileFormat::Po) => import_po(file).map(|x| vec![x]),
+        Some(TranslationFileFormat::Xliff) => import_xliff(file),
+        None => Err(ErrorKind::ImportTranslationFileUnknownFormat)?,
+    }
+}
+
+/// This function is used to Mass-Import translation files into a PackFile. The entries of each file are imported into the
+/// Loc PackedFile they were exported from, updating the existing entries and adding the new ones by key. If that Loc
+/// PackedFile doesn't exist, it's created. Files without Loc path are imported into `text/db/file_name.loc`.
+///
+/// It returns the paths of the Loc PackedFiles that already existed, and the paths of all the imported Loc PackedFiles.
+pub fn translation_files_mass_import(
+    paths: &[PathBuf],
+    pack_file: &mut PackFile
+) -> Result<(Vec<Vec<String>>, Vec<Vec<String>>)> {
+
+    // Read all the files first, so we don't import anything if one of them fails.
+    let mut imported_files = vec![];
+    let mut error_files = vec![];
+    for path in paths {
+        match import_translation_file(path) {
+            Ok(files) => for (loc_path, entries) in files {
+                let loc_path = match loc_path {
+                    Some(loc_path) => loc_path,
+                    None => {
+                        let name = path.file_stem().unwrap().to_string_lossy().to_string();
+                        let name = if name.ends_with(".loc") { name } else { format!("{}.loc", name) };
+                        vec!["text".to_owned(), "db".to_owned(), name]
+                    }
+                };
+                imported_files.push((loc_path, entries));
+            }
+            Err(_) => error_files.push(path.to_string_lossy().to_string()),
+        }
+    }
+
+    if !error_files.is_empty() {
+        let error_files_string = error_files.iter().map(|x| format!("<li>{}</li>", x)).collect::<String>();
+        return Err(ErrorKind::MassImport(error_files_string))?
+    }
+
+    let mut existing_paths: Vec<Vec<String>> = vec![];
+    let mut imported_paths: Vec<Vec<String>> = vec![];
+    let mut new_locs: Vec<(Vec<String>, Loc)> = vec![];
+    for (loc_path, entries) in &imported_files {
+        if let Some(packed_file) = pack_file.packed_files.iter_mut().find(|x| &x.path == loc_path) {
+            let mut loc = Loc::read(&packed_file.get_data_and_keep_it()?)?;
+            update_loc_entries(&mut loc, entries);
+            packed_file.set_data(loc.save());
+            if !existing_paths.contains(loc_path) { existing_paths.push(loc_path.to_vec()); }
+        }
+
+        else if let Some((_, loc)) = new_locs.iter_mut().find(|x| &x.0 == loc_path) { update_loc_entries(loc, entries); }
+        else {
+            let mut loc = Loc::new();
+            update_loc_entries(&mut loc, entries);
+            new_locs.push((loc_path.to_vec(), loc));
+        }
+
+        if !imported_paths.contains(loc_path) { imported_paths.push(loc_path.to_vec()); }
+    }
+
+    let packed_files = new_locs.iter().map(|(path, loc)| PackedFile::read_from_vec(path.to_vec(), get_current_time(), false, loc.save())).collect::<Vec<PackedFile>>();
+    let added_paths = pack_file.add_packed_files(&packed_files);
+    if added_paths.len() < packed_files.len() { Err(ErrorKind::ReservedFiles)? }
+    Ok((existing_paths, imported_paths))
+}
+
+/// This function updates the entries of the provided Loc PackedFile with the provided translations, by key.
+/// New keys are added at the end, with the tooltip flag enabled if the translation doesn't say otherwise.
+fn update_loc_entries(loc: &mut Loc, entries: &[TranslationFileEntry]) {
+    for entry in entries {
+        match loc.entries.iter_mut().find(|x| x[0] == DecodedData::StringU16(entry.key.to_owned())) {
+            Some(row) => {
+                row[1] = DecodedData::StringU16(entry.text.to_owned());
+                if let Some(tooltip) = entry.tooltip { row[2] = DecodedData::Boolean(tooltip); }
+            }
+            None => loc.entries.push(vec![
+                DecodedData::StringU16(entry.key.to_owned()),
+                DecodedData::StringU16(entry.text.to_owned()),
+                DecodedData::Boolean(entry.tooltip.unwrap_or(true)),
+            ]),
+        }
+    }
+}
+
+/// This function parses a PO file. The key of each entry is his context or, if it has none, his source.
+/// Obsolete entries, fuzzy entries (like gettext does) and plural forms other than the first one are ignored.
+pub fn import_po(file: &str) -> Result<(Option<Vec<String>>, Vec<TranslationFileEntry>)> {
+    let mut loc_path = None;
+    let mut entries = vec![];
+    let mut entry = PoEntry::default();
+    let mut field = None;
+
+    for (index, line) in file.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with("#~") { continue }
+
+        // Comments go before their entry, so they close the previous one.
+        if line.starts_with('#') {
+            if entry.translation.is_some() { finish_po_entry(&mut entry, &mut loc_path, &mut entries); }
+            if line.starts_with("#.") {
+                let comment = line[2..].trim();
+                if comment.starts_with(TOOLTIP_NOTE) { entry.tooltip = comment[TOOLTIP_NOTE.len()..].trim().parse::<bool>().ok(); }
+            }
+            else if line.starts_with("#,") && line[2..].split(',').any(|x| x.trim() == "fuzzy") { entry.is_fuzzy = true; }
+            field = None;
+            continue
+        }
+
+        // Strings split in multiple lines.
+        if line.starts_with('"') {
+            let string = unescape_po_string(line).ok_or_else(|| Error::from(ErrorKind::ImportTranslationFileIncorrectLine(index + 1)))?;
+            match field {
+                Some(PoField::Context) => if let Some(ref mut context) = entry.context { context.push_str(&string); },
+                Some(PoField::Source) => if let Some(ref mut source) = entry.source { source.push_str(&string); },
+                Some(PoField::Translation) => if let Some(ref mut translation) = entry.translation { translation.push_str(&string); },
+                Some(PoField::Ignored) => {},
+                None => return Err(ErrorKind::ImportTranslationFileIncorrectLine(index + 1))?,
+            }
+            continue
+        }
+
+        let position = line.find(char::is_whitespace).ok_or_else(|| Error::from(ErrorKind::ImportTranslationFileIncorrectLine(index + 1)))?;
+        let keyword = &line[..position];
+        let string = unescape_po_string(line[position..].trim()).ok_or_else(|| Error::from(ErrorKind::ImportTranslationFileIncorrectLine(index + 1)))?;
+
+        // A context or a source after a translation means a new entry.
+        if (keyword == "msgctxt" || keyword == "msgid") && entry.translation.is_some() { finish_po_entry(&mut entry, &mut loc_path, &mut entries); }
+        field = match keyword {
+            "msgctxt" => { entry.context = Some(string); Some(PoField::Context) }
+            "msgid" => { entry.source = Some(string); Some(PoField::Source) }
+            "msgstr" | "msgstr[0]" => { entry.translation = Some(string); Some(PoField::Translation) }
+            _ if keyword == "msgid_plural" || keyword.starts_with("msgstr[") => Some(PoField::Ignored),
+            _ => return Err(ErrorKind::ImportTranslationFileIncorrectLine(index + 1))?,
+        };
+    }
+
+    finish_po_entry(&mut entry, &mut loc_path, &mut entries);
+    Ok((loc_path, entries))
+}
+
+/// This struct holds the entry of a PO file we are reading.
+#[derive(Default)]
+struct PoEntry {
+    context: Option<String>,
+    source: Option<String>,
+    translation: Option<String>,
+    tooltip: Option<bool>,
+    is_fuzzy: bool,
+}
+
+/// This enum represents the string of a PO entry we are reading, for strings split in multiple lines.
+#[derive(Clone, Copy)]
+enum PoField {
+    Context,
+    Source,
+    Translation,
+    Ignored,
+}
+
+/// This function processes a complete PO entry, leaving it empty for the next one. The header (the entry with empty source
+/// and without context) is used to get the path of the Loc PackedFile. Entries without translation or fuzzy are ignored.
+fn finish_po_entry(
+    entry: &mut PoEntry,
+    loc_path: &mut Option<Vec<String>>,
+    entries: &mut Vec<TranslationFileEntry>,
+) {
+    let entry = std::mem::replace(entry, PoEntry::default());
+    match (entry.context, entry.source, entry.translation) {
+        (None, Some(ref source), Some(ref header)) if source.is_empty() => {
+            for line in header.lines() {
+                if line.starts_with(PO_HEADER_LOC_PATH) {
+                    let path = line[PO_HEADER_LOC_PATH.len()..].trim_start_matches(':').trim();
+                    if path.ends_with(".loc") { *loc_path = Some(path.split('/').map(|x| x.to_owned()).collect()); }
+                }
+            }
+        }
+        (context, Some(source), Some(translation)) => if !translation.is_empty() && !entry.is_fuzzy {
+            entries.push(TranslationFileEntry {
+                key: context.unwrap_or(source),
+                text: translation,
+                tooltip: entry.tooltip,
+            });
+        }
+        _ => {}
+    }
+}
+
+/// This function parses a XLIFF 1.2 file. The key of each entry is his id. Inline tags in the translations are removed,
+/// but their text is kept. Targets outside a `trans-unit` (like the ones of `alt-trans`) are ignored.
+pub fn import_xliff(file: &str) -> Result<Vec<(Option<Vec<String>>, Vec<TranslationFileEntry>)>> {
+    let mut files = vec![];
+    let mut loc_path = None;
+    let mut entries = vec![];
+    let mut key = None;
+    let mut target = None;
+    let mut notes = vec![];
+
+    // Open elements, and the depth of the element whose text we are reading (a target or a note of the current trans-unit).
+    let mut elements: Vec<String> = vec![];
+    let mut text_depth = None;
+    let mut text = String::new();
+
+    let mut reader = EventReader::new(file.as_bytes());
+    loop {
+        match reader.next() {
+            Ok(XmlEvent::StartElement { name, attributes, .. }) => {
+                let parent = elements.last().map(|x| x.as_str());
+                match (name.local_name.as_str(), parent) {
+                    ("file", _) => {
+                        loc_path = get_xml_attribute(&attributes, "original")
+                            .filter(|x| x.ends_with(".loc"))
+                            .map(|x| x.split('/').map(|x| x.to_owned()).collect::<Vec<String>>());
+                    }
+                    ("trans-unit", _) => {
+                        key = get_xml_attribute(&attributes, "id");
+                        target = None;
+                        notes.clear();
+                    }
+                    ("target", Some("trans-unit")) | ("note", Some("trans-unit")) => {
+                        text_depth = Some(elements.len());
+                        text.clear();
+                    }
+                    _ => {}
+                }
+                elements.push(name.local_name);
+            }
+
+            Ok(XmlEvent::Characters(string)) |
+            Ok(XmlEvent::Whitespace(string)) |
+            Ok(XmlEvent::CData(string)) => if text_depth.is_some() { text.push_str(&string); },
+
+            Ok(XmlEvent::EndElement { .. }) => {
+                let element = elements.pop().unwrap_or_default();
+                if text_depth == Some(elements.len()) {
+                    text_depth = None;
+                    match element.as_str() {
+                        "target" => target = Some(text.to_owned()),
+                        _ => notes.push(text.to_owned()),
+                    }
+                }
+
+                match element.as_str() {
+                    "trans-unit" => if let (Some(key), Some(text)) = (key.take(), target.take()) {
+                        if !text.is_empty() {
+                            let tooltip = notes.iter()
+                                .find(|x| x.starts_with(TOOLTIP_NOTE))
+                                .and_then(|x| x[TOOLTIP_NOTE.len()..].trim().parse::<bool>().ok());
+                            entries.push(TranslationFileEntry { key, text, tooltip });
+                        }
+                    },
+                    "file" => files.push((loc_path.take(), std::mem::replace(&mut entries, vec![]))),
+                    _ => {}
+                }
+            }
+
+            Ok(XmlEvent::EndDocument) => break,
+            Ok(_) => {},
+            Err(error) => Err(ErrorKind::ImportTranslationFileIncorrectLine(error.position().row as usize + 1))?,
+        }
+    }
+
+    if files.is_empty() { Err(ErrorKind::ImportTranslationFileUnknownFormat)? }
+    Ok(files)
+}
+
+//----------------------------------------------------------------//
+// Escaping Functions.
+//----------------------------------------------------------------//
+
+/// This function escapes a string to be used between quotes in a PO file.
+pub fn escape_po_string(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for character in text.chars() {
+        match character {
+            '\\' => escaped.push_str("\\\\"),
+            '"' => escaped.push_str("\\\""),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            _ => escaped.push(character),
+        }
+    }
+    escaped
+}
+
+/// This function unescapes a quoted string from a PO file. It returns None if the string is not between quotes.
+pub fn unescape_po_string(text: &str) -> Option<String> {
+    if text.len() < 2 || !text.starts_with('"') || !text.ends_with('"') { return None }
+    let mut unescaped = String::with_capacity(text.len());
+    let mut characters = text[1..text.len() - 1].chars();
+    while let Some(character) = characters.next() {
+        if character == '\\' {
+            match characters.next() {
+                Some('n') => unescaped.push('\n'),
+                Some('r') => unescaped.push('\r'),
+                Some('t') => unescaped.push('\t'),
+                Some(character) => unescaped.push(character),
+                None => unescaped.push('\\'),
+            }
+        }
+        else { unescaped.push(character); }
+    }
+    Some(unescaped)
+}
+
+/// This function escapes a string to be used as text in a XML file. Carriage returns are
+/// escaped too, as XML parsers turn them into line feeds.
+pub fn escape_xml_string(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;").replace('\r', "&#13;")
+}
+
+/// This function escapes a string to be used as attribute value in a XML file. Line feeds and tabs are escaped too,
+/// as XML parsers turn them into spaces in attributes.
+pub fn escape_xml_attribute(text: &str) -> String {
+    escape_xml_string(text).replace('\n', "&#10;").replace('\t', "&#9;")
+}
+
+/// This function returns the value of the provided attribute (without namespace) from the attributes of a XML element, if it has it.
+fn get_xml_attribute(attributes: &[OwnedAttribute], name: &str) -> Option<String> {
+    attributes.iter().find(|x| x.name.prefix.is_none() && x.name.local_name == name).map(|x| x.value.to_owned())
+}
@@ -0,0 +1,259 @@
+//---------------------------------------------------------------------------//
+// Copyright (c) 2017-2019 Ismael Gutiérrez González. All rights reserved.
+//
+// This file is part of the Rusted PackFile Manager (RPFM) project,
+// which can be found here: https://github.com/Frodo45127/rpfm.
+//
+// This file is licensed under the MIT license, which can be found here:
+// https://github.com/Frodo45127/rpfm/blob/master/LICENSE.
+//---------------------------------------------------------------------------//
+
+// In this file are all the functions needed to line up the entries of the Loc PackedFiles of a PackFile
+// in different languages, for the Translation View.
+
+use serde_derive::{Serialize, Deserialize};
+
+use std::collections::BTreeMap;
+use std::fs::{DirBuilder, File};
+use std::io::{BufReader, BufWriter, Write};
+use std::path::PathBuf;
+
+use crate::DEPENDENCY_DATABASE;
+use crate::RPFM_PATH;
+use crate::SUPPORTED_GAMES;
+use crate::GAME_SELECTED;
+use crate::error::Result;
+use crate::packfile::PackFile;
+use crate::packfile::packedfile::PackedFile;
+use crate::packedfile::{DecodeablePackedFileType, DecodedData, get_packed_file_type};
+use super::Loc;
+
+/// Name of the folder (in RPFM's folder) where we keep the vanilla text each translation was made from, per PackFile.
+const TRANSLATIONS_FOLDER: &str = "translations";
+
+/// Custom type for the vanilla text the translations of a PackFile were made from, as Loc path => (key => text).
+pub type TranslationMemory = BTreeMap<String, BTreeMap<String, String>>;
+
+/// This enum represents the status of an entry in one of the Loc PackedFiles of the Translation View.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum TranslationStatus {
+
+    /// The entry is translated, and the vanilla text didn't change since then.
+    Translated,
+
+    /// The Loc PackedFile doesn't have this key.
+    Missing,
+
+    /// The entry is empty, or it has the same text than the vanilla english one.
+    Untranslated,
+
+    /// The vanilla english text changed since the entry was translated.
+    Stale,
+}
+
+/// This struct represents a key and his text in each of the Loc PackedFiles of the Translation View.
+/// - key: the loc key.
+/// - vanilla_text: the vanilla english text of the key, if it's a vanilla key.
+/// - texts: the text in each Loc PackedFile, in the same order as `TranslationData::locs`. `None` if the key is not in that Loc.
+/// - translated_from: the vanilla english text each text was translated from, if we know it.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TranslationEntry {
+    pub key: String,
+    pub vanilla_text: Option<String>,
+    pub texts: Vec<Option<String>>,
+    pub translated_from: Vec<Option<String>>,
+}
+
+/// This struct holds all the data of the Translation View.
+/// - locs: the path of each Loc PackedFile of the PackFile, with his language, if we could guess it.
+/// - entries: every key in any of the Loc PackedFiles, sorted.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TranslationData {
+    pub locs: Vec<(Vec<String>, Option<String>)>,
+    pub entries: Vec<TranslationEntry>,
+}
+
+/// Implementation of `TranslationEntry`.
+impl TranslationEntry {
+
+    /// This function returns the status of this entry in the Loc PackedFile with the provided index and language.
+    pub fn get_status(&self, index: usize, language: &Option<String>) -> TranslationStatus {
+        match self.texts[index] {
+            None => TranslationStatus::Missing,
+            Some(ref text) => {
+                if text.is_empty() { TranslationStatus::Untranslated }
+                else if language.as_ref().map_or(false, |x| x != "en") && self.vanilla_text.as_ref() == Some(text) { TranslationStatus::Untranslated }
+                else {
+                    match (&self.translated_from[index], &self.vanilla_text) {
+                        (Some(translated_from), Some(vanilla_text)) if translated_from != vanilla_text => TranslationStatus::Stale,
+                        _ => TranslationStatus::Translated,
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Implementation of `TranslationData`.
+impl TranslationData {
+
+    /// This function gets the entries of all the Loc PackedFiles in the provided PackFile, lined up by key, with their vanilla english text.
+    ///
+    /// Translations we don't know the vanilla text they were made from are considered made from the current one. That is only remembered
+    /// when the translations are saved, so we can report them as stale when the vanilla text changes.
+    pub fn new(pack_file: &mut PackFile) -> Result<Self> {
+        let vanilla_texts = get_vanilla_english_texts();
+        let memory = load_translation_memory(&pack_file.get_file_name());
+        Self::new_from_data(pack_file, &vanilla_texts, &memory)
+    }
+
+    /// This function does the same as `new`, but with the vanilla english text and the text our translations were made from passed as arguments.
+    pub fn new_from_data(
+        pack_file: &mut PackFile,
+        vanilla_texts: &BTreeMap<String, String>,
+        memory: &TranslationMemory,
+    ) -> Result<Self> {
+
+        // Get the entries of every Loc PackedFile we can decode.
+        let mut locs = vec![];
+        let mut loc_texts = vec![];
+        for packed_file in pack_file.packed_files.iter_mut().filter(|x| get_packed_file_type(&x.path) == DecodeablePackedFileType::Loc) {
+            if let Ok(loc) = Loc::read(&packed_file.get_data_and_keep_it()?) {
+                locs.push((packed_file.path.to_vec(), get_loc_language(&packed_file.path)));
+                loc_texts.push(get_loc_texts(&loc));
+            }
+        }
+
+        let mut entries: BTreeMap<String, TranslationEntry> = BTreeMap::new();
+        for (index, texts) in loc_texts.iter().enumerate() {
+            for (key, text) in texts {
+                let entry = entries.entry(key.to_owned()).or_insert_with(|| TranslationEntry {
+                    key: key.to_owned(),
+                    vanilla_text: vanilla_texts.get(key).cloned(),
+                    texts: vec![None; locs.len()],
+                    translated_from: vec![None; locs.len()],
+                });
+                entry.texts[index] = Some(text.to_owned());
+            }
+        }
+
+        for entry in entries.values_mut() {
+            for (index, (path, _)) in locs.iter().enumerate() {
+                if entry.texts[index].is_some() {
+                    entry.translated_from[index] = match memory.get(&path.join("\\")).and_then(|x| x.get(&entry.key)) {
+                        Some(translated_from) => Some(translated_from.to_owned()),
+                        None => entry.vanilla_text.clone(),
+                    };
+                }
+            }
+        }
+
+        Ok(Self {
+            locs,
+            entries: entries.into_iter().map(|(_, entry)| entry).collect(),
+        })
+    }
+
+    /// This function saves the texts of this `TranslationData` to the Loc PackedFiles of the provided PackFile, updating the existing
+    /// entries and adding the new ones by key, and remembers the vanilla text each text was translated from.
+    pub fn save(&self, pack_file: &mut PackFile) -> Result<()> {
+        let mut memory = load_translation_memory(&pack_file.get_file_name());
+        self.save_with_memory(pack_file, &mut memory)?;
+        save_translation_memory(&pack_file.get_file_name(), &memory)
+    }
+
+    /// This function does the same as `save`, but remembering the vanilla text each text was translated from in the provided memory.
+    pub fn save_with_memory(&self, pack_file: &mut PackFile, memory: &mut TranslationMemory) -> Result<()> {
+        for (index, (path, _)) in self.locs.iter().enumerate() {
+            if let Some(packed_file) = pack_file.packed_files.iter_mut().find(|x| &x.path == path) {
+                let mut loc = Loc::read(&packed_file.get_data_and_keep_it()?)?;
+                let loc_memory = memory.entry(path.join("\\")).or_insert_with(BTreeMap::new);
+
+                for entry in &self.entries {
+                    if let Some(ref text) = entry.texts[index] {
+                        match loc.entries.iter_mut().find(|x| x[0] == DecodedData::StringU16(entry.key.to_owned())) {
+                            Some(row) => row[1] = DecodedData::StringU16(text.to_owned()),
+                            None => loc.entries.push(vec![
+                                DecodedData::StringU16(entry.key.to_owned()),
+                                DecodedData::StringU16(text.to_owned()),
+                                DecodedData::Boolean(true),
+                            ]),
+                        }
+
+                        if let Some(ref translated_from) = entry.translated_from[index] { loc_memory.insert(entry.key.to_owned(), translated_from.to_owned()); }
+                    }
+                }
+
+                packed_file.set_data(loc.save());
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// This function tries to guess the language of a Loc PackedFile from his path, using the language codes of the loc PackFiles
+/// of the Game Selected (`local_xx.pack`). For example, `text/db/my_mod_fr.loc` and `text/fr/my_mod.loc` are both french.
+pub fn get_loc_language(path: &[String]) -> Option<String> {
+    let languages = SUPPORTED_GAMES[&**GAME_SELECTED.lock().unwrap()].loc_packs.iter()
+        .filter_map(|x| x.trim_start_matches("local_").trim_end_matches(".pack").split('_').next().map(|x| x.to_owned()))
+        .collect::<Vec<String>>();
+
+    let path = path.join("/").to_lowercase();
+    let path = path.trim_end_matches(".loc");
+    path.split(|x: char| x == '/' || x == '_' || x == '-' || x == '.').filter(|x| languages.iter().any(|y| y == x)).last().map(|x| x.to_owned())
+}
+
+/// This function returns the entries of a Loc PackedFile, as key => text.
+fn get_loc_texts(loc: &Loc) -> BTreeMap<String, String> {
+    loc.entries.iter().filter_map(|x| match (&x[0], &x[1]) {
+        (DecodedData::StringU16(key), DecodedData::StringU16(text)) => Some((key.to_owned(), text.to_owned())),
+        _ => None,
+    }).collect()
+}
+
+/// This function returns the vanilla english text of every loc key of the Game Selected, as key => text, from the Loc PackedFiles
+/// of the dependency database, so we don't have to read the loc PackFiles of the game again. If there are none, it returns nothing.
+pub fn get_vanilla_english_texts() -> BTreeMap<String, String> {
+    get_loc_texts_from_packed_files(&DEPENDENCY_DATABASE.lock().unwrap())
+}
+
+/// This function returns the text of every loc key in the provided Loc PackedFiles, as key => text. The rest of PackedFiles are ignored.
+pub fn get_loc_texts_from_packed_files(packed_files: &[PackedFile]) -> BTreeMap<String, String> {
+    let mut texts = BTreeMap::new();
+    for packed_file in packed_files.iter().filter(|x| get_packed_file_type(&x.path) == DecodeablePackedFileType::Loc) {
+        if let Ok(loc) = packed_file.get_data().and_then(|x| Loc::read(&x)) {
+            texts.extend(get_loc_texts(&loc));
+        }
+    }
+    texts
+}
+
+/// This function returns the folder where we keep the translation memories of the PackFiles of the Game Selected.
+fn get_translation_memory_folder() -> PathBuf {
+    RPFM_PATH.to_path_buf().join(TRANSLATIONS_FOLDER).join(&**GAME_SELECTED.lock().unwrap())
+}
+
+/// This function returns the path of the file where we keep the vanilla text the translations of a PackFile were made from.
+/// They're kept per game, so PackFiles with the same name for different games don't share them.
+fn get_translation_memory_path(pack_file_name: &str) -> PathBuf {
+    get_translation_memory_folder().join(format!("{}.json", pack_file_name))
+}
+
+/// This function loads the vanilla text the translations of a PackFile were made from, as Loc path => (key => text).
+/// If there is nothing saved (or the PackFile has no name yet) it returns an empty map.
+fn load_translation_memory(pack_file_name: &str) -> TranslationMemory {
+    if pack_file_name.is_empty() { return BTreeMap::new() }
+    File::open(get_translation_memory_path(pack_file_name)).ok()
+        .and_then(|file| serde_json::from_reader(BufReader::new(file)).ok())
+        .unwrap_or_else(BTreeMap::new)
+}
+
+/// This function saves the vanilla text the translations of a PackFile were made from. PackFiles without name are ignored.
+fn save_translation_memory(pack_file_name: &str, memory: &TranslationMemory) -> Result<()> {
+    if pack_file_name.is_empty() { return Ok(()) }
+    DirBuilder::new().recursive(true).create(get_translation_memory_folder())?;
+    let mut file = BufWriter::new(File::create(get_translation_memory_path(pack_file_name))?);
+    file.write_all(serde_json::to_string_pretty(memory)?.as_bytes())?;
+    Ok(())
+}
@@ -0,0 +1,957 @@
+//---------------------------------------------------------------------------//
+// Copyright (c) 2017-2019 Ismael Gutiérrez González. All rights reserved.
+//
+// This file is part of the Rusted PackFile Manager (RPFM) project,
+// which can be found here: https://github.com/Frodo45127/rpfm.
+//
+// This file is licensed under the MIT license, which can be found here:
+// https://github.com/Frodo45127/rpfm/blob/master/LICENSE.
+//---------------------------------------------------------------------------//
+
+// In this file we define a syntax checker for Lua 5.1 scripts. It only checks the syntax, without executing
+// or type-checking anything, so it doesn't need Kailua or any other external program. The error messages try
+// to be the same ones the Lua 5.1 compiler gives, so they're familiar to modders.
+//
+// We also use the lexer here to find the DB keys and loc keys scripts pass to the CA interface functions,
+// so we can check they exist before the game finds out at runtime.
+
+use regex::Regex;
+use serde_derive::{Serialize, Deserialize};
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use std::fs::File;
+use std::io::{BufReader, Read};
+use std::path::{Path, PathBuf};
+
+use crate::DEPENDENCY_DATABASE;
+use crate::GAME_SELECTED;
+use crate::RPFM_PATH;
+use crate::SCHEMA;
+use crate::SUPPORTED_GAMES;
+use crate::error::{Error, ErrorKind, Result};
+use crate::packfile::PackFile;
+use crate::packedfile::{DecodeablePackedFileType, get_decoded_data_as_string, get_loc_keys, get_packed_file_type};
+use crate::packedfile::db::DB;
+use crate::schema::Schema;
+use crate::packedfile::text::Text;
+
+/// This struct represents a syntax error found in a Lua script.
+/// - path: the path of the PackedFile with the error. Empty if we checked a script outside a PackFile.
+/// - line: the line of the error, starting at 1.
+/// - column: the column of the error, starting at 1.
+/// - message: what's wrong with the script.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct LuaSyntaxError {
+    pub path: Vec<String>,
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+/// This enum represents the kind of a token of a Lua script.
+#[derive(Clone, Debug, PartialEq)]
+enum TokenKind {
+    Name,
+    Keyword,
+    Number,
+    String,
+    Symbol,
+    Eof,
+}
+
+/// This struct represents a token of a Lua script, with the text it has in the script and where it starts.
+#[derive(Clone, Debug)]
+struct Token {
+    kind: TokenKind,
+    text: String,
+    line: usize,
+    column: usize,
+}
+
+/// List of reserved words of Lua 5.1.
+const KEYWORDS: [&str; 21] = [
+    "and", "break", "do", "else", "elseif", "end", "false", "for", "function", "if", "in",
+    "local", "nil", "not", "or", "repeat", "return", "then", "true", "until", "while",
+];
+
+/// Maximum amount of nested blocks and expressions a script can have, like `LUAI_MAXCCALLS` in Lua 5.1.
+const MAX_SYNTAX_LEVELS: usize = 200;
+
+/// List of symbols of Lua 5.1, with the longest ones first so they're matched before their prefixes.
+const SYMBOLS: [&str; 26] = [
+    "...", "..", "==", "~=", "<=", ">=",
+    "+", "-", "*", "/", "%", "^", "#", "<", ">", "=", "(", ")", "{", "}", "[", "]", ";", ":", ",", ".",
+];
+
+/// Parameters of the CA interface functions that receive DB keys or loc keys, by their exact name (in lowercase), with what they
+/// reference. Only the parameters in this list (or in `SCRIPT_REFERENCE_FUNCTION_PARAMETERS`) are checked, as most string
+/// parameters (names, lookups, events,...) are not keys.
+const SCRIPT_REFERENCE_PARAMETERS: [(&str, ScriptReference); 48] = [
+    ("agent_subtype", ScriptReference::Table("agent_subtypes_tables")),
+    ("building_chain_key", ScriptReference::Table("building_chains_tables")),
+    ("building_key", ScriptReference::Table("building_levels_tables")),
+    ("bundle", ScriptReference::Table("effect_bundles_tables")),
+    ("confederated", ScriptReference::Table("factions_tables")),
+    ("confederator", ScriptReference::Table("factions_tables")),
+    ("declaree", ScriptReference::Table("factions_tables")),
+    ("declarer", ScriptReference::Table("factions_tables")),
+    ("description_loc_key", ScriptReference::Loc),
+    ("dilemma_key", ScriptReference::Table("dilemmas_tables")),
+    ("effect_bundle_key", ScriptReference::Table("effect_bundles_tables")),
+    ("effect_key", ScriptReference::Table("effect_bundles_tables")),
+    ("faction", ScriptReference::Table("factions_tables")),
+    ("faction1", ScriptReference::Table("factions_tables")),
+    ("faction2", ScriptReference::Table("factions_tables")),
+    ("faction_key", ScriptReference::Table("factions_tables")),
+    ("faction_name", ScriptReference::Table("factions_tables")),
+    ("factionkey", ScriptReference::Table("factions_tables")),
+    ("factionname", ScriptReference::Table("factions_tables")),
+    ("heading_loc_key", ScriptReference::Loc),
+    ("human_faction_name", ScriptReference::Table("factions_tables")),
+    ("incident_key", ScriptReference::Table("incidents_tables")),
+    ("incidentkey", ScriptReference::Table("incidents_tables")),
+    ("mission_key", ScriptReference::Table("missions_tables")),
+    ("objective_loc_key", ScriptReference::Loc),
+    ("other_faction", ScriptReference::Table("factions_tables")),
+    ("override_text_loc", ScriptReference::Loc),
+    ("personality", ScriptReference::Table("cai_personalities_tables")),
+    ("pooled_resource", ScriptReference::Table("pooled_resources_tables")),
+    ("province_key", ScriptReference::Table("provinces_tables")),
+    ("region", ScriptReference::Table("regions_tables")),
+    ("region_key", ScriptReference::Table("regions_tables")),
+    ("regionname", ScriptReference::Table("regions_tables")),
+    ("resource", ScriptReference::Table("pooled_resources_tables")),
+    ("rite_key", ScriptReference::Table("rituals_tables")),
+    ("ritual_chain_key", ScriptReference::Table("ritual_chains_tables")),
+    ("ritual_key", ScriptReference::Table("rituals_tables")),
+    ("skill_key", ScriptReference::Table("character_skills_tables")),
+    ("subtype", ScriptReference::Table("agent_subtypes_tables")),
+    ("target_faction_key", ScriptReference::Table("factions_tables")),
+    ("tech_key", ScriptReference::Table("technologies_tables")),
+    ("technology_key", ScriptReference::Table("technologies_tables")),
+    ("trait_key", ScriptReference::Table("character_traits_tables")),
+    ("unit", ScriptReference::Table("main_units_tables")),
+    ("unit_key", ScriptReference::Table("main_units_tables")),
+    ("unitkey", ScriptReference::Table("main_units_tables")),
+    ("vassal", ScriptReference::Table("factions_tables")),
+    ("vassaliser", ScriptReference::Table("factions_tables")),
+];
+
+/// Parameters of specific CA interface functions that receive DB keys or loc keys, as (function, parameter, reference). These are
+/// parameters whose name doesn't say what they reference (like `key`) or that reference something else in that function.
+/// They take priority over the ones in `SCRIPT_REFERENCE_PARAMETERS`.
+const SCRIPT_REFERENCE_FUNCTION_PARAMETERS: [(&str, &str, ScriptReference); 5] = [
+    ("faction_by_key", "key", ScriptReference::Table("factions_tables")),
+    ("force_change_cai_faction_personality", "key", ScriptReference::Table("factions_tables")),
+    ("get_localised_string", "key", ScriptReference::Loc),
+    ("region_by_key", "key", ScriptReference::Table("regions_tables")),
+    ("resource_exists", "resource_key", ScriptReference::Table("resources_tables")),
+];
+
+/// This enum represents what a string parameter of a CA interface function references.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ScriptReference {
+    Table(&'static str),
+    Loc,
+}
+
+/// Implementation of `LuaSyntaxError`.
+impl LuaSyntaxError {
+
+    /// This function creates a new `LuaSyntaxError` without path.
+    fn new(line: usize, column: usize, message: String) -> Self {
+        Self { path: vec![], line, column, message }
+    }
+}
+
+/// Display implementation of `LuaSyntaxError`.
+impl fmt::Display for LuaSyntaxError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.path.is_empty() { write!(f, "{}:{}: {}", self.line, self.column, self.message) }
+        else { write!(f, "{}:{}:{}: {}", self.path.join("/"), self.line, self.column, self.message) }
+    }
+}
+
+/// This function checks the syntax of the provided Lua 5.1 script, returning the first syntax error in it, if any.
+pub fn check_lua_syntax(script: &str) -> std::result::Result<(), LuaSyntaxError> {
+    let tokens = tokenize(script)?;
+    let mut parser = Parser { tokens, position: 0, vararg_scopes: vec![true], level: 0 };
+    parser.chunk()?;
+    if parser.current().kind != TokenKind::Eof { return Err(parser.error_near("'<eof>' expected")) }
+    Ok(())
+}
+
+/// This function returns if the PackedFile with the provided path is a Lua script: a Text PackedFile for the registry, with the `.lua` extension.
+pub fn is_lua_script(path: &[String]) -> bool {
+    get_packed_file_type(path) == DecodeablePackedFileType::Text && path.last().map_or(false, |x| x.to_lowercase().ends_with(".lua"))
+}
+
+/// This function checks the syntax of every Lua script in the provided PackFile, returning the syntax errors found, one per script.
+pub fn check_lua_scripts(pack_file: &mut PackFile) -> Result<Vec<LuaSyntaxError>> {
+    let mut errors = vec![];
+    for packed_file in pack_file.packed_files.iter_mut().filter(|x| is_lua_script(&x.path)) {
+        let text = Text::read(&packed_file.get_data_and_keep_it()?)?;
+        if let Err(mut error) = check_lua_syntax(&text.contents) {
+            error.path = packed_file.path.to_vec();
+            errors.push(error);
+        }
+    }
+    Ok(errors)
+}
+
+/// This function checks the string literals the Lua scripts of the provided PackFile pass to the CA interface functions
+/// (the ones in the Lua types file of the Game Selected) against the keys of the tables and the loc keys of the PackFile
+/// and the Dependency Database. It returns the unknown keys, with the path and line where they're used.
+///
+/// Tables we have no data for are not checked, as we cannot know if their keys are valid or not.
+pub fn check_lua_references(pack_file: &mut PackFile) -> Result<Vec<String>> {
+    let schema = match *SCHEMA.lock().unwrap() {
+        Some(ref schema) => schema.clone(),
+        None => return Err(Error::from(ErrorKind::SchemaNotFound)),
+    };
+
+    let functions = match SUPPORTED_GAMES.get(&**GAME_SELECTED.lock().unwrap()).unwrap().ca_types_file {
+        Some(ref ca_types_file) => get_script_functions(&RPFM_PATH.to_path_buf().join(PathBuf::from("lua_types")).join(PathBuf::from(ca_types_file)))?,
+        None => return Err(Error::from(ErrorKind::NoTypesFileFound)),
+    };
+
+    for packed_file in pack_file.packed_files.iter_mut() {
+        let packed_file_type = get_packed_file_type(&packed_file.path);
+        if packed_file_type == DecodeablePackedFileType::DB || packed_file_type == DecodeablePackedFileType::Loc {
+            packed_file.load_data()?;
+        }
+    }
+
+    let loc_keys = get_loc_keys(pack_file);
+    let mut table_keys: BTreeMap<&str, BTreeSet<String>> = BTreeMap::new();
+    let mut unknown_keys = vec![];
+
+    let scripts = pack_file.packed_files.iter_mut()
+        .filter(|x| is_lua_script(&x.path))
+        .map(|x| Ok((x.path.to_vec(), Text::read(&x.get_data_and_keep_it()?)?.contents)))
+        .collect::<Result<Vec<(Vec<String>, String)>>>()?;
+
+    let mut is_known = |reference: ScriptReference, value: &str| match reference {
+        ScriptReference::Loc => loc_keys.is_empty() || loc_keys.contains(value),
+        ScriptReference::Table(table_name) => {
+            let keys = table_keys.entry(table_name).or_insert_with(|| get_table_keys(table_name, pack_file, &schema));
+            keys.is_empty() || keys.contains(value)
+        }
+    };
+
+    for (path, script) in &scripts {
+        unknown_keys.append(&mut get_unknown_script_references(path, script, &functions, &mut is_known));
+    }
+
+    Ok(unknown_keys)
+}
+
+/// This function returns the string literals the provided Lua script passes to the provided CA interface functions that are not
+/// known keys, according to `is_known`, with the path and line where they're used.
+///
+/// Scripts with syntax errors are ignored, as the syntax check already reports them.
+pub fn get_unknown_script_references(
+    path: &[String],
+    script: &str,
+    functions: &BTreeMap<String, Vec<Option<ScriptReference>>>,
+    is_known: &mut dyn FnMut(ScriptReference, &str) -> bool,
+) -> Vec<String> {
+    let mut unknown_keys = vec![];
+    for (function, parameter, value, line) in get_script_string_arguments(script).unwrap_or_default() {
+        if let Some(Some(reference)) = functions.get(&function).and_then(|x| x.get(parameter)) {
+            if !is_known(*reference, &value) {
+                let source = match *reference {
+                    ScriptReference::Loc => "loc key".to_owned(),
+                    ScriptReference::Table(table_name) => format!("key of {}", table_name),
+                };
+                unknown_keys.push(format!("{}:{}: '{}' is not a known {}, used in {}.", path.join("/"), line, value, source, function));
+            }
+        }
+    }
+    unknown_keys
+}
+
+/// This function gets the CA interface functions from the provided Lua types file.
+fn get_script_functions(path: &Path) -> Result<BTreeMap<String, Vec<Option<ScriptReference>>>> {
+    let mut types_file = String::new();
+    BufReader::new(File::open(path)?).read_to_string(&mut types_file)?;
+    Ok(parse_script_functions(&types_file))
+}
+
+/// This function gets the CA interface functions from the contents of a Lua types file, with what each one of their parameters
+/// references, if it's a string parameter that receives a DB key or a loc key.
+///
+/// Parameters are matched by their exact name, using `SCRIPT_REFERENCE_FUNCTION_PARAMETERS` first and `SCRIPT_REFERENCE_PARAMETERS` after it.
+/// Functions declared more than once with different parameters (like the `new` of each script class) are ignored.
+pub fn parse_script_functions(types_file: &str) -> BTreeMap<String, Vec<Option<ScriptReference>>> {
+
+    // Declarations can span multiple lines, so we join all the Kailua annotations first.
+    let annotations = types_file.lines().filter_map(|x| if x.trim_start().starts_with("--#") { Some(&x.trim_start()[3..]) } else { None }).collect::<Vec<&str>>().join("\n");
+    let declaration_regex = Regex::new(r"assume\s+(?:global\s+)?(?:\w+\.)*(\w+)\s*:\s*(?:method|function)\s*\(").unwrap();
+
+    let mut functions: BTreeMap<String, Vec<Option<ScriptReference>>> = BTreeMap::new();
+    for declaration in declaration_regex.captures_iter(&annotations) {
+        let name = declaration[1].to_owned();
+
+        // Parameters can be functions with their own parameters, so we only split by the commas outside parenthesis.
+        let start = declaration.get(0).unwrap().end();
+        let mut parameters = vec![];
+        let mut parameter_start = start;
+        let mut depth = 0;
+        for (index, character) in annotations[start..].char_indices() {
+            match character {
+                '(' => depth += 1,
+                ')' if depth == 0 => {
+                    parameters.push(&annotations[parameter_start..start + index]);
+                    break;
+                }
+                ')' => depth -= 1,
+                ',' if depth == 0 => {
+                    parameters.push(&annotations[parameter_start..start + index]);
+                    parameter_start = start + index + 1;
+                }
+                _ => {}
+            }
+        }
+
+        let references = parameters.iter().filter(|x| !x.trim().is_empty()).map(|parameter| {
+            let mut parameter = parameter.splitn(2, ':');
+            let parameter_name = parameter.next().unwrap_or("").trim().to_lowercase();
+            let parameter_type = parameter.next().unwrap_or("").trim();
+
+            if parameter_type != "string" && parameter_type != "string?" { None }
+            else {
+                SCRIPT_REFERENCE_FUNCTION_PARAMETERS.iter().find(|(function, x, _)| *function == name && *x == parameter_name).map(|(_, _, x)| *x)
+                    .or_else(|| SCRIPT_REFERENCE_PARAMETERS.iter().find(|(x, _)| *x == parameter_name).map(|(_, x)| *x))
+            }
+        }).collect::<Vec<Option<ScriptReference>>>();
+
+        match functions.get(&name) {
+            Some(old_references) if *old_references != references => { functions.insert(name, vec![]); }
+            Some(_) => {},
+            None => { functions.insert(name, references); }
+        }
+    }
+
+    functions
+}
+
+/// This function returns the string literals passed directly as arguments of function calls in the provided Lua script,
+/// as (function name, argument position, value, line). Arguments with any other kind of expression are ignored.
+///
+/// If the script cannot be split into tokens, it returns None.
+pub fn get_script_string_arguments(script: &str) -> Option<Vec<(String, usize, String, usize)>> {
+    let tokens = tokenize(script).ok()?;
+    let mut arguments = vec![];
+    for (index, token) in tokens.iter().enumerate() {
+        if token.kind != TokenKind::Name || tokens.get(index + 1).map_or(true, |x| x.kind != TokenKind::Symbol || x.text != "(") { continue; }
+
+        let mut depth = 0;
+        let mut position = 0;
+        let mut argument: Vec<&Token> = vec![];
+        for token_argument in &tokens[index + 2..] {
+            let is_end = token_argument.kind == TokenKind::Symbol && token_argument.text == ")" && depth == 0;
+            let is_separator = token_argument.kind == TokenKind::Symbol && token_argument.text == "," && depth == 0;
+            if is_end || is_separator {
+                if let [literal] = argument[..] {
+                    if let Some(value) = get_string_literal_value(literal) {
+                        arguments.push((token.text.to_owned(), position, value, literal.line));
+                    }
+                }
+                if is_end { break; }
+                position += 1;
+                argument.clear();
+                continue;
+            }
+
+            if token_argument.kind == TokenKind::Symbol {
+                match &*token_argument.text {
+                    "(" | "{" | "[" => depth += 1,
+                    ")" | "}" | "]" => depth -= 1,
+                    _ => {},
+                }
+            }
+            argument.push(token_argument);
+        }
+    }
+    Some(arguments)
+}
+
+/// This function returns the value of a quoted string literal token, if it's a non-empty string without escape sequences.
+fn get_string_literal_value(token: &Token) -> Option<String> {
+    if token.kind != TokenKind::String || !(token.text.starts_with('"') || token.text.starts_with('\'')) { return None }
+    let value = &token.text[1..token.text.len() - 1];
+    if value.is_empty() || value.contains('\\') { None } else { Some(value.to_owned()) }
+}
+
+/// This function returns the keys of the provided table, from the PackFile and the Dependency Database.
+/// For tables with more than one key column, only the first one is used.
+fn get_table_keys(table_name: &str, pack_file: &PackFile, schema: &Schema) -> BTreeSet<String> {
+    let mut keys = BTreeSet::new();
+    let dependency_database = DEPENDENCY_DATABASE.lock().unwrap();
+    let tables = pack_file.packed_files.iter().chain(dependency_database.iter()).filter(|x| x.path.len() == 3 && x.path[0] == "db" && x.path[1] == table_name);
+    for packed_file in tables {
+        if let Ok(db) = packed_file.get_data().and_then(|x| DB::read(&x, table_name, schema)) {
+            let key_column = db.table_definition.fields.iter().position(|x| x.field_is_key).unwrap_or(0);
+            keys.extend(db.entries.iter().filter_map(|x| x.get(key_column)).map(get_decoded_data_as_string));
+        }
+    }
+    keys
+}
+
+//----------------------------------------------------------------//
+// Lexer.
+//----------------------------------------------------------------//
+
+/// This function splits a Lua script into tokens, ignoring whitespace and comments. The last token is always an `Eof` token.
+fn tokenize(script: &str) -> std::result::Result<Vec<Token>, LuaSyntaxError> {
+    let chars = script.chars().collect::<Vec<char>>();
+    let mut tokens = vec![];
+    let mut index = 0;
+    let mut line = 1;
+    let mut line_start = 0;
+
+    // Skip the shebang line, like Lua does.
+    if chars.starts_with(&['#']) {
+        while index < chars.len() && chars[index] != '\n' && chars[index] != '\r' { index += 1; }
+    }
+
+    while index < chars.len() {
+        let character = chars[index];
+        let start = index;
+        let start_line = line;
+        let column = index - line_start + 1;
+
+        // New lines. "\r\n" and "\n\r" count as only one line.
+        if character == '\n' || character == '\r' {
+            index += 1;
+            if index < chars.len() && (chars[index] == '\n' || chars[index] == '\r') && chars[index] != character { index += 1; }
+            line += 1;
+            line_start = index;
+        }
+
+        else if character.is_ascii_whitespace() || character == '\u{0B}' { index += 1; }
+
+        // Comments. Long comments can span multiple lines.
+        else if chars[index..].starts_with(&['-', '-']) {
+            index += 2;
+            if let Some(level) = get_long_bracket_level(&chars, index) {
+                read_long_bracket(&chars, &mut index, &mut line, &mut line_start, level)
+                    .map_err(|_| LuaSyntaxError::new(start_line, column, "unfinished long comment near '<eof>'".to_owned()))?;
+            }
+            else {
+                while index < chars.len() && chars[index] != '\n' && chars[index] != '\r' { index += 1; }
+            }
+        }
+
+        // Long strings.
+        else if let Some(level) = get_long_bracket_level(&chars, index) {
+            read_long_bracket(&chars, &mut index, &mut line, &mut line_start, level)
+                .map_err(|_| LuaSyntaxError::new(start_line, column, "unfinished long string near '<eof>'".to_owned()))?;
+            tokens.push(Token { kind: TokenKind::String, text: chars[start..index].iter().collect(), line: start_line, column });
+        }
+
+        // Strings.
+        else if character == '"' || character == '\'' {
+            index += 1;
+            loop {
+                let near = |index: usize| chars[start..index.min(chars.len())].iter().collect::<String>();
+                if index >= chars.len() { return Err(LuaSyntaxError::new(start_line, column, format!("unfinished string near '{}'", near(index)))) }
+                match chars[index] {
+                    '\n' | '\r' => return Err(LuaSyntaxError::new(start_line, column, format!("unfinished string near '{}'", near(index)))),
+                    '\\' => {
+                        index += 1;
+                        if index >= chars.len() { continue }
+                        if chars[index].is_ascii_digit() {
+                            let mut value = 0;
+                            let mut digits = 0;
+                            while digits < 3 && index < chars.len() && chars[index].is_ascii_digit() {
+                                value = value * 10 + chars[index].to_digit(10).unwrap();
+                                index += 1;
+                                digits += 1;
+                            }
+                            if value > 255 { return Err(LuaSyntaxError::new(start_line, column, format!("escape sequence too large near '{}'", near(index)))) }
+                        }
+
+                        // Escaped new lines are part of the string.
+                        else if chars[index] == '\n' || chars[index] == '\r' {
+                            let new_line = chars[index];
+                            index += 1;
+                            if index < chars.len() && (chars[index] == '\n' || chars[index] == '\r') && chars[index] != new_line { index += 1; }
+                            line += 1;
+                            line_start = index;
+                        }
+                        else { index += 1; }
+                    }
+                    x if x == character => { index += 1; break; }
+                    _ => index += 1,
+                }
+            }
+            tokens.push(Token { kind: TokenKind::String, text: chars[start..index].iter().collect(), line: start_line, column });
+        }
+
+        // Numbers. Like in Lua, we take everything that may be part of a number, and then check if it's a valid one. The sign
+        // of the exponent is only taken right after the digits, so `0x1e+5` is `0x1e` plus `5`, like in Lua.
+        else if character.is_ascii_digit() || (character == '.' && index + 1 < chars.len() && chars[index + 1].is_ascii_digit()) {
+            while index < chars.len() && (chars[index].is_ascii_digit() || chars[index] == '.') { index += 1; }
+            if index < chars.len() && (chars[index] == 'e' || chars[index] == 'E') {
+                index += 1;
+                if index < chars.len() && (chars[index] == '+' || chars[index] == '-') { index += 1; }
+            }
+            while index < chars.len() && (chars[index].is_ascii_alphanumeric() || chars[index] == '_') { index += 1; }
+
+            let text = chars[start..index].iter().collect::<String>();
+            if !is_valid_number(&text) { return Err(LuaSyntaxError::new(start_line, column, format!("malformed number near '{}'", text))) }
+            tokens.push(Token { kind: TokenKind::Number, text, line: start_line, column });
+        }
+
+        // Names and keywords.
+        else if character.is_ascii_alphabetic() || character == '_' {
+            while index < chars.len() && (chars[index].is_ascii_alphanumeric() || chars[index] == '_') { index += 1; }
+            let text = chars[start..index].iter().collect::<String>();
+            let kind = if KEYWORDS.contains(&&*text) { TokenKind::Keyword } else { TokenKind::Name };
+            tokens.push(Token { kind, text, line: start_line, column });
+        }
+
+        // Symbols. Anything else is an unexpected symbol.
+        else {
+            match SYMBOLS.iter().find(|x| chars[index..].starts_with(&x.chars().collect::<Vec<char>>())) {
+                Some(symbol) => {
+                    index += symbol.len();
+                    tokens.push(Token { kind: TokenKind::Symbol, text: symbol.to_string(), line: start_line, column });
+                }
+                None => return Err(LuaSyntaxError::new(start_line, column, format!("unexpected symbol near '{}'", character))),
+            }
+        }
+    }
+
+    tokens.push(Token { kind: TokenKind::Eof, text: "<eof>".to_owned(), line, column: index - line_start + 1 });
+    Ok(tokens)
+}
+
+/// This function returns the level (amount of `=`) of the long bracket (`[[`, `[==[`,...) starting at the provided index, if there is one.
+fn get_long_bracket_level(chars: &[char], index: usize) -> Option<usize> {
+    if chars.get(index) != Some(&'[') { return None }
+    let level = chars[index + 1..].iter().take_while(|x| **x == '=').count();
+    if chars.get(index + 1 + level) == Some(&'[') { Some(level) } else { None }
+}
+
+/// This function skips a long bracket (long string or the content of a long comment), keeping track of the lines on it.
+/// It returns an error if the long bracket is never closed.
+fn read_long_bracket(chars: &[char], index: &mut usize, line: &mut usize, line_start: &mut usize, level: usize) -> std::result::Result<(), ()> {
+    let closing = [vec![']'], vec!['='; level], vec![']']].concat();
+    *index += level + 2;
+    while *index < chars.len() {
+        if chars[*index..].starts_with(&closing) {
+            *index += closing.len();
+            return Ok(())
+        }
+        else if chars[*index] == '\n' || chars[*index] == '\r' {
+            let new_line = chars[*index];
+            *index += 1;
+            if *index < chars.len() && (chars[*index] == '\n' || chars[*index] == '\r') && chars[*index] != new_line { *index += 1; }
+            *line += 1;
+            *line_start = *index;
+        }
+        else { *index += 1; }
+    }
+    Err(())
+}
+
+/// This function checks if the provided text is a valid Lua 5.1 number: decimal (`1`, `1.5`, `.5`, `1e10`, `1.5E-3`) or hexadecimal (`0xFF`).
+fn is_valid_number(text: &str) -> bool {
+    if text.starts_with("0x") || text.starts_with("0X") {
+        return text.len() > 2 && text[2..].chars().all(|x| x.is_ascii_hexdigit())
+    }
+
+    let (mantissa, exponent) = match text.find(|x| x == 'e' || x == 'E') {
+        Some(position) => (&text[..position], Some(&text[position + 1..])),
+        None => (text, None),
+    };
+
+    let mut parts = mantissa.splitn(2, '.');
+    let integer = parts.next().unwrap_or("");
+    let decimal = parts.next().unwrap_or("");
+    let valid_mantissa = (!integer.is_empty() || !decimal.is_empty()) &&
+        integer.chars().all(|x| x.is_ascii_digit()) &&
+        decimal.chars().all(|x| x.is_ascii_digit());
+
+    let valid_exponent = match exponent {
+        Some(exponent) => {
+            let digits = exponent.trim_start_matches(|x| x == '+' || x == '-');
+            exponent.len() - digits.len() <= 1 && !digits.is_empty() && digits.chars().all(|x| x.is_ascii_digit())
+        }
+        None => true,
+    };
+
+    valid_mantissa && valid_exponent
+}
+
+//----------------------------------------------------------------//
+// Parser.
+//----------------------------------------------------------------//
+
+/// This struct represents a recursive descent parser for the Lua 5.1 grammar. It doesn't build any tree,
+/// it just checks the tokens follow the grammar.
+/// - tokens: the tokens of the script.
+/// - position: the token we're at.
+/// - vararg_scopes: if each one of the functions we're in accepts `...`. The main chunk always does.
+/// - level: how many blocks and expressions deep we are, so scripts nested too deep fail like in Lua instead of overflowing our stack.
+struct Parser {
+    tokens: Vec<Token>,
+    position: usize,
+    vararg_scopes: Vec<bool>,
+    level: usize,
+}
+
+/// This enum represents what a suffixed expression ends being: a function call, a variable we can assign to, or just a value.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum ExpressionKind {
+    Call,
+    Variable,
+    Value,
+}
+
+/// Type for the results of the parser, so we don't have to write the error type every time.
+type ParseResult = std::result::Result<(), LuaSyntaxError>;
+
+/// Implementation of `Parser`.
+impl Parser {
+
+    /// This function returns the current token.
+    fn current(&self) -> &Token {
+        &self.tokens[self.position]
+    }
+
+    /// This function returns the token after the current one.
+    fn next(&self) -> &Token {
+        &self.tokens[(self.position + 1).min(self.tokens.len() - 1)]
+    }
+
+    /// This function moves to the next token.
+    fn advance(&mut self) {
+        if self.position < self.tokens.len() - 1 { self.position += 1; }
+    }
+
+    /// This function checks if the current token is the provided keyword or symbol.
+    fn check(&self, text: &str) -> bool {
+        let token = self.current();
+        (token.kind == TokenKind::Keyword || token.kind == TokenKind::Symbol) && token.text == text
+    }
+
+    /// This function moves to the next token if the current one is the provided keyword or symbol, returning if it moved.
+    fn test_next(&mut self, text: &str) -> bool {
+        if self.check(text) { self.advance(); true } else { false }
+    }
+
+    /// This function expects the current token to be the provided keyword or symbol, and moves past it.
+    fn expect(&mut self, text: &str) -> ParseResult {
+        if self.test_next(text) { Ok(()) } else { Err(self.error_near(&format!("'{}' expected", text))) }
+    }
+
+    /// This function expects the current token to close the provided `what` block (`end`, `until`,...), opened at `line`.
+    fn expect_match(&mut self, what: &str, who: &str, line: usize) -> ParseResult {
+        if self.test_next(what) { Ok(()) }
+        else if line == self.current().line { Err(self.error_near(&format!("'{}' expected", what))) }
+        else { Err(self.error_near(&format!("'{}' expected (to close '{}' at line {})", what, who, line))) }
+    }
+
+    /// This function expects the current token to be a name, and moves past it.
+    fn expect_name(&mut self) -> ParseResult {
+        if self.current().kind == TokenKind::Name { self.advance(); Ok(()) } else { Err(self.error_near("<name> expected")) }
+    }
+
+    /// This function creates an error with the provided message at the current token.
+    fn error_near(&self, message: &str) -> LuaSyntaxError {
+        let token = self.current();
+        LuaSyntaxError::new(token.line, token.column, format!("{} near '{}'", message, token.text))
+    }
+
+    /// This function checks if the current token ends a block.
+    fn block_follow(&self) -> bool {
+        self.current().kind == TokenKind::Eof || ["else", "elseif", "end", "until"].iter().any(|x| self.check(x))
+    }
+
+    /// This function goes one level deeper in the script, failing if it's nested too deep.
+    fn enter_level(&mut self) -> ParseResult {
+        self.level += 1;
+        if self.level > MAX_SYNTAX_LEVELS {
+            let token = self.current();
+            Err(LuaSyntaxError::new(token.line, token.column, "chunk has too many syntax levels".to_owned()))
+        }
+        else { Ok(()) }
+    }
+
+    /// chunk ::= {stat [';']} [laststat [';']]
+    fn chunk(&mut self) -> ParseResult {
+        self.enter_level()?;
+        while !self.block_follow() {
+            let is_last = self.statement()?;
+            self.test_next(";");
+            if is_last { break; }
+        }
+        self.level -= 1;
+        Ok(())
+    }
+
+    /// This function parses a statement, returning if it was a `return` or a `break`, which must be the last statement of their block.
+    fn statement(&mut self) -> std::result::Result<bool, LuaSyntaxError> {
+        let line = self.current().line;
+        if self.test_next("if") {
+            self.expression()?;
+            self.expect("then")?;
+            self.chunk()?;
+            while self.test_next("elseif") {
+                self.expression()?;
+                self.expect("then")?;
+                self.chunk()?;
+            }
+            if self.test_next("else") { self.chunk()?; }
+            self.expect_match("end", "if", line)?;
+        }
+        else if self.test_next("while") {
+            self.expression()?;
+            self.expect("do")?;
+            self.chunk()?;
+            self.expect_match("end", "while", line)?;
+        }
+        else if self.test_next("do") {
+            self.chunk()?;
+            self.expect_match("end", "do", line)?;
+        }
+        else if self.test_next("for") {
+            self.expect_name()?;
+            if self.test_next("=") {
+                self.expression()?;
+                self.expect(",")?;
+                self.expression()?;
+                if self.test_next(",") { self.expression()?; }
+            }
+            else if self.check(",") || self.check("in") {
+                while self.test_next(",") { self.expect_name()?; }
+                self.expect("in")?;
+                self.expression_list()?;
+            }
+            else { return Err(self.error_near("'=' or 'in' expected")) }
+            self.expect("do")?;
+            self.chunk()?;
+            self.expect_match("end", "for", line)?;
+        }
+        else if self.test_next("repeat") {
+            self.chunk()?;
+            self.expect_match("until", "repeat", line)?;
+            self.expression()?;
+        }
+        else if self.test_next("function") {
+            self.expect_name()?;
+            while self.test_next(".") { self.expect_name()?; }
+            let is_method = self.test_next(":");
+            if is_method { self.expect_name()?; }
+            self.function_body(line)?;
+        }
+        else if self.test_next("local") {
+            if self.test_next("function") {
+                self.expect_name()?;
+                self.function_body(line)?;
+            }
+            else {
+                self.expect_name()?;
+                while self.test_next(",") { self.expect_name()?; }
+                if self.test_next("=") { self.expression_list()?; }
+            }
+        }
+        else if self.test_next("return") {
+            if !self.block_follow() && !self.check(";") { self.expression_list()?; }
+            return Ok(true)
+        }
+        else if self.test_next("break") { return Ok(true) }
+        else { self.expression_statement()?; }
+        Ok(false)
+    }
+
+    /// exprstat ::= functioncall | varlist '=' explist
+    fn expression_statement(&mut self) -> ParseResult {
+        let kind = self.suffixed_expression()?;
+        if self.check("=") || self.check(",") {
+            if kind != ExpressionKind::Variable { return Err(self.error_near("syntax error")) }
+            while self.test_next(",") {
+                if self.suffixed_expression()? != ExpressionKind::Variable { return Err(self.error_near("syntax error")) }
+            }
+            self.expect("=")?;
+            self.expression_list()?;
+        }
+        else if kind != ExpressionKind::Call { return Err(self.error_near("syntax error")) }
+        Ok(())
+    }
+
+    /// funcbody ::= '(' [parlist] ')' block 'end'
+    fn function_body(&mut self, line: usize) -> ParseResult {
+        self.expect("(")?;
+        let mut is_vararg = false;
+        if !self.check(")") {
+            loop {
+                if self.current().kind == TokenKind::Name { self.advance(); }
+                else if self.test_next("...") { is_vararg = true; break; }
+                else { return Err(self.error_near("<name> or '...' expected")) }
+                if !self.test_next(",") { break; }
+            }
+        }
+        self.expect(")")?;
+
+        self.vararg_scopes.push(is_vararg);
+        let result = self.chunk().and_then(|_| self.expect_match("end", "function", line));
+        self.vararg_scopes.pop();
+        result
+    }
+
+    /// explist ::= {exp ','} exp
+    fn expression_list(&mut self) -> ParseResult {
+        self.expression()?;
+        while self.test_next(",") { self.expression()?; }
+        Ok(())
+    }
+
+    /// exp ::= (simpleexp | unop exp) {binop exp}
+    fn expression(&mut self) -> ParseResult {
+        self.sub_expression(0)
+    }
+
+    /// This function parses an expression whose binary operators have more priority than the provided limit.
+    fn sub_expression(&mut self, limit: u8) -> ParseResult {
+        self.enter_level()?;
+        if self.check("not") || self.check("-") || self.check("#") {
+            self.advance();
+            self.sub_expression(UNARY_PRIORITY)?;
+        }
+        else { self.simple_expression()?; }
+
+        while let Some((left, right)) = get_binary_priority(self.current()) {
+            if left <= limit { break; }
+            self.advance();
+            self.sub_expression(right)?;
+        }
+        self.level -= 1;
+        Ok(())
+    }
+
+    /// simpleexp ::= NUMBER | STRING | nil | true | false | '...' | constructor | FUNCTION body | suffixedexp
+    fn simple_expression(&mut self) -> ParseResult {
+        let token = self.current().clone();
+        match token.kind {
+            TokenKind::Number | TokenKind::String => self.advance(),
+            TokenKind::Keyword if token.text == "nil" || token.text == "true" || token.text == "false" => self.advance(),
+            TokenKind::Keyword if token.text == "function" => {
+                self.advance();
+                self.function_body(token.line)?;
+            }
+            TokenKind::Symbol if token.text == "..." => {
+                if !self.vararg_scopes.last().unwrap() { return Err(self.error_near("cannot use '...' outside a vararg function")) }
+                self.advance();
+            }
+            TokenKind::Symbol if token.text == "{" => self.table_constructor()?,
+            _ => { self.suffixed_expression()?; }
+        }
+        Ok(())
+    }
+
+    /// primaryexp ::= NAME | '(' expr ')'
+    fn primary_expression(&mut self) -> std::result::Result<ExpressionKind, LuaSyntaxError> {
+        let line = self.current().line;
+        if self.current().kind == TokenKind::Name {
+            self.advance();
+            Ok(ExpressionKind::Variable)
+        }
+        else if self.test_next("(") {
+            self.expression()?;
+            self.expect_match(")", "(", line)?;
+            Ok(ExpressionKind::Value)
+        }
+        else { Err(self.error_near("unexpected symbol")) }
+    }
+
+    /// suffixedexp ::= primaryexp { '.' NAME | '[' exp ']' | ':' NAME funcargs | funcargs }
+    ///
+    /// It returns the kind of the expression, as only function calls can be used as statements, and only variables can be assigned.
+    fn suffixed_expression(&mut self) -> std::result::Result<ExpressionKind, LuaSyntaxError> {
+        let mut kind = self.primary_expression()?;
+        loop {
+            if self.test_next(".") {
+                self.expect_name()?;
+                kind = ExpressionKind::Variable;
+            }
+            else if self.test_next("[") {
+                self.expression()?;
+                self.expect("]")?;
+                kind = ExpressionKind::Variable;
+            }
+            else if self.test_next(":") {
+                self.expect_name()?;
+                self.function_arguments()?;
+                kind = ExpressionKind::Call;
+            }
+            else if self.check("(") || self.check("{") || self.current().kind == TokenKind::String {
+                self.function_arguments()?;
+                kind = ExpressionKind::Call;
+            }
+            else { return Ok(kind) }
+        }
+    }
+
+    /// funcargs ::= '(' [explist] ')' | constructor | STRING
+    fn function_arguments(&mut self) -> ParseResult {
+        let line = self.current().line;
+        if self.check("(") {
+
+            // Lua 5.1 doesn't allow a call to start in a different line than the function, as it's ambiguous.
+            if line != self.tokens[self.position - 1].line { return Err(self.error_near("ambiguous syntax (function call x new statement)")) }
+            self.advance();
+            if !self.check(")") { self.expression_list()?; }
+            self.expect_match(")", "(", line)
+        }
+        else if self.check("{") { self.table_constructor() }
+        else if self.current().kind == TokenKind::String { self.advance(); Ok(()) }
+        else { Err(self.error_near("function arguments expected")) }
+    }
+
+    /// constructor ::= '{' [field {sep field} [sep]] '}'
+    fn table_constructor(&mut self) -> ParseResult {
+        let line = self.current().line;
+        self.expect("{")?;
+        while !self.check("}") {
+            if self.current().kind == TokenKind::Name && self.next().kind == TokenKind::Symbol && self.next().text == "=" {
+                self.advance();
+                self.advance();
+                self.expression()?;
+            }
+            else if self.test_next("[") {
+                self.expression()?;
+                self.expect("]")?;
+                self.expect("=")?;
+                self.expression()?;
+            }
+            else { self.expression()?; }
+
+            if !self.test_next(",") && !self.test_next(";") { break; }
+        }
+        self.expect_match("}", "{", line)
+    }
+}
+
+/// Priority of the unary operators (`not`, `-`, `#`).
+const UNARY_PRIORITY: u8 = 8;
+
+/// This function returns the left and right priorities of the binary operator in the provided token, if it's one.
+fn get_binary_priority(token: &Token) -> Option<(u8, u8)> {
+    if token.kind != TokenKind::Symbol && token.kind != TokenKind::Keyword { return None }
+    match &*token.text {
+        "+" | "-" => Some((6, 6)),
+        "*" | "/" | "%" => Some((7, 7)),
+        "^" => Some((10, 9)),
+        ".." => Some((5, 4)),
+        "==" | "~=" | "<" | "<=" | ">" | ">=" => Some((3, 3)),
+        "and" => Some((2, 2)),
+        "or" => Some((1, 1)),
+        _ => None,
+    }
+}
@@ -13,7 +13,7 @@
 use csv::{ReaderBuilder, WriterBuilder, QuoteStyle};
 use serde_derive::{Serialize, Deserialize};
 
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::io::{BufReader, Read, Write};
 use std::fs::File;
 use std::path::PathBuf;
@@ -26,13 +26,21 @@ use crate::error::{Error, ErrorKind, Result};
 use crate::packfile::{PackFile, PathType};
 use crate::packfile::packedfile::PackedFile;
 use crate::packedfile::loc::*;
+use crate::packedfile::loc::markup::check_loc_markup;
 use crate::packedfile::db::*;
-use crate::schema::{FieldType, Schema, TableDefinition};
+use crate::packedfile::rigidmodel::RigidModelHeader;
+use crate::schema::{Field, FieldType, Schema, TableDefinition, LOCALISABLE_FIELDS};
+use crate::ui::escape_html;
 
 use crate::SCHEMA;
 pub mod loc;
+pub mod lua;
 pub mod db;
 pub mod rigidmodel;
+pub mod query;
+pub mod registry;
+pub mod text;
+pub mod xml;
 
 /// This enum specifies the PackedFile types we can create.
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -49,7 +57,7 @@ pub enum PackedFileType {
 }
 
 /// This enum specifies the PackedFile types we can decode.
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum DecodeablePackedFileType {
     DB,
     Loc,
@@ -74,58 +82,138 @@ pub enum DecodedData {
     OptionalStringU16(String),
 }
 
+/// This enum represents the strategies we can use when merging tables with rows with the same key.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum MergeStrategy {
+
+    // The first row found with a key is the one we keep.
+    FirstWins,
+
+    // The last row found with a key is the one we keep.
+    LastWins,
+
+    // Rows with the same key and different data stop the merge.
+    ReportConflicts,
+}
+
+/// Implementation of `DecodedData`.
+impl DecodedData {
+
+    /// This function returns the default value of a cell of the provided type.
+    pub fn default(field_type: FieldType) -> Self {
+        match field_type {
+            FieldType::Boolean => DecodedData::Boolean(false),
+            FieldType::Float => DecodedData::Float(0.0),
+            FieldType::Integer => DecodedData::Integer(0),
+            FieldType::LongInteger => DecodedData::LongInteger(0),
+            FieldType::StringU8 => DecodedData::StringU8(String::new()),
+            FieldType::StringU16 => DecodedData::StringU16(String::new()),
+            FieldType::OptionalStringU8 => DecodedData::OptionalStringU8(String::new()),
+            FieldType::OptionalStringU16 => DecodedData::OptionalStringU16(String::new()),
+        }
+    }
+
+    /// This function returns the default value of a cell of the provided field, using the default value
+    /// of his definition if it has one valid for his type.
+    pub fn default_from_field(field: &Field) -> Self {
+        match field.field_default_value {
+            Some(ref default_value) => DecodedData::StringU8(default_value.to_owned()).convert_to_type(field.field_type).unwrap_or_else(|| DecodedData::default(field.field_type)),
+            None => DecodedData::default(field.field_type),
+        }
+    }
+
+    /// This function tries to convert a cell to another type. If it's not possible, it returns None.
+    pub fn convert_to_type(&self, field_type: FieldType) -> Option<Self> {
+        let data = get_decoded_data_as_string(self);
+        match field_type {
+            FieldType::Boolean => match &*data.to_lowercase() {
+                "true" | "1" => Some(DecodedData::Boolean(true)),
+                "false" | "0" => Some(DecodedData::Boolean(false)),
+                _ => None,
+            },
+            FieldType::Float => data.parse::<f32>().ok().map(DecodedData::Float),
+            FieldType::Integer => data.parse::<i32>().ok().map(DecodedData::Integer),
+            FieldType::LongInteger => data.parse::<i64>().ok().map(DecodedData::LongInteger),
+            FieldType::StringU8 => Some(DecodedData::StringU8(data)),
+            FieldType::StringU16 => Some(DecodedData::StringU16(data)),
+            FieldType::OptionalStringU8 => Some(DecodedData::OptionalStringU8(data)),
+            FieldType::OptionalStringU16 => Some(DecodedData::OptionalStringU16(data)),
+        }
+    }
+}
+
 /// Const to use in the header of TSV PackedFiles.
 pub const TSV_HEADER_PACKFILE_LIST: &str = "PackFile List";
 pub const TSV_HEADER_LOC_PACKEDFILE: &str = "Loc PackedFile";
+pub const TSV_HEADER_QUERY_RESULT: &str = "Query Result";
+
+/// Bytes of data we check to know the type of a PackedFile from his contents.
+const SNIFFED_DATA_SIZE: usize = 1024;
+
+/// Magic numbers of the images we can open: DDS, PNG and JPG.
+const IMAGE_SIGNATURES: [&[u8]; 3] = [
+    b"DDS ",
+    &[0x89, 0x50, 0x4E, 0x47, 0x0D, 0x0A, 0x1A, 0x0A],
+    &[0xFF, 0xD8, 0xFF],
+];
+
+/// Path and placeholder text used for the Loc PackedFile we create with the missing locs of a PackFile.
+const MISSING_LOCS_PACKED_FILE_PATH: [&str; 3] = ["text", "db", "missing_locs.loc"];
+const MISSING_LOCS_PLACEHOLDER: &str = "PLACEHOLDER";
 
 //----------------------------------------------------------------//
 // Generic Functions for PackedFiles.
 //----------------------------------------------------------------//
 
-/// Function to get the type of a PackedFile.
+/// Function to get the type of a PackedFile. The type is the one of the first rule of the registry that matches his path.
 pub fn get_packed_file_type(path: &[String]) -> DecodeablePackedFileType {
+    registry::get_packed_file_type_rule(path).map_or(DecodeablePackedFileType::None, |x| x.packed_file_type)
+}
 
-    let packedfile_name = path.last().unwrap().to_owned();
-
-    // If it's in the "db" folder, it's a DB PackedFile (or you put something were it shouldn't be).
-    if path[0] == "db" { DecodeablePackedFileType::DB }
-
-    // If it ends in ".loc", it's a localisation PackedFile.
-    else if packedfile_name.ends_with(".loc") { DecodeablePackedFileType::Loc }
-
-    // If it ends in ".rigid_model_v2", it's a RigidModel PackedFile.
-    else if packedfile_name.ends_with(".rigid_model_v2") { DecodeablePackedFileType::RigidModel }
-
-    // If it ends in any of these, it's a plain text PackedFile.
-    else if packedfile_name.ends_with(".lua") ||
-            packedfile_name.ends_with(".xml") ||
-            packedfile_name.ends_with(".xml.shader") ||
-            packedfile_name.ends_with(".xml.material") ||
-            packedfile_name.ends_with(".variantmeshdefinition") ||
-            packedfile_name.ends_with(".environment") ||
-            packedfile_name.ends_with(".lighting") ||
-            packedfile_name.ends_with(".wsmodel") ||
-            packedfile_name.ends_with(".csv") ||
-            packedfile_name.ends_with(".tsv") ||
-            packedfile_name.ends_with(".inl") ||
-            packedfile_name.ends_with(".battle_speech_camera") ||
-            packedfile_name.ends_with(".bob") ||
-            packedfile_name.ends_with(".cindyscene") ||
-            packedfile_name.ends_with(".cindyscenemanager") ||
-            //packedfile_name.ends_with(".benchmark") || // This one needs special decoding/encoding.
-            packedfile_name.ends_with(".txt") { DecodeablePackedFileType::Text }
-
-    // If it ends in any of these, it's an image.
-    else if packedfile_name.ends_with(".jpg") ||
-            packedfile_name.ends_with(".jpeg") ||
-            packedfile_name.ends_with(".tga") ||
-            packedfile_name.ends_with(".dds") ||
-            packedfile_name.ends_with(".png") { DecodeablePackedFileType::Image }
-
-    // Otherwise, we don't have a decoder for that PackedFile... yet.
+/// Function to get the type of a PackedFile, looking at his data if his path doesn't match any rule of the registry.
+/// The type detected from his data is kept in the PackedFile, so his data is only checked again if it changes.
+pub fn detect_packed_file_type(packed_file: &mut PackedFile) -> DecodeablePackedFileType {
+    match get_packed_file_type(&packed_file.path) {
+        DecodeablePackedFileType::None => match packed_file.get_detected_type() {
+            Some(packed_file_type) => packed_file_type,
+            None => {
+                let packed_file_type = match packed_file.get_data_head(SNIFFED_DATA_SIZE) {
+                    Ok(data) => get_packed_file_type_from_data(&data),
+                    Err(_) => DecodeablePackedFileType::None,
+                };
+                packed_file.set_detected_type(packed_file_type.clone());
+                packed_file_type
+            }
+        }
+        packed_file_type => packed_file_type,
+    }
+}
+
+/// Function to get the type of a PackedFile from the start of his data, using the magic numbers and headers of the types we know.
+/// If it's none of them, but the data looks like text, it's a Text PackedFile.
+pub fn get_packed_file_type_from_data(data: &[u8]) -> DecodeablePackedFileType {
+    if Loc::is_loc(data) { DecodeablePackedFileType::Loc }
+    else if DB::is_db_table(data) { DecodeablePackedFileType::DB }
+    else if RigidModelHeader::is_rigid_model(data) { DecodeablePackedFileType::RigidModel }
+    else if IMAGE_SIGNATURES.iter().any(|x| data.starts_with(x)) { DecodeablePackedFileType::Image }
+    else if text::is_text(data) { DecodeablePackedFileType::Text }
     else { DecodeablePackedFileType::None }
 }
 
+/// This function returns the data of a cell as a String.
+pub fn get_decoded_data_as_string(data: &DecodedData) -> String {
+    match data {
+        DecodedData::Boolean(data) => data.to_string(),
+        DecodedData::Float(data) => data.to_string(),
+        DecodedData::Integer(data) => data.to_string(),
+        DecodedData::LongInteger(data) => data.to_string(),
+        DecodedData::StringU8(data) |
+        DecodedData::StringU16(data) |
+        DecodedData::OptionalStringU8(data) |
+        DecodedData::OptionalStringU16(data) => data.to_owned(),
+    }
+}
+
 /// This function is used to create a PackedFile outtanowhere. It returns his new path.
 pub fn create_packed_file(
     pack_file: &mut PackFile,
@@ -169,63 +257,19 @@ pub fn create_packed_file(
 }
 
 /// This function merges (if it's possible) the provided DB and LOC tables into one with the name and, if asked,
-/// it deletes the source files. Table_type means true: DB, false: LOC.
-pub fn merge_tables( 
+/// it deletes the source files. Table_type means true: DB, false: LOC. Rows with the same key are deduplicated
+/// using the provided strategy.
+pub fn merge_tables(
     pack_file: &mut PackFile,
     source_paths: &[Vec<String>],
     name: &str,
     delete_source_paths: bool,
     table_type: bool,
+    strategy: MergeStrategy,
 ) -> Result<(Vec<String>, Vec<PathType>)> {
     
-    let mut db_files = vec![];
-    let mut loc_files = vec![];
-
-    // Decode them depending on their type.
-    for path in source_paths {
-        let packed_file = pack_file.packed_files.iter().find(|x| &x.path == path).ok_or_else(|| Error::from(ErrorKind::PackedFileNotFound))?;
-        let packed_file_data = packed_file.get_data()?;
-        
-        if table_type { 
-            if let Some(ref schema) = *SCHEMA.lock().unwrap() {
-                db_files.push(DB::read(&packed_file_data, &path[1], &schema)?); 
-            }
-            else { return Err(ErrorKind::SchemaNotFound)? }
-        }
-        else { loc_files.push(Loc::read(&packed_file_data)?); }
-    }
-
     // Merge them all into one, and return error if any problem arise.
-    let packed_file_data = if table_type {
-        let mut final_entries_list = vec![];
-        let mut version = -2;
-        let mut table_definition = TableDefinition::new(0);
-
-        for table in &mut db_files {
-            if version == -2 { 
-                version = table.version; 
-                table_definition = table.table_definition.clone();
-            }
-            else if table.version != version { return Err(ErrorKind::InvalidFilesForMerging)? }
-
-            final_entries_list.append(&mut table.entries);
-        }
-
-        let mut new_table = DB::new(&db_files[0].db_type, version, table_definition);
-        new_table.entries = final_entries_list;
-        new_table.save()
-    }
-
-    else {
-        let mut final_entries_list = vec![];
-        for table in &mut loc_files {
-            final_entries_list.append(&mut table.entries);
-        }
-
-        let mut new_table = Loc::new();
-        new_table.entries = final_entries_list;
-        new_table.save()
-    };
+    let (packed_file_data, _) = get_merged_tables_data(pack_file, source_paths, table_type, strategy, SCHEMA.lock().unwrap().as_ref())?;
 
     // And then, we reach the part where we have to do the "saving to PackFile" stuff.
     let mut path = source_paths[0].to_vec();
@@ -254,7 +298,120 @@ pub fn merge_tables(
     Ok((added_path, tree_paths))
 }
 
-/// This function retrieves the entire Dependency Data for a given table definition.
+/// This function returns the list of rows that are going to be dropped or overwritten if we merge the provided tables
+/// with the provided strategy, so we can check them before merging.
+pub fn get_merge_tables_preview(
+    pack_file: &PackFile,
+    source_paths: &[Vec<String>],
+    table_type: bool,
+    strategy: MergeStrategy,
+) -> Result<Vec<String>> {
+    get_merged_tables_data(pack_file, source_paths, table_type, strategy, SCHEMA.lock().unwrap().as_ref()).map(|x| x.1)
+}
+
+/// This function merges the entries of the provided DB or LOC tables, removing the rows with duplicated keys using
+/// the provided strategy. DB Tables of different versions are updated to the newest version before merging.
+/// It returns the encoded data of the merged table, and the list of rows dropped or overwritten in the process.
+///
+/// The schema is passed as argument, so this can be used without the `SCHEMA` LazyStatic.
+pub fn get_merged_tables_data(
+    pack_file: &PackFile,
+    source_paths: &[Vec<String>],
+    table_type: bool,
+    strategy: MergeStrategy,
+    schema: Option<&Schema>,
+) -> Result<(Vec<u8>, Vec<String>)> {
+
+    let mut db_files = vec![];
+    let mut loc_files = vec![];
+
+    // Decode them depending on their type.
+    for path in source_paths {
+        let packed_file = pack_file.packed_files.iter().find(|x| &x.path == path).ok_or_else(|| Error::from(ErrorKind::PackedFileNotFound))?;
+        let packed_file_data = packed_file.get_data()?;
+
+        if table_type {
+            if let Some(schema) = schema {
+                if path.len() < 3 { return Err(ErrorKind::InvalidFilesForMerging)? }
+                db_files.push((path.join("/"), DB::read(&packed_file_data, &path[1], schema)?));
+            }
+            else { return Err(ErrorKind::SchemaNotFound)? }
+        }
+        else { loc_files.push((path.join("/"), Loc::read(&packed_file_data)?)); }
+    }
+
+    // Get the rows to merge and their key columns. For DB Tables, we need to update all of them to the same version first.
+    let (key_columns, rows) = if table_type {
+        if db_files.is_empty() || db_files.iter().any(|x| x.1.db_type != (db_files[0].1).db_type) { return Err(ErrorKind::InvalidFilesForMerging)? }
+        let table_definition = db_files.iter().max_by_key(|x| x.1.version).unwrap().1.table_definition.clone();
+        let key_columns = table_definition.fields.iter().enumerate().filter(|(_, x)| x.field_is_key).map(|(x, _)| x).collect::<Vec<usize>>();
+        let mut rows = vec![];
+        for (path, table) in &mut db_files {
+            if table.version != table_definition.version { table.set_table_definition(&table_definition); }
+            table.entries.drain(..).for_each(|x| rows.push((path.to_owned(), x)));
+        }
+        (key_columns, rows)
+    }
+    else {
+        let mut rows = vec![];
+        for (path, table) in &mut loc_files {
+            table.entries.drain(..).for_each(|x| rows.push((path.to_owned(), x)));
+        }
+        (vec![0], rows)
+    };
+
+    // Remove the duplicates, depending on the strategy we have.
+    let mut entries: Vec<Vec<DecodedData>> = vec![];
+    let mut keys: HashMap<Vec<String>, usize> = HashMap::new();
+    let mut report = vec![];
+    let mut conflicts = vec![];
+    for (path, row) in rows {
+        if key_columns.is_empty() { entries.push(row); continue; }
+        let key = key_columns.iter().map(|x| get_decoded_data_as_string(&row[*x])).collect::<Vec<String>>();
+        match keys.get(&key) {
+            Some(index) => {
+                let (key, path) = (escape_html(&key.join(", ")), escape_html(&path));
+                if entries[*index] == row { report.push(format!("Key <i>{}</i>: duplicated row from <i>{}</i> dropped.", key, path)); }
+                else {
+                    match strategy {
+                        MergeStrategy::FirstWins => report.push(format!("Key <i>{}</i>: row from <i>{}</i> dropped.", key, path)),
+                        MergeStrategy::LastWins => {
+                            report.push(format!("Key <i>{}</i>: row overwritten by the one from <i>{}</i>.", key, path));
+                            entries[*index] = row;
+                        }
+                        MergeStrategy::ReportConflicts => conflicts.push(format!("Key <i>{}</i>: different data in <i>{}</i>.", key, path)),
+                    }
+                }
+            }
+            None => {
+                keys.insert(key, entries.len());
+                entries.push(row);
+            }
+        }
+    }
+
+    if !conflicts.is_empty() { return Err(ErrorKind::MergeTablesConflicts(conflicts))? }
+
+    let packed_file_data = if table_type {
+        let table = &db_files[0].1;
+        let table_definition = db_files.iter().max_by_key(|x| x.1.version).unwrap().1.table_definition.clone();
+        let mut new_table = DB::new(&table.db_type, table_definition.version, table_definition);
+        new_table.entries = entries;
+        new_table.save()
+    }
+
+    else {
+        let mut new_table = Loc::new();
+        new_table.entries = entries;
+        new_table.save()
+    };
+
+    Ok((packed_file_data, report))
+}
+
+/// This function retrieves the entire Dependency Data for a given table definition. For each referenced
+/// column, it returns the referenced data, with the data of the lookup column of the field (if it has one)
+/// as value. If the field has no lookup column, the value is empty.
 ///
 /// NOTE: It's here and not in DB because we may get an use for this in LOC PackedFiles.
 /// NOTE2: We don't lock the LazyStatics here. We get them as arguments instead. The reason
@@ -266,7 +423,7 @@ pub fn get_dependency_data(
     dep_db: &mut Vec<PackedFile>,
     fake_dep_db: &[DB],
     pack_file: &PackFile
-) -> BTreeMap<i32, Vec<String>> {
+) -> BTreeMap<i32, BTreeMap<String, String>> {
 
     // If we reach this point, we build the dependency data of the table.
     let mut dep_data = BTreeMap::new();
@@ -275,42 +432,18 @@ pub fn get_dependency_data(
             if !dependency_data.0.is_empty() && !dependency_data.1.is_empty() {
 
                 // If the column is a reference column, fill his referenced data.
-                let mut data = vec![];
+                let mut data = BTreeMap::new();
                 let mut iter = dep_db.iter_mut();
                 while let Some(packed_file) = iter.find(|x| x.path.starts_with(&["db".to_owned(), format!("{}_tables", dependency_data.0)])) {
                     if let Ok(table) = DB::read(&packed_file.get_data_and_keep_it().unwrap(), &format!("{}_tables", dependency_data.0), &schema) {
-                        if let Some(column_index) = table.table_definition.fields.iter().position(|x| x.field_name == dependency_data.1) {
-                            for row in table.entries.iter() {
-
-                                // For now we assume any dependency is a string.
-                                match row[column_index] { 
-                                    DecodedData::StringU8(ref entry) |
-                                    DecodedData::StringU16(ref entry) |
-                                    DecodedData::OptionalStringU8(ref entry) |
-                                    DecodedData::OptionalStringU16(ref entry) => data.push(entry.to_owned()),
-                                    _ => {}
-                                }
-                            }
-                        }
+                        get_referenced_data(&table, &dependency_data.1, &field.field_lookup, &mut data);
                     } 
                 }
 
                 // Same thing for the fake dependency list, if exists.
                 let mut iter = fake_dep_db.iter();
                 if let Some(table) = iter.find(|x| x.db_type == format!("{}_tables", dependency_data.0)) {
-                    if let Some(column_index) = table.table_definition.fields.iter().position(|x| x.field_name == dependency_data.1) {
-                        for row in table.entries.iter() {
-
-                            // For now we assume any dependency is a string.
-                            match row[column_index] { 
-                                DecodedData::StringU8(ref entry) |
-                                DecodedData::StringU16(ref entry) |
-                                DecodedData::OptionalStringU8(ref entry) |
-                                DecodedData::OptionalStringU16(ref entry) => data.push(entry.to_owned()),
-                                _ => {}
-                            }
-                        }
-                    }
+                    get_referenced_data(&table, &dependency_data.1, &field.field_lookup, &mut data);
                 }
 
                 // The same for our own PackFile.
@@ -318,27 +451,11 @@ pub fn get_dependency_data(
                 while let Some(packed_file) = iter.find(|x| x.path.starts_with(&["db".to_owned(), format!("{}_tables", dependency_data.0)])) {
                     if let Ok(packed_file_data) = packed_file.get_data() {
                         if let Ok(table) = DB::read(&packed_file_data, &format!("{}_tables", dependency_data.0), &schema) {
-                            if let Some(column_index) = table.table_definition.fields.iter().position(|x| x.field_name == dependency_data.1) {
-                                for row in table.entries.iter() {
-
-                                    // For now we assume any dependency is a string.
-                                    match row[column_index] { 
-                                        DecodedData::StringU8(ref entry) |
-                                        DecodedData::StringU16(ref entry) |
-                                        DecodedData::OptionalStringU8(ref entry) |
-                                        DecodedData::OptionalStringU16(ref entry) => data.push(entry.to_owned()),
-                                        _ => {}
-                                    }
-                                }
-                            }
+                            get_referenced_data(&table, &dependency_data.1, &field.field_lookup, &mut data);
                         }
                     }
                 }
 
-                // Sort and dedup the data found.
-                data.sort_unstable_by(|a, b| a.cmp(&b));
-                data.dedup();
-
                 dep_data.insert(column as i32, data);
             }
         }
@@ -348,7 +465,42 @@ pub fn get_dependency_data(
     dep_data
 }
 
-/// This function checks all the DB Tables of the provided PackFile for dependency errors.
+/// This function adds the data of the referenced column of the provided table to the provided list, with
+/// the data of his lookup column, if any, as value.
+fn get_referenced_data(
+    table: &DB,
+    column_name: &str,
+    lookup_column_name: &Option<String>,
+    data: &mut BTreeMap<String, String>,
+) {
+    if let Some(column_index) = table.table_definition.fields.iter().position(|x| x.field_name == column_name) {
+        let lookup_column_index = match lookup_column_name {
+            Some(lookup_column_name) => table.table_definition.fields.iter().position(|x| &x.field_name == lookup_column_name),
+            None => None,
+        };
+
+        for row in table.entries.iter() {
+
+            // For now we assume any dependency is a string.
+            match row[column_index] { 
+                DecodedData::StringU8(ref entry) |
+                DecodedData::StringU16(ref entry) |
+                DecodedData::OptionalStringU8(ref entry) |
+                DecodedData::OptionalStringU16(ref entry) => {
+                    let lookup = match lookup_column_index {
+                        Some(lookup_column_index) => get_decoded_data_as_string(&row[lookup_column_index]),
+                        None => String::new(),
+                    };
+                    data.insert(entry.to_owned(), lookup);
+                },
+                _ => {}
+            }
+        }
+    }
+}
+
+/// This function checks all the DB Tables of the provided PackFile for dependency errors, and all the Loc PackedFiles
+/// for markup and placeholder errors.
 pub fn check_tables( 
     pack_file: &mut PackFile,
 ) -> Result<()> {
@@ -370,7 +522,7 @@ pub fn check_tables(
             }
 
             for packed_file in pack_file.packed_files.iter() {
-                if packed_file.path.starts_with(&["db".to_owned()]) {
+                if packed_file.path.len() == 3 && packed_file.path.starts_with(&["db".to_owned()]) {
                     if let Ok(db_data) = db::DB::read(&(packed_file.get_data().unwrap()), &packed_file.path[1], &schema) {
                         let dep_data = get_dependency_data(&db_data.table_definition, &schema, &mut dep_db, &fake_dep_db, &pack_file);
 
@@ -388,7 +540,7 @@ pub fn check_tables(
                                         _ => "NoData"
                                     };
 
-                                    if field_data != "NoData" && !field_data.is_empty() && !dep_data.contains(&field_data.to_owned()) {
+                                    if field_data != "NoData" && !field_data.is_empty() && !dep_data.contains_key(field_data) {
                                         columns.push(*column);
                                     }
                                 }
@@ -408,14 +560,147 @@ pub fn check_tables(
                 }
             }
 
-            // If all tables are Ok, return Ok. Otherwise, return an error with the list of broken tables.
-            if broken_tables.is_empty() { Ok(()) }
-            else { Err(ErrorKind::DBMissingReferences(broken_tables))? }
+            // Then check the markup and placeholders of the Loc PackedFiles. That needs the dependency database too, so we release it first.
+            drop(dep_db);
+            drop(fake_dep_db);
+            let broken_locs = check_loc_markup(pack_file)?;
+
+            // If all tables and locs are Ok, return Ok. Otherwise, return an error with the list of broken tables and loc entries.
+            if broken_tables.is_empty() && broken_locs.is_empty() { Ok(()) }
+            else { Err(ErrorKind::CheckTablesErrors(broken_tables, broken_locs))? }
         }
         None => Err(ErrorKind::SchemaNotFound)?
     }
 }
 
+/// This function checks all the DB Tables of the provided PackFile for missing localisation entries, using the current Schema and
+/// the loc keys of the PackFile and the Dependency Database. It returns the list of expected keys not found in any Loc PackedFile.
+pub fn get_missing_locs(
+    pack_file: &mut PackFile,
+) -> Result<Vec<String>> {
+
+    // Get the schema, or return an error.
+    match SCHEMA.lock().unwrap().clone() {
+        Some(schema) => {
+
+            // First, get all the loc keys we have available, from our PackFile and from the dependencies.
+            for packed_file in pack_file.packed_files.iter_mut() {
+                let packed_file_type = get_packed_file_type(&packed_file.path);
+                if packed_file_type == DecodeablePackedFileType::DB || packed_file_type == DecodeablePackedFileType::Loc {
+                    packed_file.load_data()?;
+                }
+            }
+            let loc_keys = get_loc_keys(pack_file);
+            Ok(get_missing_locs_from_keys(pack_file, &schema, &loc_keys))
+        }
+        None => Err(ErrorKind::SchemaNotFound)?
+    }
+}
+
+/// This function checks all the DB Tables of the provided PackFile for loc keys missing from the provided ones.
+///
+/// The expected loc keys follow the format `<table>_<column>_<key>`, where `table` is the name of the
+/// table without the `_tables` suffix, `column` is one of the localised columns of the table, and `key`
+/// is the content of the key columns of the row. As the localised columns are not in the binary tables,
+/// we find what columns each table uses from the loc keys we already have, and from the localised fields
+/// of the schema. The data of the DB Tables of the PackFile must be already loaded.
+pub fn get_missing_locs_from_keys(
+    pack_file: &PackFile,
+    schema: &Schema,
+    loc_keys: &BTreeSet<String>,
+) -> Vec<String> {
+
+    // Check every DB Table. Tables we cannot decode are ignored, as `check_tables` already reports them.
+    let mut missing_locs = vec![];
+    for packed_file in pack_file.packed_files.iter().filter(|x| x.path.len() == 3 && x.path[0] == "db") {
+        let data = match packed_file.get_data() {
+            Ok(data) => data,
+            Err(_) => continue,
+        };
+
+        if let Ok(db_data) = DB::read(&data, &packed_file.path[1], schema) {
+            let key_columns = db_data.table_definition.fields.iter().enumerate().filter(|(_, x)| x.field_is_key).map(|(x, _)| x).collect::<Vec<usize>>();
+            if key_columns.is_empty() { continue; }
+
+            // Get the localised columns this table uses. We always keep the longest column name that matches,
+            // so keys like `x_onscreen_name_y` are not detected as from the `onscreen` column.
+            let table_name = db_data.db_type.trim_end_matches("_tables");
+            let table_prefix = format!("{}_", table_name);
+            let mut loc_columns = BTreeSet::new();
+            for loc_key in loc_keys.range(table_prefix.to_owned()..).take_while(|x| x.starts_with(&table_prefix)) {
+                let loc_key = &loc_key[table_prefix.len()..];
+                if let Some(column) = LOCALISABLE_FIELDS.iter().filter(|x| loc_key.starts_with(&format!("{}_", x))).max_by_key(|x| x.len()) {
+                    loc_columns.insert(*column);
+                }
+            }
+
+            // Tables with no loc yet can only be checked if the schema knows their localised columns.
+            loc_columns.extend(db_data.table_definition.localised_fields.iter().map(|x| &**x));
+
+            for row in &db_data.entries {
+                let key = key_columns.iter().map(|x| get_decoded_data_as_string(&row[*x])).collect::<String>();
+
+                for column in &loc_columns {
+                    let loc_key = format!("{}{}_{}", table_prefix, column, key);
+                    if !loc_keys.contains(&loc_key) { missing_locs.push(loc_key); }
+                }
+            }
+        }
+    }
+
+    missing_locs.sort();
+    missing_locs.dedup();
+    missing_locs
+}
+
+/// This function returns all the loc keys available for the provided PackFile, from his own Loc PackedFiles and
+/// from the Dependency Database. The data of the Loc PackedFiles of the PackFile must be already loaded.
+pub fn get_loc_keys(pack_file: &PackFile) -> BTreeSet<String> {
+    let mut loc_keys = BTreeSet::new();
+    for packed_file in DEPENDENCY_DATABASE.lock().unwrap().iter().filter(|x| get_packed_file_type(&x.path) == DecodeablePackedFileType::Loc) {
+        if let Ok(data) = packed_file.get_data() {
+            if let Ok(loc) = Loc::read(&data) {
+                loc_keys.extend(loc.entries.iter().filter_map(|x| if let DecodedData::StringU16(ref key) = x[0] { Some(key.to_owned()) } else { None }));
+            }
+        }
+    }
+
+    for packed_file in pack_file.packed_files.iter().filter(|x| get_packed_file_type(&x.path) == DecodeablePackedFileType::Loc) {
+        if let Ok(loc) = Loc::read(&packed_file.get_data().unwrap()) {
+            loc_keys.extend(loc.entries.iter().filter_map(|x| if let DecodedData::StringU16(ref key) = x[0] { Some(key.to_owned()) } else { None }));
+        }
+    }
+    loc_keys
+}
+
+/// This function creates (or updates, if it already exists) a Loc PackedFile with placeholder entries for the
+/// provided loc keys. It returns the path of the Loc PackedFile, so we can update the UI with it.
+pub fn create_missing_locs_packed_file(
+    pack_file: &mut PackFile,
+    loc_keys: &[String],
+) -> Result<Vec<String>> {
+    let path = MISSING_LOCS_PACKED_FILE_PATH.iter().map(|x| (*x).to_owned()).collect::<Vec<String>>();
+
+    // If we already have a Loc with missing locs, we just add the new keys to it.
+    let mut loc = match pack_file.packed_files.iter_mut().find(|x| x.path == path) {
+        Some(packed_file) => Loc::read(&packed_file.get_data_and_keep_it()?)?,
+        None => Loc::new(),
+    };
+
+    for key in loc_keys {
+        if !loc.entries.iter().any(|x| x[0] == DecodedData::StringU16(key.to_owned())) {
+            loc.entries.push(vec![
+                DecodedData::StringU16(key.to_owned()),
+                DecodedData::StringU16(MISSING_LOCS_PLACEHOLDER.to_owned()),
+                DecodedData::Boolean(true),
+            ]);
+        }
+    }
+
+    let packed_file = PackedFile::read_from_vec(path, get_current_time(), false, loc.save());
+    pack_file.add_packed_files(&[packed_file]).get(0).cloned().ok_or_else(|| Error::from(ErrorKind::ReservedFiles))
+}
+
 //----------------------------------------------------------------//
 // TSV Functions for PackedFiles.
 //----------------------------------------------------------------//
@@ -470,7 +755,7 @@ pub fn import_tsv(
                         FieldType::Integer => entry.push(DecodedData::Integer(field.parse::<i32>().map_err(|_| Error::from(ErrorKind::ImportTSVIncorrectRow(row, column)))?)),
                         FieldType::LongInteger => entry.push(DecodedData::LongInteger(field.parse::<i64>().map_err(|_| Error::from(ErrorKind::ImportTSVIncorrectRow(row, column)))?)),
                         FieldType::StringU8 => entry.push(DecodedData::StringU8(field.to_owned())),
-                        FieldType::StringU16 => entry.push(DecodedData::StringU16(field.to_owned())),
+                        FieldType::StringU16 => entry.push(DecodedData::StringU16(if name == TSV_HEADER_LOC_PACKEDFILE { Loc::unescape_text(field) } else { field.to_owned() })),
                         FieldType::OptionalStringU8 => entry.push(DecodedData::OptionalStringU8(field.to_owned())),
                         FieldType::OptionalStringU16 => entry.push(DecodedData::OptionalStringU16(field.to_owned())),
                     }
@@ -510,8 +795,17 @@ pub fn export_tsv(
     writer.serialize(first_row_data)?;
     writer.serialize(headers)?;
 
-    // Then we serialize each entry in the DB Table.
-    for entry in data { writer.serialize(&entry)?; }
+    // Then we serialize each entry in the DB Table. The text of Locs is escaped, so tabs and newlines don't break the TSV.
+    for entry in data {
+        if first_row_data.0 == TSV_HEADER_LOC_PACKEDFILE {
+            let entry = entry.iter().map(|x| match x {
+                DecodedData::StringU16(data) => DecodedData::StringU16(Loc::escape_text(data)),
+                _ => x.clone(),
+            }).collect::<Vec<DecodedData>>();
+            writer.serialize(&entry)?;
+        }
+        else { writer.serialize(&entry)?; }
+    }
 
     // Then, we try to write it on disk. If there is an error, report it.
     let mut file = File::create(&path)?;
@@ -718,7 +1012,7 @@ pub fn tsv_mass_export(
             }
 
             // Otherwise, we check if it's a Loc PackedFile, and try to decode it and export it.
-            else if packed_file.path.last().unwrap().ends_with(".loc") {
+            else if get_packed_file_type(&packed_file.path) == DecodeablePackedFileType::Loc {
                 match Loc::read(&(packed_file.get_data_and_keep_it()?)) {
                     Ok(loc) => {
 
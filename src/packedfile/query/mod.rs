@@ -0,0 +1,689 @@
+//---------------------------------------------------------------------------//
+// Copyright (c) 2017-2019 Ismael Gutiérrez González. All rights reserved.
+//
+// This file is part of the Rusted PackFile Manager (RPFM) project,
+// which can be found here: https://github.com/Frodo45127/rpfm.
+//
+// This file is licensed under the MIT license, which can be found here:
+// https://github.com/Frodo45127/rpfm/blob/master/LICENSE.
+//---------------------------------------------------------------------------//
+
+// In this file we define the SQL-like query engine we use to search data in the DB Tables.
+// It works over the tables of the open PackFile, the Dependency Database and the fake tables
+// from the Assembly Kit, and it supports queries like:
+//
+// SELECT key, onscreen_name FROM land_units JOIN main_units WHERE armour > 80 AND onscreen_name LIKE '%Knight%' ORDER BY key LIMIT 50
+//
+// Columns are the field names of the table definitions, optionally prefixed with their table's name.
+// Joins without an `ON` are done using the references between the tables. Localised columns (like
+// `onscreen_name`) are resolved using the Loc PackedFiles. There is also a `loc` table with all the locs.
+
+use regex::{Regex, RegexBuilder};
+use serde_derive::{Serialize, Deserialize};
+
+use std::cmp::Ordering;
+use std::collections::{BTreeSet, HashMap};
+
+use crate::DEPENDENCY_DATABASE;
+use crate::FAKE_DEPENDENCY_DATABASE;
+use crate::SCHEMA;
+use crate::error::{ErrorKind, Result};
+use crate::packfile::PackFile;
+use crate::packfile::packedfile::PackedFile;
+use crate::packedfile::{DecodeablePackedFileType, DecodedData, get_decoded_data_as_string, get_packed_file_type};
+use crate::packedfile::db::DB;
+use crate::packedfile::loc::Loc;
+use crate::schema::{Schema, LOCALISABLE_FIELDS};
+
+/// Name of the virtual table with all the Loc entries.
+const LOC_TABLE_NAME: &str = "loc";
+
+/// `QueryResult`: This holds the result of a query, ready to be shown in a table or exported to TSV.
+/// `skipped_tables` are the paths of the tables we couldn't decode, and were left out of the query.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct QueryResult {
+    pub columns: Vec<String>,
+    pub rows: Vec<Vec<DecodedData>>,
+
+    #[serde(default)]
+    pub skipped_tables: Vec<String>,
+}
+
+/// `Query`: This holds a parsed query. An empty list of columns means all of them.
+/// Conditions are a list of `OR` groups of `AND` conditions.
+#[derive(Clone, Debug)]
+struct Query {
+    columns: Vec<String>,
+    from: String,
+    joins: Vec<(String, Option<(String, String)>)>,
+    conditions: Vec<Vec<Condition>>,
+    order_by: Option<(String, bool)>,
+    limit: Option<usize>,
+}
+
+/// `Condition`: This holds one of the conditions of the `WHERE` part of a query.
+#[derive(Clone, Debug)]
+struct Condition {
+    column: String,
+    operator: Operator,
+    value: String,
+}
+
+/// This enum represents the operators we can use in a condition.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Operator {
+    Equal,
+    NotEqual,
+    Lesser,
+    LesserOrEqual,
+    Greater,
+    GreaterOrEqual,
+    Like,
+    NotLike,
+    Contains,
+}
+
+/// This enum represents the tokens of a query.
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Word(String),
+    Text(String),
+    Symbol(String),
+}
+
+/// `QueryTable`: This holds the data of a table (or a group of joined tables) while running a query.
+/// Columns are always in the `table.column` format.
+struct QueryTable {
+    columns: Vec<String>,
+    references: Vec<Option<(String, String)>>,
+    rows: Vec<Vec<DecodedData>>,
+}
+
+/// Implementation of `QueryResult`.
+impl QueryResult {
+
+    /// This function parses and runs the provided query over the tables of the provided PackFile,
+    /// the Dependency Database and the fake Dependency Database.
+    pub fn new(query: &str, pack_file: &PackFile) -> Result<Self> {
+        let schema = match *SCHEMA.lock().unwrap() {
+            Some(ref schema) => schema.clone(),
+            None => return Err(ErrorKind::SchemaNotFound)?
+        };
+
+        let mut dep_db = DEPENDENCY_DATABASE.lock().unwrap();
+        let fake_dep_db = FAKE_DEPENDENCY_DATABASE.lock().unwrap();
+        Self::new_from_data(query, pack_file, &schema, &mut dep_db, &fake_dep_db)
+    }
+
+    /// This function parses and runs the provided query over the tables of the provided PackFile and dependencies.
+    pub fn new_from_data(
+        query: &str,
+        pack_file: &PackFile,
+        schema: &Schema,
+        dep_db: &mut Vec<PackedFile>,
+        fake_dep_db: &[DB],
+    ) -> Result<Self> {
+        let query = Query::parse(query)?;
+
+        // Only load the Loc data if we actually need it, as it's slow.
+        let mut columns_used = query.columns.to_vec();
+        columns_used.extend(query.joins.iter().filter_map(|x| x.1.clone()).flat_map(|x| vec![x.0, x.1]));
+        columns_used.extend(query.conditions.iter().flatten().map(|x| x.column.to_owned()));
+        if let Some((ref column, _)) = query.order_by { columns_used.push(column.to_owned()); }
+
+        let tables = query.joins.iter().map(|x| get_table_name(&x.0)).chain(vec![get_table_name(&query.from)]).collect::<Vec<String>>();
+        let locs = if tables.iter().any(|x| x == LOC_TABLE_NAME) || columns_used.iter().any(|x| LOCALISABLE_FIELDS.contains(&get_column_name(x))) {
+            get_locs(pack_file, dep_db)
+        } else { vec![] };
+
+        // Load the main table, and join it with the rest of the tables.
+        let mut skipped_tables = vec![];
+        let mut table = QueryTable::load(&query.from, &columns_used, pack_file, schema, dep_db, fake_dep_db, &locs, &mut skipped_tables)?;
+        for (join_table, on) in &query.joins {
+            let other_table = QueryTable::load(join_table, &columns_used, pack_file, schema, dep_db, fake_dep_db, &locs, &mut skipped_tables)?;
+            table.join(other_table, &get_table_name(join_table), on)?;
+        }
+
+        // Filter the rows.
+        let mut conditions = vec![];
+        for group in &query.conditions {
+            let mut conditions_group = vec![];
+            for condition in group {
+                let column = table.get_column_index(&condition.column)?;
+                let regex = if condition.operator == Operator::Like || condition.operator == Operator::NotLike {
+                    let pattern = format!("^{}$", regex::escape(&condition.value).replace('%', ".*").replace('_', "."));
+                    Some(RegexBuilder::new(&pattern).case_insensitive(true).build().map_err(|_| ErrorKind::QueryInvalid(format!("Invalid pattern: {}", condition.value)))?)
+                } else { None };
+                conditions_group.push((column, condition, regex));
+            }
+            conditions.push(conditions_group);
+        }
+
+        if !conditions.is_empty() {
+            table.rows.retain(|row| conditions.iter().any(|group| group.iter().all(|(column, condition, regex)| check_condition(&row[*column], condition, regex))));
+        }
+
+        // Sort them and limit them, if needed.
+        if let Some((ref column, descending)) = query.order_by {
+            let column = table.get_column_index(column)?;
+            table.rows.sort_by(|a, b| if descending { compare_decoded_data(&b[column], &a[column]) } else { compare_decoded_data(&a[column], &b[column]) });
+        }
+
+        if let Some(limit) = query.limit { table.rows.truncate(limit); }
+
+        // And finally, get only the columns we want.
+        if query.columns.is_empty() {
+            Ok(Self {
+                columns: table.columns,
+                rows: table.rows,
+                skipped_tables,
+            })
+        }
+        else {
+            let columns = query.columns.iter().map(|x| table.get_column_index(x)).collect::<Result<Vec<usize>>>()?;
+            Ok(Self {
+                columns: columns.iter().map(|x| table.columns[*x].to_owned()).collect(),
+                rows: table.rows.iter().map(|row| columns.iter().map(|x| row[*x].clone()).collect()).collect(),
+                skipped_tables,
+            })
+        }
+    }
+}
+
+/// Implementation of `Query`.
+impl Query {
+
+    /// This function parses a query from a String.
+    fn parse(query: &str) -> Result<Self> {
+        let tokens = tokenize(query)?;
+        let mut index = 0;
+
+        expect_keyword(&tokens, &mut index, "SELECT")?;
+        let mut columns = vec![];
+        if tokens.get(index) == Some(&Token::Symbol("*".to_owned())) { index += 1; }
+        else {
+            loop {
+                columns.push(expect_word(&tokens, &mut index)?);
+                if tokens.get(index) == Some(&Token::Symbol(",".to_owned())) { index += 1; } else { break }
+            }
+        }
+
+        expect_keyword(&tokens, &mut index, "FROM")?;
+        let from = expect_word(&tokens, &mut index)?;
+
+        let mut joins = vec![];
+        while is_keyword(&tokens, index, "JOIN") {
+            index += 1;
+            let table = expect_word(&tokens, &mut index)?;
+            let on = if is_keyword(&tokens, index, "ON") {
+                index += 1;
+                let column_a = expect_word(&tokens, &mut index)?;
+                if tokens.get(index) != Some(&Token::Symbol("=".to_owned())) { return Err(ErrorKind::QueryInvalid("Joins only support the '=' operator.".to_owned()))? }
+                index += 1;
+                let column_b = expect_word(&tokens, &mut index)?;
+                Some((column_a, column_b))
+            } else { None };
+            joins.push((table, on));
+        }
+
+        let mut conditions = vec![];
+        if is_keyword(&tokens, index, "WHERE") {
+            index += 1;
+            let mut group = vec![];
+            loop {
+                let column = expect_word(&tokens, &mut index)?;
+                let operator = expect_operator(&tokens, &mut index)?;
+                let value = match tokens.get(index) {
+                    Some(Token::Word(value)) | Some(Token::Text(value)) => value.to_owned(),
+                    _ => return Err(ErrorKind::QueryInvalid(format!("Expected a value after the condition over '{}'.", column)))?
+                };
+                index += 1;
+                group.push(Condition { column, operator, value });
+
+                if is_keyword(&tokens, index, "AND") { index += 1; }
+                else if is_keyword(&tokens, index, "OR") {
+                    index += 1;
+                    conditions.push(group);
+                    group = vec![];
+                }
+                else { break }
+            }
+            conditions.push(group);
+        }
+
+        let mut order_by = None;
+        if is_keyword(&tokens, index, "ORDER") {
+            index += 1;
+            expect_keyword(&tokens, &mut index, "BY")?;
+            let column = expect_word(&tokens, &mut index)?;
+            let descending = if is_keyword(&tokens, index, "DESC") { index += 1; true }
+            else if is_keyword(&tokens, index, "ASC") { index += 1; false }
+            else { false };
+            order_by = Some((column, descending));
+        }
+
+        let mut limit = None;
+        if is_keyword(&tokens, index, "LIMIT") {
+            index += 1;
+            let value = expect_word(&tokens, &mut index)?;
+            limit = Some(value.parse::<usize>().map_err(|_| ErrorKind::QueryInvalid(format!("Invalid limit: {}", value)))?);
+        }
+
+        if index < tokens.len() { return Err(ErrorKind::QueryInvalid(format!("Unexpected token: {:?}", tokens[index])))? }
+
+        Ok(Self {
+            columns,
+            from,
+            joins,
+            conditions,
+            order_by,
+            limit,
+        })
+    }
+}
+
+/// Implementation of `QueryTable`.
+impl QueryTable {
+
+    /// This function loads all the data of a table, from our PackFile, the Dependency Database and, if
+    /// the table is not in the Dependency Database, the fake Dependency Database. If one of the columns
+    /// used in the query is a localised column of this table, it's loaded from the Loc data.
+    ///
+    /// Rows of our PackFile override the rows of the Dependency Database with the same key, like in the game.
+    /// Tables we cannot decode are left out, and their paths are added to `skipped_tables`.
+    #[allow(clippy::too_many_arguments)]
+    fn load(
+        name: &str,
+        columns_used: &[String],
+        pack_file: &PackFile,
+        schema: &Schema,
+        dep_db: &mut Vec<PackedFile>,
+        fake_dep_db: &[DB],
+        locs: &[Vec<DecodedData>],
+        skipped_tables: &mut Vec<String>,
+    ) -> Result<Self> {
+        let table_name = get_table_name(name);
+
+        // The Loc table is special, as it's made from all the Loc PackedFiles.
+        if table_name == LOC_TABLE_NAME {
+            return Ok(Self {
+                columns: vec!["loc.key".to_owned(), "loc.text".to_owned(), "loc.tooltip".to_owned()],
+                references: vec![None; 3],
+                rows: locs.to_vec(),
+            })
+        }
+
+        let db_type = format!("{}_tables", table_name);
+        let mut tables = vec![];
+        for packed_file in pack_file.packed_files.iter().filter(|x| x.path.len() == 3 && x.path[0] == "db" && x.path[1] == db_type) {
+            match packed_file.get_data().and_then(|data| DB::read(&data, &db_type, schema)) {
+                Ok(table) => tables.push((table, true)),
+                Err(_) => skipped_tables.push(packed_file.path.join("/")),
+            }
+        }
+
+        for packed_file in dep_db.iter_mut().filter(|x| x.path.len() == 3 && x.path[0] == "db" && x.path[1] == db_type) {
+            match packed_file.get_data_and_keep_it().and_then(|data| DB::read(&data, &db_type, schema)) {
+                Ok(table) => tables.push((table, false)),
+                Err(_) => skipped_tables.push(packed_file.path.join("/")),
+            }
+        }
+
+        if tables.is_empty() {
+            if let Some(table) = fake_dep_db.iter().find(|x| x.db_type == db_type) {
+                tables.push((table.clone(), false));
+            }
+        }
+
+        if tables.is_empty() {
+            if skipped_tables.iter().any(|x| x.starts_with(&format!("db/{}/", db_type))) { return Err(ErrorKind::QueryInvalid(format!("None of the tables of '{}' could be decoded.", name)))? }
+            return Err(ErrorKind::QueryTableNotFound(name.to_owned()))?
+        }
+
+        // Tables may be from different versions, so we merge their columns by name.
+        let mut columns = vec![];
+        let mut references = vec![];
+        let mut key_columns = vec![];
+        for (table, _) in &tables {
+            for field in &table.table_definition.fields {
+                if !columns.contains(&field.field_name) {
+                    if field.field_is_key { key_columns.push(columns.len()); }
+                    columns.push(field.field_name.to_owned());
+                    references.push(field.field_is_reference.clone());
+                }
+            }
+        }
+
+        // Our tables go first, so we know their keys before we get to the ones of the dependencies.
+        let mut rows = vec![];
+        let mut pack_file_keys = BTreeSet::new();
+        for (table, is_from_pack_file) in &tables {
+            let columns_index = columns.iter().map(|x| table.table_definition.fields.iter().position(|y| &y.field_name == x)).collect::<Vec<Option<usize>>>();
+            for row in &table.entries {
+                let row = columns_index.iter().map(|x| match x {
+                    Some(column) => row[*column].clone(),
+                    None => DecodedData::StringU8(String::new()),
+                }).collect::<Vec<DecodedData>>();
+
+                if !key_columns.is_empty() {
+                    let key = key_columns.iter().map(|x| get_decoded_data_as_string(&row[*x])).collect::<Vec<String>>();
+                    if *is_from_pack_file { pack_file_keys.insert(key); }
+                    else if pack_file_keys.contains(&key) { continue; }
+                }
+                rows.push(row);
+            }
+        }
+
+        // Add the localised columns, if we use them. Their loc key is `<table>_<column>_<key>`.
+        if !key_columns.is_empty() {
+            let loc_columns = columns_used.iter()
+                .filter(|x| get_column_table_name(x).map_or(true, |x| x == table_name))
+                .map(|x| get_column_name(x))
+                .filter(|x| LOCALISABLE_FIELDS.contains(x) && !columns.iter().any(|y| y == *x))
+                .collect::<Vec<&str>>();
+
+            if !loc_columns.is_empty() {
+                let locs = locs.iter().filter_map(|x| match (&x[0], &x[1]) {
+                    (DecodedData::StringU16(key), DecodedData::StringU16(text)) => Some((key.to_owned(), text.to_owned())),
+                    _ => None,
+                }).collect::<HashMap<String, String>>();
+
+                for loc_column in &loc_columns {
+                    if columns.iter().any(|x| x == loc_column) { continue; }
+                    for row in &mut rows {
+                        let key = key_columns.iter().map(|x| get_decoded_data_as_string(&row[*x])).collect::<String>();
+                        let text = locs.get(&format!("{}_{}_{}", table_name, loc_column, key)).cloned().unwrap_or_else(String::new);
+                        row.push(DecodedData::StringU16(text));
+                    }
+                    columns.push((*loc_column).to_owned());
+                    references.push(None);
+                }
+            }
+        }
+
+        Ok(Self {
+            columns: columns.iter().map(|x| format!("{}.{}", table_name, x)).collect(),
+            references,
+            rows,
+        })
+    }
+
+    /// This function returns the index of the provided column. The column can be just its name, or its
+    /// name prefixed by its table's name. If there are multiple columns with the same name, we return the first one.
+    fn get_column_index(&self, column: &str) -> Result<usize> {
+        let column_name = get_column_name(column);
+        let position = match get_column_table_name(column) {
+            Some(table_name) => self.columns.iter().position(|x| x == &format!("{}.{}", table_name, column_name)),
+            None => self.columns.iter().position(|x| get_column_name(x) == column_name),
+        };
+        position.ok_or_else(|| ErrorKind::QueryColumnNotFound(column.to_owned()).into())
+    }
+
+    /// This function joins another table with this one. If we don't provide the columns to use for the join,
+    /// we try to find them using the references between both tables.
+    fn join(&mut self, other: Self, other_table_name: &str, on: &Option<(String, String)>) -> Result<()> {
+        let (column, other_column) = match on {
+            Some((column_a, column_b)) => {
+                if let (Ok(column), Ok(other_column)) = (self.get_column_index(column_a), other.get_column_index(column_b)) { (column, other_column) }
+                else if let (Ok(column), Ok(other_column)) = (self.get_column_index(column_b), other.get_column_index(column_a)) { (column, other_column) }
+                else { return Err(ErrorKind::QueryInvalid(format!("Invalid columns for the join with the table '{}'.", other_table_name)))? }
+            }
+
+            None => {
+                let mut columns = None;
+                for (column, reference) in self.references.iter().enumerate() {
+                    if let Some((ref_table, ref_column)) = reference {
+                        if ref_table == other_table_name {
+                            if let Ok(other_column) = other.get_column_index(&format!("{}.{}", ref_table, ref_column)) {
+                                columns = Some((column, other_column));
+                                break;
+                            }
+                        }
+                    }
+                }
+
+                if columns.is_none() {
+                    for (other_column, reference) in other.references.iter().enumerate() {
+                        if let Some((ref_table, ref_column)) = reference {
+                            if let Ok(column) = self.get_column_index(&format!("{}.{}", ref_table, ref_column)) {
+                                columns = Some((column, other_column));
+                                break;
+                            }
+                        }
+                    }
+                }
+
+                match columns {
+                    Some(columns) => columns,
+                    None => return Err(ErrorKind::QueryInvalid(format!("There are no references to join the table '{}'. Use 'ON' to specify the columns for the join.", other_table_name)))?
+                }
+            }
+        };
+
+        let mut other_rows: HashMap<String, Vec<usize>> = HashMap::new();
+        for (index, row) in other.rows.iter().enumerate() {
+            other_rows.entry(get_decoded_data_as_string(&row[other_column])).or_insert_with(Vec::new).push(index);
+        }
+
+        let mut rows = vec![];
+        for row in &self.rows {
+            if let Some(indexes) = other_rows.get(&get_decoded_data_as_string(&row[column])) {
+                for index in indexes {
+                    let mut new_row = row.to_vec();
+                    new_row.extend_from_slice(&other.rows[*index]);
+                    rows.push(new_row);
+                }
+            }
+        }
+
+        self.columns.extend(other.columns);
+        self.references.extend(other.references);
+        self.rows = rows;
+        Ok(())
+    }
+}
+
+/// This function splits a query into tokens.
+fn tokenize(query: &str) -> Result<Vec<Token>> {
+    let mut tokens = vec![];
+    let mut chars = query.chars().peekable();
+    while let Some(character) = chars.next() {
+        match character {
+            ' ' | '\t' | '\n' | '\r' => continue,
+            ',' | '*' | '=' => tokens.push(Token::Symbol(character.to_string())),
+
+            // Strings go between single quotes. To use a single quote inside them, use two of them.
+            '\'' => {
+                let mut text = String::new();
+                loop {
+                    match chars.next() {
+                        Some('\'') => if chars.peek() == Some(&'\'') { chars.next(); text.push('\''); } else { break },
+                        Some(character) => text.push(character),
+                        None => return Err(ErrorKind::QueryInvalid("Unclosed string.".to_owned()))?,
+                    }
+                }
+                tokens.push(Token::Text(text));
+            }
+
+            '!' | '<' | '>' => {
+                let mut symbol = character.to_string();
+                if let Some(&next) = chars.peek() {
+                    if next == '=' || (character == '<' && next == '>') {
+                        symbol.push(next);
+                        chars.next();
+                    }
+                }
+
+                if symbol == "!" { return Err(ErrorKind::QueryInvalid("Unexpected character: !".to_owned()))? }
+                tokens.push(Token::Symbol(symbol));
+            }
+
+            _ if character.is_alphanumeric() || character == '_' || character == '.' || character == '-' => {
+                let mut word = character.to_string();
+                while let Some(&next) = chars.peek() {
+                    if next.is_alphanumeric() || next == '_' || next == '.' || next == '-' {
+                        word.push(next);
+                        chars.next();
+                    } else { break }
+                }
+                tokens.push(Token::Word(word));
+            }
+
+            _ => return Err(ErrorKind::QueryInvalid(format!("Unexpected character: {}", character)))?,
+        }
+    }
+
+    Ok(tokens)
+}
+
+/// This function checks if the token at the provided index is the provided keyword.
+fn is_keyword(tokens: &[Token], index: usize, keyword: &str) -> bool {
+    match tokens.get(index) {
+        Some(Token::Word(word)) => word.to_uppercase() == keyword,
+        _ => false,
+    }
+}
+
+/// This function checks if the token at the provided index is the provided keyword and, if it's, advances the index.
+fn expect_keyword(tokens: &[Token], index: &mut usize, keyword: &str) -> Result<()> {
+    if is_keyword(tokens, *index, keyword) {
+        *index += 1;
+        Ok(())
+    }
+    else { Err(ErrorKind::QueryInvalid(format!("Expected '{}'.", keyword)))? }
+}
+
+/// This function returns the word at the provided index and advances the index.
+fn expect_word(tokens: &[Token], index: &mut usize) -> Result<String> {
+    match tokens.get(*index) {
+        Some(Token::Word(word)) => {
+            *index += 1;
+            Ok(word.to_owned())
+        }
+        Some(token) => Err(ErrorKind::QueryInvalid(format!("Unexpected token: {:?}", token)))?,
+        None => Err(ErrorKind::QueryInvalid("Unexpected end of the query.".to_owned()))?,
+    }
+}
+
+/// This function returns the operator at the provided index and advances the index.
+fn expect_operator(tokens: &[Token], index: &mut usize) -> Result<Operator> {
+    let operator = match tokens.get(*index) {
+        Some(Token::Symbol(symbol)) => match &**symbol {
+            "=" => Operator::Equal,
+            "!=" | "<>" => Operator::NotEqual,
+            "<" => Operator::Lesser,
+            "<=" => Operator::LesserOrEqual,
+            ">" => Operator::Greater,
+            ">=" => Operator::GreaterOrEqual,
+            _ => return Err(ErrorKind::QueryInvalid(format!("Unknown operator: {}", symbol)))?,
+        },
+        Some(Token::Word(word)) => match &*word.to_uppercase() {
+            "LIKE" => Operator::Like,
+            "CONTAINS" => Operator::Contains,
+            "NOT" if is_keyword(tokens, *index + 1, "LIKE") => {
+                *index += 1;
+                Operator::NotLike
+            }
+            _ => return Err(ErrorKind::QueryInvalid(format!("Unknown operator: {}", word)))?,
+        },
+        _ => return Err(ErrorKind::QueryInvalid("Expected an operator.".to_owned()))?,
+    };
+
+    *index += 1;
+    Ok(operator)
+}
+
+/// This function returns the name of a table without the `_tables` suffix, in lowercase.
+fn get_table_name(name: &str) -> String {
+    name.to_lowercase().trim_end_matches("_tables").to_owned()
+}
+
+/// This function returns the table's name of a column in the `table.column` format, if it has one.
+fn get_column_table_name(column: &str) -> Option<String> {
+    column.find('.').map(|x| get_table_name(&column[..x]))
+}
+
+/// This function returns the name of a column without his table's name.
+fn get_column_name(column: &str) -> &str {
+    match column.find('.') {
+        Some(index) => &column[index + 1..],
+        None => column,
+    }
+}
+
+/// This function returns the entries of all the Loc PackedFiles in our PackFile and the Dependency Database.
+/// Entries from our PackFile go first, so they take priority over the ones in the Dependency Database.
+fn get_locs(pack_file: &PackFile, dep_db: &mut Vec<PackedFile>) -> Vec<Vec<DecodedData>> {
+    let mut locs = vec![];
+    for packed_file in pack_file.packed_files.iter().filter(|x| get_packed_file_type(&x.path) == DecodeablePackedFileType::Loc) {
+        if let Ok(data) = packed_file.get_data() {
+            if let Ok(mut loc) = Loc::read(&data) {
+                locs.append(&mut loc.entries);
+            }
+        }
+    }
+
+    for packed_file in dep_db.iter_mut().filter(|x| get_packed_file_type(&x.path) == DecodeablePackedFileType::Loc) {
+        if let Ok(data) = packed_file.get_data_and_keep_it() {
+            if let Ok(mut loc) = Loc::read(&data) {
+                locs.append(&mut loc.entries);
+            }
+        }
+    }
+
+    // Remove the duplicated keys, keeping the first one.
+    let mut keys = BTreeSet::new();
+    locs.into_iter().filter(|x| keys.insert(get_decoded_data_as_string(&x[0]))).collect()
+}
+
+/// This function compares two cells. Numbers are compared as numbers, and everything else as text.
+fn compare_decoded_data(a: &DecodedData, b: &DecodedData) -> Ordering {
+    match (a, b) {
+        (DecodedData::Float(a), DecodedData::Float(b)) => a.partial_cmp(b).unwrap_or(Ordering::Equal),
+        (DecodedData::Integer(a), DecodedData::Integer(b)) => a.cmp(b),
+        (DecodedData::LongInteger(a), DecodedData::LongInteger(b)) => a.cmp(b),
+        (DecodedData::Boolean(a), DecodedData::Boolean(b)) => a.cmp(b),
+        _ => get_decoded_data_as_string(a).cmp(&get_decoded_data_as_string(b)),
+    }
+}
+
+/// This function checks if a cell fulfills a condition.
+fn check_condition(data: &DecodedData, condition: &Condition, regex: &Option<Regex>) -> bool {
+    let value = &condition.value;
+    match condition.operator {
+        Operator::Like => regex.as_ref().map_or(false, |x| x.is_match(&get_decoded_data_as_string(data))),
+        Operator::NotLike => regex.as_ref().map_or(false, |x| !x.is_match(&get_decoded_data_as_string(data))),
+        Operator::Contains => get_decoded_data_as_string(data).to_lowercase().contains(&value.to_lowercase()),
+        _ => {
+            let ordering = match data {
+                DecodedData::Boolean(data) => match &*value.to_lowercase() {
+                    "true" | "1" => Some(data.cmp(&true)),
+                    "false" | "0" => Some(data.cmp(&false)),
+                    _ => None,
+                },
+                DecodedData::Float(data) => value.parse::<f32>().ok().and_then(|x| data.partial_cmp(&x)),
+                DecodedData::Integer(data) => value.parse::<f64>().ok().and_then(|x| f64::from(*data).partial_cmp(&x)),
+                DecodedData::LongInteger(data) => value.parse::<f64>().ok().and_then(|x| (*data as f64).partial_cmp(&x)),
+                DecodedData::StringU8(data) |
+                DecodedData::StringU16(data) |
+                DecodedData::OptionalStringU8(data) |
+                DecodedData::OptionalStringU16(data) => Some((**data).cmp(&**value)),
+            };
+
+            match ordering {
+                Some(ordering) => match condition.operator {
+                    Operator::Equal => ordering == Ordering::Equal,
+                    Operator::NotEqual => ordering != Ordering::Equal,
+                    Operator::Lesser => ordering == Ordering::Less,
+                    Operator::LesserOrEqual => ordering != Ordering::Greater,
+                    Operator::Greater => ordering == Ordering::Greater,
+                    Operator::GreaterOrEqual => ordering != Ordering::Less,
+                    _ => unreachable!(),
+                },
+
+                // If the value is not valid for the type of the column, only `!=` is true.
+                None => condition.operator == Operator::NotEqual,
+            }
+        }
+    }
+}
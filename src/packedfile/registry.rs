@@ -0,0 +1,153 @@
+//---------------------------------------------------------------------------//
+// Copyright (c) 2017-2019 Ismael Gutiérrez González. All rights reserved.
+//
+// This file is part of the Rusted PackFile Manager (RPFM) project,
+// which can be found here: https://github.com/Frodo45127/rpfm.
+//
+// This file is licensed under the MIT license, which can be found here:
+// https://github.com/Frodo45127/rpfm/blob/master/LICENSE.
+//---------------------------------------------------------------------------//
+
+// In this file is the registry of PackedFile types: the list of rules we use to know what type a PackedFile is
+// from his path. It has our own rules, and the ones the user adds in the settings, which take priority over ours.
+
+use crate::PACKED_FILE_TYPES;
+use crate::packedfile::DecodeablePackedFileType;
+use crate::packedfile::text::{TextEncoding, TEXT_ENCODINGS};
+use crate::settings::Settings;
+
+/// Rules of the PackedFile types we know, in the order they're checked. Each rule is (matcher, type, encoding hint).
+///
+/// `.benchmark` files are UTF-16 LE text without BOM, and our detection can miss it when they have few ASCII characters,
+/// so they're always decoded with that encoding if they can be.
+const DEFAULT_PACKED_FILE_TYPES: [(&str, DecodeablePackedFileType, Option<TextEncoding>); 25] = [
+    ("db", DecodeablePackedFileType::DB, None),
+    (".loc", DecodeablePackedFileType::Loc, None),
+    (".rigid_model_v2", DecodeablePackedFileType::RigidModel, None),
+    (".lua", DecodeablePackedFileType::Text, None),
+    (".xml", DecodeablePackedFileType::Text, None),
+    (".xml.shader", DecodeablePackedFileType::Text, None),
+    (".xml.material", DecodeablePackedFileType::Text, None),
+    (".variantmeshdefinition", DecodeablePackedFileType::Text, None),
+    (".environment", DecodeablePackedFileType::Text, None),
+    (".lighting", DecodeablePackedFileType::Text, None),
+    (".wsmodel", DecodeablePackedFileType::Text, None),
+    (".csv", DecodeablePackedFileType::Text, None),
+    (".tsv", DecodeablePackedFileType::Text, None),
+    (".inl", DecodeablePackedFileType::Text, None),
+    (".battle_speech_camera", DecodeablePackedFileType::Text, None),
+    (".bob", DecodeablePackedFileType::Text, None),
+    (".cindyscene", DecodeablePackedFileType::Text, None),
+    (".cindyscenemanager", DecodeablePackedFileType::Text, None),
+    (".benchmark", DecodeablePackedFileType::Text, Some(TextEncoding::Utf16Le)),
+    (".txt", DecodeablePackedFileType::Text, None),
+    (".jpg", DecodeablePackedFileType::Image, None),
+    (".jpeg", DecodeablePackedFileType::Image, None),
+    (".tga", DecodeablePackedFileType::Image, None),
+    (".dds", DecodeablePackedFileType::Image, None),
+    (".png", DecodeablePackedFileType::Image, None),
+];
+
+/// This enum represents what the path of a PackedFile must match for a rule to apply to it.
+#[derive(Clone, Debug, PartialEq)]
+pub enum PackedFileMatcher {
+
+    // The name of the PackedFile ends with this extension (lowercase, with the dot).
+    Extension(String),
+
+    // The PackedFile is this path, or it's inside it.
+    Path(Vec<String>),
+}
+
+/// This struct represents a rule of the registry of PackedFile types.
+/// - matcher: what the path of a PackedFile must match.
+/// - packed_file_type: the type of the PackedFiles matched.
+/// - encoding: the encoding we expect if the PackedFiles matched are text. It's a hint, not a rule.
+#[derive(Clone, Debug)]
+pub struct PackedFileTypeRule {
+    pub matcher: PackedFileMatcher,
+    pub packed_file_type: DecodeablePackedFileType,
+    pub encoding: Option<TextEncoding>,
+}
+
+/// Implementation of `PackedFileTypeRule`.
+impl PackedFileTypeRule {
+
+    /// This function returns if this rule applies to the provided path. DB Tables need to be inside a folder inside another
+    /// one (like `db/units_tables/`) because the name of their table is taken from there, so DB rules don't apply to any other path.
+    pub fn is_match(&self, path: &[String]) -> bool {
+        self.matcher.is_match(path) && (self.packed_file_type != DecodeablePackedFileType::DB || path.len() >= 3)
+    }
+}
+
+/// Implementation of `PackedFileMatcher`.
+impl PackedFileMatcher {
+
+    /// This function creates a matcher from his text form: extensions start with a dot, and anything else is a path.
+    pub fn new(matcher: &str) -> Option<Self> {
+        let matcher = matcher.trim();
+        if matcher.starts_with('.') && matcher.len() > 1 { Some(PackedFileMatcher::Extension(matcher.to_lowercase())) }
+        else {
+            let path = matcher.split('/').filter(|x| !x.is_empty()).map(|x| x.to_owned()).collect::<Vec<String>>();
+            if path.is_empty() { None } else { Some(PackedFileMatcher::Path(path)) }
+        }
+    }
+
+    /// This function returns if the provided path is matched by this matcher.
+    pub fn is_match(&self, path: &[String]) -> bool {
+        match self {
+            PackedFileMatcher::Extension(extension) => path.last().map_or(false, |x| x.to_lowercase().ends_with(extension)),
+            PackedFileMatcher::Path(folder) => path.starts_with(folder),
+        }
+    }
+}
+
+/// This function returns the rules of the registry, for the provided settings. The ones of the user go first.
+///
+/// User rules are taken from the `custom_packed_file_types` setting, which is a list of `matcher=type[:encoding]`
+/// separated by `;`, like `.ini=text; .fxc=text:UTF-16 LE with BOM; custom_tables=db`. Invalid rules are ignored.
+pub fn get_packed_file_type_rules(settings: &Settings) -> Vec<PackedFileTypeRule> {
+    let mut rules = settings.settings_string.get("custom_packed_file_types")
+        .map_or(vec![], |x| x.split(';').filter_map(parse_packed_file_type_rule).collect::<Vec<PackedFileTypeRule>>());
+
+    rules.extend(DEFAULT_PACKED_FILE_TYPES.iter().map(|(matcher, packed_file_type, encoding)| PackedFileTypeRule {
+        matcher: PackedFileMatcher::new(matcher).unwrap(),
+        packed_file_type: packed_file_type.clone(),
+        encoding: *encoding,
+    }));
+    rules
+}
+
+/// This function returns the first rule of the registry that matches the provided path, if any.
+pub fn get_packed_file_type_rule(path: &[String]) -> Option<PackedFileTypeRule> {
+    PACKED_FILE_TYPES.lock().unwrap().iter().find(|x| x.is_match(path)).cloned()
+}
+
+/// This function returns the encoding hint for the Text PackedFile with the provided path, if it has one.
+pub fn get_text_encoding_hint(path: &[String]) -> Option<TextEncoding> {
+    get_packed_file_type_rule(path).and_then(|x| x.encoding)
+}
+
+/// This function parses a user rule of the registry. It returns None if the rule is invalid.
+fn parse_packed_file_type_rule(rule: &str) -> Option<PackedFileTypeRule> {
+    let mut rule = rule.splitn(2, '=');
+    let matcher = PackedFileMatcher::new(rule.next()?)?;
+
+    let mut type_and_encoding = rule.next()?.splitn(2, ':');
+    let packed_file_type = match &*type_and_encoding.next()?.trim().to_lowercase() {
+        "db" => DecodeablePackedFileType::DB,
+        "loc" => DecodeablePackedFileType::Loc,
+        "text" => DecodeablePackedFileType::Text,
+        "image" => DecodeablePackedFileType::Image,
+        "rigidmodel" => DecodeablePackedFileType::RigidModel,
+        "none" => DecodeablePackedFileType::None,
+        _ => return None,
+    };
+
+    let encoding = match type_and_encoding.next() {
+        Some(encoding) => Some(*TEXT_ENCODINGS.iter().find(|x| x.to_string().eq_ignore_ascii_case(encoding.trim()))?),
+        None => None,
+    };
+
+    Some(PackedFileTypeRule { matcher, packed_file_type, encoding })
+}
@@ -17,6 +17,9 @@ use serde_derive::{Serialize, Deserialize};
 use crate::common::coding_helpers;
 use crate::error::{ErrorKind, Result};
 
+/// Signature every RigidModel we support has in his first 4 bytes.
+const RIGID_MODEL_SIGNATURE: &str = "RMV2";
+
 /// Struct "RigidModel". For more info about this, check the comment at the start of "packedfile/
 /// rigidmodel/mod.rs".
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -88,6 +91,11 @@ impl RigidModel {
 /// Implementation of "RigidModelHeader"
 impl RigidModelHeader {
 
+    /// This function returns if the provided data starts with the signature of a RigidModel.
+    pub fn is_rigid_model(packed_file_data: &[u8]) -> bool {
+        packed_file_data.starts_with(RIGID_MODEL_SIGNATURE.as_bytes())
+    }
+
     /// This function reads the data from a Vec<u8> and decode it into a RigidModelHeader. This CAN FAIL,
     /// so we return Result<RigidModelHeader, Error>.
     pub fn read(packed_file_data: &[u8]) -> Result<RigidModelHeader> {
@@ -106,7 +114,7 @@ impl RigidModelHeader {
 
         // We check this, just in case we try to read some malformed file with a string in the first
         // four bytes (which is not uncommon).
-        if packed_file_header.packed_file_header_signature != "RMV2" {
+        if packed_file_header.packed_file_header_signature != RIGID_MODEL_SIGNATURE {
             return Err(ErrorKind::RigidModelNotSupportedFile)?
         }
 
@@ -0,0 +1,212 @@
+//---------------------------------------------------------------------------//
+// Copyright (c) 2017-2019 Ismael Gutiérrez González. All rights reserved.
+//
+// This file is part of the Rusted PackFile Manager (RPFM) project,
+// which can be found here: https://github.com/Frodo45127/rpfm.
+//
+// This file is licensed under the MIT license, which can be found here:
+// https://github.com/Frodo45127/rpfm/blob/master/LICENSE.
+//---------------------------------------------------------------------------//
+
+// In this file we define the PackedFile type Text for decoding and encoding it, keeping the encoding
+// (and the BOM, if any) the file had, so we don't change it when saving the file.
+
+use encoding::{Encoding, EncoderTrap};
+use encoding::all::ISO_8859_1;
+use serde_derive::{Serialize, Deserialize};
+
+use std::collections::BTreeSet;
+use std::fmt;
+
+use crate::common::coding_helpers::*;
+use crate::error::{Error, ErrorKind, Result};
+
+/// BOM of UTF-8 files.
+const BOM_UTF_8: [u8; 3] = [0xEF, 0xBB, 0xBF];
+
+/// BOM of UTF-16 Little Endian files.
+const BOM_UTF_16_LE: [u8; 2] = [0xFF, 0xFE];
+
+/// BOM of UTF-16 Big Endian files.
+const BOM_UTF_16_BE: [u8; 2] = [0xFE, 0xFF];
+
+/// List of all the encodings a Text PackedFile can use, in the order we show them in the UI.
+pub const TEXT_ENCODINGS: [TextEncoding; 6] = [
+    TextEncoding::Utf8,
+    TextEncoding::Utf8Bom,
+    TextEncoding::Utf16Le,
+    TextEncoding::Utf16LeBom,
+    TextEncoding::Utf16BeBom,
+    TextEncoding::Iso8859_1,
+];
+
+/// This enum represents the encoding of a Text PackedFile, including if it has a BOM or not.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum TextEncoding {
+    Utf8,
+    Utf8Bom,
+    Utf16Le,
+    Utf16LeBom,
+    Utf16BeBom,
+    Iso8859_1,
+}
+
+/// This struct represents a decoded Text PackedFile.
+/// - contents: the text of the PackedFile, without BOM.
+/// - encoding: the encoding the text had, and the one it'll be saved with.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Text {
+    pub contents: String,
+    pub encoding: TextEncoding,
+}
+
+/// Implementation of `Text`.
+impl Text {
+
+    /// This function decodes a Text PackedFile, detecting his encoding.
+    ///
+    /// Files with BOM use the encoding of their BOM. Files without BOM are considered UTF-16 Little Endian if they look like
+    /// UTF-16 LE text (see `is_text_utf16_le`), UTF-8 if they're valid UTF-8, and ISO-8859-1 otherwise.
+    pub fn read(packed_file_data: &[u8]) -> Result<Self> {
+        let (contents, encoding) = if packed_file_data.starts_with(&BOM_UTF_8) {
+            (decode_string_u8(&packed_file_data[3..])?, TextEncoding::Utf8Bom)
+        }
+        else if packed_file_data.starts_with(&BOM_UTF_16_LE) {
+            (decode_text_u16(&packed_file_data[2..], false)?, TextEncoding::Utf16LeBom)
+        }
+        else if packed_file_data.starts_with(&BOM_UTF_16_BE) {
+            (decode_text_u16(&packed_file_data[2..], true)?, TextEncoding::Utf16BeBom)
+        }
+        else if is_text_utf16_le(packed_file_data) {
+            (decode_text_u16(packed_file_data, false)?, TextEncoding::Utf16Le)
+        }
+        else {
+            match decode_string_u8(packed_file_data) {
+                Ok(contents) => (contents, TextEncoding::Utf8),
+                Err(_) => (decode_string_u8_iso_8859_1(packed_file_data)?, TextEncoding::Iso8859_1),
+            }
+        };
+
+        Ok(Self { contents, encoding })
+    }
+
+    /// This function decodes a Text PackedFile, using the provided encoding if it has no BOM and his data is valid
+    /// for that encoding. Otherwise, it detects his encoding like `Text::read` does. Empty files get the provided encoding.
+    pub fn read_with_hint(packed_file_data: &[u8], encoding: Option<TextEncoding>) -> Result<Self> {
+        if let Some(encoding) = encoding {
+            if packed_file_data.is_empty() { return Ok(Self { contents: String::new(), encoding }) }
+
+            let has_bom = packed_file_data.starts_with(&BOM_UTF_8) || packed_file_data.starts_with(&BOM_UTF_16_LE) || packed_file_data.starts_with(&BOM_UTF_16_BE);
+            if !has_bom {
+                let contents = match encoding {
+                    TextEncoding::Utf8 => decode_string_u8(packed_file_data).ok(),
+                    TextEncoding::Utf16Le => decode_text_u16(packed_file_data, false).ok(),
+                    TextEncoding::Iso8859_1 => decode_string_u8_iso_8859_1(packed_file_data).ok(),
+
+                    // Data without BOM can never be in an encoding with BOM.
+                    TextEncoding::Utf8Bom | TextEncoding::Utf16LeBom | TextEncoding::Utf16BeBom => None,
+                };
+
+                if let Some(contents) = contents { return Ok(Self { contents, encoding }) }
+            }
+        }
+
+        Self::read(packed_file_data)
+    }
+
+    /// This function encodes a Text PackedFile with his encoding. It fails if the text has characters his encoding
+    /// doesn't support, which can only happen with ISO-8859-1.
+    pub fn save(&self) -> Result<Vec<u8>> {
+        match self.encoding {
+            TextEncoding::Utf8 => Ok(encode_string_u8(&self.contents)),
+            TextEncoding::Utf8Bom => {
+                let mut data = BOM_UTF_8.to_vec();
+                data.extend_from_slice(&encode_string_u8(&self.contents));
+                Ok(data)
+            }
+            TextEncoding::Utf16Le => Ok(encode_string_u16(&self.contents)),
+            TextEncoding::Utf16LeBom => {
+                let mut data = BOM_UTF_16_LE.to_vec();
+                data.extend_from_slice(&encode_string_u16(&self.contents));
+                Ok(data)
+            }
+            TextEncoding::Utf16BeBom => {
+                let mut data = BOM_UTF_16_BE.to_vec();
+                self.contents.encode_utf16().for_each(|x| data.extend_from_slice(&x.to_be_bytes()));
+                Ok(data)
+            }
+            TextEncoding::Iso8859_1 => ISO_8859_1.encode(&self.contents, EncoderTrap::Strict)
+                .map_err(|_| Error::from(ErrorKind::TextEncodingUnsupportedCharacters(self.encoding.to_string()))),
+        }
+    }
+}
+
+/// This function returns if the provided data looks like text: it has a BOM, or it's UTF-16 LE or UTF-8 text without control
+/// characters. The data can be just the start of a file, so a character cut at the end doesn't count as invalid.
+pub fn is_text(packed_file_data: &[u8]) -> bool {
+    if packed_file_data.starts_with(&BOM_UTF_8) || packed_file_data.starts_with(&BOM_UTF_16_LE) || packed_file_data.starts_with(&BOM_UTF_16_BE) { return true }
+
+    let contents = if is_text_utf16_le(packed_file_data) {
+        let data = packed_file_data.chunks(2).map(|x| u16::from_le_bytes([x[0], x[1]])).collect::<Vec<u16>>();
+        Some(String::from_utf16_lossy(&data))
+    }
+    else {
+        match std::str::from_utf8(packed_file_data) {
+            Ok(contents) => Some(contents.to_owned()),
+            Err(error) if error.error_len().is_none() => Some(String::from_utf8_lossy(&packed_file_data[..error.valid_up_to()]).to_string()),
+            Err(_) => None,
+        }
+    };
+
+    contents.map_or(false, |x| !x.is_empty() && !has_control_characters(&x))
+}
+
+/// This function returns if the provided data (without BOM) looks like UTF-16 LE text. That's if a lot of his odd bytes are zeros
+/// (like ASCII text), or if it's valid UTF-16 LE without control characters but not valid UTF-8 without them, and his odd bytes are
+/// a lot less varied than his even ones (like non-ASCII text, which usually uses only a few blocks of characters).
+fn is_text_utf16_le(data: &[u8]) -> bool {
+    if data.is_empty() || data.len() % 2 != 0 { return false }
+    if data.iter().skip(1).step_by(2).filter(|x| **x == 0).count() >= data.len() / 4 { return true }
+
+    let odd_bytes = data.iter().skip(1).step_by(2).collect::<BTreeSet<&u8>>();
+    let even_bytes = data.iter().step_by(2).collect::<BTreeSet<&u8>>();
+    if odd_bytes.len() * 2 > even_bytes.len() { return false }
+
+    let is_utf8_text = match std::str::from_utf8(data) {
+        Ok(contents) => !has_control_characters(contents),
+        Err(error) if error.error_len().is_none() => !has_control_characters(&String::from_utf8_lossy(&data[..error.valid_up_to()])),
+        Err(_) => false,
+    };
+
+    let data = data.chunks(2).map(|x| u16::from_le_bytes([x[0], x[1]])).collect::<Vec<u16>>();
+    !is_utf8_text && String::from_utf16(&data).map_or(false, |x| !has_control_characters(&x))
+}
+
+/// This function returns if the provided text has control characters, other than whitespaces like line jumps or tabs.
+fn has_control_characters(text: &str) -> bool {
+    text.chars().any(|x| x.is_control() && !x.is_whitespace())
+}
+
+/// Display implementation of `TextEncoding`.
+impl fmt::Display for TextEncoding {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TextEncoding::Utf8 => write!(f, "UTF-8"),
+            TextEncoding::Utf8Bom => write!(f, "UTF-8 with BOM"),
+            TextEncoding::Utf16Le => write!(f, "UTF-16 LE"),
+            TextEncoding::Utf16LeBom => write!(f, "UTF-16 LE with BOM"),
+            TextEncoding::Utf16BeBom => write!(f, "UTF-16 BE with BOM"),
+            TextEncoding::Iso8859_1 => write!(f, "ISO-8859-1"),
+        }
+    }
+}
+
+/// This function decodes an UTF-16 text, in Little Endian or Big Endian.
+fn decode_text_u16(data: &[u8], big_endian: bool) -> Result<String> {
+    if data.len() % 2 != 0 { return Err(ErrorKind::HelperDecodingEncodingError("<p>Error trying to decode an UTF-16 String: odd number of bytes.</p>".to_owned()))? }
+    if big_endian {
+        let data = data.chunks(2).flat_map(|x| vec![x[1], x[0]]).collect::<Vec<u8>>();
+        decode_string_u16(&data)
+    }
+    else { decode_string_u16(data) }
+}
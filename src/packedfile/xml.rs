@@ -0,0 +1,134 @@
+//---------------------------------------------------------------------------//
+// Copyright (c) 2017-2019 Ismael Gutiérrez González. All rights reserved.
+//
+// This file is part of the Rusted PackFile Manager (RPFM) project,
+// which can be found here: https://github.com/Frodo45127/rpfm.
+//
+// This file is licensed under the MIT license, which can be found here:
+// https://github.com/Frodo45127/rpfm/blob/master/LICENSE.
+//---------------------------------------------------------------------------//
+
+// In this file are all the functions needed to check the XML-based PackedFiles (`.xml`, `.variantmeshdefinition`,
+// `.wsmodel`,...): if they're well-formed, and if the assets they reference (meshes, materials, textures) exist.
+// XML PackedFiles are the Text PackedFiles of the registry whose contents start with an XML tag.
+
+use xml::common::Position;
+use xml::reader::{EventReader, XmlEvent};
+
+use std::collections::BTreeSet;
+
+use crate::DATA_PACKFILES_ASSETS;
+use crate::common::get_game_selected_data_packfiles_paths;
+use crate::error::Result;
+use crate::packfile::PackFile;
+use crate::packedfile::{DecodeablePackedFileType, get_packed_file_type};
+use crate::packedfile::registry::get_text_encoding_hint;
+use crate::packedfile::text::Text;
+
+/// Extensions of the assets XML files can reference that we check. Materials are usually `.xml.material`.
+const ASSET_EXTENSIONS: [&str; 8] = [
+    ".rigid_model_v2",
+    ".wsmodel",
+    ".variantmeshdefinition",
+    ".material",
+    ".dds",
+    ".png",
+    ".tga",
+    ".jpg",
+];
+
+/// This function checks every XML PackedFile in the provided PackFile. It returns the parse errors, one per file, and the
+/// assets referenced by them that are neither in the PackFile nor in the PackFiles of the `/data` folder of the Game Selected.
+///
+/// If the game path is not configured, we cannot know what assets the game has, so only the parse errors are returned.
+pub fn check_xml_files(pack_file: &mut PackFile) -> Result<Vec<String>> {
+
+    // Assets can be in any PackFile of the game, so we use all of them. Their paths are only read once per game.
+    let mut data_assets = DATA_PACKFILES_ASSETS.lock().unwrap();
+    check_xml_files_with_assets(pack_file, &mut data_assets, get_data_packfiles_assets)
+}
+
+/// This function does the same as `check_xml_files`, but with the assets of the game passed as argument. If we don't have them
+/// yet, they're only got (with the provided function) when any XML PackedFile references an asset.
+pub fn check_xml_files_with_assets<F: FnOnce() -> Option<BTreeSet<String>>>(
+    pack_file: &mut PackFile,
+    data_assets: &mut Option<BTreeSet<String>>,
+    get_data_assets: F,
+) -> Result<Vec<String>> {
+    let mut errors = vec![];
+    let mut references = vec![];
+
+    for packed_file in pack_file.packed_files.iter_mut().filter(|x| get_packed_file_type(&x.path) == DecodeablePackedFileType::Text) {
+        let text = match Text::read_with_hint(&packed_file.get_data_and_keep_it()?, get_text_encoding_hint(&packed_file.path)) {
+            Ok(text) => text,
+            Err(_) => continue,
+        };
+        if !is_xml(&text.contents) { continue }
+        let path = packed_file.path.join("/");
+
+        let mut reader = EventReader::new(text.contents.as_bytes());
+        loop {
+            let event = reader.next();
+            let position = reader.position();
+            match event {
+                Ok(XmlEvent::StartElement { attributes, .. }) => {
+                    for attribute in &attributes {
+                        if let Some(asset) = get_asset_path(&attribute.value) { references.push((path.to_owned(), position.row + 1, asset)); }
+                    }
+                }
+                Ok(XmlEvent::Characters(text)) => {
+                    if let Some(asset) = get_asset_path(&text) { references.push((path.to_owned(), position.row + 1, asset)); }
+                }
+                Ok(XmlEvent::EndDocument) => break,
+                Ok(_) => {},
+                Err(error) => {
+                    let position = error.position();
+                    errors.push(format!("{}:{}:{}: {}", path, position.row + 1, position.column + 1, error.msg()));
+                    break;
+                }
+            }
+        }
+    }
+
+    if !references.is_empty() {
+        if data_assets.is_none() { *data_assets = get_data_assets(); }
+
+        if let Some(ref data_assets) = *data_assets {
+            let assets = pack_file.packed_files.iter().map(|x| x.path.join("/").to_lowercase()).collect::<BTreeSet<String>>();
+            for (path, line, asset) in &references {
+                if !assets.contains(asset) && !data_assets.contains(asset) {
+                    errors.push(format!("{}:{}: '{}' is neither in this PackFile nor in the game's data PackFiles.", path, line, asset));
+                }
+            }
+        }
+    }
+
+    Ok(errors)
+}
+
+/// This function returns if the provided text looks like an XML document: his first non-whitespace character opens a tag.
+pub fn is_xml(text: &str) -> bool {
+    text.trim_start_matches('\u{feff}').trim_start().starts_with('<')
+}
+
+/// This function returns the paths (lowercase) of the PackedFiles in the PackFiles of the `/data` folder of the Game Selected.
+/// Only their indexes are read. If the game path is not configured, it returns None.
+fn get_data_packfiles_assets() -> Option<BTreeSet<String>> {
+    let paths = get_game_selected_data_packfiles_paths()?;
+    let mut assets = BTreeSet::new();
+    for path in &paths {
+        if let Ok(data_pack_file) = PackFile::read(path.to_path_buf(), true) {
+            assets.extend(data_pack_file.packed_files.iter().map(|x| x.path.join("/").to_lowercase()));
+        }
+    }
+    Some(assets)
+}
+
+/// This function returns the provided text as a normalized asset path (lowercase, with `/` as separator), if it looks like one.
+fn get_asset_path(text: &str) -> Option<String> {
+    let text = text.trim();
+    if text.contains(char::is_whitespace) || !(text.contains('/') || text.contains('\\')) { return None }
+
+    let asset = text.replace('\\', "/").trim_start_matches('/').to_lowercase();
+    if ASSET_EXTENSIONS.iter().any(|x| asset.ends_with(x)) { Some(asset) } else { None }
+}
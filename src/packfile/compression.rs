@@ -27,32 +27,51 @@ use crate::common::coding_helpers::{decode_integer_u32, encode_integer_i32};
 /// This function decompress the data of a PackedFile, returning the decompressed data.
 pub fn decompress_data(data: &[u8]) -> Result<Vec<u8>> {
     if !data.is_empty() {
-        if data.len() >= 9 {
-            
-            // CA Tweaks their headers to remove 4 bytes per PackedFile, while losing +4GB File Compression Support.
-            // We need to fix their headers so the normal LZMA lib can read them.
-            let mut fixed_data: Vec<u8> = vec![];
-            fixed_data.extend_from_slice(&data[4..8]);
-            fixed_data.push(0);
-            fixed_data.extend_from_slice(&data[0..4]);
-            fixed_data.extend_from_slice(&[0; 4]);
-            fixed_data.extend_from_slice(&data[9..]);
+        let fixed_data = get_lzma_alone_data(data)?;
+        let stream = Stream::new_lzma_decoder(u64::MAX).map_err(|_| Error::from(ErrorKind::PackedFileDataCouldNotBeDecompressed))?;
+        let mut encoder = XzDecoder::new_stream(&*fixed_data, stream);
+        let mut compress_data = vec![];
+        match encoder.read_to_end(&mut compress_data) {
+            Ok(_) => Ok(compress_data),
+            Err(_) => Err(ErrorKind::PackedFileDataCouldNotBeDecompressed)?
+        }
+    }
+    else { Ok(vec![]) }
+}
 
-            // Vanilla compressed files are LZMA Alone (or legacy) level 3 compressed files, reproducibles by compressing them
-            // with default settings with 7-Zip. This should do the trick to get them decoded.
-            let stream = Stream::new_lzma_decoder(u64::MAX).map_err(|_| Error::from(ErrorKind::PackedFileDataCouldNotBeDecompressed))?;
-            let mut encoder = XzDecoder::new_stream(&*fixed_data, stream);
-            let mut compress_data = vec![];
-            match encoder.read_to_end(&mut compress_data) {
-                Ok(_) => Ok(compress_data),
-                Err(_) => Err(ErrorKind::PackedFileDataCouldNotBeDecompressed)?
-            }
+/// This function decompress only the first bytes of the data of a PackedFile, up to `size`. The rest of the data is not decompressed.
+pub fn decompress_data_head(data: &[u8], size: usize) -> Result<Vec<u8>> {
+    if !data.is_empty() {
+        let fixed_data = get_lzma_alone_data(data)?;
+        let stream = Stream::new_lzma_decoder(u64::MAX).map_err(|_| Error::from(ErrorKind::PackedFileDataCouldNotBeDecompressed))?;
+        let mut encoder = XzDecoder::new_stream(&*fixed_data, stream).take(size as u64);
+        let mut compress_data = vec![];
+        match encoder.read_to_end(&mut compress_data) {
+            Ok(_) => Ok(compress_data),
+            Err(_) => Err(ErrorKind::PackedFileDataCouldNotBeDecompressed)?
         }
-        else { Err(ErrorKind::PackedFileDataCouldNotBeDecompressed)? }
     }
     else { Ok(vec![]) }
 }
 
+/// This function turns the compressed data of a PackedFile into a normal LZMA Alone file.
+///
+/// CA Tweaks their headers to remove 4 bytes per PackedFile, while losing +4GB File Compression Support.
+/// We need to fix their headers so the normal LZMA lib can read them. Vanilla compressed files are LZMA Alone (or legacy)
+/// level 3 compressed files, reproducibles by compressing them with default settings with 7-Zip.
+fn get_lzma_alone_data(data: &[u8]) -> Result<Vec<u8>> {
+    if data.len() >= 9 {
+        let mut fixed_data: Vec<u8> = vec![];
+        fixed_data.extend_from_slice(&data[4..8]);
+        fixed_data.push(0);
+        fixed_data.extend_from_slice(&data[0..4]);
+        fixed_data.extend_from_slice(&[0; 4]);
+        fixed_data.extend_from_slice(&data[9..]);
+        Ok(fixed_data)
+    }
+    else { Err(ErrorKind::PackedFileDataCouldNotBeDecompressed)? }
+}
+
 /// This function compress the data of a PackedFile, returning the compressed data.
 ///
 /// Now, some explanation: CA uses Non-Streamed LZMA1 (or LZMA Alone) compressed files.
@@ -22,21 +22,30 @@ use regex::Regex;
 use serde_derive::Deserialize;
 use serde_xml_rs::from_reader;
 use bincode;
+use uuid::Uuid;
 
+use std::collections::{BTreeMap, BTreeSet};
 use std::fs::{File, DirBuilder};
 use std::io::{Read, Write};
 use std::path::PathBuf;
+use std::{fmt, fmt::Display};
 
 use crate::common::*;
 use crate::error::{Result, Error, ErrorKind};
+use crate::packfile::{PackFile, PathType};
+use crate::packfile::packedfile::PackedFile;
 use crate::packedfile::db::DB;
-use crate::packedfile::DecodedData;
+use crate::packedfile::{DecodedData, get_decoded_data_as_string};
 use crate::schema::*;
 use crate::DEPENDENCY_DATABASE;
 use crate::RPFM_PATH;
 use crate::GAME_SELECTED;
+use crate::SCHEMA;
 use crate::SUPPORTED_GAMES;
 
+/// Maximum amount of rows a table from the Assembly Kit can have to be used as an enum.
+const MAX_ENUM_VALUES: usize = 64;
+
 //---------------------------------------------------------------------------//
 // Types for parsing the Assembly Kit Schema Files into.
 //---------------------------------------------------------------------------//
@@ -58,9 +67,9 @@ pub struct field {
     pub field_type: String,
     pub required: String,
     pub max_length: Option<String>,
+    pub default_value: Option<String>,
 
-    // There can be multiple source_columns, but we just take the first one. The second one is usually the lookup one,
-    // and we don't support lookup columns yet.
+    // There can be multiple source_columns. The first one is the referenced one, and the second one is usually the lookup one.
     pub column_source_column: Option<Vec<String>>,
     pub column_source_table: Option<String>,
     pub field_description: Option<String>,
@@ -95,6 +104,50 @@ pub struct datafield {
     pub field_data: String,
 }
 
+//---------------------------------------------------------------------------//
+// Types for reporting the result of importing the Assembly Kit Schema Files.
+//---------------------------------------------------------------------------//
+
+/// This struct holds the report of an schema import from the Assembly Kit. It contains:
+/// - new_tables: the tables that were not in the schema.
+/// - new_versions: the versions of already existing tables that were not in the schema.
+/// - differences: table, version, field and description of each field that differs between the schema and the Assembly Kit.
+/// - failed_files: the files that failed to import, with their error.
+#[derive(Clone, Debug, Default)]
+pub struct ImportSchemaReport {
+    pub new_tables: Vec<String>,
+    pub new_versions: Vec<(String, i32)>,
+    pub differences: Vec<(String, i32, String, String)>,
+    pub failed_files: Vec<(String, String)>,
+}
+
+/// Implementation of `Display` for `ImportSchemaReport`, to show it in the UI.
+impl Display for ImportSchemaReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.new_tables.is_empty() && self.new_versions.is_empty() && self.differences.is_empty() && self.failed_files.is_empty() {
+            return write!(f, "<p>The schema is already up to date with the Assembly Kit.</p>");
+        }
+
+        if !self.new_tables.is_empty() {
+            write!(f, "<h3>New Tables</h3><ul>{}</ul>", self.new_tables.iter().map(|x| format!("<li>{}</li>", x)).collect::<String>())?;
+        }
+
+        if !self.new_versions.is_empty() {
+            write!(f, "<h3>New Versions</h3><ul>{}</ul>", self.new_versions.iter().map(|x| format!("<li>{}: version {}</li>", x.0, x.1)).collect::<String>())?;
+        }
+
+        if !self.differences.is_empty() {
+            write!(f, "<h3>Differences with the Assembly Kit</h3><ul>{}</ul>", self.differences.iter().map(|x| format!("<li>{} (v{}), <i>{}</i>: {}</li>", x.0, x.1, x.2, x.3)).collect::<String>())?;
+        }
+
+        if !self.failed_files.is_empty() {
+            write!(f, "<h3>Files that Failed to Import</h3><ul>{}</ul>", self.failed_files.iter().map(|x| format!("<li>{}: {}</li>", x.0, x.1)).collect::<String>())?;
+        }
+
+        Ok(())
+    }
+}
+
 //---------------------------------------------------------------------------//
 // Functions to process the Raw DB Tables from the Assembly Kit.
 //---------------------------------------------------------------------------//
@@ -162,76 +215,17 @@ pub fn process_raw_tables(
                 let imported_definition: root = from_reader(definition_file)?;
                 let imported_table_definition = TableDefinition::new_fake_from_assembly_kit(&imported_definition, -1, &table_name);
 
-                // Before deserializing the data, due to limitations of serde_xml_rs, we have to rename all rows, beacuse unique names for
-                // rows in each file is not supported for deserializing. Same for the fields, we have to change them to something more generic.
-                let mut buffer = String::new();
-                data_file.read_to_string(&mut buffer)?;
-                buffer = buffer.replace(&format!("<{} record_uuid", file_name_no_xml), "<rows record_uuid"); 
-                buffer = buffer.replace(&format!("<{}>", file_name_no_xml), "<rows>"); 
-                buffer = buffer.replace(&format!("</{}>", file_name_no_xml), "</rows>");
-                for field in &imported_table_definition.fields {
-                    let field_name_regex = Regex::new(&format!("\n<{}>", field.field_name)).unwrap();
-                    let field_name_regex2 = Regex::new(&format!("\n<{} .+?\">", field.field_name)).unwrap();
-                    buffer = field_name_regex.replace_all(&buffer, &*format!("\n<datafield field_name=\"{}\">", field.field_name)).to_string();
-                    buffer = field_name_regex2.replace_all(&buffer, &*format!("\n<datafield field_name=\"{}\">", field.field_name)).to_string();
-                    buffer = buffer.replace(&format!("</{}>", field.field_name), "</datafield>");
-                }
-
-                // Serde shits itself if it sees an empty field, so we have to work around that.
-                let field_data_regex1 = Regex::new("\"></datafield>").unwrap();
-                let field_data_regex2 = Regex::new("\"> </datafield>").unwrap();
-                let field_data_regex3 = Regex::new("\">  </datafield>").unwrap();
-                buffer = field_data_regex1.replace_all(&buffer, "\">Frodo Best Waifu</datafield>").to_string();
-                buffer = field_data_regex2.replace_all(&buffer, "\"> Frodo Best Waifu</datafield>").to_string();
-                buffer = field_data_regex3.replace_all(&buffer, "\">  Frodo Best Waifu</datafield>").to_string();
-                
-                // Only if the table has data we deserialize it.
-                if buffer.contains("</rows>\r\n</dataroot>") {
-                    //if cfg!(debug_assertions) { println!("{}", buffer); }
-                    let imported_data: dataroot = from_reader(buffer.as_bytes())?;
-
-                    // Now we get that mess we've created and make readable data from it.
-                    let mut entries = vec![];
-                    for row in &imported_data.rows {
-                        let mut entry = vec![];
-
-                        // Some games (Thrones, Attila, Rome 2 and Shogun 2) may have missing fields when said field is empty.
-                        // To compensate it, if we don't find a field from the definition in the table, we add it empty.
-                        for field_def in &imported_table_definition.fields {
-                            let mut exists = false;
-                            for field in &row.datafield {
-                                if field_def.field_name == field.field_name {
-                                    exists = true;
-                                    entry.push(match field_def.field_type {
-                                        FieldType::Boolean => DecodedData::Boolean(if field.field_data == "true" || field.field_data == "1" { true } else { false }),
-                                        FieldType::Float => DecodedData::Float(if let Ok(data) = field.field_data.parse::<f32>() { data } else { 0.0 }),
-                                        FieldType::Integer => DecodedData::Integer(if let Ok(data) = field.field_data.parse::<i32>() { data } else { 0 }),
-                                        FieldType::LongInteger => DecodedData::LongInteger(if let Ok(data) = field.field_data.parse::<i64>() { data } else { 0 }),
-                                        FieldType::StringU8 => DecodedData::StringU8(if field.field_data == "Frodo Best Waifu" { String::new() } else { field.field_data.to_string() }),
-                                        FieldType::StringU16 => DecodedData::StringU16(if field.field_data == "Frodo Best Waifu" { String::new() } else { field.field_data.to_string() }),
-                                        FieldType::OptionalStringU8 => DecodedData::OptionalStringU8(if field.field_data == "Frodo Best Waifu" { String::new() } else { field.field_data.to_string() }),
-                                        FieldType::OptionalStringU16 => DecodedData::OptionalStringU16(if field.field_data == "Frodo Best Waifu" { String::new() } else { field.field_data.to_string() }),
-                                    });
-                                    break;
-                                }
-                            }
-
-                            // If the field doesn't exist, we create it empty.
-                            if !exists {
-                                entry.push(DecodedData::OptionalStringU8(String::new()));
-                            }
-                        }
-                        entries.push(entry);
+                // Then read his data, and only if the table has data, create the DB object and add it to the list.
+                match read_raw_table_data(&mut data_file, file_name_no_xml, &imported_table_definition)? {
+                    Some(entries) => {
+                        let mut processed_db_file = DB::new(&table_name, -1, imported_table_definition);
+                        processed_db_file.entries = entries;
+                        processed_db_files.push(processed_db_file);
                     }
 
-                    // Then create the DB object, and add it to the list.
-                    let mut processed_db_file = DB::new(&table_name, -1, imported_table_definition);
-                    processed_db_file.entries = entries;
-                    processed_db_files.push(processed_db_file);
+                    // Otherwise skip it.
+                    None => continue,
                 }
-
-                // Otherwise skip it.
-                else { continue; }
             },
 
             // Version 0 is Napoleon and Empire. These two don't have an assembly kit, but CA released years ago their table files.
@@ -259,77 +253,527 @@ pub fn process_raw_tables(
     Ok(())
 }
 
-/// This function is the response to our prayers. It takes the Assembly Kit's DB Files to create basic definitions of each 
-/// undecoded table from the folder you provide it.
+/// This function imports raw tables from the Assembly Kit into the provided PackFile as proper DB Tables. Their fields are
+/// mapped into the definition of the table for the current version of the game. Each raw table needs his `TWaD_` definition
+/// file in the same folder. It returns the paths of the PackedFiles that got overwritten, and the paths of all the imported ones.
+pub fn import_raw_tables(
+    pack_file: &mut PackFile,
+    raw_table_paths: &[PathBuf],
+    version: i16,
+) -> Result<(Vec<Vec<String>>, Vec<Vec<String>>)> {
+
+    let mut packed_files = vec![];
+    let mut packed_files_replaced = vec![];
+    let mut error_files = vec![];
+
+    if let Some(ref schema) = *SCHEMA.lock().unwrap() {
+        for path in raw_table_paths {
+            let name = match path.file_stem() {
+                Some(name) => name.to_string_lossy().to_string(),
+                None => {
+                    error_files.push(format!("{}: invalid file name.", path.to_string_lossy()));
+                    continue;
+                }
+            };
+
+            match import_raw_table(path, version, schema) {
+                Ok(table) => {
+                    let path = vec!["db".to_owned(), table.db_type.to_owned(), name];
+                    if pack_file.packedfile_exists(&path) { packed_files_replaced.push(path.to_vec()); }
+                    packed_files.push(PackedFile::read_from_vec(path, get_current_time(), false, table.save()));
+                }
+                Err(error) => error_files.push(format!("{}: {}", path.to_string_lossy(), error)),
+            }
+        }
+    }
+    else { return Err(ErrorKind::SchemaNotFound)? }
+
+    // If any of the files returned error, return error.
+    if !error_files.is_empty() {
+        let error_files_string = error_files.iter().map(|x| format!("<li>{}</li>", x)).collect::<String>();
+        return Err(ErrorKind::MassImport(error_files_string))?
+    }
+
+    let tree_paths = packed_files.iter().map(|x| x.path.to_vec()).collect::<Vec<Vec<String>>>();
+    let added_paths = pack_file.add_packed_files(&packed_files);
+    if added_paths.len() < packed_files.len() { Err(ErrorKind::ReservedFiles)? }
+    Ok((packed_files_replaced, tree_paths))
+}
+
+/// This function imports a raw table from the Assembly Kit as a DB Table, using the definition of the table for the
+/// current version of the game. If the table is not in the game files, the newest definition in the schema is used.
+pub fn import_raw_table(
+    raw_table_path: &PathBuf,
+    version: i16,
+    schema: &Schema,
+) -> Result<DB> {
+    match version {
+        2 | 1 => {
+
+            // Get the names of the table and his definition file.
+            let file_name = raw_table_path.file_name().ok_or_else(|| Error::from(ErrorKind::IOFileNotFound))?.to_string_lossy().to_string();
+            let file_name_no_xml = raw_table_path.file_stem().ok_or_else(|| Error::from(ErrorKind::IOFileNotFound))?.to_string_lossy().to_string();
+            let table_name = format!("{}_tables", file_name_no_xml);
+            let mut definition_path = raw_table_path.to_path_buf();
+            definition_path.set_file_name(format!("TWaD_{}", file_name));
+
+            // Read the raw table with the definition from the Assembly Kit.
+            let imported_definition: root = from_reader(File::open(&definition_path)?)?;
+            let imported_table_definition = TableDefinition::new_fake_from_assembly_kit(&imported_definition, -1, &table_name);
+            let entries = read_raw_table_data(&mut File::open(&raw_table_path)?, &file_name_no_xml, &imported_table_definition)?.unwrap_or_else(|| vec![]);
+
+            // Get the version of the table used by the game and his definition.
+            let vanilla_table_data = DEPENDENCY_DATABASE.lock().unwrap().iter().filter(|x| x.path.len() == 3).find(|x| x.path[1] == table_name).map(|x| x.get_data());
+            let table_version = match vanilla_table_data {
+                Some(data) => DB::get_header_data(&data?)?.0,
+                None => DB::get_schema_versions_list(&table_name, schema).ok_or_else(|| Error::from(ErrorKind::SchemaTableDefinitionNotFound))?.iter().map(|x| x.version).max().unwrap(),
+            };
+            let table_definition = DB::get_schema(&table_name, table_version, schema).ok_or_else(|| Error::from(ErrorKind::SchemaTableDefinitionNotFound))?;
+
+            // Then, map his fields into the definition of the game.
+            let mut table = DB::new(&table_name, -1, imported_table_definition);
+            table.entries = entries;
+            table.set_table_definition(&table_definition);
+            Ok(table)
+        }
+
+        // Any other version is unsupported or a game without Assembly Kit.
+        _ => Err(ErrorKind::AssemblyKitUnsupportedVersion)?
+    }
+}
+
+/// This function reads the data file of a raw table from the Assembly Kit, using the provided definition to know what fields to expect.
+/// If the table has no data, it returns None.
+fn read_raw_table_data(
+    data_file: &mut File,
+    file_name_no_xml: &str,
+    imported_table_definition: &TableDefinition,
+) -> Result<Option<Vec<Vec<DecodedData>>>> {
+
+    // Before deserializing the data, due to limitations of serde_xml_rs, we have to rename all rows, beacuse unique names for
+    // rows in each file is not supported for deserializing. Same for the fields, we have to change them to something more generic.
+    let mut buffer = String::new();
+    data_file.read_to_string(&mut buffer)?;
+    buffer = buffer.replace(&format!("<{} record_uuid", file_name_no_xml), "<rows record_uuid"); 
+    buffer = buffer.replace(&format!("<{}>", file_name_no_xml), "<rows>"); 
+    buffer = buffer.replace(&format!("</{}>", file_name_no_xml), "</rows>");
+    for field in &imported_table_definition.fields {
+        let field_name_regex = Regex::new(&format!("\n<{}>", field.field_name)).unwrap();
+        let field_name_regex2 = Regex::new(&format!("\n<{} .+?\">", field.field_name)).unwrap();
+        buffer = field_name_regex.replace_all(&buffer, &*format!("\n<datafield field_name=\"{}\">", field.field_name)).to_string();
+        buffer = field_name_regex2.replace_all(&buffer, &*format!("\n<datafield field_name=\"{}\">", field.field_name)).to_string();
+        buffer = buffer.replace(&format!("</{}>", field.field_name), "</datafield>");
+    }
+
+    // Serde shits itself if it sees an empty field, so we have to work around that.
+    let field_data_regex1 = Regex::new("\"></datafield>").unwrap();
+    let field_data_regex2 = Regex::new("\"> </datafield>").unwrap();
+    let field_data_regex3 = Regex::new("\">  </datafield>").unwrap();
+    buffer = field_data_regex1.replace_all(&buffer, "\">Frodo Best Waifu</datafield>").to_string();
+    buffer = field_data_regex2.replace_all(&buffer, "\"> Frodo Best Waifu</datafield>").to_string();
+    buffer = field_data_regex3.replace_all(&buffer, "\">  Frodo Best Waifu</datafield>").to_string();
+    
+    // Only if the table has data we deserialize it.
+    if buffer.contains("</rows>\r\n</dataroot>") {
+        let imported_data: dataroot = from_reader(buffer.as_bytes())?;
+
+        // Now we get that mess we've created and make readable data from it.
+        let mut entries = vec![];
+        for row in &imported_data.rows {
+            let mut entry = vec![];
+
+            // Some games (Thrones, Attila, Rome 2 and Shogun 2) may have missing fields when said field is empty.
+            // To compensate it, if we don't find a field from the definition in the table, we add it empty.
+            for field_def in &imported_table_definition.fields {
+                let mut exists = false;
+                for field in &row.datafield {
+                    if field_def.field_name == field.field_name {
+                        exists = true;
+                        entry.push(match field_def.field_type {
+                            FieldType::Boolean => DecodedData::Boolean(if field.field_data == "true" || field.field_data == "1" { true } else { false }),
+                            FieldType::Float => DecodedData::Float(if let Ok(data) = field.field_data.parse::<f32>() { data } else { 0.0 }),
+                            FieldType::Integer => DecodedData::Integer(if let Ok(data) = field.field_data.parse::<i32>() { data } else { 0 }),
+                            FieldType::LongInteger => DecodedData::LongInteger(if let Ok(data) = field.field_data.parse::<i64>() { data } else { 0 }),
+                            FieldType::StringU8 => DecodedData::StringU8(if field.field_data == "Frodo Best Waifu" { String::new() } else { field.field_data.to_string() }),
+                            FieldType::StringU16 => DecodedData::StringU16(if field.field_data == "Frodo Best Waifu" { String::new() } else { field.field_data.to_string() }),
+                            FieldType::OptionalStringU8 => DecodedData::OptionalStringU8(if field.field_data == "Frodo Best Waifu" { String::new() } else { field.field_data.to_string() }),
+                            FieldType::OptionalStringU16 => DecodedData::OptionalStringU16(if field.field_data == "Frodo Best Waifu" { String::new() } else { field.field_data.to_string() }),
+                        });
+                        break;
+                    }
+                }
+
+                // If the field doesn't exist, we create it empty.
+                if !exists {
+                    entry.push(DecodedData::OptionalStringU8(String::new()));
+                }
+            }
+            entries.push(entry);
+        }
+
+        Ok(Some(entries))
+    }
+
+    // Otherwise, there is no data to return.
+    else { Ok(None) }
+}
+
+//---------------------------------------------------------------------------//
+// Functions to export DB Tables to Raw DB Tables for the Assembly Kit.
+//---------------------------------------------------------------------------//
+
+/// This function exports the selected DB Tables of the provided PackFile as raw tables for the Assembly Kit, with their `TWaD_` definitions.
+/// The Assembly Kit only supports one file per table, so tables of the same type are merged, using the newest definition between them.
+/// If more than one of the merged tables has a row with the same key, only the first one is exported.
+pub fn export_raw_tables(
+    item_types: &[PathType],
+    pack_file: &mut PackFile,
+    export_path: &PathBuf,
+) -> Result<String> {
+    export_raw_tables_with_schema(item_types, pack_file, export_path, SCHEMA.lock().unwrap().as_ref())
+}
+
+/// This function does the same as `export_raw_tables`, but with the schema passed as argument, so it can be used without the `SCHEMA` LazyStatic.
+pub fn export_raw_tables_with_schema(
+    item_types: &[PathType],
+    pack_file: &mut PackFile,
+    export_path: &PathBuf,
+    schema: Option<&Schema>,
+) -> Result<String> {
+
+    let mut tables: BTreeMap<String, DB> = BTreeMap::new();
+    let mut error_list = vec![];
+    let mut duplicated_list = vec![];
+
+    if let Some(schema) = schema {
+        for packed_file in &mut pack_file.packed_files {
+            let is_selected = item_types.iter().any(|x| match x {
+                PathType::File(path) => path == &packed_file.path,
+                PathType::Folder(path) => packed_file.path.starts_with(path),
+                PathType::PackFile => true,
+                PathType::None => false,
+            });
+
+            if is_selected && packed_file.path.len() == 3 && packed_file.path[0] == "db" {
+                match DB::read(&(packed_file.get_data_and_keep_it()?), &packed_file.path[1], schema) {
+                    Ok(mut table) => {
+                        match tables.get_mut(&table.db_type) {
+                            Some(merged_table) => {
+                                if merged_table.version < table.version { merged_table.set_table_definition(&table.table_definition); }
+                                else if merged_table.version > table.version { table.set_table_definition(&merged_table.table_definition); }
+                                merged_table.entries.append(&mut table.entries);
+                            }
+                            None => { tables.insert(table.db_type.to_owned(), table); },
+                        }
+                    }
+                    Err(_) => error_list.push(packed_file.path.join("\\")),
+                }
+            }
+        }
+    }
+    else { return Err(ErrorKind::SchemaNotFound)? }
+
+    if tables.is_empty() && error_list.is_empty() { return Ok("<p>There are no DB Tables to export in the selection.</p>".to_owned()) }
+
+    for table in tables.values_mut() {
+
+        // The Assembly Kit doesn't allow duplicated keys, so we only keep the first row with each key.
+        let key_columns = table.table_definition.fields.iter().enumerate().filter(|(_, x)| x.field_is_key).map(|(x, _)| x).collect::<Vec<usize>>();
+        if !key_columns.is_empty() {
+            let mut keys = BTreeSet::new();
+            let rows_count = table.entries.len();
+            table.entries.retain(|row| keys.insert(key_columns.iter().map(|x| get_decoded_data_as_string(&row[*x])).collect::<Vec<String>>()));
+            if table.entries.len() < rows_count { duplicated_list.push(format!("{} ({} rows)", table.db_type, rows_count - table.entries.len())); }
+        }
+
+        if export_raw_table(table, export_path).is_err() { error_list.push(table.db_type.to_owned()); }
+    }
+
+    let duplicated_string = if duplicated_list.is_empty() { String::new() } else {
+        format!("<p>Rows with duplicated keys have been removed from the following tables:</p><ul>{}</ul>", duplicated_list.iter().map(|x| format!("<li>{}</li>", x)).collect::<String>())
+    };
+
+    // If there has been errors, return ok with the list of errors.
+    if !error_list.is_empty() {
+        let error_files_string = error_list.iter().map(|x| format!("<li>{}</li>", x)).collect::<String>();
+        Ok(format!("<p>All exportable tables have been exported, except the following ones:</p><ul>{}</ul>{}", error_files_string, duplicated_string))
+    }
+
+    // Otherwise, just return success.
+    else { Ok(format!("<p>All exportable tables have been exported.</p>{}", duplicated_string)) }
+}
+
+/// This function exports a DB Table as a raw table for the Assembly Kit to the provided folder. It writes both, the data file
+/// and the `TWaD_` file with the definition of the table, generated from his `TableDefinition`.
+pub fn export_raw_table(
+    table: &DB,
+    export_path: &PathBuf,
+) -> Result<()> {
+
+    // The Assembly Kit doesn't use the "_tables" suffix in his table names.
+    let table_name = if table.db_type.ends_with("_tables") { &table.db_type[..table.db_type.len() - 7] } else { &table.db_type };
+    
+    // First, the definition of the table.
+    let mut definition = format!("<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\r\n<root>\r\n<edit_uuid>{}</edit_uuid>\r\n", Uuid::new_v4());
+    for field in &table.table_definition.fields {
+        let (field_type, required) = match field.field_type {
+            FieldType::Boolean => ("yesno", "1"),
+            FieldType::Float => ("single", "1"),
+            FieldType::Integer => ("integer", "1"),
+            FieldType::LongInteger => ("autonumber", "1"),
+            FieldType::StringU8 | FieldType::StringU16 => ("text", "1"),
+            FieldType::OptionalStringU8 | FieldType::OptionalStringU16 => ("text", "0"),
+        };
+
+        definition.push_str("<field>\r\n");
+        definition.push_str(&format!("<primary_key>{}</primary_key>\r\n", if field.field_is_key { "1" } else { "0" }));
+        definition.push_str(&format!("<name>{}</name>\r\n", escape_xml(&field.field_name)));
+        definition.push_str(&format!("<field_type>{}</field_type>\r\n", field_type));
+        definition.push_str(&format!("<required>{}</required>\r\n", required));
+        if let Some(ref default_value) = field.field_default_value {
+            definition.push_str(&format!("<default_value>{}</default_value>\r\n", escape_xml(default_value)));
+        }
+        if let Some(max_length) = field.field_max_length {
+            definition.push_str(&format!("<max_length>{}</max_length>\r\n", max_length));
+        }
+        if let Some((ref ref_table, ref ref_column)) = field.field_is_reference {
+            definition.push_str(&format!("<column_source_column>{}</column_source_column>\r\n", escape_xml(ref_column)));
+            if let Some(ref lookup) = field.field_lookup {
+                definition.push_str(&format!("<column_source_column>{}</column_source_column>\r\n", escape_xml(lookup)));
+            }
+            definition.push_str(&format!("<column_source_table>{}</column_source_table>\r\n", escape_xml(ref_table)));
+        }
+        if !field.field_description.is_empty() {
+            definition.push_str(&format!("<field_description>{}</field_description>\r\n", escape_xml(&field.field_description)));
+        }
+        definition.push_str("</field>\r\n");
+    }
+    definition.push_str("</root>\r\n");
+
+    // Then, the data of the table.
+    let mut data = format!("<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\r\n<dataroot export_time=\"{}\">\r\n<edit_uuid>{}</edit_uuid>\r\n", get_current_time(), Uuid::new_v4());
+    for row in &table.entries {
+        data.push_str(&format!("<{} record_uuid=\"{}\">\r\n", escape_xml(table_name), Uuid::new_v4()));
+        for (field, cell) in table.table_definition.fields.iter().zip(row.iter()) {
+            let cell_data = match cell {
+                DecodedData::Boolean(data) => if *data { "1".to_owned() } else { "0".to_owned() },
+                _ => escape_xml(&get_decoded_data_as_string(cell)),
+            };
+            data.push_str(&format!("<{0}>{1}</{0}>\r\n", escape_xml(&field.field_name), cell_data));
+        }
+        data.push_str(&format!("</{}>\r\n", escape_xml(table_name)));
+    }
+    data.push_str("</dataroot>\r\n");
+
+    // And write them both to disk.
+    DirBuilder::new().recursive(true).create(&export_path)?;
+    let mut definition_path = export_path.to_path_buf();
+    let mut data_path = export_path.to_path_buf();
+    definition_path.push(format!("TWaD_{}.xml", table_name));
+    data_path.push(format!("{}.xml", table_name));
+
+    File::create(definition_path)?.write_all(definition.as_bytes())?;
+    File::create(data_path)?.write_all(data.as_bytes())?;
+    Ok(())
+}
+
+/// This function escapes the characters that cannot be used as they are inside a XML file.
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;").replace('\'', "&apos;")
+}
+
+/// This function is the response to our prayers. It takes the Assembly Kit's definitions of the tables and merges them into
+/// the provided schema. Only tables that exist in the game files are imported, using the version of the table the game uses.
+/// Existing definitions are not changed. Instead, their differences with the Assembly Kit are returned in the report.
 /// 
 /// It requires:
-/// - schema: The schema where all the definitions will be put. None to put all the definitions into a new schema.
-/// - assembly_kit_schemas_path: this is the path with the TWaD_*****.xml syntax. They are usually in GameFolder/assembly_kit/raw_data/db/.
-/// - db_binary_path: this is a path containing all the tables extracted from the game we want the schemas. It should have xxx_table/table inside.
+/// - schema: The schema where all the definitions will be put.
+/// - raw_db_path: this is the path with the TWaD_*****.xml files. They are usually in GameFolder/assembly_kit/raw_data/db/.
+/// - version: the version of the raw tables of the game, from `raw_db_version`.
 pub fn import_schema(
-    schema: Option<Schema>,
-    assembly_kit_schemas_path: &PathBuf,
-    db_binary_path: &PathBuf,
-) -> Result<()> {
+    schema: &mut Schema,
+    raw_db_path: &PathBuf,
+    version: i16,
+) -> Result<ImportSchemaReport> {
+
+    // Version 0 uses xsd files instead of TWaD files, and any other version doesn't have an Assembly Kit.
+    if version != 1 && version != 2 { return Err(ErrorKind::AssemblyKitUnsupportedVersion)? }
+    let assembly_kit_schemas = get_raw_definitions(raw_db_path, version)?;
+    let mut report = ImportSchemaReport::default();
+
+    // Get the versions of the tables the game uses, so we know what versions to create.
+    let mut vanilla_versions = BTreeMap::new();
+    for packed_file in DEPENDENCY_DATABASE.lock().unwrap().iter().filter(|x| x.path.len() == 3 && x.path[0] == "db") {
+        if let Ok(data) = packed_file.get_data() {
+            if let Ok((table_version, _, _)) = DB::get_header_data(&data) {
+                vanilla_versions.insert(packed_file.path[1].to_owned(), table_version);
+            }
+        }
+    }
 
-    // Get the schema, then get all the raw schema files.
-    let mut schema = if let Some(schema) = schema { schema } else { Schema::new() };
-    let assembly_kit_schemas = get_raw_definitions(assembly_kit_schemas_path, 2).unwrap();
-    for path in &assembly_kit_schemas {
+    // Without them we would skip every table, and report the schema as up to date.
+    if vanilla_versions.is_empty() { return Err(ErrorKind::AssemblyKitNoVanillaTables)? }
 
-        // Always print his path. If it breaks, we want to know where.
-        println!("{:?}", path);
+    // Multiple tables can use the same enum, so we only read each one once.
+    let mut enum_values_cache = BTreeMap::new();
 
-        // We read the file and deserialize it as a `root`.
-        let file = File::open(&path).expect("Couldn't open file");
-        let imported_table_definition: root = from_reader(file).unwrap();
+    for path in &assembly_kit_schemas {
 
         // Get his name and version. We only add it if his table actually exists.
-        let mut file_name = path.file_stem().unwrap().to_str().unwrap().to_string();
-        let table_name = format!("{}_tables", file_name.split_off(5));
-        let mut db_binary_path = db_binary_path.clone();
-        db_binary_path.push(table_name.to_owned());
-        match get_files_from_subdir(&db_binary_path) {
-            Ok(db_file_path) => {
-
-                // If we found something...
-                if !db_file_path.is_empty() {
-                    match File::open(&db_file_path[0]) {
-                        Ok(ref mut file) => {
-
-                            // Read the table...
-                            let mut pack_file_buffered = vec![];
-                            file.read_to_end(&mut pack_file_buffered).expect("Error reading file.");
-
-                            // Get his version and, if there is not a table with that version in the current schema, add it. Otherwise, ignore it.
-                            let version = DB::get_header_data(&pack_file_buffered).unwrap().0;
-                            if let Some(ref mut table_definitions) = schema.tables_definitions.iter_mut().find(|x| x.name == table_name) {
-                                if table_definitions.versions.iter().find(|x| x.version == version).is_some() {
-                                    continue;
-                                }
-                                else {
-                                    let table_definition = TableDefinition::new_from_assembly_kit(&imported_table_definition, version, &table_name);
-                                    table_definitions.add_table_definition(table_definition);
-                                }
-                            }
+        let file_name = path.file_stem().map(|x| x.to_string_lossy().to_string()).unwrap_or_else(String::new);
+        let table_name = format!("{}_tables", file_name.get(5..).unwrap_or(""));
+        let table_version = match vanilla_versions.get(&table_name) {
+            Some(table_version) => *table_version,
+            None => continue,
+        };
+
+        // We read the file and deserialize it as a `root`. If it fails, we just report it and continue with the next one.
+        let imported_table_definition: root = match File::open(&path).map_err(Error::from).and_then(|x| from_reader(x).map_err(Error::from)) {
+            Ok(imported_table_definition) => imported_table_definition,
+            Err(error) => {
+                report.failed_files.push((path.to_string_lossy().to_string(), error.to_string()));
+                continue;
+            }
+        };
+
+        // Fields referencing tables that only exist in the Assembly Kit are enums, so we get their allowed values from those tables.
+        let mut table_definition = TableDefinition::new_from_assembly_kit(&imported_table_definition, table_version, &table_name);
+        for field in &mut table_definition.fields {
+            if let Some((ref ref_table, ref ref_column)) = field.field_is_reference {
+                if !vanilla_versions.contains_key(&format!("{}_tables", ref_table)) {
+                    let cache_key = (ref_table.to_owned(), ref_column.to_owned(), field.field_lookup.clone());
+                    field.field_enum_values = enum_values_cache.entry(cache_key).or_insert_with(|| get_raw_table_enum_values(raw_db_path, ref_table, ref_column, &field.field_lookup)).clone();
+                }
+            }
+        }
 
-                            else {
-                                let mut table_definitions = TableDefinitions::new(&table_name);
-                                let table_definition = TableDefinition::new_from_assembly_kit(&imported_table_definition, version, &table_name);
-                                table_definitions.add_table_definition(table_definition);
-                                schema.add_table_definitions(table_definitions);
+        match schema.tables_definitions.iter_mut().find(|x| x.name == table_name) {
+            Some(table_definitions) => {
+                match table_definitions.versions.iter_mut().find(|x| x.version == table_version) {
+                    Some(current_table_definition) => {
+                        report.differences.append(&mut get_table_definition_differences(&table_name, current_table_definition, &table_definition));
+
+                        // Fill the metadata our definition is missing with the one from the Assembly Kit.
+                        for field in &mut current_table_definition.fields {
+                            if let Some(imported_field) = table_definition.fields.iter().find(|x| x.field_name == field.field_name) {
+                                if field.field_default_value.is_none() { field.field_default_value = imported_field.field_default_value.clone(); }
+                                if field.field_max_length.is_none() { field.field_max_length = imported_field.field_max_length; }
+                                if field.field_lookup.is_none() && field.field_is_reference == imported_field.field_is_reference { field.field_lookup = imported_field.field_lookup.clone(); }
+                                if field.field_enum_values.is_empty() { field.field_enum_values = imported_field.field_enum_values.clone(); }
                             }
-
                         }
-                        Err(_) => continue,
+                        if current_table_definition.localised_fields.is_empty() { current_table_definition.localised_fields = table_definition.localised_fields.to_vec(); }
+                    },
+                    None => {
+                        table_definitions.add_table_definition(table_definition);
+                        report.new_versions.push((table_name, table_version));
                     }
                 }
             }
-            Err(_) => continue,
+
+            None => {
+                let mut table_definitions = TableDefinitions::new(&table_name);
+                table_definitions.add_table_definition(table_definition);
+                schema.add_table_definitions(table_definitions);
+                report.new_tables.push(table_name);
+            }
         }
     }
 
-    Schema::save(&schema, "schema_wh.json")?;
+    Ok(report)
+}
 
-    Ok(())
+/// This function returns the values of the provided column of a raw table from the Assembly Kit, with the values of the provided
+/// lookup column as labels. Tables that fail to be read, or that have too many rows to be an enum, return no values.
+fn get_raw_table_enum_values(
+    raw_db_path: &PathBuf,
+    table_name: &str,
+    column: &str,
+    lookup: &Option<String>,
+) -> BTreeMap<String, String> {
+    let mut definition_path = raw_db_path.to_path_buf();
+    let mut data_path = raw_db_path.to_path_buf();
+    definition_path.push(format!("TWaD_{}.xml", table_name));
+    data_path.push(format!("{}.xml", table_name));
+
+    let imported_definition: root = match File::open(&definition_path).map_err(Error::from).and_then(|x| from_reader(x).map_err(Error::from)) {
+        Ok(imported_definition) => imported_definition,
+        Err(_) => return BTreeMap::new(),
+    };
+
+    let table_definition = TableDefinition::new_fake_from_assembly_kit(&imported_definition, -1, &format!("{}_tables", table_name));
+    let column_index = match table_definition.fields.iter().position(|x| x.field_name == column) {
+        Some(column_index) => column_index,
+        None => return BTreeMap::new(),
+    };
+    let lookup_index = lookup.as_ref().and_then(|lookup| table_definition.fields.iter().position(|x| &x.field_name == lookup));
+
+    let entries = match File::open(&data_path).map_err(Error::from).and_then(|mut x| read_raw_table_data(&mut x, table_name, &table_definition)) {
+        Ok(Some(entries)) => entries,
+        _ => return BTreeMap::new(),
+    };
+
+    if entries.len() > MAX_ENUM_VALUES { return BTreeMap::new() }
+    entries.iter().map(|row| (
+        get_decoded_data_as_string(&row[column_index]),
+        lookup_index.map(|x| get_decoded_data_as_string(&row[x])).unwrap_or_else(String::new)
+    )).collect()
+}
+
+/// This function returns the descriptions of the fields of every table in the Assembly Kit, as table name => (field name => description).
+/// Tables that fail to be read are ignored.
+pub fn get_field_descriptions(
+    raw_db_path: &PathBuf,
+    version: i16,
+) -> Result<BTreeMap<String, BTreeMap<String, String>>> {
+
+    // Version 0 uses xsd files instead of TWaD files, and any other version doesn't have an Assembly Kit.
+    if version != 1 && version != 2 { return Err(ErrorKind::AssemblyKitUnsupportedVersion)? }
+    let mut descriptions = BTreeMap::new();
+    for path in &get_raw_definitions(raw_db_path, version)? {
+        let file_name = path.file_stem().map(|x| x.to_string_lossy().to_string()).unwrap_or_else(String::new);
+        let table_name = format!("{}_tables", file_name.get(5..).unwrap_or(""));
+        if let Ok(imported_table_definition) = File::open(&path).map_err(Error::from).and_then(|x| from_reader::<_, root>(x).map_err(Error::from)) {
+            let field_descriptions = imported_table_definition.field.iter()
+                .filter_map(|x| x.field_description.as_ref().filter(|y| !y.is_empty()).map(|y| (x.name.to_owned(), y.to_owned())))
+                .collect::<BTreeMap<String, String>>();
+            if !field_descriptions.is_empty() { descriptions.insert(table_name, field_descriptions); }
+        }
+    }
+
+    Ok(descriptions)
+}
+
+/// This function returns the differences between the fields of a definition of our schema and the one from the Assembly Kit.
+/// The Assembly Kit doesn't differentiate between StringU8 and StringU16, so that's not reported as a difference.
+fn get_table_definition_differences(
+    table_name: &str,
+    current_table_definition: &TableDefinition,
+    imported_table_definition: &TableDefinition,
+) -> Vec<(String, i32, String, String)> {
+    let mut differences = vec![];
+    for imported_field in &imported_table_definition.fields {
+        if let Some(field) = current_table_definition.fields.iter().find(|x| x.field_name == imported_field.field_name) {
+            let field_type = match field.field_type {
+                FieldType::StringU16 => FieldType::StringU8,
+                FieldType::OptionalStringU16 => FieldType::OptionalStringU8,
+                _ => field.field_type,
+            };
+
+            if field_type != imported_field.field_type {
+                differences.push((table_name.to_owned(), current_table_definition.version, field.field_name.to_owned(), format!("Type: {} in the schema, {} in the Assembly Kit.", field.field_type, imported_field.field_type)));
+            }
+
+            if field.field_is_key != imported_field.field_is_key {
+                differences.push((table_name.to_owned(), current_table_definition.version, field.field_name.to_owned(), format!("Key: {} in the schema, {} in the Assembly Kit.", field.field_is_key, imported_field.field_is_key)));
+            }
+
+            if field.field_is_reference != imported_field.field_is_reference {
+                let reference = field.field_is_reference.as_ref().map(|x| format!("{}/{}", x.0, x.1)).unwrap_or_else(|| "None".to_owned());
+                let imported_reference = imported_field.field_is_reference.as_ref().map(|x| format!("{}/{}", x.0, x.1)).unwrap_or_else(|| "None".to_owned());
+                differences.push((table_name.to_owned(), current_table_definition.version, field.field_name.to_owned(), format!("Reference: {} in the schema, {} in the Assembly Kit.", reference, imported_reference)));
+            }
+        }
+    }
+    differences
 }
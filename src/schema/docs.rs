@@ -0,0 +1,154 @@
+//---------------------------------------------------------------------------//
+// Copyright (c) 2017-2019 Ismael Gutiérrez González. All rights reserved.
+//
+// This file is part of the Rusted PackFile Manager (RPFM) project,
+// which can be found here: https://github.com/Frodo45127/rpfm.
+//
+// This file is licensed under the MIT license, which can be found here:
+// https://github.com/Frodo45127/rpfm/blob/master/LICENSE.
+//---------------------------------------------------------------------------//
+
+// In this file are all the functions needed to generate the documentation of a schema, as MarkDown files ready
+// to be used in an mdBook, like the one in "docs_src".
+
+use std::collections::BTreeMap;
+use std::fs::{DirBuilder, File};
+use std::io::{Read, Write};
+use std::path::Path;
+
+use crate::error::{Error, ErrorKind, Result};
+use crate::schema::{Field, Schema};
+
+/// This function generates the documentation of the provided schema into the provided folder. It generates:
+/// - One page per table (`<table_name>.md`), with all his versions, their fields, and the tables referencing it.
+/// - An index page (`index.md`), with links to every table.
+///
+/// The provided folder has to be inside the source folder of an mdBook, as the entries of all the pages are added to the `SUMMARY.md`
+/// of the book, replacing the ones from previous runs. Field descriptions missing in the schema are taken from the provided descriptions (table name => (field name => description)),
+/// usually the ones from the Assembly Kit.
+pub fn generate_schema_docs(
+    schema: &Schema,
+    title: &str,
+    field_descriptions: &BTreeMap<String, BTreeMap<String, String>>,
+    output_path: &Path,
+) -> Result<()> {
+
+    // Check we have a book to add the docs to before generating anything.
+    let summary_path = output_path.parent().map(|x| x.join("SUMMARY.md")).filter(|x| x.is_file()).ok_or_else(|| Error::from(ErrorKind::SchemaDocsSummaryNotFound))?;
+
+    DirBuilder::new().recursive(true).create(output_path)?;
+    let folder_name = output_path.file_name().map(|x| x.to_string_lossy().to_string()).unwrap_or_else(String::new);
+
+    // Get the tables referencing each table first, so we can link them both ways.
+    let mut referenced_by: BTreeMap<String, Vec<(String, String)>> = BTreeMap::new();
+    for table_definitions in &schema.tables_definitions {
+        for table_definition in &table_definitions.versions {
+            for field in &table_definition.fields {
+                if let Some((ref ref_table, _)) = field.field_is_reference {
+                    let references = referenced_by.entry(format!("{}_tables", ref_table)).or_insert_with(Vec::new);
+                    let reference = (table_definitions.name.to_owned(), field.field_name.to_owned());
+                    if !references.contains(&reference) { references.push(reference); }
+                }
+            }
+        }
+    }
+
+    let mut index = format!("# {}\n\nThis is the list of all the tables in the schema, with their versions and fields. Click in any of them to see his definitions.\n\n", title);
+    let mut summary = format!("- [{}](./{}/index.md)\n", title, folder_name);
+    for table_definitions in &schema.tables_definitions {
+        let descriptions = field_descriptions.get(&table_definitions.name);
+        let mut page = format!("# {}\n\n", table_definitions.name);
+
+        for table_definition in &table_definitions.versions {
+            page.push_str(&format!("## Version {}\n\n", table_definition.version));
+            page.push_str("| Field | Type | Key | Reference | Description | Notes |\n");
+            page.push_str("| ----- | ---- | --- | --------- | ----------- | ----- |\n");
+            for field in &table_definition.fields {
+                let reference = match field.field_is_reference {
+                    Some((ref ref_table, ref ref_column)) => {
+                        let ref_table_name = format!("{}_tables", ref_table);
+                        if schema.get_table_definitions(&ref_table_name).is_some() { format!("[{}](./{}.md) / {}", ref_table, ref_table_name, escape_markdown_cell(ref_column)) }
+                        else { format!("{} / {}", ref_table, escape_markdown_cell(ref_column)) }
+                    }
+                    None => String::new(),
+                };
+
+                let description = if field.field_description.is_empty() {
+                    descriptions.and_then(|x| x.get(&field.field_name)).map(|x| x.to_owned()).unwrap_or_else(String::new)
+                } else { field.field_description.to_owned() };
+
+                page.push_str(&format!("| {} | {} | {} | {} | {} | {} |\n",
+                    escape_markdown_cell(&field.field_name),
+                    field.field_type,
+                    if field.field_is_key { "Yes" } else { "" },
+                    reference,
+                    escape_markdown_cell(&description),
+                    escape_markdown_cell(&get_field_notes(field)),
+                ));
+            }
+            page.push_str("\n");
+        }
+
+        if let Some(references) = referenced_by.get(&table_definitions.name) {
+            page.push_str("## Referenced By\n\n");
+            for (table_name, field_name) in references {
+                page.push_str(&format!("- [{}](./{}.md): *{}*.\n", table_name, table_name, field_name));
+            }
+            page.push_str("\n");
+        }
+
+        File::create(output_path.join(format!("{}.md", table_definitions.name)))?.write_all(page.as_bytes())?;
+        index.push_str(&format!("- [{}](./{}.md)\n", table_definitions.name, table_definitions.name));
+        summary.push_str(&format!("\t- [{}](./{}/{}.md)\n", table_definitions.name, folder_name, table_definitions.name));
+    }
+
+    File::create(output_path.join("index.md"))?.write_all(index.as_bytes())?;
+
+    let mut book_summary = String::new();
+    File::open(&summary_path)?.read_to_string(&mut book_summary)?;
+    File::create(&summary_path)?.write_all(update_book_summary(&book_summary, &summary, &folder_name).as_bytes())?;
+    Ok(())
+}
+
+/// This function returns the provided `SUMMARY.md` of an mdBook with the provided entries of the docs of an schema in it. The entries
+/// of the docs in the provided folder already in the summary are replaced. If there are none, the new ones are added at the end of the first part of the summary.
+pub fn update_book_summary(book_summary: &str, entries: &str, folder_name: &str) -> String {
+    let mut lines = book_summary.lines().map(|x| x.to_owned()).collect::<Vec<String>>();
+    let index_link = format!("](./{}/index.md)", folder_name);
+    let entries = entries.lines().map(|x| x.to_owned());
+
+    match lines.iter().position(|x| x.trim_start().starts_with("- [") && x.trim_end().ends_with(&index_link)) {
+
+        // The old entries are the index one and everything nested under it.
+        Some(start) => {
+            let indentation = lines[start].len() - lines[start].trim_start().len();
+            let end = lines.iter().skip(start + 1).position(|x| x.trim().is_empty() || x.len() - x.trim_start().len() <= indentation).map_or(lines.len(), |x| x + start + 1);
+            let prefix = lines[start][..indentation].to_owned();
+            lines.splice(start..end, entries.map(|x| format!("{}{}", prefix, x)));
+        }
+        None => {
+            let mut position = lines.iter().position(|x| x.trim_start().starts_with("---")).unwrap_or_else(|| lines.len());
+            while position > 0 && lines[position - 1].trim().is_empty() { position -= 1; }
+            lines.splice(position..position, entries);
+        }
+    }
+
+    lines.join("\n") + "\n"
+}
+
+/// This function returns the metadata of a field (default value, max length, allowed values and lookup column) as text.
+fn get_field_notes(field: &Field) -> String {
+    let mut notes = vec![];
+    if let Some(ref default_value) = field.field_default_value { notes.push(format!("Default: {}.", default_value)); }
+    if let Some(max_length) = field.field_max_length { notes.push(format!("Max length: {}.", max_length)); }
+    if !field.field_enum_values.is_empty() {
+        notes.push(format!("{}: {}.", if field.field_is_bitflag { "Flags" } else { "Values" }, field.field_enum_values.iter().map(|(value, label)| format!("{} ({})", value, label)).collect::<Vec<String>>().join(", ")));
+    }
+    if let Some(ref lookup) = field.field_lookup { notes.push(format!("Lookup: {}.", lookup)); }
+    notes.join(" ")
+}
+
+/// This function escapes the text of a cell of a MarkDown table, so it doesn't break the table.
+fn escape_markdown_cell(text: &str) -> String {
+    text.replace('|', "\\|").replace("\r\n", " ").replace('\n', " ")
+}
@@ -12,7 +12,8 @@
 
 use serde_derive::{Serialize, Deserialize};
 
-use std::path::PathBuf;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
 use std::fs::File;
 use std::io::{BufReader, Read, Write};
 use std::{fmt, fmt::Display};
@@ -23,22 +24,51 @@ use crate::updater::Versions;
 use crate::error::Result;
 
 pub mod assembly_kit;
+pub mod docs;
 
 /// Name of the schemas versions file.
-const SCHEMA_VERSIONS_FILE: &'static str = "versions.json";
+pub const SCHEMA_VERSIONS_FILE: &'static str = "versions.json";
+
+/// Name of the file with the checksums of the schemas, used to check them before updating.
+pub const SCHEMA_CHECKSUMS_FILE: &'static str = "checksums.json";
 
 /// URL used to download new schemas.
 pub const SCHEMA_UPDATE_URL_MASTER: &'static str = "https://raw.githubusercontent.com/Frodo45127/rpfm/master/schemas/";
 
+/// Names of the columns CA uses for localised data in the DB Tables. These are not in the binary tables,
+/// only in the Loc PackedFiles, as the `<column>` part of the `<table>_<column>_<key>` loc keys.
+pub const LOCALISABLE_FIELDS: [&str; 16] = [
+    "localised_text",
+    "localised_name",
+    "localised_tooltip",
+    "description",
+    "objectives_team_1",
+    "objectives_team_2",
+    "short_description_text",
+    "historical_description_text",
+    "strengths_weaknesses_text",
+    "onscreen",
+    "onscreen_text",
+    "onscreen_name",
+    "onscreen_description",
+    "on_screen_name",
+    "on_screen_description",
+    "on_screen_target",
+];
+
 /// This struct holds the entire schema for the currently selected game (by "game" I mean the PackFile
 /// Type).
 /// It has:
 /// - game: the game for what the loaded definitions are intended.
 /// - version: custom variable to keep track of the updates to the schema.
 /// - tables_definition: the actual definitions.
+/// - removed_versions: the versions (table name, version) of the shipped schema removed by the user. Only used in local overlays.
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Schema {
     pub tables_definitions: Vec<TableDefinitions>,
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub removed_versions: Vec<(String, i32)>,
 }
 
 /// This struct holds the definitions for a table. It has:
@@ -53,6 +83,7 @@ pub struct TableDefinitions {
 /// This struct holds the definitions for a version of a table. It has:
 /// - version: the version of the table these definitions are for.
 /// - fields: the different fields this table has.
+/// - localised_fields: the fields of this table that are in the Loc PackedFiles instead of in the table, if we know them.
 ///
 /// NOTE: the versions are:
 /// - 0: for unversioned tables.
@@ -63,6 +94,9 @@ pub struct TableDefinitions {
 pub struct TableDefinition {
     pub version: i32,
     pub fields: Vec<Field>,
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub localised_fields: Vec<String>,
 }
 
 /// This struct holds the type of a field of a table. It has:
@@ -70,6 +104,11 @@ pub struct TableDefinition {
 /// - field_is_key: true if the field is a key field and his column needs to be put in the beginning of the TreeView.
 /// - field_is_reference: if this field is a reference of another, this has (table name, field name).
 /// - field_type: the type of the field.
+/// - field_default_value: the value new rows get in this field, if it's not the default one of his type.
+/// - field_max_length: the maximum amount of characters the data of this field can have, if it has a limit.
+/// - field_enum_values: the only values this field accepts (as text), with their labels.
+/// - field_is_bitflag: if true, the values of `field_enum_values` are flags, and this field accepts any combination of them.
+/// - field_lookup: if this field is a reference, the column of the referenced table to show alongside the referenced data.
 #[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
 pub struct Field {
     pub field_name: String,
@@ -77,6 +116,21 @@ pub struct Field {
     pub field_is_key: bool,
     pub field_is_reference: Option<(String, String)>,
     pub field_description: String,
+
+    #[serde(default)]
+    pub field_default_value: Option<String>,
+
+    #[serde(default)]
+    pub field_max_length: Option<u32>,
+
+    #[serde(default)]
+    pub field_enum_values: BTreeMap<String, String>,
+
+    #[serde(default)]
+    pub field_is_bitflag: bool,
+
+    #[serde(default)]
+    pub field_lookup: Option<String>,
 }
 
 /// Enum FieldType: This enum is used to define the possible types of a field in the schema.
@@ -92,6 +146,45 @@ pub enum FieldType {
     OptionalStringU16
 }
 
+/// This struct holds the differences between two schemas. It has:
+/// - tables_added: tables only in the new schema.
+/// - tables_removed: tables only in the old schema.
+/// - versions_added: versions (table name, version) only in the new schema, of tables in both schemas.
+/// - versions_removed: versions (table name, version) only in the old schema, of tables in both schemas.
+/// - versions_changed: versions in both schemas with different definitions.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct SchemaDiff {
+    pub tables_added: Vec<String>,
+    pub tables_removed: Vec<String>,
+    pub versions_added: Vec<(String, i32)>,
+    pub versions_removed: Vec<(String, i32)>,
+    pub versions_changed: Vec<TableDefinitionDiff>,
+}
+
+/// This struct holds the differences between two definitions of the same version of a table. It has:
+/// - table_name: the name of the table.
+/// - version: the version of the table.
+/// - fields_added: fields only in the new definition.
+/// - fields_removed: fields only in the old definition.
+/// - fields_changed: fields in both definitions with differences.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct TableDefinitionDiff {
+    pub table_name: String,
+    pub version: i32,
+    pub fields_added: Vec<Field>,
+    pub fields_removed: Vec<Field>,
+    pub fields_changed: Vec<FieldDiff>,
+}
+
+/// This struct holds the differences between two versions of a field. It has:
+/// - field_name: the name of the field.
+/// - changes: the changed properties, as (property, old value, new value).
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct FieldDiff {
+    pub field_name: String,
+    pub changes: Vec<(String, String, String)>,
+}
+
 /// Implementation of "Schema"
 impl Schema {
 
@@ -100,6 +193,7 @@ impl Schema {
     pub fn new() -> Self {
         Self {
             tables_definitions: vec![],
+            removed_versions: vec![],
         }
     }
 
@@ -117,18 +211,46 @@ impl Schema {
         self.tables_definitions.iter().position(|x| x.name == table_name)
     }
 
-    /// This function takes an schema file and reads it into a "Schema" object.
+    /// This function takes an schema file and reads it into a "Schema" object, with the local overlay
+    /// of that schema (if exists) merged on top of it.
     pub fn load(schema_file: &str) -> Result<Self> {
+        let mut schema = Self::load_shipped(schema_file)?;
+        if let Some(local_schema) = Self::load_local(schema_file)? {
+            schema.merge(&local_schema);
+        }
+        Ok(schema)
+    }
+
+    /// This function takes an schema file and reads it into a "Schema" object, ignoring the local overlay.
+    pub fn load_shipped(schema_file: &str) -> Result<Self> {
 
         let mut path = RPFM_PATH.to_path_buf();
         path.push("schemas");
         path.push(schema_file);
 
-        let file = BufReader::new(File::open(&path)?);
+        Self::load_from_path(&path)
+    }
+
+    /// This function reads the schema file in the provided path, wherever it is, into a "Schema" object.
+    pub fn load_from_path(path: &Path) -> Result<Self> {
+        let file = BufReader::new(File::open(path)?);
         serde_json::from_reader(file).map_err(|x| From::from(x))
     }
 
-    /// This function takes an "Schema" object and saves it into a schema file.
+    /// This function reads the local overlay of an schema file, if it exists.
+    pub fn load_local(schema_file: &str) -> Result<Option<Self>> {
+
+        let mut path = RPFM_PATH.to_path_buf();
+        path.push("schemas");
+        path.push(Self::get_local_schema_file_name(schema_file));
+
+        if !path.is_file() { return Ok(None) }
+        let file = BufReader::new(File::open(&path)?);
+        Ok(Some(serde_json::from_reader(file)?))
+    }
+
+    /// This function takes an "Schema" object and saves it into a schema file. It's written to a temporal file first,
+    /// and then moved over the schema file, so an error while writing it doesn't leave a broken schema behind.
     pub fn save(&self, schema_file: &str) -> Result<()> {
 
         let json = serde_json::to_string_pretty(&self)?;
@@ -136,11 +258,164 @@ impl Schema {
         path.push("schemas");
         path.push(schema_file);
 
-        let mut file = File::create(&path)?;
-        file.write_all(json.as_bytes())?;
+        let temp_path = path.with_file_name(format!("{}.tmp", schema_file));
+        File::create(&temp_path)?.write_all(json.as_bytes())?;
+        std::fs::rename(&temp_path, &path)?;
         Ok(())
     }
 
+    /// This function saves the definitions of an "Schema" object that are not in the shipped schema
+    /// into the local overlay of that schema, so they survive schema updates. If there are no differences,
+    /// the overlay gets removed.
+    pub fn save_local(&self, schema_file: &str) -> Result<()> {
+
+        // Without the shipped schema we cannot know what to keep in the overlay, so we don't touch it.
+        let shipped_schema = Self::load_shipped(schema_file)?;
+        let mut path = RPFM_PATH.to_path_buf();
+        path.push("schemas");
+        path.push(Self::get_local_schema_file_name(schema_file));
+        self.save_local_to_path(&shipped_schema, &path)
+    }
+
+    /// This function saves the local overlay of an "Schema" object against the provided shipped schema
+    /// to the provided path, wherever it is. If there are no differences, the file in that path gets removed.
+    pub fn save_local_to_path(&self, shipped_schema: &Self, path: &Path) -> Result<()> {
+        let local_schema = self.get_local_overlay(shipped_schema);
+        if local_schema.tables_definitions.is_empty() && local_schema.removed_versions.is_empty() {
+            if path.is_file() { std::fs::remove_file(path)?; }
+            Ok(())
+        }
+        else {
+            let json = serde_json::to_string_pretty(&local_schema)?;
+            let mut file = File::create(path)?;
+            file.write_all(json.as_bytes())?;
+            Ok(())
+        }
+    }
+
+    /// This function returns the local overlay of an "Schema" object against the provided shipped schema. That is,
+    /// the versions that are not in the shipped schema (or differ from it), and the shipped versions we no longer have.
+    pub fn get_local_overlay(&self, shipped_schema: &Self) -> Self {
+        let mut local_schema = Self::new();
+        for table_definitions in &self.tables_definitions {
+            let shipped_table_definitions = shipped_schema.tables_definitions.iter().find(|x| x.name == table_definitions.name);
+            let mut local_table_definitions = TableDefinitions::new(&table_definitions.name);
+            for table_definition in &table_definitions.versions {
+                let is_shipped = match shipped_table_definitions {
+                    Some(shipped_table_definitions) => shipped_table_definitions.versions.contains(table_definition),
+                    None => false,
+                };
+                if !is_shipped { local_table_definitions.versions.push(table_definition.clone()); }
+            }
+            if !local_table_definitions.versions.is_empty() { local_schema.tables_definitions.push(local_table_definitions); }
+        }
+
+        // Shipped versions we no longer have are versions removed by the user.
+        for shipped_table_definitions in &shipped_schema.tables_definitions {
+            let table_definitions = self.tables_definitions.iter().find(|x| x.name == shipped_table_definitions.name);
+            for shipped_table_definition in &shipped_table_definitions.versions {
+                let is_removed = match table_definitions {
+                    Some(table_definitions) => table_definitions.versions.iter().all(|x| x.version != shipped_table_definition.version),
+                    None => true,
+                };
+                if is_removed { local_schema.removed_versions.push((shipped_table_definitions.name.to_owned(), shipped_table_definition.version)); }
+            }
+        }
+
+        local_schema
+    }
+
+    /// This function returns the name of the file of the local overlay of an schema file.
+    pub fn get_local_schema_file_name(schema_file: &str) -> String {
+        match schema_file.rfind(".json") {
+            Some(position) => format!("{}.local.json", &schema_file[..position]),
+            None => format!("{}.local", schema_file),
+        }
+    }
+
+    /// This function merges the definitions of another "Schema" on top of this one. Versions present in
+    /// both schemas are replaced with the ones of the other "Schema", and versions removed in the other
+    /// "Schema" are removed from this one.
+    pub fn merge(&mut self, other: &Self) {
+        for (table_name, version) in &other.removed_versions {
+            if let Some(index) = self.get_table_definitions(table_name) {
+                self.tables_definitions[index].versions.retain(|x| x.version != *version);
+                if self.tables_definitions[index].versions.is_empty() { self.tables_definitions.remove(index); }
+            }
+        }
+
+        for table_definitions in &other.tables_definitions {
+            match self.get_table_definitions(&table_definitions.name) {
+                Some(index) => table_definitions.versions.iter().for_each(|x| self.tables_definitions[index].add_table_definition(x.clone())),
+                None => self.tables_definitions.push(table_definitions.clone()),
+            }
+        }
+
+        self.tables_definitions.sort_unstable_by(|a, b| a.name.cmp(&b.name));
+        self.tables_definitions.iter_mut().for_each(|x| x.versions.sort_unstable_by(|a, b| b.version.cmp(&a.version)));
+    }
+
+    /// This function returns the list of tables (and versions) defined in the provided local overlay that
+    /// are also defined in this "Schema", but in a different way.
+    pub fn get_local_conflicts(&self, local_schema: &Self) -> Vec<String> {
+        let mut conflicts = vec![];
+        for local_table_definitions in &local_schema.tables_definitions {
+            if let Some(table_definitions) = self.tables_definitions.iter().find(|x| x.name == local_table_definitions.name) {
+                for local_table_definition in &local_table_definitions.versions {
+                    if let Some(table_definition) = table_definitions.versions.iter().find(|x| x.version == local_table_definition.version) {
+                        if table_definition != local_table_definition {
+                            conflicts.push(format!("{}, version {}", local_table_definitions.name, local_table_definition.version));
+                        }
+                    }
+                }
+            }
+        }
+        conflicts
+    }
+
+    /// This function returns the differences between this "Schema" (the old one) and the provided one (the new one).
+    pub fn get_diff(&self, new_schema: &Self) -> SchemaDiff {
+        let mut diff = SchemaDiff::default();
+        for old_table_definitions in &self.tables_definitions {
+            match new_schema.tables_definitions.iter().find(|x| x.name == old_table_definitions.name) {
+                Some(new_table_definitions) => {
+                    for old_table_definition in &old_table_definitions.versions {
+                        match new_table_definitions.versions.iter().find(|x| x.version == old_table_definition.version) {
+                            Some(new_table_definition) => {
+                                if let Some(table_definition_diff) = old_table_definition.get_diff(new_table_definition, &old_table_definitions.name) {
+                                    diff.versions_changed.push(table_definition_diff);
+                                }
+                            }
+                            None => diff.versions_removed.push((old_table_definitions.name.to_owned(), old_table_definition.version)),
+                        }
+                    }
+
+                    for new_table_definition in &new_table_definitions.versions {
+                        if old_table_definitions.versions.iter().all(|x| x.version != new_table_definition.version) {
+                            diff.versions_added.push((new_table_definitions.name.to_owned(), new_table_definition.version));
+                        }
+                    }
+                }
+                None => diff.tables_removed.push(old_table_definitions.name.to_owned()),
+            }
+        }
+
+        for new_table_definitions in &new_schema.tables_definitions {
+            if self.tables_definitions.iter().all(|x| x.name != new_table_definitions.name) {
+                diff.tables_added.push(new_table_definitions.name.to_owned());
+            }
+        }
+
+        diff
+    }
+
+    /// This function returns the differences between two schema files, wherever they are. It doesn't need network access.
+    pub fn get_diff_from_files(old_path: &Path, new_path: &Path) -> Result<SchemaDiff> {
+        let old_schema = Self::load_from_path(old_path)?;
+        let new_schema = Self::load_from_path(new_path)?;
+        Ok(old_schema.get_diff(&new_schema))
+    }
+
     /// This function generates the diff between the local schemas and the remote ones and, if it detects
     /// that you're using the git repo (debug), it adds the diff to the proper place in the docs. 
     pub fn generate_schema_diff() -> Result<()> {
@@ -173,7 +448,7 @@ impl Schema {
 
             // For this, first we get both schemas. Then, compare them table by table looking for differences.
             // Uncomment and tweak the commented schema_current to test against a local schema.
-            let schema_local = Schema::load(schema_name).unwrap();
+            let schema_local = Schema::load_shipped(schema_name).unwrap();
             //let schema_current = Schema::load("schema_att.json").unwrap();
             let schema_current: Schema = reqwest::get(&format!("{}/{}", SCHEMA_UPDATE_URL_MASTER, schema_name))?.json()?;
 
@@ -358,43 +633,31 @@ impl TableDefinition {
         TableDefinition {
             version,
             fields: vec![],
+            localised_fields: vec![],
         }
     }
 
     /// This function creates a new table definition from an imported definition from the assembly kit.
-    /// Note that this import the loc fields (they need to be removed manually later) and it doesn't
-    /// import the version (this... I think I can do some trick for it).
+    /// Note that the loc fields are not imported as fields, as they're not in the binary tables. We only keep their names.
     pub fn new_from_assembly_kit(imported_table_definition: &assembly_kit::root, version: i32, table_name: &str) -> TableDefinition {
         let mut fields = vec![];
+        let mut localised_fields = vec![];
         for field in &imported_table_definition.field {
 
             // First, we need to disable a number of known fields that are not in the final tables. We
             // check if the current field is one of them, and ignore it if it's.
-            if field.name == "game_expansion_key" || // This one exists in one of the advices tables.
-                field.name == "localised_text" ||
-                field.name == "localised_name" ||
-                field.name == "localised_tooltip" ||
-                field.name == "description" ||
-                field.name == "objectives_team_1" ||
-                field.name == "objectives_team_2" ||
-                field.name == "short_description_text" ||
-                field.name == "historical_description_text" ||
-                field.name == "strengths_weaknesses_text" ||
-                field.name == "onscreen" ||
-                field.name == "onscreen_text" ||
-                field.name == "onscreen_name" ||
-                field.name == "onscreen_description" ||
-                field.name == "on_screen_name" ||
-                field.name == "on_screen_description" ||
-                field.name == "on_screen_target" {
+            // The localised ones are kept apart, as they're only in the Loc PackedFiles.
+            if field.name == "game_expansion_key" { continue; } // This one exists in one of the advices tables.
+            if LOCALISABLE_FIELDS.contains(&&*field.name) {
+                localised_fields.push(field.name.to_owned());
                 continue;
             }
             let field_name = field.name.to_owned();
             let field_is_key = field.primary_key == "1";
-            let field_is_reference = if field.column_source_table != None {
-                Some((field.column_source_table.clone().unwrap().to_owned(), field.column_source_column.clone().unwrap()[0].to_owned()))
-            }
-            else {None};
+            let field_is_reference = match (&field.column_source_table, &field.column_source_column) {
+                (Some(table), Some(columns)) if !columns.is_empty() => Some((table.to_owned(), columns[0].to_owned())),
+                _ => None,
+            };
 
             let field_type = match &*field.field_type {
                 "yesno" => FieldType::Boolean,
@@ -448,19 +711,21 @@ impl TableDefinition {
                 None => String::new(),
             };
 
-            let new_field = Field::new(
+            let mut new_field = Field::new(
                 field_name,
                 field_type,
                 field_is_key,
                 field_is_reference,
                 field_description
             );
+            new_field.set_assembly_kit_metadata(field);
             fields.push(new_field);
         }
 
         TableDefinition {
             version,
             fields,
+            localised_fields,
         }
     }
         
@@ -472,10 +737,10 @@ impl TableDefinition {
 
             let field_name = field.name.to_owned();
             let field_is_key = field.primary_key == "1";
-            let field_is_reference = if field.column_source_table != None {
-                Some((field.column_source_table.clone().unwrap().to_owned(), field.column_source_column.clone().unwrap()[0].to_owned()))
-            }
-            else {None};
+            let field_is_reference = match (&field.column_source_table, &field.column_source_column) {
+                (Some(table), Some(columns)) if !columns.is_empty() => Some((table.to_owned(), columns[0].to_owned())),
+                _ => None,
+            };
 
             let field_type = match &*field.field_type {
                 "yesno" => FieldType::Boolean,
@@ -529,19 +794,21 @@ impl TableDefinition {
                 None => String::new(),
             };
 
-            let new_field = Field::new(
+            let mut new_field = Field::new(
                 field_name,
                 field_type,
                 field_is_key,
                 field_is_reference,
                 field_description
             );
+            new_field.set_assembly_kit_metadata(field);
             fields.push(new_field);
         }
 
         TableDefinition {
             version,
             fields,
+            localised_fields: vec![],
         }
     }
 
@@ -566,6 +833,50 @@ impl TableDefinition {
         }
     }
 
+    /// This function returns the differences between this definition (the old one) and the provided one (the new one),
+    /// if there are any. Fields are matched by name, and they're only reported as moved if their position between
+    /// the fields both definitions have changed, so adding or removing a field doesn't report every field after it.
+    pub fn get_diff(&self, new_table_definition: &Self, table_name: &str) -> Option<TableDefinitionDiff> {
+        let mut diff = TableDefinitionDiff {
+            table_name: table_name.to_owned(),
+            version: self.version,
+            ..Default::default()
+        };
+
+        let old_common_fields = self.fields.iter().filter(|x| new_table_definition.fields.iter().any(|y| y.field_name == x.field_name)).map(|x| &x.field_name).collect::<Vec<&String>>();
+        let new_common_fields = new_table_definition.fields.iter().filter(|x| self.fields.iter().any(|y| y.field_name == x.field_name)).map(|x| &x.field_name).collect::<Vec<&String>>();
+
+        for (old_position, old_field) in self.fields.iter().enumerate() {
+            match new_table_definition.fields.iter().position(|x| x.field_name == old_field.field_name) {
+                Some(new_position) => {
+                    let mut changes = old_field.get_diff(&new_table_definition.fields[new_position]);
+                    let old_common_position = old_common_fields.iter().position(|x| **x == old_field.field_name);
+                    let new_common_position = new_common_fields.iter().position(|x| **x == old_field.field_name);
+                    if old_common_position != new_common_position {
+                        changes.insert(0, ("Position".to_owned(), format!("{}", old_position), format!("{}", new_position)));
+                    }
+
+                    if !changes.is_empty() {
+                        diff.fields_changed.push(FieldDiff {
+                            field_name: old_field.field_name.to_owned(),
+                            changes,
+                        });
+                    }
+                }
+                None => diff.fields_removed.push(old_field.clone()),
+            }
+        }
+
+        for new_field in &new_table_definition.fields {
+            if self.fields.iter().all(|x| x.field_name != new_field.field_name) {
+                diff.fields_added.push(new_field.clone());
+            }
+        }
+
+        if diff.fields_added.is_empty() && diff.fields_removed.is_empty() && diff.fields_changed.is_empty() { None }
+        else { Some(diff) }
+    }
+
     /// This function generates a MarkDown diff of two versions of an specific table and adds it to the provided changes list.
     pub fn get_pretty_diff(
         &self,
@@ -664,6 +975,82 @@ impl TableDefinition {
     }
 }
 
+/// Implementation of "SchemaDiff".
+impl SchemaDiff {
+
+    /// This function returns true if there are no differences.
+    pub fn is_empty(&self) -> bool {
+        self.tables_added.is_empty() &&
+        self.tables_removed.is_empty() &&
+        self.versions_added.is_empty() &&
+        self.versions_removed.is_empty() &&
+        self.versions_changed.is_empty()
+    }
+
+    /// This function returns the differences as a JSON String.
+    pub fn to_json(&self) -> Result<String> {
+        serde_json::to_string_pretty(&self).map_err(From::from)
+    }
+
+    /// This function returns the differences as a MarkDown String, with the same format as the changelogs of the schemas.
+    pub fn to_markdown(&self) -> String {
+        let mut diff = vec![];
+
+        for (index, table_name) in self.tables_added.iter().enumerate() {
+            if index == 0 { diff.push("- **New tables**:".to_owned()); }
+            diff.push(format!("  - *{}*.", table_name));
+        }
+
+        for (index, table_name) in self.tables_removed.iter().enumerate() {
+            if index == 0 { diff.push("- **Removed tables**:".to_owned()); }
+            diff.push(format!("  - *{}*.", table_name));
+        }
+
+        for (index, (table_name, version)) in self.versions_added.iter().enumerate() {
+            if index == 0 { diff.push("- **New versions**:".to_owned()); }
+            diff.push(format!("  - *{}*: *{}*.", table_name, version));
+        }
+
+        for (index, (table_name, version)) in self.versions_removed.iter().enumerate() {
+            if index == 0 { diff.push("- **Removed versions**:".to_owned()); }
+            diff.push(format!("  - *{}*: *{}*.", table_name, version));
+        }
+
+        for (index, table_definition_diff) in self.versions_changed.iter().enumerate() {
+            if index == 0 { diff.push("- **Changed versions**:".to_owned()); }
+            diff.push(format!("  - ***{}*** (version *{}*):", table_definition_diff.table_name, table_definition_diff.version));
+
+            for (index, field) in table_definition_diff.fields_added.iter().enumerate() {
+                if index == 0 { diff.push("    - **New fields**:".to_owned()); }
+                diff.push(format!("      - ***{}***: *{}*.", field.field_name, field.field_type));
+            }
+
+            for (index, field) in table_definition_diff.fields_removed.iter().enumerate() {
+                if index == 0 { diff.push("    - **Removed fields**:".to_owned()); }
+                diff.push(format!("      - *{}*.", field.field_name));
+            }
+
+            for (index, field_diff) in table_definition_diff.fields_changed.iter().enumerate() {
+                if index == 0 { diff.push("    - **Changed fields**:".to_owned()); }
+                diff.push(format!("      - **{}**:", field_diff.field_name));
+                for (property, old_value, new_value) in &field_diff.changes {
+                    diff.push(format!("        - ***{}***: *{}* => *{}*.", property, old_value, new_value));
+                }
+            }
+        }
+
+        if diff.is_empty() { "No differences found.\n".to_owned() }
+        else { diff.join("\n") + "\n" }
+    }
+
+    /// This function saves the differences to the provided path as JSON, and to the same path with the "md" extension as MarkDown.
+    pub fn save(&self, path: &Path) -> Result<()> {
+        File::create(path)?.write_all(self.to_json()?.as_bytes())?;
+        File::create(path.with_extension("md"))?.write_all(self.to_markdown().as_bytes())?;
+        Ok(())
+    }
+}
+
 /// Implementation of "Field".
 impl Field {
 
@@ -676,8 +1063,124 @@ impl Field {
             field_type,
             field_is_key,
             field_is_reference,
-            field_description
+            field_description,
+            field_default_value: None,
+            field_max_length: None,
+            field_enum_values: BTreeMap::new(),
+            field_is_bitflag: false,
+            field_lookup: None,
+        }
+    }
+
+    /// This function fills the metadata of the field (default value, max length and lookup column) with
+    /// the one of the provided field from the Assembly Kit.
+    pub fn set_assembly_kit_metadata(&mut self, imported_field: &assembly_kit::field) {
+        self.field_default_value = match imported_field.default_value {
+            Some(ref default_value) if !default_value.is_empty() => Some(default_value.to_owned()),
+            _ => None,
+        };
+
+        self.field_max_length = match imported_field.max_length {
+            Some(ref max_length) => max_length.parse::<u32>().ok().filter(|x| *x > 0),
+            None => None,
+        };
+
+        self.field_lookup = match imported_field.column_source_column {
+            Some(ref columns) if self.field_is_reference.is_some() && columns.len() > 1 => Some(columns[1].to_owned()),
+            _ => None,
+        };
+    }
+
+    /// This function checks if the provided data (as text) is valid for this field, according to his max length
+    /// and his allowed values. If it isn't, it returns the reason why.
+    pub fn get_constraint_error(&self, data: &str) -> Option<String> {
+        if let Some(max_length) = self.field_max_length {
+            let length = data.chars().count();
+            if length > max_length as usize {
+                return Some(format!("This field can only have {} characters, but it has {}.", max_length, length));
+            }
+        }
+
+        if !self.field_enum_values.is_empty() {
+            if self.field_is_bitflag {
+                let flags = self.get_bitflags();
+                let mask = flags.iter().fold(0, |mask, flag| mask | flag.0);
+                if data.parse::<i64>().map_or(true, |value| value & !mask != 0) {
+                    return Some(format!("This field only accepts combinations of the following flags: {}.", flags.iter().map(|x| x.0.to_string()).collect::<Vec<String>>().join(", ")));
+                }
+            }
+            else if !self.field_enum_values.contains_key(data) {
+                return Some(format!("This field only accepts the following values: {}.", self.field_enum_values.keys().map(|x| &**x).collect::<Vec<&str>>().join(", ")));
+            }
+        }
+
+        None
+    }
+
+    /// This function returns the flags (value, label) of a bitflag field, sorted by value. Allowed values that are not numbers are ignored.
+    pub fn get_bitflags(&self) -> Vec<(i64, String)> {
+        let mut flags = self.field_enum_values.iter().filter_map(|(value, label)| value.parse::<i64>().ok().map(|value| (value, label.to_owned()))).collect::<Vec<(i64, String)>>();
+        flags.sort_by_key(|x| x.0);
+        flags
+    }
+
+    /// This function returns the label of the provided data (as text) for this field. For bitflag fields, it's the labels of all the flags in the data.
+    pub fn get_enum_label(&self, data: &str) -> String {
+        if self.field_is_bitflag {
+            let value = data.parse::<i64>().unwrap_or(0);
+            self.get_bitflags().iter().filter(|x| x.0 != 0 && value & x.0 == x.0).map(|x| &*x.1).collect::<Vec<&str>>().join(", ")
+        }
+        else { self.field_enum_values.get(data).map(|x| x.to_owned()).unwrap_or_else(String::new) }
+    }
+
+    /// This function returns the properties that changed between this field (the old one) and the provided one
+    /// (the new one), as (property, old value, new value). The name is not compared.
+    pub fn get_diff(&self, new_field: &Self) -> Vec<(String, String, String)> {
+        let reference_to_string = |reference: &Option<(String, String)>| match reference {
+            Some((ref_table, ref_column)) => format!("{}/{}", ref_table, ref_column),
+            None => "None".to_owned(),
+        };
+        let option_to_string = |option: Option<String>| option.unwrap_or_else(|| "None".to_owned());
+        let enum_values_to_string = |enum_values: &BTreeMap<String, String>| enum_values.iter().map(|(value, label)| format!("{}: {}", value, label)).collect::<Vec<String>>().join(", ");
+
+        let mut changes = vec![];
+        if self.field_type != new_field.field_type {
+            changes.push(("Type".to_owned(), format!("{}", self.field_type), format!("{}", new_field.field_type)));
+        }
+
+        if self.field_is_key != new_field.field_is_key {
+            changes.push(("Is Key".to_owned(), format!("{}", self.field_is_key), format!("{}", new_field.field_is_key)));
         }
+
+        if self.field_is_reference != new_field.field_is_reference {
+            changes.push(("Is Reference".to_owned(), reference_to_string(&self.field_is_reference), reference_to_string(&new_field.field_is_reference)));
+        }
+
+        if self.field_description != new_field.field_description {
+            changes.push(("Description".to_owned(), self.field_description.to_owned(), new_field.field_description.to_owned()));
+        }
+
+        if self.field_default_value != new_field.field_default_value {
+            changes.push(("Default Value".to_owned(), option_to_string(self.field_default_value.clone()), option_to_string(new_field.field_default_value.clone())));
+        }
+
+        if self.field_max_length != new_field.field_max_length {
+            changes.push(("Max Length".to_owned(), option_to_string(self.field_max_length.map(|x| x.to_string())), option_to_string(new_field.field_max_length.map(|x| x.to_string()))));
+        }
+
+        if self.field_enum_values != new_field.field_enum_values {
+            changes.push(("Allowed Values".to_owned(), enum_values_to_string(&self.field_enum_values), enum_values_to_string(&new_field.field_enum_values)));
+        }
+
+        if self.field_is_bitflag != new_field.field_is_bitflag {
+            changes.push(("Is Bitflag".to_owned(), format!("{}", self.field_is_bitflag), format!("{}", new_field.field_is_bitflag)));
+        }
+
+        if self.field_lookup != new_field.field_lookup {
+            changes.push(("Lookup".to_owned(), option_to_string(self.field_lookup.clone()), option_to_string(new_field.field_lookup.clone())));
+        }
+
+        changes
     }
 }
 
@@ -22,6 +22,7 @@ use crate::RPFM_PATH;
 use crate::SUPPORTED_GAMES;
 use crate::error::Result;
 use crate::packfile::PFHVersion;
+use crate::schema::SCHEMA_UPDATE_URL_MASTER;
 
 pub mod shortcuts;
 
@@ -98,9 +99,12 @@ impl Settings {
         settings_bool.insert("allow_editing_of_ca_packfiles".to_owned(), false);
         settings_bool.insert("check_updates_on_start".to_owned(), true);
         settings_bool.insert("check_schema_updates_on_start".to_owned(), true);
+        settings_string.insert("schema_update_url".to_owned(), SCHEMA_UPDATE_URL_MASTER.to_owned());
         settings_bool.insert("use_dependency_checker".to_owned(), false);
         settings_bool.insert("use_lazy_loading".to_owned(), true);
         settings_bool.insert("optimize_not_renamed_packedfiles".to_owned(), false);
+        settings_string.insert("optimize_float_tolerance".to_owned(), "0.001".to_owned());
+        settings_string.insert("custom_packed_file_types".to_owned(), "".to_owned());
 
         // Debug Settings.
         settings_bool.insert("check_for_missing_table_definitions".to_owned(), false);
@@ -60,28 +60,45 @@ impl Shortcuts {
         menu_bar_packfile.insert("save_packfile".to_owned(), "Ctrl+S".to_owned());
         menu_bar_packfile.insert("save_packfile_as".to_owned(), "Ctrl+Shift+S".to_owned());
         menu_bar_packfile.insert("load_all_ca_packfiles".to_owned(), "Ctrl+G".to_owned());
+        menu_bar_packfile.insert("check_conflicts".to_owned(), "Ctrl+Alt+C".to_owned());
         menu_bar_packfile.insert("preferences".to_owned(), "Ctrl+P".to_owned());
         menu_bar_packfile.insert("quit".to_owned(), "Ctrl+Q".to_owned());
 
         menu_bar_game_selected.insert("open_game_data_folder".to_owned(), "Ctrl+Shift+O".to_owned());
         menu_bar_game_selected.insert("open_game_assembly_kit_folder".to_owned(), "Ctrl+Alt+O".to_owned());
+        menu_bar_game_selected.insert("import_schema_from_assembly_kit".to_owned(), "Ctrl+Alt+I".to_owned());
+        menu_bar_game_selected.insert("validate_schema".to_owned(), "Ctrl+Alt+K".to_owned());
+        menu_bar_game_selected.insert("diff_schema_files".to_owned(), "Ctrl+Alt+D".to_owned());
+        menu_bar_game_selected.insert("generate_schema_docs".to_owned(), "Ctrl+Alt+G".to_owned());
+        menu_bar_game_selected.insert("open_schema_editor".to_owned(), "Ctrl+Alt+E".to_owned());
 
         menu_bar_about.insert("about_qt".to_owned(), "Ctrl+Alt+H".to_owned());
         menu_bar_about.insert("about_rpfm".to_owned(), "Ctrl+Shift+H".to_owned());
         menu_bar_about.insert("open_manual".to_owned(), "Ctrl+H".to_owned());
         menu_bar_about.insert("check_updates".to_owned(), "Ctrl+U".to_owned());
         menu_bar_about.insert("check_schema_updates".to_owned(), "Ctrl+Shift+U".to_owned());
+        menu_bar_about.insert("update_schemas_from_file".to_owned(), "Ctrl+Alt+U".to_owned());
 
         tree_view.insert("add_file".to_owned(), "Ctrl+A".to_owned());
         tree_view.insert("add_folder".to_owned(), "Ctrl+Shift+A".to_owned());
         tree_view.insert("add_from_packfile".to_owned(), "Ctrl+Alt+A".to_owned());
         tree_view.insert("check_tables".to_owned(), "Ctrl+Shift+I".to_owned());
+        tree_view.insert("check_missing_locs".to_owned(), "Ctrl+Shift+L".to_owned());
+        tree_view.insert("check_script_references".to_owned(), "Ctrl+Shift+R".to_owned());
+        tree_view.insert("check_xml_files".to_owned(), "Ctrl+Shift+K".to_owned());
+        tree_view.insert("open_translation_view".to_owned(), "Ctrl+Shift+T".to_owned());
+        tree_view.insert("query_tables".to_owned(), "Ctrl+Shift+Q".to_owned());
         tree_view.insert("create_folder".to_owned(), "Ctrl+F".to_owned());
         tree_view.insert("create_db".to_owned(), "Ctrl+D".to_owned());
         tree_view.insert("create_loc".to_owned(), "Ctrl+L".to_owned());
         tree_view.insert("create_text".to_owned(), "Ctrl+T".to_owned());
         tree_view.insert("mass_import_tsv".to_owned(), "Ctrl+.".to_owned());
         tree_view.insert("mass_export_tsv".to_owned(), "Ctrl+,".to_owned());
+        tree_view.insert("import_assembly_kit_tables".to_owned(), "Ctrl+Alt+.".to_owned());
+        tree_view.insert("export_assembly_kit_tables".to_owned(), "Ctrl+Alt+,".to_owned());
+        tree_view.insert("import_translation_files".to_owned(), "Ctrl+Shift+.".to_owned());
+        tree_view.insert("export_po".to_owned(), "Ctrl+Shift+,".to_owned());
+        tree_view.insert("export_xliff".to_owned(), "Ctrl+Shift+X".to_owned());
         tree_view.insert("merge_tables".to_owned(), "Ctrl+M".to_owned());
         tree_view.insert("delete".to_owned(), "Del".to_owned());
         tree_view.insert("extract".to_owned(), "Ctrl+E".to_owned());
@@ -23,12 +23,16 @@ use qt_widgets::line_edit::LineEdit;
 use qt_widgets::main_window::MainWindow;
 use qt_widgets::message_box::{MessageBox, Icon};
 use qt_widgets::push_button::PushButton;
+use qt_widgets::table_view::TableView;
+use qt_widgets::text_edit::TextEdit;
 use qt_widgets::tree_view::TreeView;
 use qt_widgets::widget::Widget;
 
 use qt_gui::brush::Brush;
 use qt_gui::icon;
 use qt_gui::key_sequence::KeySequence;
+use qt_gui::list::ListStandardItemMutPtr;
+use qt_gui::standard_item::StandardItem;
 use qt_gui::standard_item_model::StandardItemModel;
 
 use qt_core::abstract_item_model::AbstractItemModel;
@@ -36,10 +40,11 @@ use qt_core::connection::Signal;
 use qt_core::flags::Flags;
 use qt_core::model_index::ModelIndex;
 use qt_core::object::Object;
-use qt_core::qt::ShortcutContext;
+use qt_core::qt::{CheckState, ShortcutContext};
 use qt_core::reg_exp::RegExp;
 use qt_core::slots::{SlotBool, SlotNoArgs, SlotStringRef, SlotModelIndexRef};
 use qt_core::sort_filter_proxy_model::SortFilterProxyModel;
+use qt_core::variant::Variant;
 
 use cpp_utils::{CppBox, StaticCast};
 
@@ -74,6 +79,7 @@ use crate::common::communications::*;
 use crate::error::{Error, ErrorKind, Result};
 use crate::packedfile::*;
 use crate::packedfile::db::*;
+use crate::packedfile::query::QueryResult;
 use crate::schema::*;
 use crate::ui::packfile_treeview::*;
 use crate::ui::table_state::TableStateData;
@@ -92,6 +98,8 @@ pub mod shortcuts;
 pub mod table_state;
 pub mod updater;
 pub mod qt_custom_stuff;
+pub mod schema_editor;
+pub mod translation_view;
 
 //----------------------------------------------------------------------------//
 //             UI Structs (to hold slots, actions and what not)
@@ -632,7 +640,7 @@ pub fn create_global_search_dialog(app_ui: &AppUI) -> Option<String> {
 }
 
 /// This function creates the entire "Merge Tables" dialog. It returns the stuff set in it.
-pub fn create_merge_tables_dialog(app_ui: &AppUI) -> Option<(String, bool)> {
+pub fn create_merge_tables_dialog(app_ui: &AppUI) -> Option<(String, bool, MergeStrategy)> {
 
     let mut dialog = unsafe { Dialog::new_unsafe(app_ui.window as *mut Widget) };
     dialog.set_window_title(&QString::from_std_str("Merge Tables"));
@@ -645,10 +653,19 @@ pub fn create_merge_tables_dialog(app_ui: &AppUI) -> Option<(String, bool)> {
 
     let mut delete_source_tables = CheckBox::new(&QString::from_std_str("Delete original tables"));
 
+    // The strategy to use with rows with the same key.
+    let mut strategy_label = Label::new(&QString::from_std_str("Rows with the same key:"));
+    let mut strategy_dropdown = ComboBox::new();
+    strategy_dropdown.add_item(&QString::from_std_str("First wins"));
+    strategy_dropdown.add_item(&QString::from_std_str("Last wins"));
+    strategy_dropdown.add_item(&QString::from_std_str("Report conflicts"));
+
     let accept_button = PushButton::new(&QString::from_std_str("Accept")).into_raw();
-    unsafe { main_grid.as_mut().unwrap().add_widget((name.static_cast_mut() as *mut Widget, 0, 0, 1, 1)); }
-    unsafe { main_grid.as_mut().unwrap().add_widget((delete_source_tables.static_cast_mut() as *mut Widget, 1, 0, 1, 1)); }
-    unsafe { main_grid.as_mut().unwrap().add_widget((accept_button as *mut Widget, 2, 0, 1, 1)); }
+    unsafe { main_grid.as_mut().unwrap().add_widget((name.static_cast_mut() as *mut Widget, 0, 0, 1, 2)); }
+    unsafe { main_grid.as_mut().unwrap().add_widget((delete_source_tables.static_cast_mut() as *mut Widget, 1, 0, 1, 2)); }
+    unsafe { main_grid.as_mut().unwrap().add_widget((strategy_label.static_cast_mut() as *mut Widget, 2, 0, 1, 1)); }
+    unsafe { main_grid.as_mut().unwrap().add_widget((strategy_dropdown.static_cast_mut() as *mut Widget, 2, 1, 1, 1)); }
+    unsafe { main_grid.as_mut().unwrap().add_widget((accept_button as *mut Widget, 3, 0, 1, 2)); }
 
     // What happens when we hit the "Search" button.
     unsafe { accept_button.as_mut().unwrap().signals().released().connect(&dialog.slots().accept()); }
@@ -657,7 +674,12 @@ pub fn create_merge_tables_dialog(app_ui: &AppUI) -> Option<(String, bool)> {
     if dialog.exec() == 1 { 
         let text = name.text().to_std_string();
         let delete_source_tables = delete_source_tables.is_checked();
-        if !text.is_empty() { Some((text, delete_source_tables)) }
+        let strategy = match strategy_dropdown.current_index() {
+            0 => MergeStrategy::FirstWins,
+            1 => MergeStrategy::LastWins,
+            _ => MergeStrategy::ReportConflicts,
+        };
+        if !text.is_empty() { Some((text, delete_source_tables, strategy)) }
         else { None }
     }
     
@@ -665,6 +687,170 @@ pub fn create_merge_tables_dialog(app_ui: &AppUI) -> Option<(String, bool)> {
     else { None }
 }
 
+/// This function creates the entire "Query Tables" dialog. The queries are run in the background thread,
+/// and their results are shown in the dialog, from where they can be exported to TSV.
+pub fn create_query_tables_dialog(
+    app_ui: &AppUI,
+    sender_qt: &Sender<Commands>,
+    sender_qt_data: &Sender<Data>,
+    receiver_qt: &Rc<RefCell<Receiver<Data>>>,
+) {
+
+    //-------------------------------------------------------------------------------------------//
+    // Creating the Query Tables Dialog...
+    //-------------------------------------------------------------------------------------------//
+
+    // Create the "Query Tables" Dialog and configure it.
+    let dialog = unsafe { Dialog::new_unsafe(app_ui.window as *mut Widget).into_raw() };
+    unsafe { dialog.as_mut().unwrap().set_window_title(&QString::from_std_str("Query Tables")); }
+    unsafe { dialog.as_mut().unwrap().set_modal(true); }
+    unsafe { dialog.as_mut().unwrap().resize((1000, 600)); }
+
+    // Create the main Grid and his stuff.
+    let main_grid = create_grid_layout_unsafe(dialog as *mut Widget);
+    let query_line_edit = LineEdit::new(()).into_raw();
+    let run_button = PushButton::new(&QString::from_std_str("Run")).into_raw();
+    let table_view = TableView::new().into_raw();
+    let model = StandardItemModel::new(()).into_raw();
+    let export_button = PushButton::new(&QString::from_std_str("Export to TSV")).into_raw();
+
+    unsafe { query_line_edit.as_mut().unwrap().set_placeholder_text(&QString::from_std_str("SELECT key, onscreen_name FROM land_units JOIN main_units WHERE armour > 80 AND onscreen_name LIKE '%Knight%'")); }
+    unsafe { table_view.as_mut().unwrap().set_model(model as *mut AbstractItemModel); }
+    unsafe { table_view.as_mut().unwrap().set_sorting_enabled(true); }
+    unsafe { table_view.as_mut().unwrap().horizontal_header().as_mut().unwrap().set_stretch_last_section(true); }
+    unsafe { export_button.as_mut().unwrap().set_enabled(false); }
+
+    // Add all the widgets to the main grid.
+    unsafe { main_grid.as_mut().unwrap().add_widget((query_line_edit as *mut Widget, 0, 0, 1, 1)); }
+    unsafe { main_grid.as_mut().unwrap().add_widget((run_button as *mut Widget, 0, 1, 1, 1)); }
+    unsafe { main_grid.as_mut().unwrap().add_widget((table_view as *mut Widget, 1, 0, 1, 2)); }
+    unsafe { main_grid.as_mut().unwrap().add_widget((export_button as *mut Widget, 2, 0, 1, 2)); }
+
+    //-------------------------------------------------------------------------------------------//
+    // Actions for the Query Tables Dialog...
+    //-------------------------------------------------------------------------------------------//
+
+    // The result of the last query we run.
+    let window = app_ui.window;
+    let query_result: Rc<RefCell<Option<QueryResult>>> = Rc::new(RefCell::new(None));
+
+    // What happens when we hit the "Run" button.
+    let slot_run_query = SlotNoArgs::new(clone!(
+        query_result,
+        sender_qt,
+        sender_qt_data,
+        receiver_qt => move || {
+            let query = unsafe { query_line_edit.as_ref().unwrap().text().to_std_string() };
+            if query.is_empty() { return }
+
+            unsafe { (dialog.as_mut().unwrap() as &mut Widget).set_enabled(false); }
+            sender_qt.send(Commands::RunQuery).unwrap();
+            sender_qt_data.send(Data::String(query)).unwrap();
+            match check_message_validity_tryrecv(&receiver_qt) {
+                Data::QueryResult(result) => {
+
+                    // Load the result to the table.
+                    unsafe { model.as_mut().unwrap().clear(); }
+                    for row in &result.rows {
+                        let mut qlist = ListStandardItemMutPtr::new(());
+                        for cell in row {
+                            let mut item = match cell {
+                                DecodedData::Boolean(data) => {
+                                    let mut item = StandardItem::new(());
+                                    item.set_checkable(true);
+                                    item.set_check_state(if *data { CheckState::Checked } else { CheckState::Unchecked });
+                                    item
+                                }
+                                DecodedData::Float(data) => {
+                                    let mut item = StandardItem::new(());
+                                    item.set_data((&Variant::new2(*data), 2));
+                                    item
+                                }
+                                DecodedData::Integer(data) => {
+                                    let mut item = StandardItem::new(());
+                                    item.set_data((&Variant::new0(*data), 2));
+                                    item
+                                }
+                                DecodedData::LongInteger(data) => {
+                                    let mut item = StandardItem::new(());
+                                    item.set_data((&Variant::new2(*data), 2));
+                                    item
+                                }
+                                DecodedData::StringU8(data) |
+                                DecodedData::StringU16(data) |
+                                DecodedData::OptionalStringU8(data) |
+                                DecodedData::OptionalStringU16(data) => StandardItem::new(&QString::from_std_str(data)),
+                            };
+
+                            item.set_editable(false);
+                            unsafe { qlist.append_unsafe(&item.into_raw()); }
+                        }
+                        unsafe { model.as_mut().unwrap().append_row(&qlist); }
+                    }
+
+                    for (index, column) in result.columns.iter().enumerate() {
+                        let item = StandardItem::new(&QString::from_std_str(column)).into_raw();
+                        unsafe { model.as_mut().unwrap().set_horizontal_header_item(index as i32, item) };
+                    }
+
+                    // If we had to skip tables, tell the user, as the result may be missing rows.
+                    if !result.skipped_tables.is_empty() {
+                        let report = format!("<p>The following tables couldn't be decoded, so they were left out of the query:</p><ul>{}</ul>", result.skipped_tables.iter().map(|x| format!("<li>{}</li>", escape_html(x))).collect::<String>());
+                        show_report_dialog(window, "Skipped Tables", &report);
+                    }
+
+                    unsafe { export_button.as_mut().unwrap().set_enabled(true); }
+                    *query_result.borrow_mut() = Some(result);
+                }
+                Data::Error(error) => show_dialog(window, false, error),
+                _ => panic!(THREADS_MESSAGE_ERROR),
+            }
+            unsafe { (dialog.as_mut().unwrap() as &mut Widget).set_enabled(true); }
+        }
+    ));
+
+    // What happens when we hit the "Export to TSV" button.
+    let slot_export_query_result = SlotNoArgs::new(clone!(
+        query_result,
+        sender_qt,
+        sender_qt_data,
+        receiver_qt => move || {
+            if let Some(ref result) = *query_result.borrow() {
+
+                // Create a File Chooser to get the destination path and configure it.
+                let mut file_dialog = unsafe { FileDialog::new_unsafe((
+                    dialog as *mut Widget,
+                    &QString::from_std_str("Export TSV File..."),
+                )) };
+
+                file_dialog.set_accept_mode(qt_widgets::file_dialog::AcceptMode::Save);
+                file_dialog.set_confirm_overwrite(true);
+                file_dialog.set_name_filter(&QString::from_std_str("TSV Files (*.tsv)"));
+                file_dialog.set_default_suffix(&QString::from_std_str("tsv"));
+
+                // Run it and, if we receive 1 (Accept), export the result.
+                if file_dialog.exec() == 1 {
+                    let path = PathBuf::from(file_dialog.selected_files().at(0).to_std_string());
+                    sender_qt.send(Commands::ExportTSVPackedFile).unwrap();
+                    sender_qt_data.send(Data::VecVecDecodedDataPathBufVecStringTupleStrI32((result.rows.to_vec(), path, result.columns.to_vec(), (TSV_HEADER_QUERY_RESULT.to_owned(), 1)))).unwrap();
+                    match check_message_validity_recv2(&receiver_qt) {
+                        Data::Success => {},
+                        Data::Error(error) => show_dialog(window, false, error),
+                        _ => panic!(THREADS_MESSAGE_ERROR),
+                    }
+                }
+            }
+        }
+    ));
+
+    unsafe { run_button.as_mut().unwrap().signals().released().connect(&slot_run_query); }
+    unsafe { query_line_edit.as_mut().unwrap().signals().return_pressed().connect(&slot_run_query); }
+    unsafe { export_button.as_mut().unwrap().signals().released().connect(&slot_export_query_result); }
+
+    // Execute the dialog. Everything is done in the slots, so we don't care about the result.
+    unsafe { dialog.as_mut().unwrap().exec(); }
+}
+
 //----------------------------------------------------------------------------//
 //                    Enums & Structs needed for the UI
 //----------------------------------------------------------------------------//
@@ -679,8 +865,8 @@ enum IconType {
     // For folders.
     Folder,
 
-    // For files. Includes the path without the Packfile.
-    File(Vec<String>),
+    // For files. Includes the path without the Packfile, and the type of the file.
+    File(Vec<String>, DecodeablePackedFileType),
 }
 
 //----------------------------------------------------------------------------//
@@ -715,6 +901,39 @@ pub fn show_dialog<T: Display>(
     dialog.exec();
 }
 
+/// This function shows a dialog with a report, for when the text is too long to be shown in a normal dialog.
+/// It requires:
+/// - window: a pointer to the main window of the program, to set it as a parent.
+/// - title: the title of the dialog.
+/// - report: the text of the report. It can be HTML.
+pub fn show_report_dialog(
+    window: *mut MainWindow,
+    title: &str,
+    report: &str
+) {
+
+    // Create the dialog.
+    let dialog = unsafe { Dialog::new_unsafe(window as *mut Widget).into_raw() };
+    unsafe { dialog.as_mut().unwrap().set_window_title(&QString::from_std_str(title)); }
+    unsafe { dialog.as_mut().unwrap().set_modal(true); }
+    unsafe { dialog.as_mut().unwrap().resize((800, 600)); }
+
+    // Create the main Grid and the report view.
+    let main_grid = create_grid_layout_unsafe(dialog as *mut Widget);
+    let report_view = TextEdit::new(()).into_raw();
+    unsafe { report_view.as_mut().unwrap().set_read_only(true); }
+    unsafe { report_view.as_mut().unwrap().set_html(&QString::from_std_str(report)); }
+    unsafe { main_grid.as_mut().unwrap().add_widget((report_view as *mut Widget, 0, 0, 1, 1)); }
+
+    // Run the dialog.
+    unsafe { dialog.as_mut().unwrap().exec(); }
+}
+
+/// This function escapes the characters of the provided text that have a meaning in HTML, so it can be shown as plain text in our dialogs.
+pub fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
 /// This function deletes whatever it's in the right side of the screen, leaving it empty.
 /// Also, each time this triggers we consider there is no PackedFile open.
 pub fn purge_them_all(app_ui: &AppUI, packedfiles_open_in_packedfile_view: &Rc<RefCell<BTreeMap<i32, Rc<RefCell<Vec<String>>>>>>) {
@@ -969,8 +969,18 @@ impl PackedFileDBDecoder {
                                                 table_definitions_index = schema.borrow().get_table_definitions(&stuff_non_ui.packed_file_path[1]).unwrap() as i32;
                                             }
 
-                                            // We replace his fields with the ones from the TableView.
-                                            table_definition.borrow_mut().fields = Self::return_data_from_data_view(&stuff);
+                                            // We replace his fields with the ones from the TableView, keeping the metadata the decoder doesn't show.
+                                            let mut fields = Self::return_data_from_data_view(&stuff);
+                                            for field in &mut fields {
+                                                if let Some(old_field) = table_definition.borrow().fields.iter().find(|x| x.field_name == field.field_name) {
+                                                    field.field_default_value = old_field.field_default_value.clone();
+                                                    field.field_max_length = old_field.field_max_length;
+                                                    field.field_enum_values = old_field.field_enum_values.clone();
+                                                    field.field_is_bitflag = old_field.field_is_bitflag;
+                                                    if field.field_is_reference == old_field.field_is_reference { field.field_lookup = old_field.field_lookup.clone(); }
+                                                }
+                                            }
+                                            table_definition.borrow_mut().fields = fields;
 
                                             // We add our `TableDefinition` to the main `Schema` and sort it, so the TableDefinition is in the right place.
                                             schema.borrow_mut().tables_definitions[table_definitions_index as usize].add_table_definition(table_definition.borrow().clone());
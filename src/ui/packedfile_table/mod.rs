@@ -119,6 +119,7 @@ pub struct PackedFileTableView {
     pub slot_context_menu_delete: SlotBool<'static>,
     pub slot_context_menu_apply_maths_to_selection: SlotBool<'static>,
     pub slot_context_menu_rewrite_selection: SlotBool<'static>,
+    pub slot_context_menu_edit_bitflags: SlotBool<'static>,
     pub slot_context_menu_clone: SlotBool<'static>,
     pub slot_context_menu_clone_and_append: SlotBool<'static>,
     pub slot_context_menu_copy: SlotBool<'static>,
@@ -194,8 +195,8 @@ impl PackedFileTableView {
         // Get the entire dependency data for this table.
         sender_qt.send(Commands::DecodeDependencyDB).unwrap();
         sender_qt_data.send(Data::TableDefinition((&**table_definition).clone())).unwrap();
-        let dependency_data: Rc<BTreeMap<i32, Vec<String>>> = Rc::new(match check_message_validity_recv2(&receiver_qt) { 
-            Data::BTreeMapI32VecString(data) => data,
+        let dependency_data: Rc<BTreeMap<i32, BTreeMap<String, String>>> = Rc::new(match check_message_validity_recv2(&receiver_qt) { 
+            Data::BTreeMapI32BTreeMapStringString(data) => data,
             Data::Error(_) => BTreeMap::new(),
             _ => panic!(THREADS_MESSAGE_ERROR), 
         });
@@ -369,6 +370,7 @@ impl PackedFileTableView {
         let mut context_menu_apply_submenu = Menu::new(&QString::from_std_str("A&pply..."));
         let context_menu_apply_maths_to_selection = context_menu_apply_submenu.add_action(&QString::from_std_str("&Apply Maths to Selection"));
         let context_menu_rewrite_selection = context_menu_apply_submenu.add_action(&QString::from_std_str("&Rewrite Selection"));
+        let context_menu_edit_bitflags = context_menu_apply_submenu.add_action(&QString::from_std_str("Edit &Bitflags..."));
 
         let mut context_menu_clone_submenu = Menu::new(&QString::from_std_str("&Clone..."));
         let context_menu_clone = context_menu_clone_submenu.add_action(&QString::from_std_str("&Clone and Insert"));
@@ -444,6 +446,7 @@ impl PackedFileTableView {
         unsafe { table_view.as_mut().unwrap().add_action(context_menu_delete); }
         unsafe { table_view.as_mut().unwrap().add_action(context_menu_apply_maths_to_selection); }
         unsafe { table_view.as_mut().unwrap().add_action(context_menu_rewrite_selection); }
+        unsafe { table_view.as_mut().unwrap().add_action(context_menu_edit_bitflags); }
         unsafe { table_view.as_mut().unwrap().add_action(context_menu_clone); }
         unsafe { table_view.as_mut().unwrap().add_action(context_menu_clone_and_append); }
         unsafe { table_view.as_mut().unwrap().add_action(context_menu_copy); }
@@ -466,6 +469,7 @@ impl PackedFileTableView {
         unsafe { context_menu_delete.as_mut().unwrap().set_status_tip(&QString::from_std_str("Delete all the selected rows.")); }
         unsafe { context_menu_apply_maths_to_selection.as_mut().unwrap().set_status_tip(&QString::from_std_str("Apply a simple mathematical operation to every cell in the selected cells.")); }
         unsafe { context_menu_rewrite_selection.as_mut().unwrap().set_status_tip(&QString::from_std_str("Rewrite the selected cells using a pattern.")); }
+        unsafe { context_menu_edit_bitflags.as_mut().unwrap().set_status_tip(&QString::from_std_str("Choose the flags of the selected cell. Only works on fields marked as bitflags in the schema.")); }
         unsafe { context_menu_clone.as_mut().unwrap().set_status_tip(&QString::from_std_str("Duplicate the selected rows and insert the new rows under the original ones.")); }
         unsafe { context_menu_clone_and_append.as_mut().unwrap().set_status_tip(&QString::from_std_str("Duplicate the selected rows and append the new rows at the end of the table.")); }
         unsafe { context_menu_copy.as_mut().unwrap().set_status_tip(&QString::from_std_str("Copy whatever is selected to the Clipboard.")); }
@@ -805,6 +809,10 @@ impl PackedFileTableView {
                                 else { can_apply = false; break } 
                             }
                             context_menu_apply_maths_to_selection.as_mut().unwrap().set_enabled(can_apply);
+
+                            // Bitflags are edited one cell at a time.
+                            let can_edit_bitflags = indexes.count(()) == 1 && columns.len() == 1 && table_definition.fields[columns[0] as usize].field_is_bitflag;
+                            context_menu_edit_bitflags.as_mut().unwrap().set_enabled(can_edit_bitflags);
                         }
                     }
 
@@ -813,6 +821,7 @@ impl PackedFileTableView {
                         unsafe {
                             context_menu_apply_maths_to_selection.as_mut().unwrap().set_enabled(false);
                             context_menu_rewrite_selection.as_mut().unwrap().set_enabled(false);
+                            context_menu_edit_bitflags.as_mut().unwrap().set_enabled(false);
                             context_menu_clone.as_mut().unwrap().set_enabled(false);
                             context_menu_clone_and_append.as_mut().unwrap().set_enabled(false);
                             context_menu_copy.as_mut().unwrap().set_enabled(false);
@@ -870,6 +879,42 @@ impl PackedFileTableView {
                 dependency_data,
                 table_definition => move |item| {
 
+                    // Floats are edited as text, so we need to turn them back into floats, or restore their old value if they're not valid floats.
+                    let column = unsafe { item.as_mut().unwrap().column() };
+                    if table_definition.fields[column as usize].field_type == FieldType::Float {
+                        let mut blocker = unsafe { SignalBlocker::new(model.as_mut().unwrap().static_cast_mut() as &mut Object) };
+                        let text = unsafe { item.as_mut().unwrap().text().to_std_string() };
+                        match text.trim().parse::<f32>() {
+                            Ok(data) => unsafe { item.as_mut().unwrap().set_data((&Variant::new2(data), 2)) },
+                            Err(_) => {
+                                let table_state_data = table_state_data.borrow();
+                                let undo_model = table_state_data.get(&*packed_file_path.borrow()).unwrap().undo_model;
+                                let item_old = unsafe { undo_model.as_mut().unwrap().item((item.as_mut().unwrap().row(), column)) };
+                                unsafe { item.as_mut().unwrap().set_data((&item_old.as_mut().unwrap().data(2), 2)) };
+                                blocker.unblock();
+                                return
+                            }
+                        }
+                        blocker.unblock();
+                    }
+
+                    // The combos of fields with allowed values show their labels, so we need to turn them back into the values they stand for.
+                    let field = &table_definition.fields[column as usize];
+                    if !field.field_enum_values.is_empty() && !field.field_is_bitflag {
+                        let text = unsafe { item.as_mut().unwrap().text().to_std_string() };
+                        if !field.field_enum_values.contains_key(&text) {
+                            if let Some(value) = field.field_enum_values.iter().find(|(value, label)| get_enum_display_text(value, label) == text).map(|x| x.0) {
+                                let mut blocker = unsafe { SignalBlocker::new(model.as_mut().unwrap().static_cast_mut() as &mut Object) };
+                                match field.field_type {
+                                    FieldType::Integer => if let Ok(value) = value.parse::<i32>() { unsafe { item.as_mut().unwrap().set_data((&Variant::new0(value), 2)) } },
+                                    FieldType::LongInteger => if let Ok(value) = value.parse::<i64>() { unsafe { item.as_mut().unwrap().set_data((&Variant::new2(value), 2)) } },
+                                    _ => unsafe { item.as_mut().unwrap().set_text(&QString::from_std_str(value)) },
+                                }
+                                blocker.unblock();
+                            }
+                        }
+                    }
+
                     // If we are NOT UNDOING, paint the item as edited and add the edition to the undo list.
                     if !*undo_lock.borrow() {
                         {
@@ -901,6 +946,12 @@ impl PackedFileTableView {
                         }
                     }
 
+                    // Check if it respects the constraints of his field. We block the signals, so this doesn't get rettriggered again.
+                    let column = unsafe { item.as_mut().unwrap().column() };
+                    let mut blocker = unsafe { SignalBlocker::new(model.as_mut().unwrap().static_cast_mut() as &mut Object) };
+                    Self::check_field_constraints(&table_definition.fields[column as usize], item);
+                    blocker.unblock();
+
                     // If we are editing the Dependency Manager, check for PackFile errors too.
                     if let TableType::DependencyManager(_) = *table_type.borrow() { Self::check_dependency_packfile_errors(model); }
                 }
@@ -1349,6 +1400,31 @@ impl PackedFileTableView {
                 }
             )),
 
+            slot_context_menu_edit_bitflags: SlotBool::new(clone!(
+                table_definition,
+                app_ui => move |_| {
+
+                    // This is only enabled with a single bitflag cell selected, so we just need to set the value the user chose.
+                    let indexes = unsafe { filter_model.as_mut().unwrap().map_selection_to_source(&table_view.as_mut().unwrap().selection_model().as_mut().unwrap().selection()).indexes() };
+                    if indexes.count(()) == 1 {
+                        let model_index = indexes.at(0);
+                        if model_index.is_valid() {
+                            let item = unsafe { model.as_ref().unwrap().item_from_index(model_index) };
+                            let field = &table_definition.fields[unsafe { item.as_ref().unwrap().column() } as usize];
+                            let value = unsafe { item.as_ref().unwrap().text().to_std_string() }.parse::<i64>().unwrap_or(0);
+                            if let Some(new_value) = create_edit_bitflags_dialog(&app_ui, field, value) {
+                                if new_value != value {
+                                    match field.field_type {
+                                        FieldType::Integer => unsafe { item.as_mut().unwrap().set_data((&Variant::new0(new_value as i32), 2)) },
+                                        FieldType::LongInteger => unsafe { item.as_mut().unwrap().set_data((&Variant::new2(new_value), 2)) },
+                                        _ => unsafe { item.as_mut().unwrap().set_text(&QString::from_std_str(new_value.to_string())) },
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            )),
             slot_context_menu_clone: SlotBool::new(clone!(
                 global_search_explicit_paths,
                 packed_file_path,
@@ -1558,18 +1634,8 @@ impl PackedFileTableView {
                             let cell_data = match cell {
                                 DecodedData::Boolean(ref data) => if *data { "true".to_owned() } else { "false".to_owned() },
 
-                                // Floats need to be tweaked to fix trailing zeroes and precission issues, like turning 0.5000004 into 0.5.
-                                DecodedData::Float(ref data) => {
-                                    let data_str = format!("{}", data);
-
-                                    // If we have more than 3 decimals, we limit it to three, then do magic to remove trailing zeroes.
-                                    if let Some(position) = data_str.find('.') {
-                                        let decimals = &data_str[position..].len();
-                                        if *decimals > 3 { format!("{}", format!("{:.3}", data).parse::<f32>().unwrap()) }
-                                        else { data_str }
-                                    }
-                                    else { data_str }
-                                },
+                                // Floats are printed with the shortest representation that keeps their exact value.
+                                DecodedData::Float(ref data) => format!("{}", data),
                                 DecodedData::Integer(ref data) => format!("{}", data),
                                 DecodedData::LongInteger(ref data) => format!("{}", data),
 
@@ -1577,7 +1643,7 @@ impl PackedFileTableView {
                                 DecodedData::StringU8(ref data) |
                                 DecodedData::StringU16(ref data) |
                                 DecodedData::OptionalStringU8(ref data) |
-                                DecodedData::OptionalStringU16(ref data) => format!("\"{}\"", data.replace('\\', "\\\\").replace('\"', "\\\"")),
+                                DecodedData::OptionalStringU16(ref data) => format!("\"{}\"", data.replace('\\', "\\\\").replace('\"', "\\\"").replace('\n', "\\n").replace('\r', "\\r").replace('\t', "\\t")),
                             };
 
                             // And push it to the list.
@@ -1922,6 +1988,7 @@ impl PackedFileTableView {
                             if SETTINGS.lock().unwrap().settings_bool["use_dependency_checker"] && field.field_is_reference.is_some() {
                                 Self::check_references(&dependency_data, column as i32, item.as_mut_ptr());
                             }
+                            Self::check_field_constraints(field, item.as_mut_ptr());
 
                             // Add the cell to the list.
                             qlist_unordered.push((column_logical_index, item.into_raw()));
@@ -2894,6 +2961,7 @@ impl PackedFileTableView {
         unsafe { context_menu_delete.as_mut().unwrap().signals().triggered().connect(&slots.slot_context_menu_delete); }
         unsafe { context_menu_apply_maths_to_selection.as_mut().unwrap().signals().triggered().connect(&slots.slot_context_menu_apply_maths_to_selection); }
         unsafe { context_menu_rewrite_selection.as_mut().unwrap().signals().triggered().connect(&slots.slot_context_menu_rewrite_selection); }
+        unsafe { context_menu_edit_bitflags.as_mut().unwrap().signals().triggered().connect(&slots.slot_context_menu_edit_bitflags); }
         unsafe { context_menu_clone.as_mut().unwrap().signals().triggered().connect(&slots.slot_context_menu_clone); }
         unsafe { context_menu_clone_and_append.as_mut().unwrap().signals().triggered().connect(&slots.slot_context_menu_clone_and_append); }
         unsafe { context_menu_copy.as_mut().unwrap().signals().triggered().connect(&slots.slot_context_menu_copy); }
@@ -2932,6 +3000,7 @@ impl PackedFileTableView {
             context_menu_delete.as_mut().unwrap().set_enabled(false);
             context_menu_apply_maths_to_selection.as_mut().unwrap().set_enabled(false);
             context_menu_rewrite_selection.as_mut().unwrap().set_enabled(false);
+            context_menu_edit_bitflags.as_mut().unwrap().set_enabled(false);
             context_menu_clone.as_mut().unwrap().set_enabled(false);
             context_menu_clone_and_append.as_mut().unwrap().set_enabled(false);
             context_menu_copy.as_mut().unwrap().set_enabled(false);
@@ -3044,13 +3113,14 @@ impl PackedFileTableView {
         model: *mut StandardItemModel,
         data: &TableType,
         table_definition: &TableDefinition,
-        dependency_data: &BTreeMap<i32, Vec<String>>,
+        dependency_data: &BTreeMap<i32, BTreeMap<String, String>>,
     ) {
         // First, we delete all the data from the `ListStore`. Just in case there is something there.
         // This wipes out header information, so remember to run "build_columns" after this.
         unsafe { model.as_mut().unwrap().clear(); }
 
-        // Set the right data, depending on the table type you get.
+        // Set the right data, depending on the table type you get. Loc text is escaped, so it can be edited in a single line.
+        let is_loc = if let TableType::LOC(_) = data { true } else { false };
         let data = match data {
             TableType::DependencyManager(data) => &data,
             TableType::DB(data) => &data.entries,
@@ -3073,21 +3143,10 @@ impl PackedFileTableView {
                         item
                     }
 
-                    // Floats need to be tweaked to fix trailing zeroes and precission issues, like turning 0.5000004 into 0.5.
-                    // Also, they should be limited to 3 decimals.
+                    // Floats are stored as they are, so they don't lose precision when saving the table back.
                     DecodedData::Float(ref data) => {
-                        let data = {
-                            let data_str = format!("{}", data);
-                            if let Some(position) = data_str.find('.') {
-                                let decimals = &data_str[position..].len();
-                                if *decimals > 3 { format!("{:.3}", data).parse::<f32>().unwrap() }
-                                else { *data }
-                            }
-                            else { *data }
-                        };
-
                         let mut item = StandardItem::new(());
-                        item.set_data((&Variant::new2(data), 2));
+                        item.set_data((&Variant::new2(*data), 2));
                         item
                     },
                     DecodedData::Integer(ref data) => {
@@ -3101,6 +3160,7 @@ impl PackedFileTableView {
                         item
                     },
                     // All these are Strings, so it can be together,
+                    DecodedData::StringU16(ref data) if is_loc => StandardItem::new(&QString::from_std_str(&Loc::escape_text(data))),
                     DecodedData::StringU8(ref data) |
                     DecodedData::StringU16(ref data) |
                     DecodedData::OptionalStringU8(ref data) |
@@ -3111,6 +3171,7 @@ impl PackedFileTableView {
                 if SETTINGS.lock().unwrap().settings_bool["use_dependency_checker"] && table_definition.fields[index].field_is_reference.is_some() {
                     Self::check_references(dependency_data, index as i32, item.as_mut_ptr());
                 }
+                Self::check_field_constraints(&table_definition.fields[index], item.as_mut_ptr());
 
                 unsafe { qlist.append_unsafe(&item.into_raw()); }
             }
@@ -3156,12 +3217,15 @@ impl PackedFileTableView {
         }
 
         // Here we assing the ItemDelegates, so each type has his own widget with validation included.
-        // LongInteger uses normal string controls due to QSpinBox being limited to i32.
+        // Floats use an editable combo without values, as the QDoubleSpinBox rounds them to a few decimals. They're validated when edited.
         // The rest don't need any kind of validation. For now.
         for (column, field) in table_definition.fields.iter().enumerate() {
             match field.field_type {
                 FieldType::Boolean => {},
-                FieldType::Float => unsafe { qt_custom_stuff::new_doublespinbox_item_delegate(table_view as *mut Object, column as i32) },
+                FieldType::Float => {
+                    let list = StringList::new(());
+                    unsafe { qt_custom_stuff::new_combobox_item_delegate(table_view as *mut Object, column as i32, &list as *const StringList, true) };
+                },
                 FieldType::Integer => unsafe { qt_custom_stuff::new_spinbox_item_delegate(table_view as *mut Object, column as i32, 32) },
                 FieldType::LongInteger => unsafe { qt_custom_stuff::new_spinbox_item_delegate(table_view as *mut Object, column as i32, 64) },
                 FieldType::StringU8 => {},
@@ -3175,10 +3239,21 @@ impl PackedFileTableView {
         if !SETTINGS.lock().unwrap().settings_bool["disable_combos_on_tables"] {
             for (column, data) in dependency_data {
                 let mut list = StringList::new(());
-                data.iter().for_each(|x| list.append(&QString::from_std_str(x)));
+                data.keys().for_each(|x| list.append(&QString::from_std_str(x)));
                 let list: *mut StringList = &mut list;
                 unsafe { qt_custom_stuff::new_combobox_item_delegate(table_view as *mut Object, *column, list as *const StringList, true)};
             }
+
+            // Fields with a set of allowed values get a combo with them and their labels, replacing the reference one if they have it.
+            // Bitflags are edited with their own dialog, so they don't get one.
+            for (column, field) in table_definition.fields.iter().enumerate() {
+                if !field.field_enum_values.is_empty() && !field.field_is_bitflag {
+                    let mut list = StringList::new(());
+                    field.field_enum_values.iter().for_each(|(value, label)| list.append(&QString::from_std_str(get_enum_display_text(value, label))));
+                    let list: *mut StringList = &mut list;
+                    unsafe { qt_custom_stuff::new_combobox_item_delegate(table_view as *mut Object, column as i32, list as *const StringList, false)};
+                }
+            }
         }
     }
 
@@ -3192,6 +3267,7 @@ impl PackedFileTableView {
 
         // Remove every entry from the DB, then add each one again from the model.
         // The model already validates the data BEFORE accepting it, so all the data here should be valid.
+        let is_loc = if let TableType::LOC(_) = table_type { true } else { false };
         let packed_file_data = match table_type {
             TableType::DependencyManager(data) => data,
             TableType::DB(data) => &mut data.entries,
@@ -3217,6 +3293,7 @@ impl PackedFileTableView {
 
                         // All these are just normal Strings.
                         FieldType::StringU8 => DecodedData::StringU8(QString::to_std_string(&model.as_mut().unwrap().item((row as i32, column as i32)).as_mut().unwrap().text())),
+                        FieldType::StringU16 if is_loc => DecodedData::StringU16(Loc::unescape_text(&QString::to_std_string(&model.as_mut().unwrap().item((row as i32, column as i32)).as_mut().unwrap().text()))),
                         FieldType::StringU16 => DecodedData::StringU16(QString::to_std_string(&model.as_mut().unwrap().item((row as i32, column as i32)).as_mut().unwrap().text())),
                         FieldType::OptionalStringU8 => DecodedData::OptionalStringU8(QString::to_std_string(&model.as_mut().unwrap().item((row as i32, column as i32)).as_mut().unwrap().text())),
                         FieldType::OptionalStringU16 => DecodedData::OptionalStringU16(QString::to_std_string(&model.as_mut().unwrap().item((row as i32, column as i32)).as_mut().unwrap().text())),
@@ -3291,7 +3368,7 @@ impl PackedFileTableView {
     /// The rest is just usual stuff used to save tables.
     pub fn undo_redo(
         app_ui: &AppUI,
-        dependency_data: &Rc<BTreeMap<i32, Vec<String>>>,
+        dependency_data: &Rc<BTreeMap<i32, BTreeMap<String, String>>>,
         sender_qt: &Sender<Commands>,
         sender_qt_data: &Sender<Data>,
         receiver_qt: &Rc<RefCell<Receiver<Data>>>,
@@ -3784,9 +3861,9 @@ impl PackedFileTableView {
         }
     }
 
-    // Function to check if an specific field's data is in their references.
+    // Function to check if an specific field's data is in their references. If the reference has lookup data, it's shown as tooltip.
     fn check_references(
-        dependency_data: &BTreeMap<i32, Vec<String>>,
+        dependency_data: &BTreeMap<i32, BTreeMap<String, String>>,
         column: i32,
         item: *mut StandardItem,
     ) {
@@ -3794,9 +3871,41 @@ impl PackedFileTableView {
         if let Some(ref_data) = dependency_data.get(&column) {
 
             let text = unsafe { item.as_mut().unwrap().text().to_std_string() };
-            if ref_data.contains(&text) { unsafe { item.as_mut().unwrap().set_foreground(&Brush::new(if SETTINGS.lock().unwrap().settings_bool["use_dark_theme"] { GlobalColor::White } else { GlobalColor::Black })); } }
-            else if ref_data.is_empty() { unsafe { item.as_mut().unwrap().set_foreground(&Brush::new(GlobalColor::Blue)); } }
-            else { unsafe { item.as_mut().unwrap().set_foreground(&Brush::new(GlobalColor::Red)); } }
+            match ref_data.get(&text) {
+                Some(lookup) => {
+                    unsafe { item.as_mut().unwrap().set_foreground(&Brush::new(if SETTINGS.lock().unwrap().settings_bool["use_dark_theme"] { GlobalColor::White } else { GlobalColor::Black })); }
+                    unsafe { item.as_mut().unwrap().set_tool_tip(&QString::from_std_str(lookup)); }
+                }
+                None => {
+                    if ref_data.is_empty() { unsafe { item.as_mut().unwrap().set_foreground(&Brush::new(GlobalColor::Blue)); } }
+                    else { unsafe { item.as_mut().unwrap().set_foreground(&Brush::new(GlobalColor::Red)); } }
+                    unsafe { item.as_mut().unwrap().set_tool_tip(&QString::from_std_str("")); }
+                }
+            }
+        }
+    }
+
+    // Function to check if an specific field's data respects the max length and allowed values of his field.
+    // If it doesn't, the cell is painted red and the reason is shown as tooltip. Otherwise, the label of his value (if any) is shown as tooltip.
+    fn check_field_constraints(
+        field: &Field,
+        item: *mut StandardItem,
+    ) {
+        if field.field_type == FieldType::Boolean || (field.field_max_length.is_none() && field.field_enum_values.is_empty()) { return }
+
+        let text = unsafe { item.as_mut().unwrap().text().to_std_string() };
+        match field.get_constraint_error(&text) {
+            Some(error) => {
+                unsafe { item.as_mut().unwrap().set_foreground(&Brush::new(GlobalColor::Red)); }
+                unsafe { item.as_mut().unwrap().set_tool_tip(&QString::from_std_str(error)); }
+            }
+
+            // References are painted by their own check, so we only restore the color of non-reference fields.
+            None => if field.field_is_reference.is_none() {
+                let label = field.get_enum_label(&text);
+                unsafe { item.as_mut().unwrap().set_foreground(&Brush::new(if SETTINGS.lock().unwrap().settings_bool["use_dark_theme"] { GlobalColor::White } else { GlobalColor::Black })); }
+                unsafe { item.as_mut().unwrap().set_tool_tip(&QString::from_std_str(label)); }
+            }
         }
     }
 
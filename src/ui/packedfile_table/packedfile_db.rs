@@ -62,7 +62,7 @@ pub fn create_db_view(
         update_global_search_stuff,
         table_state_data,
         &table_definition,
-        Some(packed_file_path.borrow()[1].to_owned()),
+        Some(packed_file_data.db_type.to_owned()),
         &Rc::new(RefCell::new(TableType::DB(packed_file_data))),
     )
 }
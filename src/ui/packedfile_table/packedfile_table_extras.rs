@@ -10,6 +10,7 @@
 
 // In this file are all the helper functions used by the PackedFile Tables.
 
+use qt_widgets::check_box::CheckBox;
 use qt_widgets::dialog::Dialog;
 use qt_widgets::group_box::GroupBox;
 use qt_widgets::label::Label;
@@ -35,7 +36,8 @@ use std::cmp::Ordering;
 use crate::SETTINGS;
 use crate::QString;
 use crate::AppUI;
-use crate::schema::{FieldType, TableDefinition};
+use crate::packedfile::DecodedData;
+use crate::schema::{Field, TableDefinition};
 use crate::ui::create_grid_layout_unsafe;
 
 /// This function creates the entire "Apply Maths" dialog for tables. It returns the operation to apply.
@@ -120,6 +122,40 @@ And, in case you ask, works with numeric cells too, as long as the resulting tex
     } else { None }
 }
 
+/// This function creates the entire "Edit Bitflags" dialog for tables, with a checkbox for each flag of the provided field.
+/// It returns the new value of the cell, or None if the dialog is cancelled.
+pub fn create_edit_bitflags_dialog(app_ui: &AppUI, field: &Field, value: i64) -> Option<i64> {
+
+    // Create and configure the dialog.
+    let mut dialog = unsafe { Dialog::new_unsafe(app_ui.window as *mut Widget) };
+    dialog.set_window_title(&QString::from_std_str(format!("Edit Bitflags of \"{}\"", field.field_name)));
+    dialog.set_modal(true);
+    let main_grid = create_grid_layout_unsafe(dialog.static_cast_mut() as *mut Widget);
+
+    // One checkbox per flag, checked if the flag is in the current value.
+    let flags = field.get_bitflags();
+    let mut checkboxes = vec![];
+    for (index, (flag, label)) in flags.iter().enumerate() {
+        let checkbox = CheckBox::new(&QString::from_std_str(get_enum_display_text(&flag.to_string(), label))).into_raw();
+        unsafe { checkbox.as_mut().unwrap().set_checked(*flag != 0 && value & flag == *flag); }
+        unsafe { main_grid.as_mut().unwrap().add_widget((checkbox as *mut Widget, index as i32, 0, 1, 1)); }
+        checkboxes.push(checkbox);
+    }
+
+    let accept_button = PushButton::new(&QString::from_std_str("Accept")).into_raw();
+    unsafe { main_grid.as_mut().unwrap().add_widget((accept_button as *mut Widget, flags.len() as i32, 0, 1, 1)); }
+    unsafe { accept_button.as_mut().unwrap().signals().released().connect(&dialog.slots().accept()); }
+
+    if dialog.exec() == 1 {
+        Some(flags.iter().zip(checkboxes.iter()).filter(|(_, checkbox)| unsafe { checkbox.as_ref().unwrap().is_checked() }).fold(0, |value, (flag, _)| value | flag.0))
+    } else { None }
+}
+
+/// This function returns the text shown in the combos for an allowed value of a field: the value, followed by his label if it has one.
+pub fn get_enum_display_text(value: &str, label: &str) -> String {
+    if label.is_empty() { value.to_owned() } else { format!("{} - {}", value, label) }
+}
+
 /// This function sorts the VISUAL SELECTION. That means, the selection just as you see it on screen.
 /// This should be provided with the indexes OF THE VIEW/FILTER, NOT THE MODEL.
 pub fn sort_indexes_visually(indexes_sorted: &mut Vec<&ModelIndex>, table_view: *mut TableView) {
@@ -176,7 +212,8 @@ pub fn dedup_indexes_per_row(indexes: &mut Vec<ModelIndex>) {
     }
 }
 
-/// This function creates as many new empty rows with default values for each column type as you ask it for.
+/// This function creates as many new empty rows with default values for each column as you ask it for.
+/// If the field has a default value in his definition, that one is used instead of the default one of his type.
 pub fn create_empty_rows(table_definition: &TableDefinition, amount: u32) -> Vec<ListStandardItemMutPtr> {
 
     // For each field we have, just create a cell of his type with default values, and add it to the list.
@@ -184,35 +221,35 @@ pub fn create_empty_rows(table_definition: &TableDefinition, amount: u32) -> Vec
     for _ in 0..amount {
         let mut qlist = ListStandardItemMutPtr::new(());
         for field in &table_definition.fields {
-            let mut item = match field.field_type {
-                FieldType::Boolean => {
+            let mut item = match DecodedData::default_from_field(field) {
+                DecodedData::Boolean(data) => {
                     let mut item = StandardItem::new(());
                     item.set_editable(false);
                     item.set_checkable(true);
-                    item.set_check_state(CheckState::Unchecked);
+                    item.set_check_state(if data { CheckState::Checked } else { CheckState::Unchecked });
                     item
                 },
-                FieldType::Float => {
+                DecodedData::Float(data) => {
                     let mut item = StandardItem::new(());
-                    item.set_data((&Variant::new2(0.0f32), 2));
+                    item.set_data((&Variant::new2(data), 2));
                     item
                 },
-                FieldType::Integer => {
+                DecodedData::Integer(data) => {
                     let mut item = StandardItem::new(());
-                    item.set_data((&Variant::new0(0i32), 2));
+                    item.set_data((&Variant::new0(data), 2));
                     item
                 },
-                FieldType::LongInteger => {
+                DecodedData::LongInteger(data) => {
                     let mut item = StandardItem::new(());
-                    item.set_data((&Variant::new2(0i64), 2));
+                    item.set_data((&Variant::new2(data), 2));
                     item
                 },
 
                 // All these are Strings, so it can be together.
-                FieldType::StringU8 |
-                FieldType::StringU16 |
-                FieldType::OptionalStringU8 |
-                FieldType::OptionalStringU16 => StandardItem::new(&QString::from_std_str("")),
+                DecodedData::StringU8(ref data) |
+                DecodedData::StringU16(ref data) |
+                DecodedData::OptionalStringU8(ref data) |
+                DecodedData::OptionalStringU16(ref data) => StandardItem::new(&QString::from_std_str(data)),
             };
 
             // Paint the cells.
@@ -11,12 +11,16 @@
 // In this file are all the helper functions used by the UI when editing Text PackedFiles.
 
 use qt_widgets::action::Action;
+use qt_widgets::combo_box::ComboBox;
 use qt_widgets::dialog::Dialog;
 use qt_widgets::dialog_button_box::{DialogButtonBox, StandardButton};
 use qt_widgets::plain_text_edit::PlainTextEdit;
 use qt_widgets::widget::Widget;
 
+use qt_gui::text_cursor::{MoveOperation, MoveMode};
+
 use qt_core::connection::Signal;
+use qt_core::timer::Timer;
 
 use std::cell::RefCell;
 use std::rc::Rc;
@@ -27,6 +31,8 @@ use crate::AppUI;
 use crate::Commands;
 use crate::Data;
 use crate::common::communications::*;
+use crate::packedfile::lua::is_lua_script;
+use crate::packedfile::text::{Text, TEXT_ENCODINGS};
 use crate::ui::*;
 use crate::error::Result;
 
@@ -40,17 +46,24 @@ pub mod packfile_notes;
 /// Enum `TableType`: used to distinguis between DB and Loc.
 #[derive(Clone)]
 pub enum TextType {
-    PackedFile(String),
+    PackedFile(Text),
     Notes(String),
 }
 
+/// Time (in ms) without changes in the text before we save it.
+const SAVE_DELAY: i32 = 500;
+
 /// Struct `PackedFileTextView`: contains all the stuff we need to give to the program to show a
 /// `PlainTextEdit` with the data of a plain text PackedFile, allowing us to manipulate it.
 pub struct PackedFileTextView {
+    pub text_changed: SlotNoArgs<'static>,
     pub save_changes: SlotNoArgs<'static>,
     pub check_syntax: SlotNoArgs<'static>,
+    pub check_with_kailua: SlotNoArgs<'static>,
+    pub convert_encoding: SlotNoArgs<'static>,
     pub close_note: SlotNoArgs<'static>,
     pub close_note_action: *mut Action,
+    pub save_timer: *mut Timer,
 }
 
 //----------------------------------------------------------------//
@@ -74,39 +87,88 @@ impl PackedFileTextView {
     ) -> Result<Self> {
 
         let text = match *text_type.borrow() {
-            TextType::PackedFile(ref text) => text.to_owned(),
+            TextType::PackedFile(ref text) => text.contents.to_owned(),
             TextType::Notes(ref text) => text.to_owned(),
         };
 
-        // Create the PlainTextEdit and the checking button.
+        // Create the PlainTextEdit and the checking buttons.
         let plain_text_edit = PlainTextEdit::new(&QString::from_std_str(&text)).into_raw();
         let check_syntax_button = PushButton::new(&QString::from_std_str("Check Syntax")).into_raw();
+        let check_with_kailua_button = PushButton::new(&QString::from_std_str("Check with Kailua")).into_raw();
         let close_button = PushButton::new(&QString::from_std_str("Close Note")).into_raw();
+        let encoding_combobox = ComboBox::new().into_raw();
+        let convert_encoding_button = PushButton::new(&QString::from_std_str("Convert Encoding")).into_raw();
+
+        // Add it to the view. Text PackedFiles also get the encoding they'll be saved with, and the button to change it.
+        unsafe { layout.as_mut().unwrap().add_widget((plain_text_edit as *mut Widget, 0, 0, 1, 2)); }
+        if let TextType::PackedFile(ref text) = *text_type.borrow() {
+            // Lua scripts can always be checked for syntax errors. Kailua is only available if we have a types file for the game.
+            if is_lua_script(&packed_file_path.borrow()) {
+                if SUPPORTED_GAMES.get(&**GAME_SELECTED.lock().unwrap()).unwrap().ca_types_file.is_some() {
+                    unsafe { layout.as_mut().unwrap().add_widget((check_syntax_button as *mut Widget, 1, 0, 1, 1)); }
+                    unsafe { layout.as_mut().unwrap().add_widget((check_with_kailua_button as *mut Widget, 1, 1, 1, 1)); }
+                }
+                else { unsafe { layout.as_mut().unwrap().add_widget((check_syntax_button as *mut Widget, 1, 0, 1, 2)); } }
+            }
 
-        // Add it to the view.
-        unsafe { layout.as_mut().unwrap().add_widget((plain_text_edit as *mut Widget, 0, 0, 1, 1)); }
-        if let TextType::PackedFile(_) = *text_type.borrow() {
-            if packed_file_path.borrow().last().unwrap().ends_with(".lua") && SUPPORTED_GAMES.get(&**GAME_SELECTED.lock().unwrap()).unwrap().ca_types_file.is_some() {
-                unsafe { layout.as_mut().unwrap().add_widget((check_syntax_button as *mut Widget, 1, 0, 1, 1)); }
+            TEXT_ENCODINGS.iter().for_each(|x| unsafe { encoding_combobox.as_mut().unwrap().add_item(&QString::from_std_str(x.to_string())) });
+            if let Some(index) = TEXT_ENCODINGS.iter().position(|x| *x == text.encoding) {
+                unsafe { encoding_combobox.as_mut().unwrap().set_current_index(index as i32); }
             }
+            unsafe { layout.as_mut().unwrap().add_widget((encoding_combobox as *mut Widget, 2, 0, 1, 1)); }
+            unsafe { layout.as_mut().unwrap().add_widget((convert_encoding_button as *mut Widget, 2, 1, 1, 1)); }
         }
 
+        // If the last save failed, we don't report the error again until a save works, so we don't spam the user while typing.
+        let save_failed = Rc::new(RefCell::new(false));
+
+        // Saving the text means encoding it in the background thread, so we don't do it on every keystroke. Instead, we keep the
+        // last text and save it when the user stops typing for a moment. The timer is not a child of the view, so the last changes
+        // are still saved if the view is closed before it triggers.
+        let pending_text: Rc<RefCell<Option<String>>> = Rc::new(RefCell::new(None));
+        let save_timer = Timer::new().into_raw();
+        unsafe { save_timer.as_mut().unwrap().set_single_shot(true); }
+        unsafe { save_timer.as_mut().unwrap().set_interval(SAVE_DELAY); }
+
         // Create the stuff needed for this to work.
         let stuff = Self {
+            text_changed: SlotNoArgs::new(clone!(
+                pending_text => move || {
+                    *pending_text.borrow_mut() = Some(unsafe { plain_text_edit.as_mut().unwrap().to_plain_text().to_std_string() });
+                    unsafe { save_timer.as_mut().unwrap().start(()); }
+                }
+            )),
+
             save_changes: SlotNoArgs::new(clone!(
                 packed_file_path,
                 app_ui,
                 text_type,
+                save_failed,
+                pending_text,
                 receiver_qt,
                 sender_qt,
                 sender_qt_data => move || {
 
-                    // Get the text from the PlainTextEdit and save it, depending on his type.
-                    let text = unsafe { plain_text_edit.as_mut().unwrap().to_plain_text().to_std_string() };
-                    match *text_type.borrow() {
-                        TextType::PackedFile(_) => {
+                    // Get the last text from the PlainTextEdit and save it, depending on his type.
+                    let text = match pending_text.borrow_mut().take() {
+                        Some(text) => text,
+                        None => return,
+                    };
+                    let text_type = text_type.borrow().clone();
+                    match text_type {
+                        TextType::PackedFile(mut packed_file_text) => {
+                            packed_file_text.contents = text;
                             sender_qt.send(Commands::EncodePackedFileText).unwrap();
-                            sender_qt_data.send(Data::StringVecString((text, packed_file_path.borrow().to_vec()))).unwrap();
+                            sender_qt_data.send(Data::TextVecString((packed_file_text, packed_file_path.borrow().to_vec()))).unwrap();
+                            match check_message_validity_recv2(&receiver_qt) {
+                                Data::Success => *save_failed.borrow_mut() = false,
+                                Data::Error(error) => {
+                                    if !*save_failed.borrow() { show_dialog(app_ui.window, false, error); }
+                                    *save_failed.borrow_mut() = true;
+                                    return
+                                }
+                                _ => panic!(THREADS_MESSAGE_ERROR),
+                            }
 
                             update_treeview(
                                 &sender_qt,
@@ -151,6 +213,37 @@ impl PackedFileTextView {
             )),
 
             check_syntax: SlotNoArgs::new(clone!(
+                packed_file_path,
+                app_ui,
+                sender_qt,
+                receiver_qt => move || {
+
+                    // Tell the background thread to check every Lua script in the PackFile, and return the errors.
+                    sender_qt.send(Commands::CheckLuaSyntax).unwrap();
+                    let errors = match check_message_validity_recv2(&receiver_qt) {
+                        Data::VecLuaSyntaxError(data) => data,
+                        Data::Error(error) => return show_dialog(app_ui.window, false, error),
+                        _ => panic!(THREADS_MESSAGE_ERROR),
+                    };
+
+                    if errors.is_empty() { return show_dialog(app_ui.window, true, "No syntax errors found in the Lua scripts of this PackFile.") }
+
+                    // If the script we have open has an error, move the cursor to it.
+                    if let Some(error) = errors.iter().find(|x| x.path == *packed_file_path.borrow()) {
+                        let mut cursor = unsafe { plain_text_edit.as_mut().unwrap().text_cursor() };
+                        cursor.move_position(MoveOperation::Start);
+                        cursor.move_position((MoveOperation::NextBlock, MoveMode::Move, error.line as i32 - 1));
+                        cursor.move_position((MoveOperation::NextCharacter, MoveMode::Move, error.column as i32 - 1));
+                        unsafe { plain_text_edit.as_mut().unwrap().set_text_cursor(&cursor); }
+                        unsafe { plain_text_edit.as_mut().unwrap().set_focus(()); }
+                    }
+
+                    let report = errors.iter().map(|x| x.to_string()).collect::<Vec<String>>().join("\n");
+                    show_script_report_dialog(&app_ui, "Syntax Errors Found!", &report);
+                }
+            )),
+
+            check_with_kailua: SlotNoArgs::new(clone!(
                 app_ui,
                 sender_qt,
                 receiver_qt => move || {
@@ -165,29 +258,56 @@ impl PackedFileTextView {
 
                     let mut clean_result = String::new();
                     result.iter().for_each(|x| clean_result.push_str(&format!("{}\n", x)));
+                    show_script_report_dialog(&app_ui, "Script Checked!", &clean_result);
+                }
+            )),
+            convert_encoding: SlotNoArgs::new(clone!(
+                packed_file_path,
+                app_ui,
+                text_type,
+                save_failed,
+                receiver_qt,
+                sender_qt,
+                sender_qt_data => move || {
 
-                    // Create the dialog.
-                    let dialog = unsafe { Dialog::new_unsafe(app_ui.window as *mut Widget).into_raw() };
-
-                    // Create the Grid.
-                    let grid = create_grid_layout_unsafe(dialog as *mut Widget);
-
-                    // Configure the dialog.
-                    unsafe { dialog.as_mut().unwrap().set_window_title(&QString::from_std_str("Script Checked!")); }
-                    unsafe { dialog.as_mut().unwrap().set_modal(false); }
-                    unsafe { dialog.as_mut().unwrap().resize((950, 500)); }
-
-                    // Create the Text View and the ButtonBox.
-                    let mut error_report = PlainTextEdit::new(&QString::from_std_str(clean_result));
-                    let mut button_box = DialogButtonBox::new(());
-                    error_report.set_read_only(true);
-                    let close_button = button_box.add_button(StandardButton::Close);
-                    unsafe { close_button.as_mut().unwrap().signals().released().connect(&dialog.as_mut().unwrap().slots().close()); }
-                    unsafe { grid.as_mut().unwrap().add_widget((error_report.into_raw() as *mut Widget, 0, 0, 1, 1)); }
-                    unsafe { grid.as_mut().unwrap().add_widget((button_box.into_raw() as *mut Widget, 1, 0, 1, 1)); }
-
-                    // Show the Dialog, so it doesn't block the program.
-                    unsafe { dialog.as_mut().unwrap().show(); }
+                    // Save the text with the selected encoding. If it cannot be saved with it, keep the old one.
+                    let index = unsafe { encoding_combobox.as_mut().unwrap().current_index() };
+                    if index < 0 { return }
+                    let encoding = TEXT_ENCODINGS[index as usize];
+                    let old_encoding = if let TextType::PackedFile(ref mut packed_file_text) = *text_type.borrow_mut() {
+                        let old_encoding = packed_file_text.encoding;
+                        packed_file_text.encoding = encoding;
+                        packed_file_text.contents = unsafe { plain_text_edit.as_mut().unwrap().to_plain_text().to_std_string() };
+                        old_encoding
+                    } else { return };
+
+                    let packed_file_text = if let TextType::PackedFile(ref packed_file_text) = *text_type.borrow() { packed_file_text.clone() } else { return };
+                    sender_qt.send(Commands::EncodePackedFileText).unwrap();
+                    sender_qt_data.send(Data::TextVecString((packed_file_text, packed_file_path.borrow().to_vec()))).unwrap();
+                    match check_message_validity_recv2(&receiver_qt) {
+                        Data::Success => {
+                            *save_failed.borrow_mut() = false;
+                            update_treeview(
+                                &sender_qt,
+                                &sender_qt_data,
+                                &receiver_qt,
+                                &app_ui,
+                                app_ui.folder_tree_view,
+                                Some(app_ui.folder_tree_filter),
+                                app_ui.folder_tree_model,
+                                TreeViewOperation::Modify(vec![TreePathType::File(packed_file_path.borrow().to_vec())]),
+                            );
+                            show_dialog(app_ui.window, true, format!("Text PackedFile converted to {}.", encoding));
+                        }
+                        Data::Error(error) => {
+                            if let TextType::PackedFile(ref mut packed_file_text) = *text_type.borrow_mut() { packed_file_text.encoding = old_encoding; }
+                            if let Some(index) = TEXT_ENCODINGS.iter().position(|x| *x == old_encoding) {
+                                unsafe { encoding_combobox.as_mut().unwrap().set_current_index(index as i32); }
+                            }
+                            show_dialog(app_ui.window, false, error);
+                        }
+                        _ => panic!(THREADS_MESSAGE_ERROR),
+                    }
                 }
             )),
             close_note: SlotNoArgs::new(clone!(
@@ -200,11 +320,15 @@ impl PackedFileTextView {
                 }
             )),
             close_note_action: Action::new(&QString::from_std_str("&Close")).into_raw(),
+            save_timer,
         };
 
         // Actions to trigger the slots.
-        unsafe { plain_text_edit.as_ref().unwrap().signals().text_changed().connect(&stuff.save_changes); }
+        unsafe { plain_text_edit.as_ref().unwrap().signals().text_changed().connect(&stuff.text_changed); }
+        unsafe { save_timer.as_ref().unwrap().signals().timeout().connect(&stuff.save_changes); }
         unsafe { check_syntax_button.as_ref().unwrap().signals().released().connect(&stuff.check_syntax); }
+        unsafe { check_with_kailua_button.as_ref().unwrap().signals().released().connect(&stuff.check_with_kailua); }
+        unsafe { convert_encoding_button.as_ref().unwrap().signals().released().connect(&stuff.convert_encoding); }
 
         // If it's a note, add the close button to the view.
         if let TextType::Notes(_) = *text_type.borrow() {
@@ -222,3 +346,30 @@ impl PackedFileTextView {
         Ok(stuff)
     }
 }
+
+/// This function shows a non-modal dialog with the provided report of a script check.
+fn show_script_report_dialog(app_ui: &AppUI, title: &str, report: &str) {
+
+    // Create the dialog.
+    let dialog = unsafe { Dialog::new_unsafe(app_ui.window as *mut Widget).into_raw() };
+
+    // Create the Grid.
+    let grid = create_grid_layout_unsafe(dialog as *mut Widget);
+
+    // Configure the dialog.
+    unsafe { dialog.as_mut().unwrap().set_window_title(&QString::from_std_str(title)); }
+    unsafe { dialog.as_mut().unwrap().set_modal(false); }
+    unsafe { dialog.as_mut().unwrap().resize((950, 500)); }
+
+    // Create the Text View and the ButtonBox.
+    let mut error_report = PlainTextEdit::new(&QString::from_std_str(report));
+    let mut button_box = DialogButtonBox::new(());
+    error_report.set_read_only(true);
+    let close_button = button_box.add_button(StandardButton::Close);
+    unsafe { close_button.as_mut().unwrap().signals().released().connect(&dialog.as_mut().unwrap().slots().close()); }
+    unsafe { grid.as_mut().unwrap().add_widget((error_report.into_raw() as *mut Widget, 0, 0, 1, 1)); }
+    unsafe { grid.as_mut().unwrap().add_widget((button_box.into_raw() as *mut Widget, 1, 0, 1, 1)); }
+
+    // Show the Dialog, so it doesn't block the program.
+    unsafe { dialog.as_mut().unwrap().show(); }
+}
@@ -40,7 +40,7 @@ pub fn create_text_view(
     sender_qt.send(Commands::DecodePackedFileText).unwrap();
     sender_qt_data.send(Data::VecString(packed_file_path.borrow().to_vec())).unwrap();
     let text = match check_message_validity_recv2(&receiver_qt) { 
-        Data::String(data) => data,
+        Data::Text(data) => data,
         Data::Error(error) => return Err(error),
         _ => panic!(THREADS_MESSAGE_ERROR), 
     };
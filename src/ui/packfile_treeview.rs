@@ -20,6 +20,7 @@ use qt_core::qt::GlobalColor;
 use serde_derive::{Serialize, Deserialize};
 
 use std::cell::RefCell;
+use std::collections::BTreeMap;
 use std::rc::Rc;
 
 use crate::TREEVIEW_ICONS;
@@ -28,6 +29,7 @@ use crate::AppUI;
 use crate::QString;
 use crate::ui::*;
 use crate::packfile::PathType;
+use crate::packedfile::{DecodeablePackedFileType, get_packed_file_type};
 
 //----------------------------------------------------------------//
 // Enums and Structs for the TreeView.
@@ -239,6 +241,7 @@ pub fn update_treeview(
             let pack_file_name = data.0;
             let pack_file_last_modified_date = data.1;
             let mut sorted_path_list = data.2;
+            let detected_types = get_detected_packed_file_types(sender_qt, sender_qt_data, receiver_qt_data, is_extra_packfile, &sorted_path_list);
 
             // First, we clean the TreeStore and whatever was created in the TreeView.
             unsafe { model.as_mut().unwrap().clear(); }
@@ -316,7 +319,7 @@ pub fn update_treeview(
                         unsafe { file.as_mut().unwrap().set_editable(false); }
                         unsafe { file.as_mut().unwrap().set_data((&Variant::new0(1i32), 20)); }
                         unsafe { file.as_mut().unwrap().set_data((&Variant::new0(0i32), 21)); }
-                        set_icon_to_item(file, IconType::File(path.to_vec()));
+                        set_icon_to_item(file, IconType::File(path.to_vec(), get_tree_packed_file_type(path, &detected_types)));
                         unsafe { parent.as_mut().unwrap().append_row_unsafe(file); }
                     }
 
@@ -377,6 +380,8 @@ pub fn update_treeview(
         // BIG NOTE: This only works for files OR EMPTY FOLDERS. If you want to add a folder with files,
         // add his files individually, not the folder!!!
         TreeViewOperation::Add(item_types) => {
+            let paths = item_types.iter().filter_map(|x| if let TreePathType::File(path) = x { Some(path.to_vec()) } else { None }).collect::<Vec<Vec<String>>>();
+            let detected_types = get_detected_packed_file_types(sender_qt, sender_qt_data, receiver_qt_data, false, &paths);
 
             // For each path in our list of paths to add...
             for item_type in &item_types {
@@ -445,7 +450,7 @@ pub fn update_treeview(
 
                                 if let TreePathType::File(ref path) = &item_type {
                                     unsafe { item.as_mut().unwrap().set_data((&Variant::new0(1i32), 20)); }
-                                    set_icon_to_item(item, IconType::File(path.to_vec()));
+                                    set_icon_to_item(item, IconType::File(path.to_vec(), get_tree_packed_file_type(path, &detected_types)));
                                 }
 
                                 else if let TreePathType::Folder(_) = &item_type {
@@ -1332,48 +1337,34 @@ fn set_icon_to_item(
         IconType::Folder => unsafe { item.as_mut().unwrap().set_icon(&TREEVIEW_ICONS.folder); },
 
         // For files.
-        IconType::File(path) => {
-
-            // Get the name of the file.
-            let packed_file_name = path.last().unwrap();
-
-            // If it's in the "db" folder, it's a DB PackedFile (or you put something were it shouldn't be).
-            if path[0] == "db" { unsafe { item.as_mut().unwrap().set_icon(&TREEVIEW_ICONS.table); } }
-
-            // If it ends in ".loc", it's a localisation PackedFile.
-            else if packed_file_name.ends_with(".loc") { unsafe { item.as_mut().unwrap().set_icon(&TREEVIEW_ICONS.table); } }
-
-            // If it ends in ".rigid_model_v2", it's a RigidModel PackedFile.
-            else if packed_file_name.ends_with(".rigid_model_v2") { unsafe { item.as_mut().unwrap().set_icon(&TREEVIEW_ICONS.rigid_model); } }
-
-            // If it ends in any of these, it's a plain text PackedFile.
-            else if packed_file_name.ends_with(".lua") { unsafe { item.as_mut().unwrap().set_icon(&TREEVIEW_ICONS.text_generic); } }
-            else if packed_file_name.ends_with(".xml") { unsafe { item.as_mut().unwrap().set_icon(&TREEVIEW_ICONS.text_xml); } }
-            else if packed_file_name.ends_with(".xml.shader") { unsafe { item.as_mut().unwrap().set_icon(&TREEVIEW_ICONS.text_xml); } }
-            else if packed_file_name.ends_with(".xml.material") { unsafe { item.as_mut().unwrap().set_icon(&TREEVIEW_ICONS.text_xml); } }
-            else if packed_file_name.ends_with(".variantmeshdefinition") { unsafe { item.as_mut().unwrap().set_icon(&TREEVIEW_ICONS.text_xml); } }
-            else if packed_file_name.ends_with(".environment") { unsafe { item.as_mut().unwrap().set_icon(&TREEVIEW_ICONS.text_xml); } }
-            else if packed_file_name.ends_with(".lighting") { unsafe { item.as_mut().unwrap().set_icon(&TREEVIEW_ICONS.text_generic); } }
-            else if packed_file_name.ends_with(".wsmodel") { unsafe { item.as_mut().unwrap().set_icon(&TREEVIEW_ICONS.text_generic); } }
-            else if packed_file_name.ends_with(".csv") { unsafe { item.as_mut().unwrap().set_icon(&TREEVIEW_ICONS.text_csv); } }
-            else if packed_file_name.ends_with(".tsv") { unsafe { item.as_mut().unwrap().set_icon(&TREEVIEW_ICONS.text_csv); } }
-            else if packed_file_name.ends_with(".inl") { unsafe { item.as_mut().unwrap().set_icon(&TREEVIEW_ICONS.text_generic); } }
-            else if packed_file_name.ends_with(".battle_speech_camera") { unsafe { item.as_mut().unwrap().set_icon(&TREEVIEW_ICONS.text_generic); } }
-            else if packed_file_name.ends_with(".bob") { unsafe { item.as_mut().unwrap().set_icon(&TREEVIEW_ICONS.text_generic); } }
-            else if packed_file_name.ends_with(".cindyscene") { unsafe { item.as_mut().unwrap().set_icon(&TREEVIEW_ICONS.text_generic); } }
-            else if packed_file_name.ends_with(".cindyscenemanager") { unsafe { item.as_mut().unwrap().set_icon(&TREEVIEW_ICONS.text_generic); } }
-            //else if packed_file_name.ends_with(".benchmark") || // This one needs special decoding/encoding.
-            else if packed_file_name.ends_with(".txt") { unsafe { item.as_mut().unwrap().set_icon(&TREEVIEW_ICONS.text_txt); } }
-
-            // If it ends in any of these, it's an image.
-            else if packed_file_name.ends_with(".jpg") { unsafe { item.as_mut().unwrap().set_icon(&TREEVIEW_ICONS.image_jpg); } }
-            else if packed_file_name.ends_with(".jpeg") { unsafe { item.as_mut().unwrap().set_icon(&TREEVIEW_ICONS.image_jpg); } }
-            else if packed_file_name.ends_with(".tga") { unsafe { item.as_mut().unwrap().set_icon(&TREEVIEW_ICONS.image_generic); } }
-            else if packed_file_name.ends_with(".dds") { unsafe { item.as_mut().unwrap().set_icon(&TREEVIEW_ICONS.image_generic); } }
-            else if packed_file_name.ends_with(".png") { unsafe { item.as_mut().unwrap().set_icon(&TREEVIEW_ICONS.image_png); } }
-
-            // Otherwise, it's a generic file.
-            else { unsafe { item.as_mut().unwrap().set_icon(&TREEVIEW_ICONS.file); } }
+        IconType::File(path, packed_file_type) => {
+
+            // Get the name of the file, and the icon of his type. Some text and image extensions have their own icon.
+            let packed_file_name = path.last().unwrap().to_lowercase();
+            let icon = match packed_file_type {
+                DecodeablePackedFileType::DB | DecodeablePackedFileType::Loc => &TREEVIEW_ICONS.table,
+                DecodeablePackedFileType::RigidModel => &TREEVIEW_ICONS.rigid_model,
+                DecodeablePackedFileType::Text => {
+                    if packed_file_name.ends_with(".xml") ||
+                        packed_file_name.ends_with(".xml.shader") ||
+                        packed_file_name.ends_with(".xml.material") ||
+                        packed_file_name.ends_with(".variantmeshdefinition") ||
+                        packed_file_name.ends_with(".environment") { &TREEVIEW_ICONS.text_xml }
+                    else if packed_file_name.ends_with(".csv") || packed_file_name.ends_with(".tsv") { &TREEVIEW_ICONS.text_csv }
+                    else if packed_file_name.ends_with(".txt") { &TREEVIEW_ICONS.text_txt }
+                    else { &TREEVIEW_ICONS.text_generic }
+                }
+                DecodeablePackedFileType::Image => {
+                    if packed_file_name.ends_with(".jpg") || packed_file_name.ends_with(".jpeg") { &TREEVIEW_ICONS.image_jpg }
+                    else if packed_file_name.ends_with(".png") { &TREEVIEW_ICONS.image_png }
+                    else { &TREEVIEW_ICONS.image_generic }
+                }
+
+                // Otherwise, it's a generic file.
+                DecodeablePackedFileType::None => &TREEVIEW_ICONS.file,
+            };
+
+            unsafe { item.as_mut().unwrap().set_icon(icon); }
         }
     }
 }
@@ -1557,3 +1548,27 @@ fn sort_item_in_tree_view(
     }
 }
 
+/// This function asks the background thread for the types of the provided PackedFiles of a PackFile that can only be known from their data.
+/// PackedFiles with a type in the registry are not asked for, and if there is none left, we don't even ask.
+fn get_detected_packed_file_types(
+    sender_qt: &Sender<Commands>,
+    sender_qt_data: &Sender<Data>,
+    receiver_qt_data: &Rc<RefCell<Receiver<Data>>>,
+    is_extra_packfile: bool,
+    paths: &[Vec<String>],
+) -> BTreeMap<Vec<String>, DecodeablePackedFileType> {
+    let paths = paths.iter().filter(|x| get_packed_file_type(x) == DecodeablePackedFileType::None).cloned().collect::<Vec<Vec<String>>>();
+    if paths.is_empty() { return BTreeMap::new() }
+
+    sender_qt.send(Commands::GetDetectedPackedFileTypes).unwrap();
+    sender_qt_data.send(Data::BoolVecVecString((is_extra_packfile, paths))).unwrap();
+    if let Data::VecVecStringDecodeablePackedFileType(data) = check_message_validity_recv2(&receiver_qt_data) { data.into_iter().collect() } else { panic!(THREADS_MESSAGE_ERROR); }
+}
+
+/// This function returns the type of the PackedFile with the provided path, for the TreeView. His path goes first, then his data.
+fn get_tree_packed_file_type(path: &[String], detected_types: &BTreeMap<Vec<String>, DecodeablePackedFileType>) -> DecodeablePackedFileType {
+    match get_packed_file_type(path) {
+        DecodeablePackedFileType::None => detected_types.get(path).cloned().unwrap_or(DecodeablePackedFileType::None),
+        packed_file_type => packed_file_type,
+    }
+}
@@ -0,0 +1,476 @@
+//---------------------------------------------------------------------------//
+// Copyright (c) 2017-2019 Ismael Gutiérrez González. All rights reserved.
+//
+// This file is part of the Rusted PackFile Manager (RPFM) project,
+// which can be found here: https://github.com/Frodo45127/rpfm.
+//
+// This file is licensed under the MIT license, which can be found here:
+// https://github.com/Frodo45127/rpfm/blob/master/LICENSE.
+//---------------------------------------------------------------------------//
+
+// In this file are all the functions needed to create and manage the Schema Editor dialog.
+
+use qt_widgets::abstract_item_view::{EditTrigger, SelectionMode};
+use qt_widgets::combo_box::ComboBox;
+use qt_widgets::dialog::Dialog;
+use qt_widgets::label::Label;
+use qt_widgets::line_edit::LineEdit;
+use qt_widgets::message_box::{MessageBox, Icon};
+use qt_widgets::push_button::PushButton;
+use qt_widgets::table_view::TableView;
+use qt_widgets::tree_view::TreeView;
+use qt_widgets::widget::Widget;
+
+use qt_gui::list::ListStandardItemMutPtr;
+use qt_gui::standard_item::StandardItem;
+use qt_gui::standard_item_model::StandardItemModel;
+
+use qt_core::abstract_item_model::AbstractItemModel;
+use qt_core::connection::Signal;
+use qt_core::flags::Flags;
+use qt_core::qt::{CheckState, Orientation};
+use qt_core::slots::{SlotNoArgs, SlotStringRef, SlotItemSelectionRefItemSelectionRef};
+use qt_core::variant::Variant;
+
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::rc::Rc;
+use std::sync::mpsc::{Sender, Receiver};
+
+use crate::SCHEMA;
+use crate::AppUI;
+use crate::Commands;
+use crate::Data;
+use crate::QString;
+use crate::common::*;
+use crate::common::communications::*;
+use crate::error::ErrorKind;
+use crate::packedfile::db::DB;
+use crate::schema::{Field, Schema, TableDefinition};
+use crate::ui::*;
+
+/// Titles of the columns of the fields TableView, in order.
+const FIELD_COLUMNS: [&str; 10] = ["Name", "Type", "Key", "Ref. Table", "Ref. Column", "Default Value", "Max Length", "Allowed Values", "Bitflag", "Description"];
+
+/// This function creates the entire "Schema Editor" dialog. It lets you browse all the tables of the schema of
+/// the Game Selected and their versions, edit the fields of a version, copy fields between versions of the same table,
+/// delete versions and check the differences between versions. Nothing is saved until you hit "Save Schema".
+pub fn create_schema_editor_dialog(
+    app_ui: &AppUI,
+    sender_qt: &Sender<Commands>,
+    sender_qt_data: &Sender<Data>,
+    receiver_qt: &Rc<RefCell<Receiver<Data>>>,
+) {
+
+    // If we don't have an schema, there is nothing to edit.
+    let schema = match *SCHEMA.lock().unwrap() {
+        Some(ref schema) => Rc::new(RefCell::new(schema.clone())),
+        None => return show_dialog(app_ui.window, false, ErrorKind::SchemaNotFound),
+    };
+
+    //-------------------------------------------------------------------------------------------//
+    // Creating the Schema Editor Dialog...
+    //-------------------------------------------------------------------------------------------//
+
+    // Create the "Schema Editor" Dialog and configure it.
+    let dialog = unsafe { Dialog::new_unsafe(app_ui.window as *mut Widget).into_raw() };
+    unsafe { dialog.as_mut().unwrap().set_window_title(&QString::from_std_str("Schema Editor")); }
+    unsafe { dialog.as_mut().unwrap().set_modal(true); }
+    unsafe { dialog.as_mut().unwrap().resize((1200, 700)); }
+
+    // Create the main Grid and his stuff.
+    let main_grid = create_grid_layout_unsafe(dialog as *mut Widget);
+    let filter_line_edit = LineEdit::new(()).into_raw();
+    let tree_view = TreeView::new().into_raw();
+    let tree_model = StandardItemModel::new(()).into_raw();
+    let table_view = TableView::new().into_raw();
+    let table_model = StandardItemModel::new(()).into_raw();
+
+    let copy_label = Label::new(&QString::from_std_str("Copy selected fields to version:")).into_raw();
+    let copy_combobox = ComboBox::new().into_raw();
+    let copy_button = PushButton::new(&QString::from_std_str("Copy")).into_raw();
+    let diff_label = Label::new(&QString::from_std_str("Compare with version:")).into_raw();
+    let diff_combobox = ComboBox::new().into_raw();
+    let diff_button = PushButton::new(&QString::from_std_str("Show Diff")).into_raw();
+    let delete_button = PushButton::new(&QString::from_std_str("Delete Version")).into_raw();
+    let save_button = PushButton::new(&QString::from_std_str("Save Schema")).into_raw();
+
+    unsafe { filter_line_edit.as_mut().unwrap().set_placeholder_text(&QString::from_std_str("Type here to filter the tables.")); }
+    unsafe { tree_view.as_mut().unwrap().set_model(tree_model as *mut AbstractItemModel); }
+    unsafe { tree_view.as_mut().unwrap().header().as_mut().unwrap().set_visible(false); }
+    unsafe { tree_view.as_mut().unwrap().set_edit_triggers(Flags::from_enum(EditTrigger::NoEditTriggers)); }
+    unsafe { tree_view.as_mut().unwrap().set_selection_mode(SelectionMode::Single); }
+    unsafe { table_view.as_mut().unwrap().set_model(table_model as *mut AbstractItemModel); }
+    unsafe { table_view.as_mut().unwrap().set_alternating_row_colors(true); }
+    unsafe { table_view.as_mut().unwrap().horizontal_header().as_mut().unwrap().set_stretch_last_section(true); }
+
+    // Add all the widgets to the main grid.
+    unsafe { main_grid.as_mut().unwrap().add_widget((filter_line_edit as *mut Widget, 0, 0, 1, 1)); }
+    unsafe { main_grid.as_mut().unwrap().add_widget((tree_view as *mut Widget, 1, 0, 4, 1)); }
+    unsafe { main_grid.as_mut().unwrap().add_widget((table_view as *mut Widget, 0, 1, 2, 4)); }
+    unsafe { main_grid.as_mut().unwrap().add_widget((copy_label as *mut Widget, 2, 1, 1, 1)); }
+    unsafe { main_grid.as_mut().unwrap().add_widget((copy_combobox as *mut Widget, 2, 2, 1, 1)); }
+    unsafe { main_grid.as_mut().unwrap().add_widget((copy_button as *mut Widget, 2, 3, 1, 1)); }
+    unsafe { main_grid.as_mut().unwrap().add_widget((delete_button as *mut Widget, 2, 4, 1, 1)); }
+    unsafe { main_grid.as_mut().unwrap().add_widget((diff_label as *mut Widget, 3, 1, 1, 1)); }
+    unsafe { main_grid.as_mut().unwrap().add_widget((diff_combobox as *mut Widget, 3, 2, 1, 1)); }
+    unsafe { main_grid.as_mut().unwrap().add_widget((diff_button as *mut Widget, 3, 3, 1, 1)); }
+    unsafe { main_grid.as_mut().unwrap().add_widget((save_button as *mut Widget, 4, 4, 1, 1)); }
+    unsafe { main_grid.as_mut().unwrap().set_column_stretch(0, 1); }
+    unsafe { main_grid.as_mut().unwrap().set_column_stretch(1, 2); }
+
+    // Load the schema and disable everything that needs a version selected.
+    let version_widgets = [copy_combobox as *mut Widget, copy_button as *mut Widget, diff_combobox as *mut Widget, diff_button as *mut Widget, delete_button as *mut Widget];
+    load_schema_to_tree_view(tree_model, &schema.borrow(), "");
+    set_version_widgets_enabled(&version_widgets, false);
+
+    //-------------------------------------------------------------------------------------------//
+    // Actions for the Schema Editor Dialog...
+    //-------------------------------------------------------------------------------------------//
+
+    // The version (table name, version) currently loaded in the fields TableView.
+    let window = app_ui.window;
+    let current_version: Rc<RefCell<Option<(String, i32)>>> = Rc::new(RefCell::new(None));
+
+    // What happens when we change the filter of the tables.
+    let slot_filter_changed = SlotStringRef::new(clone!(
+        schema,
+        current_version => move |filter| {
+            save_table_view_to_schema(table_model, &mut schema.borrow_mut(), &current_version.borrow());
+            *current_version.borrow_mut() = None;
+            unsafe { table_model.as_mut().unwrap().clear(); }
+            load_schema_to_tree_view(tree_model, &schema.borrow(), &filter.to_std_string());
+            set_version_widgets_enabled(&version_widgets, false);
+        }
+    ));
+
+    // What happens when we select a table or a version in the TreeView.
+    let slot_version_selected = SlotItemSelectionRefItemSelectionRef::new(clone!(
+        schema,
+        current_version => move |selection, _| {
+
+            // Keep the changes done to the previous version before loading the new one.
+            save_table_view_to_schema(table_model, &mut schema.borrow_mut(), &current_version.borrow());
+            unsafe { table_model.as_mut().unwrap().clear(); }
+            unsafe { copy_combobox.as_mut().unwrap().clear(); }
+            unsafe { diff_combobox.as_mut().unwrap().clear(); }
+
+            let indexes = selection.indexes();
+            let selected_version = if indexes.count(()) == 1 {
+                let model_index = indexes.at(0);
+                let item = unsafe { tree_model.as_mut().unwrap().item_from_index(&model_index) };
+                let parent = unsafe { item.as_mut().unwrap().parent() };
+                if parent.is_null() { None }
+                else {
+                    let table_name = unsafe { parent.as_mut().unwrap().text().to_std_string() };
+                    let version = unsafe { item.as_mut().unwrap().text().to_std_string() };
+                    version.parse::<i32>().ok().map(|version| (table_name, version))
+                }
+            } else { None };
+
+            match selected_version {
+                Some((ref table_name, version)) => {
+                    if let Some(table_definitions) = schema.borrow().tables_definitions.iter().find(|x| &x.name == table_name) {
+                        if let Some(table_definition) = table_definitions.versions.iter().find(|x| x.version == version) {
+                            load_table_definition_to_table_view(table_model, table_definition);
+                        }
+
+                        for other_version in table_definitions.versions.iter().filter(|x| x.version != version) {
+                            unsafe { copy_combobox.as_mut().unwrap().add_item(&QString::from_std_str(format!("{}", other_version.version))); }
+                            unsafe { diff_combobox.as_mut().unwrap().add_item(&QString::from_std_str(format!("{}", other_version.version))); }
+                        }
+                    }
+                    set_version_widgets_enabled(&version_widgets, true);
+                }
+                None => set_version_widgets_enabled(&version_widgets, false),
+            }
+
+            *current_version.borrow_mut() = selected_version;
+        }
+    ));
+
+    // What happens when we hit the "Copy" button. The fields are inserted in the target version at the same positions they have in
+    // the current one, and the current one is left untouched, as removing fields from it would change how his tables are decoded.
+    let slot_copy_fields = SlotNoArgs::new(clone!(
+        schema,
+        current_version => move || {
+            if let Some((ref table_name, version)) = *current_version.borrow() {
+                let target_version = match unsafe { copy_combobox.as_mut().unwrap().current_text().to_std_string().parse::<i32>() } {
+                    Ok(target_version) => target_version,
+                    Err(_) => return,
+                };
+
+                // Get the selected rows, without duplicates.
+                let indexes = unsafe { table_view.as_mut().unwrap().selection_model().as_mut().unwrap().selection().indexes() };
+                let mut rows = (0..indexes.count(())).map(|x| indexes.at(x).row() as usize).collect::<Vec<usize>>();
+                rows.sort();
+                rows.dedup();
+                if rows.is_empty() { return }
+
+                save_table_view_to_schema(table_model, &mut schema.borrow_mut(), &current_version.borrow());
+
+                let mut schema = schema.borrow_mut();
+                let table_definitions = match schema.tables_definitions.iter_mut().find(|x| &x.name == table_name) {
+                    Some(table_definitions) => table_definitions,
+                    None => return,
+                };
+
+                // Fields with a name already in use in the target version cannot be copied there.
+                let fields = match table_definitions.versions.iter().find(|x| x.version == version) {
+                    Some(table_definition) => rows.iter().filter_map(|x| table_definition.fields.get(*x).map(|y| (*x, y.clone()))).collect::<Vec<(usize, Field)>>(),
+                    None => return,
+                };
+
+                if let Some(target_definition) = table_definitions.versions.iter_mut().find(|x| x.version == target_version) {
+                    if let Some((_, field)) = fields.iter().find(|(_, x)| target_definition.fields.iter().any(|y| y.field_name == x.field_name)) {
+                        return show_dialog(window, false, format!("<p>The field \"{}\" already exists in the version {} of this table.</p>", field.field_name, target_version));
+                    }
+
+                    let mut message_box = unsafe { MessageBox::new_unsafe((
+                        Icon::Warning,
+                        &QString::from_std_str("Copy Fields"),
+                        &QString::from_std_str(format!("<p>Are you sure you want to copy {} field/s to the version {} of the table \"{}\"?</p><p>This changes how the tables with that version are decoded.</p>", fields.len(), target_version, table_name)),
+                        Flags::from_int(16384) | Flags::from_int(65536),
+                        dialog as *mut Widget,
+                    )) };
+
+                    if message_box.exec() == 16384 {
+                        for (index, field) in fields {
+                            let index = if index > target_definition.fields.len() { target_definition.fields.len() } else { index };
+                            target_definition.fields.insert(index, field);
+                        }
+                    }
+                }
+            }
+        }
+    ));
+
+    // What happens when we hit the "Show Diff" button.
+    let slot_show_diff = SlotNoArgs::new(clone!(
+        schema,
+        current_version => move || {
+            if let Some((ref table_name, version)) = *current_version.borrow() {
+                let other_version = match unsafe { diff_combobox.as_mut().unwrap().current_text().to_std_string().parse::<i32>() } {
+                    Ok(other_version) => other_version,
+                    Err(_) => return,
+                };
+
+                save_table_view_to_schema(table_model, &mut schema.borrow_mut(), &current_version.borrow());
+
+                let schema = schema.borrow();
+                if let Some(table_definitions) = schema.tables_definitions.iter().find(|x| &x.name == table_name) {
+                    let table_definition = table_definitions.versions.iter().find(|x| x.version == version);
+                    let other_table_definition = table_definitions.versions.iter().find(|x| x.version == other_version);
+                    if let (Some(table_definition), Some(other_table_definition)) = (table_definition, other_table_definition) {
+                        let mut changes = vec![];
+                        table_definition.get_pretty_diff(other_table_definition, table_name, &mut changes);
+
+                        let report = if changes.is_empty() { format!("<p>There are no differences between the versions {} and {} of this table.</p>", other_version, version) }
+                        else {
+                            let changes = escape_html(&changes.join("\n"));
+                            format!("<p>Changes from the version {} to the version {}:</p><pre>{}</pre>", other_version, version, changes)
+                        };
+                        show_report_dialog(window, "Schema Diff", &report);
+                    }
+                }
+            }
+        }
+    ));
+
+    // What happens when we hit the "Delete Version" button.
+    let slot_delete_version = SlotNoArgs::new(clone!(
+        schema,
+        current_version => move || {
+            let selected_version = current_version.borrow().clone();
+            if let Some((table_name, version)) = selected_version {
+
+                let mut message_box = unsafe { MessageBox::new_unsafe((
+                    Icon::Warning,
+                    &QString::from_std_str("Delete Version"),
+                    &QString::from_std_str(format!("<p>Are you sure you want to delete the version {} of the table \"{}\"?</p><p>Versions from the schema shipped with RPFM are not removed from it, but marked as deleted in your local changes, so they stay deleted after updating the schemas.</p>", version, escape_html(&table_name))),
+                    Flags::from_int(16384) | Flags::from_int(65536),
+                    dialog as *mut Widget,
+                )) };
+
+                if message_box.exec() == 16384 {
+                    {
+                        let mut schema = schema.borrow_mut();
+                        if let Err(error) = DB::remove_table_version(&table_name, version, &mut schema) {
+                            return show_dialog(window, false, error);
+                        }
+                        schema.tables_definitions.retain(|x| !x.versions.is_empty());
+                    }
+
+                    // The deleted version is gone, so there is nothing to keep from the TableView.
+                    *current_version.borrow_mut() = None;
+                    unsafe { table_model.as_mut().unwrap().clear(); }
+                    load_schema_to_tree_view(tree_model, &schema.borrow(), &unsafe { filter_line_edit.as_mut().unwrap().text().to_std_string() });
+                    set_version_widgets_enabled(&version_widgets, false);
+                }
+            }
+        }
+    ));
+
+    // What happens when we hit the "Save Schema" button.
+    let slot_save_schema = SlotNoArgs::new(clone!(
+        schema,
+        current_version,
+        sender_qt,
+        sender_qt_data,
+        receiver_qt => move || {
+            save_table_view_to_schema(table_model, &mut schema.borrow_mut(), &current_version.borrow());
+            schema.borrow_mut().tables_definitions.sort_unstable_by(|a, b| a.name.cmp(&b.name));
+            schema.borrow_mut().tables_definitions.iter_mut().for_each(|x| x.versions.sort_unstable_by(|a, b| b.version.cmp(&a.version)));
+
+            sender_qt.send(Commands::SaveSchema).unwrap();
+            sender_qt_data.send(Data::Schema(schema.borrow().clone())).unwrap();
+            match check_message_validity_recv2(&receiver_qt) {
+                Data::Success => show_dialog(window, true, "Schema successfully saved."),
+                Data::Error(error) => show_dialog(window, false, error),
+                _ => panic!(THREADS_MESSAGE_ERROR),
+            }
+        }
+    ));
+
+    unsafe { filter_line_edit.as_mut().unwrap().signals().text_changed().connect(&slot_filter_changed); }
+    unsafe { tree_view.as_mut().unwrap().selection_model().as_ref().unwrap().signals().selection_changed().connect(&slot_version_selected); }
+    unsafe { copy_button.as_mut().unwrap().signals().released().connect(&slot_copy_fields); }
+    unsafe { diff_button.as_mut().unwrap().signals().released().connect(&slot_show_diff); }
+    unsafe { delete_button.as_mut().unwrap().signals().released().connect(&slot_delete_version); }
+    unsafe { save_button.as_mut().unwrap().signals().released().connect(&slot_save_schema); }
+
+    // Execute the dialog. Everything is done in the slots, so we don't care about the result.
+    unsafe { dialog.as_mut().unwrap().exec(); }
+}
+
+/// This function loads the tables (and their versions) of the provided schema with a name containing
+/// the provided filter into the TreeView of the Schema Editor.
+fn load_schema_to_tree_view(
+    model: *mut StandardItemModel,
+    schema: &Schema,
+    filter: &str,
+) {
+    unsafe { model.as_mut().unwrap().clear(); }
+    let filter = filter.to_lowercase();
+    for table_definitions in schema.tables_definitions.iter().filter(|x| x.name.to_lowercase().contains(&filter)) {
+        let table_item = StandardItem::new(&QString::from_std_str(&table_definitions.name)).into_raw();
+        for table_definition in &table_definitions.versions {
+            let version_item = StandardItem::new(&QString::from_std_str(format!("{}", table_definition.version))).into_raw();
+            unsafe { table_item.as_mut().unwrap().append_row_unsafe(version_item); }
+        }
+        unsafe { model.as_mut().unwrap().append_row_unsafe(table_item); }
+    }
+}
+
+/// This function loads the fields of the provided version of a table into the fields TableView of the Schema Editor.
+/// The type of the fields is not editable, as changing it would break the decoding of the table.
+fn load_table_definition_to_table_view(
+    model: *mut StandardItemModel,
+    table_definition: &TableDefinition,
+) {
+    unsafe { model.as_mut().unwrap().clear(); }
+    for field in &table_definition.fields {
+        let mut qlist = ListStandardItemMutPtr::new(());
+        let (ref_table, ref_column) = match field.field_is_reference {
+            Some((ref ref_table, ref ref_column)) => (ref_table.to_owned(), ref_column.to_owned()),
+            None => (String::new(), String::new()),
+        };
+
+        let name = StandardItem::new(&QString::from_std_str(&field.field_name));
+        let mut field_type = StandardItem::new(&QString::from_std_str(format!("{}", field.field_type)));
+        let mut is_key = StandardItem::new(());
+        let ref_table = StandardItem::new(&QString::from_std_str(ref_table));
+        let ref_column = StandardItem::new(&QString::from_std_str(ref_column));
+        let default_value = StandardItem::new(&QString::from_std_str(field.field_default_value.as_ref().map(|x| &**x).unwrap_or("")));
+        let max_length = StandardItem::new(&QString::from_std_str(field.field_max_length.map(|x| x.to_string()).unwrap_or_else(String::new)));
+        let mut enum_values = StandardItem::new(&QString::from_std_str(enum_values_to_string(&field.field_enum_values)));
+        let mut is_bitflag = StandardItem::new(());
+        let description = StandardItem::new(&QString::from_std_str(&field.field_description));
+
+        field_type.set_editable(false);
+        is_key.set_editable(false);
+        is_key.set_checkable(true);
+        is_key.set_check_state(if field.field_is_key { CheckState::Checked } else { CheckState::Unchecked });
+        enum_values.set_tool_tip(&QString::from_std_str("Allowed values, as \"value=label\" and separated by \";\". Leave it empty to allow any value."));
+        is_bitflag.set_editable(false);
+        is_bitflag.set_checkable(true);
+        is_bitflag.set_check_state(if field.field_is_bitflag { CheckState::Checked } else { CheckState::Unchecked });
+
+        unsafe { qlist.append_unsafe(&name.into_raw()); }
+        unsafe { qlist.append_unsafe(&field_type.into_raw()); }
+        unsafe { qlist.append_unsafe(&is_key.into_raw()); }
+        unsafe { qlist.append_unsafe(&ref_table.into_raw()); }
+        unsafe { qlist.append_unsafe(&ref_column.into_raw()); }
+        unsafe { qlist.append_unsafe(&default_value.into_raw()); }
+        unsafe { qlist.append_unsafe(&max_length.into_raw()); }
+        unsafe { qlist.append_unsafe(&enum_values.into_raw()); }
+        unsafe { qlist.append_unsafe(&is_bitflag.into_raw()); }
+        unsafe { qlist.append_unsafe(&description.into_raw()); }
+        unsafe { model.as_mut().unwrap().append_row(&qlist); }
+    }
+
+    for (index, title) in FIELD_COLUMNS.iter().enumerate() {
+        unsafe { model.as_mut().unwrap().set_header_data((index as i32, Orientation::Horizontal, &Variant::new0(&QString::from_std_str(title)))); }
+    }
+}
+
+/// This function saves the fields in the fields TableView of the Schema Editor into the provided version (table name, version)
+/// of the schema. The rows of the TableView are in the same order as the fields of the version, so the metadata not shown
+/// in the TableView is kept.
+fn save_table_view_to_schema(
+    model: *mut StandardItemModel,
+    schema: &mut Schema,
+    version: &Option<(String, i32)>,
+) {
+    if let Some((ref table_name, version)) = *version {
+        if let Some(table_definitions) = schema.tables_definitions.iter_mut().find(|x| &x.name == table_name) {
+            if let Some(table_definition) = table_definitions.versions.iter_mut().find(|x| x.version == version) {
+                let row_count = unsafe { model.as_mut().unwrap().row_count(()) } as usize;
+                for (row, field) in table_definition.fields.iter_mut().enumerate().take(row_count) {
+                    let row = row as i32;
+                    let ref_table = unsafe { model.as_mut().unwrap().item((row, 3)).as_mut().unwrap().text().to_std_string() };
+                    let ref_column = unsafe { model.as_mut().unwrap().item((row, 4)).as_mut().unwrap().text().to_std_string() };
+                    let field_is_reference = if ref_table.is_empty() || ref_column.is_empty() { None } else { Some((ref_table, ref_column)) };
+
+                    // If the reference changed, the lookup column is no longer valid.
+                    if field.field_is_reference != field_is_reference { field.field_lookup = None; }
+
+                    field.field_name = unsafe { model.as_mut().unwrap().item((row, 0)).as_mut().unwrap().text().to_std_string() };
+                    field.field_is_key = unsafe { model.as_mut().unwrap().item((row, 2)).as_mut().unwrap().check_state() == CheckState::Checked };
+                    field.field_is_reference = field_is_reference;
+                    // Invalid max lengths are ignored, keeping the previous one.
+                    let default_value = unsafe { model.as_mut().unwrap().item((row, 5)).as_mut().unwrap().text().to_std_string() };
+                    let max_length = unsafe { model.as_mut().unwrap().item((row, 6)).as_mut().unwrap().text().to_std_string() };
+                    let enum_values = unsafe { model.as_mut().unwrap().item((row, 7)).as_mut().unwrap().text().to_std_string() };
+                    field.field_default_value = if default_value.is_empty() { None } else { Some(default_value) };
+                    if max_length.trim().is_empty() { field.field_max_length = None; }
+                    else if let Ok(max_length) = max_length.trim().parse::<u32>() { field.field_max_length = if max_length > 0 { Some(max_length) } else { None }; }
+                    field.field_enum_values = string_to_enum_values(&enum_values);
+
+                    field.field_is_bitflag = unsafe { model.as_mut().unwrap().item((row, 8)).as_mut().unwrap().check_state() == CheckState::Checked };
+                    field.field_description = unsafe { model.as_mut().unwrap().item((row, 9)).as_mut().unwrap().text().to_std_string() };
+                }
+            }
+        }
+    }
+}
+
+/// This function turns the allowed values of a field into the text shown in the Schema Editor: "value=label" pairs separated by ";".
+fn enum_values_to_string(enum_values: &BTreeMap<String, String>) -> String {
+    enum_values.iter().map(|(value, label)| if label.is_empty() { value.to_owned() } else { format!("{}={}", value, label) }).collect::<Vec<String>>().join("; ")
+}
+
+/// This function turns the text of the allowed values of a field in the Schema Editor back into the allowed values. Empty values are ignored.
+fn string_to_enum_values(text: &str) -> BTreeMap<String, String> {
+    text.split(';').filter_map(|x| {
+        let mut pair = x.splitn(2, '=');
+        let value = pair.next().unwrap_or("").trim();
+        let label = pair.next().unwrap_or("").trim();
+        if value.is_empty() { None } else { Some((value.to_owned(), label.to_owned())) }
+    }).collect()
+}
+
+/// This function enables or disables the provided widgets of the Schema Editor.
+fn set_version_widgets_enabled(widgets: &[*mut Widget], enabled: bool) {
+    widgets.iter().for_each(|x| unsafe { x.as_mut().unwrap().set_enabled(enabled); });
+}
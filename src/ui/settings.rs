@@ -44,6 +44,7 @@ use crate::QString;
 use crate::common::*;
 use crate::common::communications::*;
 use crate::error::ErrorKind;
+use crate::schema::SCHEMA_UPDATE_URL_MASTER;
 use crate::settings::Settings;
 use super::shortcuts::ShortcutsDialog;
 use super::{create_grid_layout_unsafe, show_dialog};
@@ -67,6 +68,9 @@ pub struct SettingsDialog {
     pub extra_use_dependency_checker: *mut CheckBox,
     pub extra_use_lazy_loading_checker: *mut CheckBox,
     pub extra_optimize_not_renamed_packedfiles_checker: *mut CheckBox,
+    pub extra_optimize_float_tolerance_line_edit: *mut LineEdit,
+    pub extra_schema_update_url_line_edit: *mut LineEdit,
+    pub extra_custom_packed_file_types_line_edit: *mut LineEdit,
     pub debug_check_for_missing_table_definitions: *mut CheckBox,
 }
 
@@ -269,6 +273,9 @@ impl SettingsDialog {
         let mut use_dependency_checker_label = Label::new(&QString::from_std_str("Enable Dependency Checker for DB Tables:"));
         let mut use_lazy_loading_label = Label::new(&QString::from_std_str("Use Lazy-Loading for PackFiles:"));
         let mut optimize_not_renamed_packedfiles_label = Label::new(&QString::from_std_str("Optimize Non-Renamed PackedFiles:"));
+        let mut optimize_float_tolerance_label = Label::new(&QString::from_std_str("Float Tolerance when Optimizing:"));
+        let mut schema_update_url_label = Label::new(&QString::from_std_str("Schema Updates Source:"));
+        let mut custom_packed_file_types_label = Label::new(&QString::from_std_str("Custom PackedFile Types:"));
         
         let mut check_for_missing_table_definitions_label = Label::new(&QString::from_std_str("Check for Missing Table Definitions"));
 
@@ -278,6 +285,9 @@ impl SettingsDialog {
         let mut use_dependency_checker_checkbox = CheckBox::new(());
         let mut use_lazy_loading_checkbox = CheckBox::new(());
         let mut optimize_not_renamed_packedfiles_checkbox = CheckBox::new(());
+        let mut optimize_float_tolerance_line_edit = LineEdit::new(());
+        let mut schema_update_url_line_edit = LineEdit::new(());
+        let mut custom_packed_file_types_line_edit = LineEdit::new(());
 
         let mut check_for_missing_table_definitions_checkbox = CheckBox::new(());
 
@@ -287,6 +297,9 @@ impl SettingsDialog {
         let check_schema_updates_on_start_tip = QString::from_std_str("If you enable this, RPFM will check for schema updates at the start of the program,\nand allow you to automatically download it if there is any update available.");
         let use_dependency_checker_tip = QString::from_std_str("If you enable this, when opening a DB Table RPFM will try to get his dependencies and mark all cells with a reference to another table as 'Not Found In Table' (Red), 'Referenced Table Not Found' (Blue) or 'Correct Reference' (Black). It makes opening a big table a bit slower.");
         let use_lazy_loading_tip = QString::from_std_str("If you enable this, PackFiles will load their data on-demand from the disk instead of loading the entire PackFile to Ram. This reduces Ram usage by a lot, but if something else changes/deletes the PackFile while it's open, the PackFile will likely be unrecoverable and you'll lose whatever is in it.\nIf you mainly mod in Warhammer 2's /data folder LEAVE THIS DISABLED, as a bug in the Assembly Kit causes PackFiles to become broken/be deleted when you have this enabled.");
+        let optimize_float_tolerance_tip = QString::from_std_str("Maximum difference between two float values for the 'Optimize PackFile' feature to consider them equal.\nValues are never changed by the optimizer, this is only used to compare your rows with the vanilla ones. Set it to 0 to only remove rows that are exactly equal.");
+        let schema_update_url_tip = QString::from_std_str("Where RPFM looks for schema updates. By default it's RPFM's Github repo, but you can change it to the URL of a mirror, or to a local folder, as long as it contains the 'versions.json', the 'checksums.json' and the schemas.\nEvery schema is checked against the 'checksums.json' before replacing the installed one.");
+        let custom_packed_file_types_tip = QString::from_std_str("Extra rules to know the type of a PackedFile, checked before the default ones. They're separated by ';', and each one is 'matcher=type' or 'matcher=type:encoding'.\nThe matcher is an extension (starting with a dot) or a path. The type is 'db', 'loc', 'text', 'image', 'rigidmodel' or 'none'. 'db' rules only apply to files inside a table folder, like 'db/units_tables/'. The encoding is the one to use for text files without BOM, like 'UTF-16 LE'.\nFor example: '.ini=text; .fxc=text:UTF-16 LE; movies=none'. Invalid rules are ignored.");
         let optimize_not_renamed_packedfiles_tip = QString::from_std_str("If you enable this, when running the 'Optimize PackFile' feature RPFM will optimize Tables and Locs that have the same name as their vanilla counterparts.\nUsually, those files are intended to fully override their vanilla counterparts, so by default (this setting off) they are ignored by the optimizer. But it can be useful sometimes to optimize them too (AssKit including too many files), so that's why this setting exists.");
         
         let check_for_missing_table_definitions_tip = QString::from_std_str("If you enable this, RPFM will try to decode EVERY TABLE in the current PackFile when opening it or when changing the Game Selected, and it'll output all the tables without an schema to a \"missing_table_definitions.txt\" file.\nDEBUG FEATURE, VERY SLOW. DON'T ENABLE IT UNLESS YOU REALLY WANT TO USE IT.");
@@ -298,6 +311,9 @@ impl SettingsDialog {
         use_dependency_checker_checkbox.set_tool_tip(&use_dependency_checker_tip);
         use_lazy_loading_checkbox.set_tool_tip(&use_lazy_loading_tip);
         optimize_not_renamed_packedfiles_checkbox.set_tool_tip(&optimize_not_renamed_packedfiles_tip);
+        optimize_float_tolerance_line_edit.set_tool_tip(&optimize_float_tolerance_tip);
+        schema_update_url_line_edit.set_tool_tip(&schema_update_url_tip);
+        custom_packed_file_types_line_edit.set_tool_tip(&custom_packed_file_types_tip);
 
         check_for_missing_table_definitions_checkbox.set_tool_tip(&check_for_missing_table_definitions_tip);
 
@@ -308,6 +324,9 @@ impl SettingsDialog {
         use_dependency_checker_label.set_tool_tip(&use_dependency_checker_tip);
         use_lazy_loading_label.set_tool_tip(&use_lazy_loading_tip);
         optimize_not_renamed_packedfiles_label.set_tool_tip(&optimize_not_renamed_packedfiles_tip);
+        optimize_float_tolerance_label.set_tool_tip(&optimize_float_tolerance_tip);
+        schema_update_url_label.set_tool_tip(&schema_update_url_tip);
+        custom_packed_file_types_label.set_tool_tip(&custom_packed_file_types_tip);
 
         check_for_missing_table_definitions_label.set_tool_tip(&check_for_missing_table_definitions_tip);
 
@@ -333,6 +352,15 @@ impl SettingsDialog {
         unsafe { extra_settings_grid.as_mut().unwrap().add_widget((optimize_not_renamed_packedfiles_label.into_raw() as *mut Widget, 6, 0, 1, 1)); }
         unsafe { extra_settings_grid.as_mut().unwrap().add_widget((optimize_not_renamed_packedfiles_checkbox.static_cast_mut() as *mut Widget, 6, 1, 1, 1)); }
 
+        unsafe { extra_settings_grid.as_mut().unwrap().add_widget((optimize_float_tolerance_label.into_raw() as *mut Widget, 7, 0, 1, 1)); }
+        unsafe { extra_settings_grid.as_mut().unwrap().add_widget((optimize_float_tolerance_line_edit.static_cast_mut() as *mut Widget, 7, 1, 1, 1)); }
+
+        unsafe { extra_settings_grid.as_mut().unwrap().add_widget((schema_update_url_label.into_raw() as *mut Widget, 8, 0, 1, 1)); }
+        unsafe { extra_settings_grid.as_mut().unwrap().add_widget((schema_update_url_line_edit.static_cast_mut() as *mut Widget, 8, 1, 1, 1)); }
+
+        unsafe { extra_settings_grid.as_mut().unwrap().add_widget((custom_packed_file_types_label.into_raw() as *mut Widget, 9, 0, 1, 1)); }
+        unsafe { extra_settings_grid.as_mut().unwrap().add_widget((custom_packed_file_types_line_edit.static_cast_mut() as *mut Widget, 9, 1, 1, 1)); }
+
         unsafe { extra_settings_grid.as_mut().unwrap().add_widget((debug_settings_frame as *mut Widget, 99, 0, 1, 2)); }
 
         unsafe { debug_settings_grid.as_mut().unwrap().add_widget((check_for_missing_table_definitions_label.static_cast_mut() as *mut Widget, 0, 0, 1, 1)); }
@@ -447,6 +475,9 @@ impl SettingsDialog {
             extra_use_dependency_checker: use_dependency_checker_checkbox.into_raw(),
             extra_use_lazy_loading_checker: use_lazy_loading_checkbox.into_raw(),
             extra_optimize_not_renamed_packedfiles_checker: optimize_not_renamed_packedfiles_checkbox.into_raw(),
+            extra_optimize_float_tolerance_line_edit: optimize_float_tolerance_line_edit.into_raw(),
+            extra_schema_update_url_line_edit: schema_update_url_line_edit.into_raw(),
+            extra_custom_packed_file_types_line_edit: custom_packed_file_types_line_edit.into_raw(),
             debug_check_for_missing_table_definitions: check_for_missing_table_definitions_checkbox.into_raw(),
         };
 
@@ -517,6 +548,9 @@ impl SettingsDialog {
         unsafe { self.extra_use_dependency_checker.as_mut().unwrap().set_checked(settings.settings_bool["use_dependency_checker"]); }
         unsafe { self.extra_use_lazy_loading_checker.as_mut().unwrap().set_checked(settings.settings_bool["use_lazy_loading"]); }
         unsafe { self.extra_optimize_not_renamed_packedfiles_checker.as_mut().unwrap().set_checked(settings.settings_bool["optimize_not_renamed_packedfiles"]); }
+        unsafe { self.extra_optimize_float_tolerance_line_edit.as_mut().unwrap().set_text(&QString::from_std_str(&settings.settings_string["optimize_float_tolerance"])); }
+        unsafe { self.extra_schema_update_url_line_edit.as_mut().unwrap().set_text(&QString::from_std_str(&settings.settings_string["schema_update_url"])); }
+        unsafe { self.extra_custom_packed_file_types_line_edit.as_mut().unwrap().set_text(&QString::from_std_str(&settings.settings_string["custom_packed_file_types"])); }
 
         // Load the Debug Stuff.
         unsafe { self.debug_check_for_missing_table_definitions.as_mut().unwrap().set_checked(settings.settings_bool["check_for_missing_table_definitions"]); }
@@ -571,6 +605,20 @@ impl SettingsDialog {
         unsafe { settings.settings_bool.insert("use_lazy_loading".to_owned(), self.extra_use_lazy_loading_checker.as_mut().unwrap().is_checked()); }
         unsafe { settings.settings_bool.insert("optimize_not_renamed_packedfiles".to_owned(), self.extra_optimize_not_renamed_packedfiles_checker.as_mut().unwrap().is_checked()); }
 
+        // Only if we have a valid tolerance, we save it. Otherwise we keep the default one.
+        let float_tolerance = unsafe { self.extra_optimize_float_tolerance_line_edit.as_mut().unwrap().text().to_std_string() };
+        if let Ok(tolerance) = float_tolerance.trim().parse::<f32>() {
+            if tolerance >= 0.0 { settings.settings_string.insert("optimize_float_tolerance".to_owned(), tolerance.to_string()); }
+        }
+
+        // If the schema update source is empty, go back to the default one.
+        let schema_update_url = unsafe { self.extra_schema_update_url_line_edit.as_mut().unwrap().text().to_std_string() };
+        if schema_update_url.trim().is_empty() { settings.settings_string.insert("schema_update_url".to_owned(), SCHEMA_UPDATE_URL_MASTER.to_owned()); }
+        else { settings.settings_string.insert("schema_update_url".to_owned(), schema_update_url.trim().to_owned()); }
+
+        let custom_packed_file_types = unsafe { self.extra_custom_packed_file_types_line_edit.as_mut().unwrap().text().to_std_string() };
+        settings.settings_string.insert("custom_packed_file_types".to_owned(), custom_packed_file_types.trim().to_owned());
+
         // Get the Debug Settings.
         unsafe { settings.settings_bool.insert("check_for_missing_table_definitions".to_owned(), self.debug_check_for_missing_table_definitions.as_mut().unwrap().is_checked()); }
 
@@ -0,0 +1,353 @@
+//---------------------------------------------------------------------------//
+// Copyright (c) 2017-2019 Ismael Gutiérrez González. All rights reserved.
+//
+// This file is part of the Rusted PackFile Manager (RPFM) project,
+// which can be found here: https://github.com/Frodo45127/rpfm.
+//
+// This file is licensed under the MIT license, which can be found here:
+// https://github.com/Frodo45127/rpfm/blob/master/LICENSE.
+//---------------------------------------------------------------------------//
+
+// In this file are all the functions needed to create and manage the Translation View dialog.
+
+use qt_widgets::action::Action;
+use qt_widgets::check_box::CheckBox;
+use qt_widgets::dialog::Dialog;
+use qt_widgets::label::Label;
+use qt_widgets::line_edit::LineEdit;
+use qt_widgets::push_button::PushButton;
+use qt_widgets::table_view::TableView;
+use qt_widgets::widget::Widget;
+
+use qt_gui::brush::Brush;
+use qt_gui::list::ListStandardItemMutPtr;
+use qt_gui::slots::SlotStandardItemMutPtr;
+use qt_gui::standard_item::StandardItem;
+use qt_gui::standard_item_model::StandardItemModel;
+
+use qt_core::abstract_item_model::AbstractItemModel;
+use qt_core::connection::Signal;
+use qt_core::object::Object;
+use qt_core::qt::{CaseSensitivity, GlobalColor, Orientation};
+use qt_core::reg_exp::RegExp;
+use qt_core::signal_blocker::SignalBlocker;
+use qt_core::slots::{SlotCInt, SlotNoArgs, SlotStringRef};
+use qt_core::sort_filter_proxy_model::SortFilterProxyModel;
+use qt_core::variant::Variant;
+
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::rc::Rc;
+use std::sync::mpsc::{Sender, Receiver};
+
+use crate::SETTINGS;
+use crate::AppUI;
+use crate::Commands;
+use crate::Data;
+use crate::QString;
+use crate::common::*;
+use crate::common::communications::*;
+use crate::error::ErrorKind;
+use crate::packedfile::loc::Loc;
+use crate::packedfile::loc::translations::{TranslationData, TranslationEntry, TranslationStatus};
+use crate::ui::*;
+use crate::ui::packfile_treeview::*;
+use crate::ui::table_state::TableStateData;
+
+/// Index of the first column with the text of a Loc PackedFile. The ones before it are the key and the vanilla english text.
+const FIRST_LOC_COLUMN: i32 = 2;
+
+/// This function creates the entire "Translation View" dialog. It shows the entries of all the Loc PackedFiles of the open
+/// PackFile side by side, lined up by key, next to their vanilla english text, and highlights the missing, untranslated
+/// and stale ones (translations made from a vanilla text that changed since then). Nothing is saved until you hit "Save".
+pub fn create_translation_view_dialog(
+    app_ui: &AppUI,
+    sender_qt: &Sender<Commands>,
+    sender_qt_data: &Sender<Data>,
+    receiver_qt: &Rc<RefCell<Receiver<Data>>>,
+    packedfiles_open_in_packedfile_view: &Rc<RefCell<BTreeMap<i32, Rc<RefCell<Vec<String>>>>>>,
+    global_search_explicit_paths: &Rc<RefCell<Vec<Vec<String>>>>,
+    update_global_search_stuff: *mut Action,
+    table_state_data: &Rc<RefCell<BTreeMap<Vec<String>, TableStateData>>>,
+) {
+
+    // Get the entries of all the Loc PackedFiles. This can take a while, as it has to load the vanilla locs.
+    unsafe { (app_ui.window.as_mut().unwrap() as &mut Widget).set_enabled(false); }
+    sender_qt.send(Commands::GetTranslationData).unwrap();
+    let data = match check_message_validity_tryrecv(&receiver_qt) {
+        Data::TranslationData(data) => data,
+        Data::Error(error) => {
+            unsafe { (app_ui.window.as_mut().unwrap() as &mut Widget).set_enabled(true); }
+            return show_dialog(app_ui.window, false, error);
+        }
+        _ => panic!(THREADS_MESSAGE_ERROR),
+    };
+    unsafe { (app_ui.window.as_mut().unwrap() as &mut Widget).set_enabled(true); }
+
+    if data.locs.is_empty() { return show_dialog(app_ui.window, false, "There are no Loc PackedFiles in this PackFile."); }
+    let data = Rc::new(RefCell::new(data));
+
+    //-------------------------------------------------------------------------------------------//
+    // Creating the Translation View Dialog...
+    //-------------------------------------------------------------------------------------------//
+
+    // Create the "Translation View" Dialog and configure it.
+    let dialog = unsafe { Dialog::new_unsafe(app_ui.window as *mut Widget).into_raw() };
+    unsafe { dialog.as_mut().unwrap().set_window_title(&QString::from_std_str("Translation View")); }
+    unsafe { dialog.as_mut().unwrap().set_modal(true); }
+    unsafe { dialog.as_mut().unwrap().resize((1200, 700)); }
+
+    // Create the main Grid and his stuff.
+    let main_grid = create_grid_layout_unsafe(dialog as *mut Widget);
+    let filter_line_edit = LineEdit::new(()).into_raw();
+    let problems_checkbox = CheckBox::new(&QString::from_std_str("Only show missing, untranslated or stale entries")).into_raw();
+    let table_view = TableView::new().into_raw();
+    let model = StandardItemModel::new(()).into_raw();
+    let key_filter = SortFilterProxyModel::new().into_raw();
+    let status_filter = SortFilterProxyModel::new().into_raw();
+    let legend_label = Label::new(&QString::from_std_str("Red: missing. Yellow: untranslated. Magenta: stale, the vanilla text changed since it was translated. Hover a cell for details.")).into_raw();
+    let mark_up_to_date_button = PushButton::new(&QString::from_std_str("Mark Selection as Up to Date")).into_raw();
+    let save_button = PushButton::new(&QString::from_std_str("Save")).into_raw();
+
+    unsafe { filter_line_edit.as_mut().unwrap().set_placeholder_text(&QString::from_std_str("Type here to filter the keys. Works with Regex too!")); }
+    unsafe { key_filter.as_mut().unwrap().set_source_model(model as *mut AbstractItemModel); }
+    unsafe { key_filter.as_mut().unwrap().set_filter_key_column(0); }
+    unsafe { key_filter.as_mut().unwrap().set_filter_case_sensitivity(CaseSensitivity::Insensitive); }
+    unsafe { status_filter.as_mut().unwrap().set_source_model(key_filter as *mut AbstractItemModel); }
+    unsafe { status_filter.as_mut().unwrap().set_filter_key_column(get_status_column(&data.borrow())); }
+    unsafe { table_view.as_mut().unwrap().set_model(status_filter as *mut AbstractItemModel); }
+    unsafe { table_view.as_mut().unwrap().set_alternating_row_colors(true); }
+    unsafe { table_view.as_mut().unwrap().horizontal_header().as_mut().unwrap().set_stretch_last_section(true); }
+
+    // Add all the widgets to the main grid.
+    unsafe { main_grid.as_mut().unwrap().add_widget((filter_line_edit as *mut Widget, 0, 0, 1, 1)); }
+    unsafe { main_grid.as_mut().unwrap().add_widget((problems_checkbox as *mut Widget, 0, 1, 1, 2)); }
+    unsafe { main_grid.as_mut().unwrap().add_widget((table_view as *mut Widget, 1, 0, 1, 3)); }
+    unsafe { main_grid.as_mut().unwrap().add_widget((legend_label as *mut Widget, 2, 0, 1, 1)); }
+    unsafe { main_grid.as_mut().unwrap().add_widget((mark_up_to_date_button as *mut Widget, 2, 1, 1, 1)); }
+    unsafe { main_grid.as_mut().unwrap().add_widget((save_button as *mut Widget, 2, 2, 1, 1)); }
+    unsafe { main_grid.as_mut().unwrap().set_column_stretch(0, 1); }
+
+    // Load the data and hide the column we only use for filtering.
+    load_translation_data_to_table_view(model, &data.borrow());
+    unsafe { table_view.as_mut().unwrap().set_column_hidden(get_status_column(&data.borrow()), true); }
+    for column in 0..get_status_column(&data.borrow()) {
+        unsafe { table_view.as_mut().unwrap().set_column_width(column, 300); }
+    }
+
+    //-------------------------------------------------------------------------------------------//
+    // Actions for the Translation View Dialog...
+    //-------------------------------------------------------------------------------------------//
+
+    let app_ui = *app_ui;
+    let window = app_ui.window;
+
+    // What happens when we change the filter of the keys.
+    let slot_filter_changed = SlotStringRef::new(move |filter| {
+        unsafe { key_filter.as_mut().unwrap().set_filter_reg_exp(&RegExp::new(filter)); }
+    });
+
+    // What happens when we toggle the "Only show problems" checkbox. The status column is empty for rows without problems.
+    let slot_problems_toggled = SlotCInt::new(move |state| {
+        let pattern = if state == 2 { "." } else { "" };
+        unsafe { status_filter.as_mut().unwrap().set_filter_reg_exp(&RegExp::new(&QString::from_std_str(pattern))); }
+    });
+
+    // What happens when we edit a text. The rows of the model are in the same order as the entries.
+    let slot_item_changed = SlotStandardItemMutPtr::new(clone!(
+        data => move |item| {
+            let row = unsafe { item.as_mut().unwrap().row() };
+            let column = unsafe { item.as_mut().unwrap().column() };
+            let mut data = data.borrow_mut();
+            let index = (column - FIRST_LOC_COLUMN) as usize;
+            if column < FIRST_LOC_COLUMN || index >= data.locs.len() { return }
+
+            // Edited texts are made from the current vanilla text.
+            let text = Loc::unescape_text(&unsafe { item.as_mut().unwrap().text().to_std_string() });
+            let entry = &mut data.entries[row as usize];
+            entry.texts[index] = Some(text);
+            entry.translated_from[index] = entry.vanilla_text.clone();
+
+            let data = &*data;
+            let mut blocker = unsafe { SignalBlocker::new(model.as_mut().unwrap().static_cast_mut() as &mut Object) };
+            paint_translation_item(item, &data.entries[row as usize], index, &data.locs[index].1);
+            update_status_item(model, row, &data.entries[row as usize], data);
+            blocker.unblock();
+        }
+    ));
+
+    // What happens when we hit the "Mark Selection as Up to Date" button.
+    let slot_mark_up_to_date = SlotNoArgs::new(clone!(
+        data => move || {
+            let indexes = unsafe { status_filter.as_mut().unwrap().map_selection_to_source(&table_view.as_mut().unwrap().selection_model().as_mut().unwrap().selection()) };
+            let indexes = unsafe { key_filter.as_mut().unwrap().map_selection_to_source(&indexes).indexes() };
+
+            let mut data = data.borrow_mut();
+            let mut blocker = unsafe { SignalBlocker::new(model.as_mut().unwrap().static_cast_mut() as &mut Object) };
+            for index in 0..indexes.count(()) {
+                let model_index = indexes.at(index);
+                let (row, column) = (model_index.row(), model_index.column());
+                let index = (column - FIRST_LOC_COLUMN) as usize;
+                if column < FIRST_LOC_COLUMN || index >= data.locs.len() { continue }
+
+                let entry = &mut data.entries[row as usize];
+                if entry.texts[index].is_some() { entry.translated_from[index] = entry.vanilla_text.clone(); }
+
+                let data = &*data;
+                let item = unsafe { model.as_mut().unwrap().item((row, column)) };
+                paint_translation_item(item, &data.entries[row as usize], index, &data.locs[index].1);
+                update_status_item(model, row, &data.entries[row as usize], data);
+            }
+            blocker.unblock();
+        }
+    ));
+
+    // What happens when we hit the "Save" button.
+    let slot_save = SlotNoArgs::new(clone!(
+        data,
+        packedfiles_open_in_packedfile_view,
+        global_search_explicit_paths,
+        table_state_data,
+        sender_qt,
+        sender_qt_data,
+        receiver_qt => move || {
+
+            // Don't do anything if there is a PackedFile open. This fixes the situation where you could overwrite data already in the UI.
+            if !packedfiles_open_in_packedfile_view.borrow().is_empty() { return show_dialog(window, false, ErrorKind::PackedFileIsOpen) }
+
+            sender_qt.send(Commands::SaveTranslationData).unwrap();
+            sender_qt_data.send(Data::TranslationData(data.borrow().clone())).unwrap();
+            match check_message_validity_recv2(&receiver_qt) {
+
+                // If it's success, mark the Loc PackedFiles as modified, and remove them from the data history.
+                Data::Success => {
+                    let paths = data.borrow().locs.iter().map(|(path, _)| path.to_vec()).collect::<Vec<Vec<String>>>();
+                    update_treeview(
+                        &sender_qt,
+                        &sender_qt_data,
+                        &receiver_qt,
+                        &app_ui,
+                        app_ui.folder_tree_view,
+                        Some(app_ui.folder_tree_filter),
+                        app_ui.folder_tree_model,
+                        TreeViewOperation::Modify(paths.iter().map(|x| TreePathType::File(x.to_vec())).collect()),
+                    );
+
+                    // Update the global search stuff, if needed.
+                    global_search_explicit_paths.borrow_mut().append(&mut paths.to_vec());
+                    unsafe { update_global_search_stuff.as_mut().unwrap().trigger(); }
+
+                    for path in &paths {
+                        table_state_data.borrow_mut().insert(path.to_vec(), TableStateData::new_empty());
+                    }
+
+                    show_dialog(window, true, "Translations successfully saved.");
+                }
+                Data::Error(error) => show_dialog(window, false, error),
+                _ => panic!(THREADS_MESSAGE_ERROR),
+            }
+        }
+    ));
+
+    unsafe { filter_line_edit.as_mut().unwrap().signals().text_changed().connect(&slot_filter_changed); }
+    unsafe { problems_checkbox.as_mut().unwrap().signals().state_changed().connect(&slot_problems_toggled); }
+    unsafe { model.as_mut().unwrap().signals().item_changed().connect(&slot_item_changed); }
+    unsafe { mark_up_to_date_button.as_mut().unwrap().signals().released().connect(&slot_mark_up_to_date); }
+    unsafe { save_button.as_mut().unwrap().signals().released().connect(&slot_save); }
+
+    // Execute the dialog. Everything is done in the slots, so we don't care about the result.
+    unsafe { dialog.as_mut().unwrap().exec(); }
+}
+
+/// This function returns the index of the hidden column with the problems of each row.
+fn get_status_column(data: &TranslationData) -> i32 {
+    FIRST_LOC_COLUMN + data.locs.len() as i32
+}
+
+/// This function loads the provided `TranslationData` into the model of the Translation View. The key and the vanilla
+/// english text are not editable. Texts are escaped, like in the Loc PackedFile View.
+fn load_translation_data_to_table_view(
+    model: *mut StandardItemModel,
+    data: &TranslationData,
+) {
+    let mut blocker = unsafe { SignalBlocker::new(model.as_mut().unwrap().static_cast_mut() as &mut Object) };
+    unsafe { model.as_mut().unwrap().clear(); }
+    for (row, entry) in data.entries.iter().enumerate() {
+        let mut qlist = ListStandardItemMutPtr::new(());
+        let mut key = StandardItem::new(&QString::from_std_str(&entry.key));
+        let mut vanilla_text = StandardItem::new(&QString::from_std_str(Loc::escape_text(entry.vanilla_text.as_ref().map_or("", |x| x))));
+        key.set_editable(false);
+        vanilla_text.set_editable(false);
+
+        unsafe { qlist.append_unsafe(&key.into_raw()); }
+        unsafe { qlist.append_unsafe(&vanilla_text.into_raw()); }
+        for (index, (_, language)) in data.locs.iter().enumerate() {
+            let item = StandardItem::new(&QString::from_std_str(Loc::escape_text(entry.texts[index].as_ref().map_or("", |x| x)))).into_raw();
+            paint_translation_item(item, entry, index, language);
+            unsafe { qlist.append_unsafe(&item); }
+        }
+        unsafe { qlist.append_unsafe(&StandardItem::new(()).into_raw()); }
+        unsafe { model.as_mut().unwrap().append_row(&qlist); }
+        update_status_item(model, row as i32, entry, data);
+    }
+
+    let mut titles = vec!["Key".to_owned(), "Vanilla (English)".to_owned()];
+    for (path, language) in &data.locs {
+        match language {
+            Some(language) => titles.push(format!("{} ({})", path.join("/"), language)),
+            None => titles.push(path.join("/")),
+        }
+    }
+    titles.push("Status".to_owned());
+
+    for (index, title) in titles.iter().enumerate() {
+        unsafe { model.as_mut().unwrap().set_header_data((index as i32, Orientation::Horizontal, &Variant::new0(&QString::from_std_str(title)))); }
+    }
+    blocker.unblock();
+}
+
+/// This function paints the item of the provided entry in the Loc PackedFile with the provided index depending on his status,
+/// and sets a tooltip explaining it. Remember to block the signals of the model before calling it.
+fn paint_translation_item(
+    item: *mut StandardItem,
+    entry: &TranslationEntry,
+    index: usize,
+    language: &Option<String>,
+) {
+    let use_dark_theme = SETTINGS.lock().unwrap().settings_bool["use_dark_theme"];
+    let (color, tooltip) = match entry.get_status(index, language) {
+        TranslationStatus::Translated => (None, String::new()),
+        TranslationStatus::Missing => (Some(if use_dark_theme { GlobalColor::DarkRed } else { GlobalColor::Red }), "This key is missing in this Loc PackedFile.".to_owned()),
+        TranslationStatus::Untranslated => (Some(if use_dark_theme { GlobalColor::DarkYellow } else { GlobalColor::Yellow }), "This entry is not translated.".to_owned()),
+        TranslationStatus::Stale => (Some(if use_dark_theme { GlobalColor::DarkMagenta } else { GlobalColor::Magenta }), format!(
+            "The vanilla text changed since this entry was translated.\n\nTranslated from:\n{}\n\nCurrent vanilla text:\n{}",
+            entry.translated_from[index].as_ref().map_or("", |x| x),
+            entry.vanilla_text.as_ref().map_or("", |x| x),
+        )),
+    };
+
+    // 8 is the BackgroundRole. An empty Variant removes the background.
+    match color {
+        Some(color) => unsafe { item.as_mut().unwrap().set_background(&Brush::new(color)); }
+        None => unsafe { item.as_mut().unwrap().set_data((&Variant::new0(()), 8)); }
+    }
+    unsafe { item.as_mut().unwrap().set_tool_tip(&QString::from_std_str(tooltip)); }
+}
+
+/// This function updates the hidden status column of the provided row with the problems of the entry. It's empty if there are none.
+fn update_status_item(
+    model: *mut StandardItemModel,
+    row: i32,
+    entry: &TranslationEntry,
+    data: &TranslationData,
+) {
+    let status = data.locs.iter().enumerate()
+        .map(|(index, (_, language))| entry.get_status(index, language))
+        .filter(|x| *x != TranslationStatus::Translated)
+        .map(|x| format!("{:?}", x))
+        .collect::<Vec<String>>()
+        .join(" ");
+
+    let item = unsafe { model.as_mut().unwrap().item((row, get_status_column(data))) };
+    unsafe { item.as_mut().unwrap().set_text(&QString::from_std_str(status)); }
+}
@@ -10,6 +10,7 @@
 
 // Here it goes all the stuff related to the UI part of the "Update Checker" and the future "Autoupdater".
 
+use qt_widgets::file_dialog::FileDialog;
 use qt_widgets::message_box;
 use qt_widgets::message_box::MessageBox;
 use qt_widgets::widget::Widget;
@@ -188,7 +189,7 @@ pub fn check_schema_updates(
                 message
             }
             APIResponseSchema::SuccessNoUpdate => "<h4>No new schema updates available</h4> <p>More luck next time :)</p>".to_owned(),
-            APIResponseSchema::Error => "<h4>Error while checking new updates :(</h4> <p>If you see this message, there has been a problem with your connection to the Github.com server. Please, make sure you can access to <a href=\"https://api.github.com\">https://api.github.com</a> and try again.</p>".to_owned(),
+            APIResponseSchema::Error => "<h4>Error while checking new updates :(</h4> <p>If you see this message, there has been a problem with your connection to the source of the schema updates. Please, make sure you can access to it (by default, <a href=\"https://raw.githubusercontent.com\">https://raw.githubusercontent.com</a>), or that the source set in the settings is correct, and try again.</p>".to_owned(),
         };
 
         // Change the text of the dialog with the updated message.
@@ -206,7 +207,7 @@ pub fn check_schema_updates(
                 unsafe { update_button.as_mut().unwrap().set_enabled(false); }
 
                 match check_message_validity_tryrecv(&receiver_qt) {
-                    Data::Success => show_dialog(app_ui.window, true, "<h4>Schemas updated and reloaded</h4><p>You can continue using RPFM now.</p>"),
+                    Data::VecString(conflicts) => show_schema_update_result(app_ui, &conflicts),
                     Data::Error(error) => show_dialog(app_ui.window, true, error),
                     _ => panic!(THREADS_MESSAGE_ERROR),
                 }
@@ -264,7 +265,7 @@ pub fn check_schema_updates(
                 unsafe { update_button.as_mut().unwrap().set_enabled(false); }
 
                 match check_message_validity_tryrecv(&receiver_qt) {
-                    Data::Success => show_dialog(app_ui.window, true, "<h4>Schemas updated and reloaded</h4><p>You can continue using RPFM now.</p>"),
+                    Data::VecString(conflicts) => show_schema_update_result(app_ui, &conflicts),
                     Data::Error(error) => show_dialog(app_ui.window, true, error),
                     _ => panic!(THREADS_MESSAGE_ERROR),
                 }
@@ -273,6 +274,50 @@ pub fn check_schema_updates(
     }
 }
 
+/// This function updates the schemas from a local folder (by selecting his `versions.json`) or a `.tar`/`.tar.xz` archive,
+/// for when we cannot reach the source of the schema updates.
+pub fn update_schemas_from_file(
+    app_ui: &AppUI,
+    sender_qt: &Sender<Commands>,
+    sender_qt_data: &Sender<Data>,
+    receiver_qt: &Rc<RefCell<Receiver<Data>>>,
+) {
+
+    // Create the FileDialog to get the source of the update.
+    let mut file_dialog = unsafe { FileDialog::new_unsafe((
+        app_ui.window as *mut Widget,
+        &QString::from_std_str("Select Schema Update"),
+    )) };
+    file_dialog.set_name_filter(&QString::from_std_str("Schema Updates (versions.json *.tar *.tar.xz *.txz)"));
+
+    // Run it and expect a response (1 => Accept, 0 => Cancel).
+    if file_dialog.exec() == 1 {
+        let path = PathBuf::from(file_dialog.selected_files().at(0).to_std_string());
+        unsafe { (app_ui.window.as_mut().unwrap() as &mut Widget).set_enabled(false); }
+        sender_qt.send(Commands::UpdateSchemasFromPath).unwrap();
+        sender_qt_data.send(Data::PathBuf(path)).unwrap();
+        match check_message_validity_tryrecv(&receiver_qt) {
+            Data::VecString(conflicts) => show_schema_update_result(app_ui, &conflicts),
+            Data::Success => show_dialog(app_ui.window, true, "<h4>No new schema updates available</h4> <p>The schemas in the selected update are not newer than the ones you have.</p>"),
+            Data::Error(error) => show_dialog(app_ui.window, false, error),
+            _ => panic!(THREADS_MESSAGE_ERROR),
+        }
+        unsafe { (app_ui.window.as_mut().unwrap() as &mut Widget).set_enabled(true); }
+    }
+}
+
+/// This function shows the result of an schema update, reporting the definitions of the local schemas
+/// that the update defines in a different way, if any.
+fn show_schema_update_result(app_ui: &AppUI, conflicts: &[String]) {
+    if conflicts.is_empty() { show_dialog(app_ui.window, true, "<h4>Schemas updated and reloaded</h4><p>You can continue using RPFM now.</p>"); }
+    else {
+        let mut message = "<h4>Schemas updated and reloaded</h4><p>The following definitions of your local schemas are defined in a different way by the update. Your local definitions are still the ones in use:</p><ul>".to_owned();
+        conflicts.iter().for_each(|x| message.push_str(&format!("<li>{}</li>", escape_html(x))));
+        message.push_str("</ul>");
+        show_dialog(app_ui.window, true, message);
+    }
+}
+
 /// This function check network stuff based on what operation we pass it. It REQUIRES to be executed
 /// in a different thread.
 fn network_thread(
@@ -386,10 +431,9 @@ fn network_thread(
         // When we want to check if there is a schema's update available...
         "check_schema_updates" => {
             let apiresponse = 
-                if let Ok(mut remote_versions) = reqwest::get("https://raw.githubusercontent.com/Frodo45127/rpfm/master/schemas/versions.json") {
-                    if let Ok(remote_versions) = remote_versions.json() {
+                if let Ok(source) = SchemaUpdateSource::from_settings() {
+                    if let Ok(remote_versions) = source.get_versions() {
 
-                        let remote_versions: Versions = remote_versions;
                         let local_versions: Versions = serde_json::from_reader(BufReader::new(File::open(RPFM_PATH.to_path_buf().join(PathBuf::from("schemas/versions.json"))).unwrap())).unwrap();
 
                         // If both versions are equal, we have no updates.
@@ -13,18 +13,37 @@
 use restson::RestPath;
 use serde_derive::{Serialize, Deserialize};
 
+use xz2::read::XzDecoder;
+
 use std::collections::BTreeMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::fs::File;
-use std::io::{Write, BufWriter};
+use std::io::{Read, Write, BufReader, BufWriter};
 
 use crate::RPFM_PATH;
-use crate::schema::{SCHEMA_UPDATE_URL_MASTER, Schema};
-use crate::error;
+use crate::SETTINGS;
+use crate::schema::{SCHEMA_CHECKSUMS_FILE, SCHEMA_UPDATE_URL_MASTER, SCHEMA_VERSIONS_FILE, Schema};
+use crate::error::{self, ErrorKind};
 
 /// Custom type for the versions of the schemas.
 pub type Versions = BTreeMap<String, u32>;
 
+/// Custom type for the checksums of the schemas.
+pub type Checksums = BTreeMap<String, String>;
+
+/// This enum represents the places from where we can get schema updates.
+pub enum SchemaUpdateSource {
+
+    /// An URL to a folder with the schemas, like the one in RPFM's repo, or a mirror of it.
+    Url(String),
+
+    /// A local folder with the schemas.
+    Folder(PathBuf),
+
+    /// The files inside a `.tar` or `.tar.xz` archive with the schemas, by their path relative to the folder with the `versions.json`.
+    Archive(BTreeMap<String, Vec<u8>>),
+}
+
 #[derive(Serialize,Deserialize,Debug)]
 pub struct LastestRelease {
     pub name: String,
@@ -38,6 +57,73 @@ pub struct Schemas {
     pub version: u32,
 }
 
+/// Implementation of `SchemaUpdateSource`.
+impl SchemaUpdateSource {
+
+    /// This function returns the source for schema updates set in the settings. It can be an URL or a local path.
+    pub fn from_settings() -> error::Result<Self> {
+        let source = SETTINGS.lock().unwrap().settings_string.get("schema_update_url").cloned().unwrap_or_else(|| SCHEMA_UPDATE_URL_MASTER.to_owned());
+        if source.starts_with("http://") || source.starts_with("https://") { Ok(SchemaUpdateSource::Url(source.trim_end_matches('/').to_owned())) }
+        else { Self::from_path(&PathBuf::from(source)) }
+    }
+
+    /// This function returns the source for schema updates in the provided path. It can be a folder, the `versions.json` file
+    /// inside a folder, or a `.tar`/`.tar.xz` archive.
+    pub fn from_path(path: &Path) -> error::Result<Self> {
+        let file_name = path.file_name().map(|x| x.to_string_lossy().to_lowercase()).unwrap_or_else(String::new);
+        if path.is_dir() { Ok(SchemaUpdateSource::Folder(path.to_path_buf())) }
+        else if path.is_file() && file_name == SCHEMA_VERSIONS_FILE { Ok(SchemaUpdateSource::Folder(path.parent().unwrap().to_path_buf())) }
+        else if path.is_file() && file_name.ends_with(".tar") {
+            let mut data = vec![];
+            BufReader::new(File::open(path)?).read_to_end(&mut data)?;
+            Ok(SchemaUpdateSource::Archive(get_archive_schemas_files(read_tar(&data)?)))
+        }
+        else if path.is_file() && (file_name.ends_with(".tar.xz") || file_name.ends_with(".txz")) {
+            let mut data = vec![];
+            XzDecoder::new(BufReader::new(File::open(path)?)).read_to_end(&mut data)?;
+            Ok(SchemaUpdateSource::Archive(get_archive_schemas_files(read_tar(&data)?)))
+        }
+        else { Err(ErrorKind::SchemaUpdateSourceNotValid(path.to_string_lossy().to_string()))? }
+    }
+
+    /// This function returns the data of the file with the provided name from this source, if it exists.
+    pub fn get_file(&self, file_name: &str) -> error::Result<Option<Vec<u8>>> {
+        match self {
+
+            // NOTE: Github's API has a limit of 1MB per file, so we take them directly from raw.githubusercontent.com instead.
+            SchemaUpdateSource::Url(url) => {
+                let response = reqwest::get(&format!("{}/{}", url, file_name))?;
+                if response.status() == reqwest::StatusCode::NOT_FOUND { return Ok(None) }
+
+                let mut data = vec![];
+                response.error_for_status()?.copy_to(&mut data)?;
+                Ok(Some(data))
+            }
+            SchemaUpdateSource::Folder(path) => {
+                let path = path.join(file_name);
+                if path.is_file() {
+                    let mut data = vec![];
+                    BufReader::new(File::open(path)?).read_to_end(&mut data)?;
+                    Ok(Some(data))
+                } else { Ok(None) }
+            }
+            SchemaUpdateSource::Archive(files) => Ok(files.get(file_name).cloned()),
+        }
+    }
+
+    /// This function returns the versions of the schemas in this source.
+    pub fn get_versions(&self) -> error::Result<Versions> {
+        let data = self.get_file(SCHEMA_VERSIONS_FILE)?.ok_or_else(|| ErrorKind::SchemaUpdateFileNotFound(SCHEMA_VERSIONS_FILE.to_owned()))?;
+        Ok(serde_json::from_slice(&data)?)
+    }
+
+    /// This function returns the checksums of the schemas in this source.
+    pub fn get_checksums(&self) -> error::Result<Checksums> {
+        let data = self.get_file(SCHEMA_CHECKSUMS_FILE)?.ok_or_else(|| ErrorKind::SchemaUpdateFileNotFound(SCHEMA_CHECKSUMS_FILE.to_owned()))?;
+        Ok(serde_json::from_slice(&data)?)
+    }
+}
+
 // Path of the REST endpoint: e.g. http://<baseurl>/anything
 impl RestPath<()> for LastestRelease {
     fn get_path(_: ()) -> Result<String, restson::Error> {
@@ -45,38 +131,145 @@ impl RestPath<()> for LastestRelease {
     }
 }
 
-/// This function gets the lastest version of the schemas from RPFM's main repo, and updates them if needed.
+/// This function gets the schemas with a newer version in the provided source, checks them and, if all of them are correct, updates them.
+///
+/// The source must have a `checksums.json` file, and every schema to update must be in it, and match his checksum. If any schema
+/// is missing or doesn't pass the checks, no schema is updated.
+///
+/// Local overlays of the schemas are never touched. Instead, it returns the list of definitions in them that
+/// the updated schemas define in a different way.
 pub fn update_schemas(
+    source: &SchemaUpdateSource,
     local_versions: &Versions,
     remote_versions: &Versions,
-) -> error::Result<()> {
+) -> error::Result<Vec<String>> {
 
-    // For each schema in the repo, get his equivalent local_schema's path.
+    // First, we get and check all the schemas we need to update. Only when all of them are correct, we save them.
+    let checksums = source.get_checksums()?;
+    let mut schemas = vec![];
     for (remote_schema_name, remote_schema_version) in remote_versions {
 
-        // If the schema exist in our local_versions, depending on the version we update it or not.
+        // The names of the schemas are used as file names, so we don't allow anything but schema files in the schemas folder.
+        if !is_valid_schema_file_name(remote_schema_name) { return Err(ErrorKind::SchemaUpdateInvalidFileName(remote_schema_name.to_owned()))? }
+
+        // If the schema exist in our local_versions, depending on the version we update it or not. Otherwise, it's a new schema.
         if let Some(local_schema_version) = local_versions.get(remote_schema_name) {
+            if remote_schema_version <= local_schema_version { continue; }
+        }
 
-            // If it's an update over our own schema, we download it and overwrite the current schema.
-            // NOTE: Github's API has a limit of 1MB per file, so we take it directly from raw.githubusercontent.com instead.
-            if remote_schema_version > local_schema_version {
-                let response: Schema = reqwest::get(&format!("{}/{}", SCHEMA_UPDATE_URL_MASTER, remote_schema_name))?.json()?;
-                response.save(remote_schema_name)?;
-            }
+        let data = source.get_file(remote_schema_name)?.ok_or_else(|| ErrorKind::SchemaUpdateFileNotFound(remote_schema_name.to_owned()))?;
+        match checksums.get(remote_schema_name) {
+            Some(checksum) => if !checksum.eq_ignore_ascii_case(&get_checksum(&data)) { return Err(ErrorKind::SchemaUpdateChecksumMismatch(remote_schema_name.to_owned()))? },
+            None => return Err(ErrorKind::SchemaUpdateChecksumMismatch(remote_schema_name.to_owned()))?,
         }
 
-        // Otherwise, it's a new schema, so we just download it.
-        else {
-            let response: Schema = reqwest::get(&format!("{}/{}", SCHEMA_UPDATE_URL_MASTER, remote_schema_name))?.json()?;
-            response.save(remote_schema_name)?;
+        let schema: Schema = serde_json::from_slice(&data)?;
+        schemas.push((remote_schema_name, *remote_schema_version, schema));
+    }
+
+    // Then we save them, and check them against our local overlays.
+    let mut conflicts = vec![];
+    let mut versions = local_versions.clone();
+    for (schema_name, schema_version, schema) in &schemas {
+        schema.save(schema_name)?;
+        versions.insert(schema_name.to_string(), *schema_version);
+        if let Some(local_schema) = Schema::load_local(schema_name)? {
+            conflicts.extend(schema.get_local_conflicts(&local_schema).iter().map(|x| format!("{}: {}", schema_name, x)));
+        }
+    }
+
+    // Now we update the "versions.json" to reflect the update. Like the schemas, it's written to a temporal file first, so it's never left half-written.
+    let versions_path = RPFM_PATH.to_path_buf().join(PathBuf::from("schemas")).join(SCHEMA_VERSIONS_FILE);
+    let versions_temp_path = versions_path.with_file_name(format!("{}.tmp", SCHEMA_VERSIONS_FILE));
+    {
+        let mut file = BufWriter::new(File::create(&versions_temp_path)?);
+        file.write_all(serde_json::to_string_pretty(&versions)?.as_bytes())?;
+    }
+    std::fs::rename(&versions_temp_path, &versions_path)?;
+
+    // If we reach this place, return the conflicts with the local overlays.
+    Ok(conflicts)
+}
+
+/// This function returns if the provided remote versions have any schema newer than (or missing from) the local ones.
+pub fn has_schema_updates(local_versions: &Versions, remote_versions: &Versions) -> bool {
+    remote_versions.iter().any(|(schema_name, remote_version)| local_versions.get(schema_name).map_or(true, |local_version| remote_version > local_version))
+}
+
+/// This function returns if the provided name is a valid name for a schema file: `schema_xxx.json`, with only letters, numbers
+/// and underscores in the `xxx` part, so it cannot point outside the schemas folder or to a local overlay.
+pub fn is_valid_schema_file_name(schema_name: &str) -> bool {
+    schema_name.starts_with("schema_") &&
+        schema_name.ends_with(".json") &&
+        schema_name.len() > 12 &&
+        schema_name[7..schema_name.len() - 5].chars().all(|x| x.is_ascii_alphanumeric() || x == '_')
+}
+
+/// This function returns the checksum (CRC32, as an hexadecimal string) of the provided data.
+pub fn get_checksum(data: &[u8]) -> String {
+    format!("{:08x}", crc32fast::hash(data))
+}
+
+/// This function returns the regular files inside the provided `.tar` archive, by their full path inside the archive.
+/// It supports the long names of GNU and ustar archives. Any other kind of entry (folders, links, PAX headers,...) is ignored.
+pub fn read_tar(data: &[u8]) -> error::Result<BTreeMap<String, Vec<u8>>> {
+    let mut files = BTreeMap::new();
+    let mut long_name = None;
+    let mut index = 0;
+
+    // Each file is a 512 bytes header followed by his data, padded to 512 bytes. The archive ends with empty headers.
+    while index + 512 <= data.len() {
+        let header = &data[index..index + 512];
+        if header.iter().all(|x| *x == 0) { break; }
+
+        let mut name = get_tar_string(&header[..100]);
+        let size_octal = String::from_utf8_lossy(&header[124..136]).trim_matches(|x: char| x == '\0' || x == ' ').to_owned();
+        let size = usize::from_str_radix(&size_octal, 8).map_err(|_| ErrorKind::SchemaUpdateSourceNotValid(name.to_owned()))?;
+        let file_type = header[156];
+
+        // Ustar archives split long paths between the name and a prefix.
+        if &header[257..262] == b"ustar" {
+            let prefix = get_tar_string(&header[345..500]);
+            if !prefix.is_empty() { name = format!("{}/{}", prefix, name); }
+        }
+
+        index += 512;
+        if index + size > data.len() { return Err(ErrorKind::SchemaUpdateSourceNotValid(name))? }
+        let entry_data = &data[index..index + size];
+        index += (size + 511) / 512 * 512;
+
+        match file_type {
+
+            // GNU archives put long names in their own entry, just before the entry of the file.
+            b'L' => long_name = Some(get_tar_string(entry_data)),
+            b'0' | 0 => {
+                let name = long_name.take().unwrap_or(name);
+                let name = name.trim_start_matches("./");
+                if !name.is_empty() && !name.ends_with('/') { files.insert(name.to_owned(), entry_data.to_vec()); }
+            }
+            _ => long_name = None,
         }
     }
 
-    // Now we update the "versions.json" to reflect the update.
-    let versions_path = RPFM_PATH.to_path_buf().join(PathBuf::from("schemas/versions.json"));
-    let mut file = BufWriter::new(File::create(&versions_path)?);
-    file.write_all(serde_json::to_string_pretty(&remote_versions)?.as_bytes())?;
+    Ok(files)
+}
 
-    // If we reach this place, return success.
-    Ok(())
+/// This function returns the text of a field of a `.tar` header, which ends in the first null byte, if it has one.
+fn get_tar_string(data: &[u8]) -> String {
+    String::from_utf8_lossy(data.split(|x| *x == 0).next().unwrap_or(&[])).to_string()
+}
+
+/// This function returns the files of a schema update archive, by their path relative to the folder with the `versions.json`
+/// (the shallowest one, if there are more than one). Files outside that folder are ignored. If there is no `versions.json`,
+/// the files are returned as they are.
+pub fn get_archive_schemas_files(files: BTreeMap<String, Vec<u8>>) -> BTreeMap<String, Vec<u8>> {
+    let root = files.keys()
+        .filter(|x| *x == SCHEMA_VERSIONS_FILE || x.ends_with(&format!("/{}", SCHEMA_VERSIONS_FILE)))
+        .min_by_key(|x| x.matches('/').count())
+        .map(|x| x[..x.len() - SCHEMA_VERSIONS_FILE.len()].to_owned());
+
+    match root {
+        Some(root) => files.into_iter().filter(|(path, _)| path.starts_with(&root)).map(|(path, data)| (path[root.len()..].to_owned(), data)).collect(),
+        None => files,
+    }
 }